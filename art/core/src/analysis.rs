@@ -0,0 +1,319 @@
+//! A module for analysis passes over movies.
+//!
+//! Captured movies usually contain more than just the animated characters. Elements like score digits, lives counters and other HUD
+//! parts are drawn with sprites as well, but they hardly ever move. These passes find such sprites, such that they can be excluded from
+//! further processing.
+//!
+//! The tracking pass follows sprites from frame to frame and assigns them stable identities, such that an object can be processed as a
+//! whole, across all frames in which it appears.
+
+use crate::geom_art::Size;
+use crate::movie::{Movie, SpriteId};
+use crate::sprite::Sprite;
+use std::collections::{HashMap, HashSet};
+
+/// Finds the static sprites in a movie.
+///
+/// A sprite is static if an identical sprite (same tile, palette, position and flags) occurs in at least a fraction of the frames.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `min_fraction`: The minimum fraction of the frames in which a sprite has to occur to be considered static. This must be in the
+///   range `(0, 1]`.
+///
+/// returns: The static sprites.
+pub fn find_static_sprites(movie: &Movie, min_fraction: f64) -> Result<HashSet<Sprite>, String> {
+    if !(min_fraction > 0.0 && min_fraction <= 1.0) {
+        return Err(format!(
+            "Invalid fraction: {min_fraction} (expected a value greater than 0 and at most 1)."
+        ));
+    }
+
+    let frame_count = movie.frames().len();
+    let mut occurrences: HashMap<&Sprite, usize> = HashMap::new();
+    for frame in movie.frames() {
+        // A sprite that occurs multiple times in the same frame only counts once
+        let unique: HashSet<&Sprite> = frame.sprites().iter().collect();
+        for sprite in unique {
+            *occurrences.entry(sprite).or_default() += 1;
+        }
+    }
+
+    Ok(occurrences
+        .into_iter()
+        .filter(|(_, count)| *count as f64 >= min_fraction * frame_count as f64)
+        .map(|(sprite, _)| sprite.clone())
+        .collect())
+}
+
+/// Removes the static sprites from a movie (see [`find_static_sprites()`]).
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `min_fraction`: The minimum fraction of the frames in which a sprite has to occur to be considered static.
+///
+/// returns: The number of sprites that have been removed from the frames.
+pub fn strip_static_sprites(movie: &mut Movie, min_fraction: f64) -> Result<usize, String> {
+    let static_sprites = find_static_sprites(movie, min_fraction)?;
+    Ok(movie.remove_sprites(|sprite| static_sprites.contains(sprite)))
+}
+
+/// The additional cost of matching two sprites that show different artwork (a different tile or different flip flags), in pixels.
+///
+/// Animated objects change their tiles, so such sprites can still be matched, but a sprite that shows the same artwork is preferred
+/// unless it is considerably further away.
+const ARTWORK_CHANGE_COST: u32 = 8;
+
+/// Determines the identities of the sprites in a movie.
+///
+/// Every sprite is matched with a sprite in the previous frame that uses the same palette and lies within a maximum distance. Matches
+/// with the same artwork and a small distance are preferred and every sprite is matched at most once. Sprites that can not be matched
+/// get a new identity.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `max_distance`: The maximum distance (horizontal plus vertical, in pixels) that a sprite can move from one frame to the next.
+///   Distances wrap around at the edges of the screen, like the sprites do.
+///
+/// returns: For every frame, the identities of its sprites (see [`Movie::set_sprite_ids()`]).
+pub fn track_sprites(movie: &Movie, max_distance: u32) -> Vec<Vec<SpriteId>> {
+    let screen = movie.screen_size();
+    let mut next_id = 0;
+    let mut result: Vec<Vec<SpriteId>> = Vec::with_capacity(movie.frames().len());
+    let mut previous: &[Sprite] = &[];
+    for frame in movie.frames() {
+        let sprites = frame.sprites();
+        let mut ids: Vec<Option<SpriteId>> = vec![None; sprites.len()];
+
+        if let Some(previous_ids) = result.last() {
+            let mut candidates = Vec::new();
+            for (i, from) in previous.iter().enumerate() {
+                for (j, to) in sprites.iter().enumerate() {
+                    if let Some(cost) = match_cost(from, to, screen, max_distance) {
+                        candidates.push((cost, i, j));
+                    }
+                }
+            }
+            // Sorting by the indices as well keeps the result deterministic
+            candidates.sort_unstable();
+            let mut matched = vec![false; previous.len()];
+            for (_, i, j) in candidates {
+                if !matched[i] && ids[j].is_none() {
+                    matched[i] = true;
+                    ids[j] = Some(previous_ids[i]);
+                }
+            }
+        }
+
+        result.push(
+            ids.into_iter()
+                .map(|id| {
+                    id.unwrap_or_else(|| {
+                        next_id += 1;
+                        SpriteId::new(next_id - 1)
+                    })
+                })
+                .collect(),
+        );
+        previous = sprites;
+    }
+    result
+}
+
+/// Tracks the sprites in a movie (see [`track_sprites()`]) and stores the identities on the movie.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `max_distance`: The maximum distance that a sprite can move from one frame to the next.
+///
+/// returns: The number of distinct identities.
+pub fn assign_sprite_ids(movie: &mut Movie, max_distance: u32) -> usize {
+    let ids = track_sprites(movie, max_distance);
+    let count = ids
+        .iter()
+        .flatten()
+        .map(|id| id.value() + 1)
+        .max()
+        .unwrap_or(0);
+    // The unwrap is OK here, because the identities have been determined for the same frames
+    movie.set_sprite_ids(ids).unwrap();
+    count
+}
+
+/// Determines the cost of matching a sprite with a sprite in the next frame (see [`track_sprites()`]).
+///
+/// returns: The cost or `None` if the sprites can not be matched.
+fn match_cost(from: &Sprite, to: &Sprite, screen: Size, max_distance: u32) -> Option<u32> {
+    if from.palette() != to.palette() {
+        return None;
+    }
+
+    let (from_pos, to_pos) = (from.position(), to.position());
+    let distance = wrapping_distance(from_pos.x.raw(), to_pos.x.raw(), screen.width.raw())
+        + wrapping_distance(from_pos.y.raw(), to_pos.y.raw(), screen.height.raw());
+    if distance > max_distance {
+        return None;
+    }
+
+    let same_artwork =
+        from.tile() == to.tile() && from.h_flip() == to.h_flip() && from.v_flip() == to.v_flip();
+    Some(if same_artwork {
+        distance
+    } else {
+        distance + ARTWORK_CHANGE_COST
+    })
+}
+
+/// Determines the distance between two coordinates along an axis that wraps around.
+fn wrapping_distance(a: u32, b: u32, len: u32) -> u32 {
+    let distance = (a % len).abs_diff(b % len);
+    distance.min(len - distance)
+}
+
+#[cfg(test)]
+mod test_analysis {
+    use super::{assign_sprite_ids, find_static_sprites, strip_static_sprites, track_sprites};
+    use crate::geom_art::Size;
+    use crate::movie::{FrameRate, Movie, MovieFrame, SpriteId, SpriteLocation};
+    use crate::sprite::{Color, Palette, PaletteRef, Sprite, Tile, TileRef, TileSurface};
+
+    fn sprite(tile: usize, x: u32) -> Sprite {
+        Sprite::new(
+            TileRef::new(tile),
+            PaletteRef::new(0),
+            (x, 8).into(),
+            false,
+            false,
+        )
+    }
+
+    /// Creates a movie with a HUD sprite in every frame, a character that moves every frame and a sprite that only flashes once.
+    fn create_movie() -> Movie {
+        let hud = sprite(0, 200);
+        let frames = (0..4)
+            .map(|i| {
+                let mut sprites = vec![hud.clone(), sprite(1, 10 + i)];
+                if i == 2 {
+                    sprites.push(sprite(1, 100));
+                }
+                MovieFrame::new(u64::from(i), sprites)
+            })
+            .collect();
+        Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            vec![
+                Tile::new(TileSurface::new(Size::new(8, 8))),
+                Tile::new(TileSurface::new(Size::new(16, 16))),
+            ],
+            frames,
+            FrameRate::Ntsc,
+        )
+    }
+
+    #[test]
+    fn test_find_static_sprites() {
+        let movie = create_movie();
+        let found = find_static_sprites(&movie, 0.9).unwrap();
+        assert_eq!(1, found.len());
+        assert!(found.contains(&sprite(0, 200)));
+
+        // Every sprite occurs in at least a quarter of the frames
+        assert_eq!(6, find_static_sprites(&movie, 0.25).unwrap().len());
+
+        assert!(find_static_sprites(&movie, 0.0).is_err());
+        assert!(find_static_sprites(&movie, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_strip_static_sprites() {
+        let mut movie = create_movie();
+        assert_eq!(Ok(4), strip_static_sprites(&mut movie, 1.0));
+        assert!(movie
+            .frames()
+            .iter()
+            .all(|frame| !frame.sprites().contains(&sprite(0, 200))));
+        assert_eq!(2, movie.frames()[2].sprites().len());
+    }
+
+    #[test]
+    fn test_track_sprites() {
+        let movie = create_movie();
+        let ids = track_sprites(&movie, 4);
+        let id = SpriteId::new;
+        assert_eq!(
+            vec![
+                vec![id(0), id(1)],
+                vec![id(0), id(1)],
+                vec![id(0), id(1), id(2)],
+                vec![id(0), id(1)],
+            ],
+            ids
+        );
+
+        // Without any movement, the character gets a new identity in every frame
+        let ids = track_sprites(&movie, 0);
+        assert_eq!(vec![id(0), id(5)], ids[3]);
+    }
+
+    #[test]
+    fn test_track_sprites_prefers_same_artwork() {
+        let base = create_movie();
+        // The character changes its tile, while a sprite with the original tile appears further away
+        let frames = vec![
+            MovieFrame::new(0, vec![sprite(1, 10)]),
+            MovieFrame::new(1, vec![sprite(0, 11), sprite(1, 14)]),
+            MovieFrame::new(2, vec![sprite(0, 255)]),
+        ];
+        let movie = Movie::new(
+            base.screen_size(),
+            base.palettes().to_vec(),
+            base.tiles().to_vec(),
+            frames,
+            base.frame_rate(),
+        );
+        let ids = track_sprites(&movie, 16);
+        assert_eq!(vec![SpriteId::new(1), SpriteId::new(0)], ids[1]);
+        // Distances wrap around at the edge of the screen
+        assert_eq!(vec![SpriteId::new(1)], ids[2]);
+    }
+
+    #[test]
+    fn test_assign_sprite_ids() {
+        let mut movie = create_movie();
+        assert_eq!(None, movie.sprite_ids(0));
+        assert_eq!(3, assign_sprite_ids(&mut movie, 4));
+        let character = movie
+            .sprite_id(SpriteLocation {
+                frame: 3,
+                sprite: 1,
+            })
+            .unwrap();
+        assert_eq!(4, movie.find_sprites_by_id(character).len());
+        assert_eq!(
+            vec![SpriteLocation {
+                frame: 2,
+                sprite: 2
+            }],
+            movie.find_sprites_by_id(SpriteId::new(2))
+        );
+
+        assert!(movie.set_sprite_ids(vec![Vec::new(); 4]).is_err());
+        assert!(movie.set_sprite_ids(Vec::new()).is_err());
+
+        // The identities follow the sprites when sprites are removed
+        let before = movie.find_sprites_by_id(character).len();
+        assert!(strip_static_sprites(&mut movie, 1.0).unwrap() > 0);
+        for (frame_idx, frame) in movie.frames().iter().enumerate() {
+            assert_eq!(
+                frame.sprites().len(),
+                movie.sprite_ids(frame_idx).unwrap().len()
+            );
+        }
+        assert_eq!(before, movie.find_sprites_by_id(character).len());
+    }
+}
@@ -0,0 +1,199 @@
+//! A module for storing many small buffers in a single allocation.
+//!
+//! A movie can contain thousands of tiles. If every tile keeps its pixel data in its own `Vec`, the data is scattered across the heap
+//! and every tile pays for the overhead of a separate allocation. An [`ArenaSlice`] is a handle (an offset and a length) into a shared
+//! arena instead. [`pack()`] moves the data of many slices into one contiguous arena.
+
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// A slice of a shared arena.
+///
+/// Cloning a slice is cheap, because the arena is shared. A slice that is modified through [`ArenaSlice::make_mut()`] is detached from
+/// the arena first (unless it already owns the entire arena), such that other slices are not affected.
+pub(crate) struct ArenaSlice<T> {
+    arena: Arc<[T]>,
+    offset: usize,
+    len: usize,
+}
+
+impl<T> ArenaSlice<T> {
+    /// Creates a new instance that owns its own arena.
+    pub(crate) fn from_vec(data: Vec<T>) -> Self {
+        let len = data.len();
+        Self {
+            arena: data.into(),
+            offset: 0,
+            len,
+        }
+    }
+
+    /// Retrieves the data.
+    pub(crate) fn as_slice(&self) -> &[T] {
+        &self.arena[self.offset..self.offset + self.len]
+    }
+}
+
+impl<T> ArenaSlice<T>
+where
+    T: Clone,
+{
+    /// Retrieves the data mutably. If the arena is shared or contains other data, the slice is detached from it first.
+    pub(crate) fn make_mut(&mut self) -> &mut [T] {
+        if self.offset != 0
+            || self.len != self.arena.len()
+            || Arc::get_mut(&mut self.arena).is_none()
+        {
+            *self = Self::from_vec(self.as_slice().to_vec());
+        }
+        // The unwrap is OK here, because the arena has just been made unique
+        Arc::get_mut(&mut self.arena).unwrap()
+    }
+}
+
+/// Moves the data of multiple slices into a single, contiguous arena.
+///
+/// # Arguments
+///
+/// * `slices`: The slices. The data of every slice is placed after the data of the previous slice.
+pub(crate) fn pack<'a, T>(slices: impl IntoIterator<Item = &'a mut ArenaSlice<T>>)
+where
+    T: Clone + 'a,
+{
+    let mut slices: Vec<_> = slices.into_iter().collect();
+    let total_len = slices.iter().map(|slice| slice.len).sum();
+    let mut data = Vec::with_capacity(total_len);
+    for slice in &slices {
+        data.extend_from_slice(slice.as_slice());
+    }
+
+    let arena: Arc<[T]> = data.into();
+    let mut offset = 0;
+    for slice in &mut slices {
+        slice.arena = Arc::clone(&arena);
+        slice.offset = offset;
+        offset += slice.len;
+    }
+}
+
+impl<T> Deref for ArenaSlice<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T> Clone for ArenaSlice<T> {
+    fn clone(&self) -> Self {
+        Self {
+            arena: Arc::clone(&self.arena),
+            offset: self.offset,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> Debug for ArenaSlice<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl<T> PartialEq for ArenaSlice<T>
+where
+    T: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T> Eq for ArenaSlice<T> where T: Eq {}
+
+impl<T> Hash for ArenaSlice<T>
+where
+    T: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The same as for a Vec, such that caches are not affected by the storage
+        self.as_slice().hash(state)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<T> serde::Serialize for ArenaSlice<T>
+where
+    T: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // The same as for a Vec, such that the format is not affected by the storage
+        self.as_slice().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de, T> serde::Deserialize<'de> for ArenaSlice<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Self::from_vec)
+    }
+}
+
+#[cfg(test)]
+mod test_arena_slice {
+    use super::{pack, ArenaSlice};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_pack() {
+        let mut slices = [
+            ArenaSlice::from_vec(vec![1, 2]),
+            ArenaSlice::from_vec(Vec::new()),
+            ArenaSlice::from_vec(vec![3, 4, 5]),
+        ];
+        pack(slices.iter_mut());
+
+        assert_eq!(&[1, 2], slices[0].as_slice());
+        assert!(slices[1].is_empty());
+        assert_eq!(&[3, 4, 5], &*slices[2]);
+        assert!(Arc::ptr_eq(&slices[0].arena, &slices[2].arena));
+        assert_eq!(5, slices[0].arena.len());
+    }
+
+    #[test]
+    fn test_make_mut() {
+        let mut slices = [
+            ArenaSlice::from_vec(vec![1, 2]),
+            ArenaSlice::from_vec(vec![3, 4, 5]),
+        ];
+        pack(slices.iter_mut());
+        let copy = slices[1].clone();
+
+        // Modifying a packed slice detaches it and leaves the other slices alone
+        slices[1].make_mut()[0] = 7;
+        assert_eq!(&[7, 4, 5], slices[1].as_slice());
+        assert_eq!(&[3, 4, 5], copy.as_slice());
+        assert_eq!(&[1, 2], slices[0].as_slice());
+        assert_ne!(copy, slices[1]);
+
+        // A slice that owns its arena is modified in place
+        let arena = Arc::as_ptr(&slices[1].arena);
+        slices[1].make_mut()[1] = 8;
+        assert_eq!(arena, Arc::as_ptr(&slices[1].arena));
+        assert_eq!(&[7, 8, 5], slices[1].as_slice());
+    }
+}
@@ -0,0 +1,276 @@
+//! A module for color conversions.
+//!
+//! The colors of the artwork are the raw colors of the source hardware, expanded to 8 bits per component. The SNES (and the VES) use
+//! 5 bits per component, packed into 15 bits. The functions in this module convert between these representations.
+//!
+//! A CRT (and most emulators) displays these colors differently than a computer monitor. A [`ColorTransform`] approximates that look
+//! for exported images.
+
+use crate::sprite::Color;
+use rgb::RGB8;
+
+/// The weights of the red, green and blue components for calculating the luma (ITU-R BT.601).
+const LUMA_WEIGHTS: [f32; 3] = [0.299, 0.587, 0.114];
+
+/// The mask for a 5-bit color component.
+const COMPONENT_5BIT_MASK: u8 = 0b11111;
+
+/// Expands a 5-bit color component to 8 bits.
+///
+/// The bit pattern is repeated across the lower bits, such that the full range is used (`0` becomes `0` and `31` becomes `255`).
+///
+/// # Arguments
+///
+/// * `bits`: The color component. Only the 5 least-significant bits are considered.
+#[inline(always)]
+pub fn expand_5bit(bits: u8) -> u8 {
+    let bits = bits & COMPONENT_5BIT_MASK;
+    bits << 3 | bits >> 2
+}
+
+/// Reduces an 8-bit color component to 5 bits. This is the inverse of [`expand_5bit()`].
+///
+/// # Arguments
+///
+/// * `value`: The color component.
+#[inline(always)]
+pub fn reduce_to_5bit(value: u8) -> u8 {
+    value >> 3
+}
+
+/// Converts a 15-bit color in the SNES format to an [`RGB8`].
+///
+/// The format is as follows (refer to section A-17 in the SNES developer manual):
+/// * Bits 0-4: Red component.
+/// * Bits 5-9: Green component.
+/// * Bits 10-14: Blue component.
+/// * Bit 15: Unused.
+///
+/// # Arguments
+///
+/// * `value`: The color.
+pub fn rgb_from_bgr555(value: u16) -> RGB8 {
+    // The casts are OK here, because expand_5bit() only considers the 5 least-significant bits
+    RGB8::new(
+        expand_5bit(value as u8),
+        expand_5bit((value >> 5) as u8),
+        expand_5bit((value >> 10) as u8),
+    )
+}
+
+/// Converts an [`RGB8`] to a 15-bit color in the SNES format. This is the inverse of [`rgb_from_bgr555()`].
+///
+/// # Arguments
+///
+/// * `rgb`: The color.
+pub fn rgb_to_bgr555(rgb: RGB8) -> u16 {
+    u16::from(reduce_to_5bit(rgb.r))
+        | u16::from(reduce_to_5bit(rgb.g)) << 5
+        | u16::from(reduce_to_5bit(rgb.b)) << 10
+}
+
+/// A transformation that is applied to the colors of an exported image.
+///
+/// Transformed images are meant for viewing only. They can not be imported again without losing the original colors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ColorTransform {
+    gamma: f32,
+    saturation: f32,
+}
+
+impl Default for ColorTransform {
+    fn default() -> Self {
+        Self::RAW
+    }
+}
+
+impl ColorTransform {
+    /// The transform that leaves all colors unchanged.
+    pub const RAW: Self = Self {
+        gamma: 1.0,
+        saturation: 1.0,
+    };
+
+    /// An approximation of an NTSC TV: a CRT has a gamma of about 2.4 (rather than the 2.2 of sRGB) and a lower saturation.
+    pub const TV: Self = Self {
+        gamma: 2.4 / 2.2,
+        saturation: 0.85,
+    };
+
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `gamma`: The gamma exponent, which is applied to the normalized components. Values above 1 darken the mid-tones, values below
+    ///   1 brighten them.
+    /// * `saturation`: The saturation factor. A value of 0 results in grayscale, a value of 1 leaves the saturation unchanged.
+    pub fn new(gamma: f32, saturation: f32) -> Result<Self, String> {
+        if !(gamma.is_finite() && gamma > 0.0) {
+            return Err(format!(
+                "Invalid gamma: {gamma} (expected a positive value)."
+            ));
+        }
+        if !(saturation.is_finite() && saturation >= 0.0) {
+            return Err(format!(
+                "Invalid saturation: {saturation} (expected a non-negative value)."
+            ));
+        }
+        Ok(Self { gamma, saturation })
+    }
+
+    /// Retrieves the gamma exponent.
+    pub fn gamma(&self) -> f32 {
+        self.gamma
+    }
+
+    /// Retrieves the saturation factor.
+    pub fn saturation(&self) -> f32 {
+        self.saturation
+    }
+
+    /// Determines whether this transform leaves all colors unchanged.
+    pub fn is_raw(&self) -> bool {
+        *self == Self::RAW
+    }
+
+    /// Applies the transform to an RGB color. The gamma adjustment is applied first.
+    pub fn apply_rgb(&self, rgb: RGB8) -> RGB8 {
+        if self.is_raw() {
+            return rgb;
+        }
+
+        let gamma = |component: u8| (f32::from(component) / 255.0).powf(self.gamma);
+        let components = [gamma(rgb.r), gamma(rgb.g), gamma(rgb.b)];
+        let luma: f32 = components
+            .iter()
+            .zip(LUMA_WEIGHTS)
+            .map(|(component, weight)| component * weight)
+            .sum();
+        let saturate = |component: f32| {
+            let value = luma + self.saturation * (component - luma);
+            // The cast is OK here, because the value has been clamped to the range of a u8
+            (value.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        RGB8::new(
+            saturate(components[0]),
+            saturate(components[1]),
+            saturate(components[2]),
+        )
+    }
+
+    /// Applies the transform to a [`Color`]. Transparent colors remain transparent.
+    pub fn apply(&self, color: Color) -> Color {
+        match color {
+            Color::Opaque(rgb) => Color::Opaque(self.apply_rgb(rgb)),
+            Color::Transparent => Color::Transparent,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_bit_depth {
+    use super::{expand_5bit, reduce_to_5bit, rgb_from_bgr555, rgb_to_bgr555};
+    use rgb::RGB8;
+
+    #[test]
+    fn test_expand_5bit() {
+        assert_eq!(0, expand_5bit(0));
+        assert_eq!(0b11010110, expand_5bit(0b11010));
+        assert_eq!(255, expand_5bit(31));
+        // Only the 5 least-significant bits are considered
+        assert_eq!(expand_5bit(0b11010), expand_5bit(0b11111010));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for bits in 0..32 {
+            assert_eq!(bits, reduce_to_5bit(expand_5bit(bits)));
+        }
+        for value in 0..0x8000 {
+            assert_eq!(value, rgb_to_bgr555(rgb_from_bgr555(value)));
+        }
+    }
+
+    #[test]
+    fn test_bgr555() {
+        //                                 b     g     r
+        let rgb = rgb_from_bgr555(0b0_00111_10110_01100);
+        assert_eq!(
+            RGB8::new(expand_5bit(12), expand_5bit(22), expand_5bit(7)),
+            rgb
+        );
+        // The unused bit is ignored
+        assert_eq!(rgb, rgb_from_bgr555(0b1_00111_10110_01100));
+    }
+}
+
+#[cfg(test)]
+mod test_color_transform {
+    use super::ColorTransform;
+    use crate::sprite::Color;
+    use rgb::RGB8;
+
+    #[test]
+    fn test_new() {
+        assert_eq!(Ok(ColorTransform::RAW), ColorTransform::new(1.0, 1.0));
+        assert!(ColorTransform::new(0.0, 1.0).is_err());
+        assert!(ColorTransform::new(f32::NAN, 1.0).is_err());
+        assert!(ColorTransform::new(1.0, -0.5).is_err());
+    }
+
+    #[test]
+    fn test_raw() {
+        assert!(ColorTransform::default().is_raw());
+        for rgb in [
+            RGB8::new(0, 0, 0),
+            RGB8::new(255, 255, 255),
+            RGB8::new(12, 200, 99),
+        ] {
+            assert_eq!(rgb, ColorTransform::RAW.apply_rgb(rgb));
+        }
+    }
+
+    #[test]
+    fn test_gamma() {
+        let darken = ColorTransform::new(2.0, 1.0).unwrap();
+        // Black and white are fixed points
+        assert_eq!(RGB8::new(0, 0, 0), darken.apply_rgb(RGB8::new(0, 0, 0)));
+        assert_eq!(
+            RGB8::new(255, 255, 255),
+            darken.apply_rgb(RGB8::new(255, 255, 255))
+        );
+        // (128 / 255)^2 * 255 = 64.25
+        assert_eq!(
+            RGB8::new(64, 64, 64),
+            darken.apply_rgb(RGB8::new(128, 128, 128))
+        );
+    }
+
+    #[test]
+    fn test_saturation() {
+        let grayscale = ColorTransform::new(1.0, 0.0).unwrap();
+        // The luma of pure red is 0.299
+        assert_eq!(
+            RGB8::new(76, 76, 76),
+            grayscale.apply_rgb(RGB8::new(255, 0, 0))
+        );
+        // Gray colors are not affected by the saturation
+        let tv = ColorTransform::new(1.0, ColorTransform::TV.saturation()).unwrap();
+        assert_eq!(
+            RGB8::new(100, 100, 100),
+            tv.apply_rgb(RGB8::new(100, 100, 100))
+        );
+    }
+
+    #[test]
+    fn test_transparent() {
+        assert_eq!(
+            Color::Transparent,
+            ColorTransform::TV.apply(Color::Transparent)
+        );
+        assert_ne!(
+            Color::new(200, 40, 40),
+            ColorTransform::TV.apply(Color::new(200, 40, 40))
+        );
+    }
+}
@@ -0,0 +1,217 @@
+//! Module containing the versioned binary layout of the serialized types.
+//!
+//! Binary formats (like bincode) are not self-describing: they can not skip fields and they can not tell a missing field from the
+//! next value. In order to add fields to a type without breaking the files that have been written before, such types are written as
+//! a marker, a layout version and the fields. The marker is a value that can not occur as the first value of the original
+//! (unversioned) layout, which means that files without a marker can still be read as the original layout. When fields are added
+//! later on, the version is increased and the earlier versions are read by [`BinaryLayout::from_earlier()`].
+//!
+//! Human-readable formats (like JSON or RON) do not use this layout, since they can skip and default fields by name.
+
+use serde::de::{DeserializeOwned, Error as _, SeqAccess, Visitor};
+use serde::ser::SerializeTuple as _;
+use serde::{Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+/// A type with a versioned binary layout.
+pub(crate) trait BinaryLayout: Sized {
+    /// The type of the marker. This must be the type of the first value of the original layout.
+    type Marker: Copy + Debug + PartialEq + Serialize + DeserializeOwned;
+    /// The marker that precedes the versioned layout.
+    const MARKER: Self::Marker;
+    /// The current version of the layout.
+    const VERSION: u16;
+    /// The fields of the current layout, as a tuple.
+    type Fields: DeserializeOwned;
+
+    /// Creates an instance from the fields of the current layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `fields`: The fields.
+    ///
+    /// returns: The instance or an error message if the fields are invalid.
+    fn from_fields(fields: Self::Fields) -> Result<Self, String>;
+
+    /// Creates an instance from the fields of an earlier version of the layout. By default, earlier versions are not supported.
+    ///
+    /// # Arguments
+    ///
+    /// * `version`: The version of the layout, which is lower than [`BinaryLayout::VERSION`].
+    /// * `seq`: The remaining values (the fields, as a tuple).
+    ///
+    /// returns: The instance or an error.
+    fn from_earlier<'de, A: SeqAccess<'de>>(version: u16, seq: A) -> Result<Self, A::Error> {
+        let _ = seq;
+        Err(A::Error::custom(format!(
+            "Unsupported layout version {version}."
+        )))
+    }
+
+    /// Creates an instance from the original layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `first`: The first value of the original layout, which has already been read.
+    /// * `seq`: The remaining values.
+    ///
+    /// returns: The instance or an error.
+    fn from_legacy<'de, A: SeqAccess<'de>>(first: Self::Marker, seq: A) -> Result<Self, A::Error>;
+}
+
+/// Serializes a [`BinaryLayout`] type.
+///
+/// # Arguments
+///
+/// * `fields`: The fields of the current layout, as a tuple (usually of references).
+/// * `serializer`: The serializer.
+///
+/// returns: The result of the serializer.
+pub(crate) fn serialize<T, F, S>(fields: &F, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: BinaryLayout,
+    F: Serialize,
+    S: Serializer,
+{
+    let mut state = serializer.serialize_tuple(3)?;
+    state.serialize_element(&T::MARKER)?;
+    state.serialize_element(&T::VERSION)?;
+    state.serialize_element(fields)?;
+    state.end()
+}
+
+/// Deserializes a [`BinaryLayout`] type, either from the current layout or from the original layout.
+///
+/// # Arguments
+///
+/// * `deserializer`: The deserializer.
+///
+/// returns: The instance or an error.
+pub(crate) fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: BinaryLayout,
+    D: Deserializer<'de>,
+{
+    // The length is unknown, since the original layout is read by the type itself
+    deserializer.deserialize_tuple(usize::MAX, LayoutVisitor(PhantomData))
+}
+
+struct LayoutVisitor<T>(PhantomData<T>);
+
+impl<T> LayoutVisitor<T> {
+    fn next<'de, A, V>(&self, seq: &mut A, index: usize) -> Result<V, A::Error>
+    where
+        A: SeqAccess<'de>,
+        V: serde::Deserialize<'de>,
+        Self: Visitor<'de>,
+    {
+        seq.next_element()?
+            .ok_or_else(|| A::Error::invalid_length(index, self))
+    }
+}
+
+impl<'de, T: BinaryLayout> Visitor<'de> for LayoutVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        write!(formatter, "a versioned or an original binary layout")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let first: T::Marker = self.next(&mut seq, 0)?;
+        if first != T::MARKER {
+            return T::from_legacy(first, seq);
+        }
+
+        let version: u16 = self.next(&mut seq, 1)?;
+        if version > T::VERSION {
+            return Err(A::Error::custom(format!(
+                "Unsupported layout version {version} (the latest supported version is {}).",
+                T::VERSION
+            )));
+        }
+
+        if version < T::VERSION {
+            return T::from_earlier(version, seq);
+        }
+
+        let fields = self.next(&mut seq, 2)?;
+        T::from_fields(fields).map_err(A::Error::custom)
+    }
+}
+
+/// Reads the next value of an original layout.
+///
+/// # Arguments
+///
+/// * `seq`: The values.
+///
+/// returns: The value or an error if there are no more values.
+pub(crate) fn next_legacy<'de, A, V>(seq: &mut A) -> Result<V, A::Error>
+where
+    A: SeqAccess<'de>,
+    V: serde::Deserialize<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| A::Error::custom("Unexpected end of the original layout."))
+}
+
+/// Reads a `Vec` of an original layout, of which the length has already been read.
+///
+/// # Arguments
+///
+/// * `len`: The length.
+/// * `seq`: The values.
+///
+/// returns: The `Vec` or an error if there are not enough values.
+pub(crate) fn next_legacy_vec<'de, A, V>(len: u64, seq: &mut A) -> Result<Vec<V>, A::Error>
+where
+    A: SeqAccess<'de>,
+    V: serde::Deserialize<'de>,
+{
+    let len = usize::try_from(len).map_err(A::Error::custom)?;
+    // Do not trust the length for the allocation, since it could be anything
+    let mut vec = Vec::with_capacity(len.min(4096));
+    for _ in 0..len {
+        vec.push(next_legacy(seq)?);
+    }
+    Ok(vec)
+}
+
+/// Implements `Serialize` and `Deserialize` for a [`BinaryLayout`] type.
+///
+/// Human-readable formats use `$def`, which is a `#[serde(remote = "...")]` definition of the type. Binary formats use the versioned
+/// layout, of which the fields are taken from `$value` by `$fields`.
+macro_rules! impl_serde {
+    ($ty:ident, $def:ident, |$value:ident| $fields:expr) => {
+        impl serde::Serialize for $ty {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                if serializer.is_human_readable() {
+                    $def::serialize(self, serializer)
+                } else {
+                    let $value = self;
+                    $crate::compat::serialize::<Self, _, _>(&$fields, serializer)
+                }
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                if deserializer.is_human_readable() {
+                    $def::deserialize(deserializer)
+                } else {
+                    $crate::compat::deserialize(deserializer)
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use impl_serde;
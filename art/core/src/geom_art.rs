@@ -28,3 +28,8 @@ pub type Size = ves_geom::Size<ArtworkSpaceUnit>;
 ///
 /// See also [`ArtworkSpaceUnit`].
 pub type Rect = ves_geom::Rect<ArtworkSpaceUnit>;
+
+/// A polygon in "artwork space".
+///
+/// See also [`ArtworkSpaceUnit`].
+pub type Polygon = ves_geom::Polygon<ArtworkSpaceUnit>;
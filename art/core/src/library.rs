@@ -0,0 +1,506 @@
+//! A module for art libraries.
+//!
+//! An [`ArtLibrary`] is the curated counterpart of a [`Movie`]. It contains the tiles, palettes, cels (metasprites) and animations that
+//! make up the artwork of a game, but none of the frame or playback data of a capture session. It is the canonical output of the curation
+//! workflow and the input for the asset compiler.
+
+use crate::geom_art::Size;
+use crate::movie::{FrameRate, Movie, MovieFrame};
+use crate::sprite::{
+    Animation, AnimationFrame, Cel, CelRef, FlipState, Palette, PaletteRef, Sprite, SpriteSource,
+    Tile, TileRef,
+};
+use std::borrow::Cow;
+use ves_cache::VecCacheMut;
+
+/// A library of curated artwork.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ArtLibrary {
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+    cels: Vec<Cel>,
+    animations: Vec<Animation>,
+}
+
+impl ArtLibrary {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `palettes`: The palettes.
+    /// * `tiles`: The tiles.
+    /// * `cels`: The cels. The sprites in these cels refer to `palettes` and `tiles`.
+    /// * `animations`: The animations. The frames in these animations refer to `cels`.
+    pub fn new(
+        palettes: Vec<Palette>,
+        tiles: Vec<Tile>,
+        cels: Vec<Cel>,
+        animations: Vec<Animation>,
+    ) -> Self {
+        Self {
+            palettes,
+            tiles,
+            cels,
+            animations,
+        }
+    }
+
+    /// Creates a new instance from a [`Movie`].
+    ///
+    /// The palettes and tiles are taken over as-is. Every frame in the movie results in one [`Cel`], in the same order as the frames. The
+    /// resulting library contains no animations.
+    ///
+    /// The cels keep track of the [`SpriteSource`] of every sprite. The OAM slot of a sprite is its index in the frame, which only
+    /// matches the emulator if no sprites have been removed from the frame (for instance by cropping).
+    ///
+    /// # Arguments
+    ///
+    /// * `movie`: The movie.
+    pub fn from_movie(movie: &Movie) -> Self {
+        let cels = movie
+            .frames()
+            .iter()
+            .map(|frame| {
+                let mut cel = Cel::new(frame.sprites().to_vec());
+                let sources = (0..frame.sprites().len())
+                    .map(|oam_slot| SpriteSource {
+                        frame_number: frame.frame_number(),
+                        oam_slot,
+                    })
+                    .collect();
+                // The unwrap is OK here, because there is exactly one source per sprite
+                cel.set_sources(sources).unwrap();
+                cel
+            })
+            .collect();
+
+        Self::new(
+            movie.palettes().to_vec(),
+            movie.tiles().to_vec(),
+            cels,
+            Vec::new(),
+        )
+    }
+
+    /// Creates a [`Movie`] from this library.
+    ///
+    /// Every [`Cel`] results in one frame, numbered by the index of the cel. This is mainly useful for previewing the contents of a
+    /// library in tools that operate on movies.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen_size`: The screen size for the movie.
+    /// * `frame_rate`: The frame rate for the movie.
+    pub fn to_movie(&self, screen_size: Size, frame_rate: FrameRate) -> Movie {
+        let frames = self
+            .cels
+            .iter()
+            .enumerate()
+            .map(|(i, cel)| MovieFrame::new(i as u64, cel.sprites().to_vec()))
+            .collect();
+
+        Movie::new(
+            screen_size,
+            self.palettes.clone(),
+            self.tiles.clone(),
+            frames,
+            frame_rate,
+        )
+    }
+
+    /// Retrieves the palettes.
+    pub fn palettes(&self) -> &[Palette] {
+        &self.palettes
+    }
+
+    /// Retrieves the tiles.
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Retrieves the cels.
+    pub fn cels(&self) -> &[Cel] {
+        &self.cels
+    }
+
+    /// Retrieves the animations.
+    pub fn animations(&self) -> &[Animation] {
+        &self.animations
+    }
+
+    /// Merges another library into this one.
+    ///
+    /// The contents of `other` are appended to the contents of this library, after which [`ArtLibrary::dedup()`] is applied, such that
+    /// palettes, tiles and cels that exist in both libraries are only kept once.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The library to merge into this one.
+    pub fn merge(&mut self, other: &ArtLibrary) {
+        let palette_offset = self.palettes.len();
+        let tile_offset = self.tiles.len();
+        let cel_offset = self.cels.len();
+
+        self.palettes.extend_from_slice(&other.palettes);
+        self.tiles.extend_from_slice(&other.tiles);
+        self.cels.extend(other.cels.iter().map(|cel| {
+            remap_cel(
+                cel,
+                |tile| {
+                    (
+                        TileRef::new(tile.value() + tile_offset),
+                        FlipState::default(),
+                    )
+                },
+                |palette| PaletteRef::new(palette.value() + palette_offset),
+            )
+        }));
+        self.animations
+            .extend(other.animations.iter().map(|animation| {
+                remap_animation(animation, |cel| CelRef::new(cel.value() + cel_offset))
+            }));
+
+        self.dedup();
+    }
+
+    /// Removes duplicate palettes, tiles and cels.
+    ///
+    /// Tiles are stored in their canonical form (see [`Tile::canonical_form()`]), such that tiles that only differ in their orientation
+    /// are stored once. The flip flags of the sprites are adjusted to make up for this.
+    ///
+    /// All references are updated accordingly. The first occurrence of every value determines its position in the output.
+    pub fn dedup(&mut self) {
+        let mut palette_cache = VecCacheMut::<Palette, PaletteRef>::new();
+        let palette_map: Vec<PaletteRef> = self
+            .palettes
+            .drain(..)
+            .map(|palette| palette_cache.offer(Cow::Owned(palette)))
+            .collect();
+
+        let mut tile_cache = VecCacheMut::<Tile, TileRef>::new();
+        let tile_map: Vec<(TileRef, FlipState)> = self
+            .tiles
+            .drain(..)
+            .map(|tile| {
+                let (canonical, flip) = tile.canonical_form();
+                (tile_cache.offer(Cow::Owned(canonical)), flip)
+            })
+            .collect();
+
+        let mut cel_cache = VecCacheMut::<Cel, CelRef>::new();
+        let cel_map: Vec<CelRef> = self
+            .cels
+            .iter()
+            .map(|cel| {
+                let cel = remap_cel(
+                    cel,
+                    |tile| tile_map[tile.value()],
+                    |palette| palette_map[palette.value()],
+                );
+                cel_cache.offer(Cow::Owned(cel))
+            })
+            .collect();
+
+        self.palettes = palette_cache.into_vec();
+        self.tiles = tile_cache.into_vec();
+        self.cels = cel_cache.into_vec();
+        self.animations = self
+            .animations
+            .iter()
+            .map(|animation| remap_animation(animation, |cel| cel_map[cel.value()]))
+            .collect();
+    }
+
+    /// Removes all palettes and tiles that are not referenced by any cel.
+    ///
+    /// All references are updated accordingly. Cels are always retained, since a curated cel need not be part of an animation.
+    pub fn remove_unreferenced(&mut self) {
+        let mut palettes_used = vec![false; self.palettes.len()];
+        let mut tiles_used = vec![false; self.tiles.len()];
+        for sprite in self.cels.iter().flat_map(|cel| cel.sprites()) {
+            palettes_used[sprite.palette().value()] = true;
+            tiles_used[sprite.tile().value()] = true;
+        }
+
+        let palette_map = retain_used(&mut self.palettes, &palettes_used);
+        let tile_map = retain_used(&mut self.tiles, &tiles_used);
+
+        // The unwraps are OK here, because we only retained the values that are referenced by the cels
+        self.cels = self
+            .cels
+            .iter()
+            .map(|cel| {
+                remap_cel(
+                    cel,
+                    |tile| {
+                        (
+                            TileRef::new(tile_map[tile.value()].unwrap()),
+                            FlipState::default(),
+                        )
+                    },
+                    |palette| PaletteRef::new(palette_map[palette.value()].unwrap()),
+                )
+            })
+            .collect();
+    }
+}
+
+/// Creates a copy of a [`Cel`] with remapped references.
+///
+/// # Arguments
+///
+/// * `cel`: The source cel.
+/// * `tile_fn`: The mapping function for the tile references. It also returns the flips that the new tile needs in order to look like
+///   the old one, which are combined with the flip flags of the sprites.
+/// * `palette_fn`: The mapping function for the palette references.
+fn remap_cel(
+    cel: &Cel,
+    tile_fn: impl Fn(TileRef) -> (TileRef, FlipState),
+    palette_fn: impl Fn(PaletteRef) -> PaletteRef,
+) -> Cel {
+    let sprites = cel
+        .sprites()
+        .iter()
+        .map(|sprite| {
+            let (tile, flip) = tile_fn(sprite.tile());
+            Sprite::new(
+                tile,
+                palette_fn(sprite.palette()),
+                sprite.position(),
+                sprite.h_flip() ^ flip.h_flip,
+                sprite.v_flip() ^ flip.v_flip,
+            )
+        })
+        .collect();
+    let mut out = Cel::new(sprites);
+    // The unwrap is OK here, because the number of sprites has not changed
+    out.set_sources(cel.sources().to_vec()).unwrap();
+    out
+}
+
+/// Creates a copy of an [`Animation`] with remapped references.
+///
+/// # Arguments
+///
+/// * `animation`: The source animation.
+/// * `cel_fn`: The mapping function for the cel references.
+fn remap_animation(animation: &Animation, cel_fn: impl Fn(CelRef) -> CelRef) -> Animation {
+    let mut out = Animation::default();
+    out.as_mut().extend(
+        animation
+            .as_ref()
+            .iter()
+            .map(|frame| AnimationFrame::new(cel_fn(frame.cel()))),
+    );
+    out
+}
+
+/// Retains the values that are flagged as used.
+///
+/// # Arguments
+///
+/// * `values`: The values.
+/// * `used`: The flags that specify which values should be retained. This slice must be of the same length as `values`.
+///
+/// # Returns
+/// A mapping from the old index to the new index (or `None` if the value was removed).
+fn retain_used<T>(values: &mut Vec<T>, used: &[bool]) -> Vec<Option<usize>> {
+    let mut next_index = 0;
+    let map = used
+        .iter()
+        .map(|used| {
+            used.then(|| {
+                next_index += 1;
+                next_index - 1
+            })
+        })
+        .collect();
+
+    let mut used_iter = used.iter();
+    values.retain(|_| *used_iter.next().unwrap());
+    map
+}
+
+impl From<&Movie> for ArtLibrary {
+    fn from(movie: &Movie) -> Self {
+        Self::from_movie(movie)
+    }
+}
+
+#[cfg(test)]
+mod test_art_library {
+    use super::ArtLibrary;
+    use crate::geom_art::{Point, Size};
+    use crate::movie::{FrameRate, Movie, MovieFrame};
+    use crate::sprite::{
+        Animation, AnimationFrame, Cel, CelRef, Color, Palette, PaletteIndex, PaletteRef, Sprite,
+        SpriteSource, Tile, TileRef, TileSurface,
+    };
+    use crate::surface::Surface as _;
+
+    fn animation(cels: &[usize]) -> Animation {
+        let mut animation = Animation::default();
+        animation.as_mut().extend(
+            cels.iter()
+                .map(|cel| AnimationFrame::new(CelRef::new(*cel))),
+        );
+        animation
+    }
+
+    fn create_movie() -> Movie {
+        let palettes = vec![Palette::new_filled(16, Color::new(1, 2, 3))];
+        let tiles = vec![
+            Tile::new(TileSurface::new(Size::new(8, 8))),
+            Tile::new(TileSurface::new(Size::new(16, 16))),
+        ];
+        let sprite = |tile: usize, x: u32, y: u32| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(0),
+                Point::new(x, y),
+                false,
+                false,
+            )
+        };
+        let frames = vec![
+            MovieFrame::new(0, vec![sprite(0, 10, 20), sprite(1, 18, 20)]),
+            MovieFrame::new(1, vec![sprite(1, 12, 20)]),
+        ];
+
+        Movie::new(
+            Size::new(256, 224),
+            palettes,
+            tiles,
+            frames,
+            FrameRate::Ntsc,
+        )
+    }
+
+    #[test]
+    fn test_from_movie() {
+        let movie = create_movie();
+        let library = ArtLibrary::from_movie(&movie);
+
+        assert_eq!(movie.palettes(), library.palettes());
+        assert_eq!(movie.tiles(), library.tiles());
+        assert_eq!(2, library.cels().len());
+        assert_eq!(movie.frames()[0].sprites(), library.cels()[0].sprites());
+        assert_eq!(movie.frames()[1].sprites(), library.cels()[1].sprites());
+        assert!(library.animations().is_empty());
+        assert_eq!(
+            Some(&SpriteSource {
+                frame_number: 0,
+                oam_slot: 1
+            }),
+            library.cels()[0].sources().get(1)
+        );
+    }
+
+    #[test]
+    fn test_merge() {
+        let movie = create_movie();
+        let mut library = ArtLibrary::from_movie(&movie);
+        let other = library.clone();
+        library.merge(&other);
+
+        assert_eq!(ArtLibrary::from_movie(&movie), library);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let movie = create_movie();
+        let mut library = ArtLibrary::from_movie(&movie);
+        // Duplicate the first tile and refer to the duplicate from a new cel
+        library.tiles.push(library.tiles[0].clone());
+        library.cels.push(Cel::new(vec![Sprite::new(
+            TileRef::new(2),
+            PaletteRef::new(0),
+            Point::new(10, 20),
+            false,
+            false,
+        )]));
+        library.animations.push(animation(&[0, 2, 1]));
+
+        library.dedup();
+
+        assert_eq!(movie.tiles(), library.tiles());
+        assert_eq!(3, library.cels().len());
+        assert_eq!(TileRef::new(0), library.cels()[2].sprites()[0].tile());
+        assert_eq!(vec![animation(&[0, 2, 1])], library.animations());
+    }
+
+    #[test]
+    fn test_dedup_flipped() {
+        let mut surface = TileSurface::new(Size::new(3, 2));
+        for (pixel, value) in surface.data_mut().iter_mut().zip([1, 2, 3, 4, 5, 6]) {
+            *pixel = PaletteIndex::new(value);
+        }
+        let tile = Tile::new(surface);
+        let tiles = vec![tile.flipped(true, false), tile.clone()];
+        let sprite = |tile: usize, h_flip: bool| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(0),
+                Point::new(1, 2),
+                h_flip,
+                false,
+            )
+        };
+        let cels = vec![
+            Cel::new(vec![sprite(0, false), sprite(0, true)]),
+            Cel::new(vec![sprite(1, true), sprite(1, false)]),
+        ];
+        let palettes = vec![Palette::new_filled(16, Color::new(1, 2, 3))];
+        let mut library = ArtLibrary::new(palettes, tiles, cels, Vec::new());
+
+        library.dedup();
+
+        // Both orientations are stored as the canonical form, so the cels that look the same become the same
+        assert_eq!(&[tile], library.tiles());
+        assert_eq!(
+            &[Cel::new(vec![sprite(0, true), sprite(0, false)])],
+            library.cels()
+        );
+    }
+
+    #[test]
+    fn test_remove_unreferenced() {
+        let palettes = vec![
+            Palette::new_filled(16, Color::new(1, 2, 3)),
+            Palette::new_filled(16, Color::new(4, 5, 6)),
+        ];
+        let tiles = vec![
+            Tile::new(TileSurface::new(Size::new(8, 8))),
+            Tile::new(TileSurface::new(Size::new(16, 16))),
+            Tile::new(TileSurface::new(Size::new(32, 32))),
+        ];
+        let cels = vec![Cel::new(vec![Sprite::new(
+            TileRef::new(2),
+            PaletteRef::new(1),
+            Point::new(1, 2),
+            true,
+            false,
+        )])];
+        let mut library = ArtLibrary::new(palettes.clone(), tiles.clone(), cels, Vec::new());
+
+        library.remove_unreferenced();
+
+        assert_eq!(&palettes[1..], library.palettes());
+        assert_eq!(&tiles[2..], library.tiles());
+        let sprite = &library.cels()[0].sprites()[0];
+        assert_eq!(TileRef::new(0), sprite.tile());
+        assert_eq!(PaletteRef::new(0), sprite.palette());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let movie = create_movie();
+        let actual = ArtLibrary::from(&movie).to_movie(movie.screen_size(), movie.frame_rate());
+
+        assert_eq!(movie, actual);
+    }
+}
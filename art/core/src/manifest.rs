@@ -0,0 +1,70 @@
+//! Reproducibility manifests.
+//!
+//! A manifest records everything that went into a movie: the input files with their SHA-256 hashes, the version of the extractor
+//! and the post-processing passes. It is written next to the movie (see `movie create --manifest` in `ves-art-snes-cli`) and can be
+//! used to verify which capture produced the movie and to re-create the movie later.
+
+use crate::postprocess::PassConfig;
+
+/// A reproducibility manifest.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifest {
+    /// The name and version of the tool that created the movie.
+    pub tool: String,
+    /// The version of the extractor. Changes to the extraction logic can change the output for the same input files.
+    pub extractor_version: String,
+    /// The format of the input files.
+    pub format: String,
+    /// The post-processing passes, in order. These are in the same format as a pass configuration file, such that they can be fed
+    /// back into the tool as-is.
+    pub passes: Vec<PassConfig>,
+    /// The input files, ordered by path.
+    pub inputs: Vec<HashedFile>,
+    /// The output file.
+    pub output: HashedFile,
+}
+
+/// A file with its SHA-256 hash.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HashedFile {
+    /// The path, as it has been provided to the tool.
+    pub path: String,
+    /// The SHA-256 hash of the contents as a lowercase hexadecimal string.
+    pub sha256: String,
+}
+
+#[cfg(all(test, feature = "serde_support"))]
+mod test_manifest {
+    use super::{HashedFile, Manifest};
+    use crate::postprocess::PassConfig;
+
+    #[test]
+    fn test_deserialize() {
+        let json = r#"{
+            "tool": "ves-art-snes-cli 0.1.0",
+            "extractor_version": "0.1.0",
+            "format": "Mesen-S",
+            "passes": [{ "pass": "dedup" }],
+            "inputs": [{ "path": "frame_1.json", "sha256": "00ff" }],
+            "output": { "path": "movie.bincode", "sha256": "ff00" }
+        }"#;
+        let manifest: Manifest = serde_json::from_str(json).unwrap();
+        assert_eq!(vec![PassConfig::Dedup], manifest.passes);
+        assert_eq!(
+            vec![HashedFile {
+                path: "frame_1.json".to_string(),
+                sha256: "00ff".to_string(),
+            }],
+            manifest.inputs
+        );
+        assert_eq!("movie.bincode", manifest.output.path);
+    }
+}
@@ -0,0 +1,285 @@
+//! A module for determining which pixels of the artwork are opaque.
+//!
+//! The main component is the [`OpacityMask`], which can be created with [`Tile::opacity_mask()`](crate::sprite::Tile::opacity_mask)
+//! for a single tile or with [`Cel::opacity_mask()`](crate::sprite::Cel::opacity_mask) for a composition of sprites.
+
+use crate::geom_art::{ArtworkSpaceUnit, Point, Polygon, Rect, Size};
+
+/// The number of bits in a word of the mask.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A bitmask that specifies for every pixel in an area whether it is opaque.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct OpacityMask {
+    size: Size,
+    bits: Vec<u64>,
+}
+
+impl OpacityMask {
+    /// Creates a new instance in which all pixels are transparent.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The size of the mask.
+    pub fn new(size: Size) -> Self {
+        let len: usize = (size.width * size.height).into();
+        Self {
+            size,
+            bits: vec![0; len.div_ceil(WORD_BITS)],
+        }
+    }
+
+    /// Retrieves the size.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    fn offset(&self, point: Point) -> Option<usize> {
+        if point.x >= self.size.width || point.y >= self.size.height {
+            None
+        } else {
+            Some((point.y * self.size.width + point.x).into())
+        }
+    }
+
+    /// Determines whether a pixel is opaque.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The position of the pixel.
+    ///
+    /// returns: `true` if the pixel is opaque. Pixels outside the mask are always transparent.
+    pub fn is_opaque(&self, point: impl Into<Point>) -> bool {
+        match self.offset(point.into()) {
+            Some(offset) => self.bits[offset / WORD_BITS] & (1 << (offset % WORD_BITS)) != 0,
+            None => false,
+        }
+    }
+
+    /// Marks a pixel as opaque or transparent.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The position of the pixel.
+    /// * `opaque`: Whether the pixel is opaque.
+    ///
+    /// # Panics
+    /// This function panics if the point lies outside the mask.
+    pub fn set_opaque(&mut self, point: impl Into<Point>, opaque: bool) {
+        let point = point.into();
+        let offset = self
+            .offset(point)
+            .unwrap_or_else(|| panic!("Point {:?} lies outside the mask.", point));
+        let bit = 1 << (offset % WORD_BITS);
+        if opaque {
+            self.bits[offset / WORD_BITS] |= bit;
+        } else {
+            self.bits[offset / WORD_BITS] &= !bit;
+        }
+    }
+
+    /// Determines whether all pixels are transparent.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|word| *word == 0)
+    }
+
+    /// Counts the opaque pixels.
+    pub fn opaque_count(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Calculates the smallest rectangle that contains all opaque pixels.
+    ///
+    /// returns: The [`Rect`] or `None` if the mask is empty.
+    pub fn bounding_rect(&self) -> Option<Rect> {
+        self.outline_rects().into_iter().reduce(|a, b| {
+            Rect::new(
+                (a.min_x().min(b.min_x()), a.min_y().min(b.min_y())),
+                (a.max_x().max(b.max_x()), a.max_y().max(b.max_y())),
+            )
+        })
+    }
+
+    /// Decomposes the opaque pixels into rectangles.
+    ///
+    /// The rectangles do not overlap and together cover exactly the opaque pixels, which makes them suitable for tight hitboxes
+    /// and for highlighting the outline of a sprite. Horizontal runs of opaque pixels are merged with identical runs in the rows
+    /// directly above them.
+    ///
+    /// returns: The rectangles, ordered by their bottom edge and then from left to right.
+    pub fn outline_rects(&self) -> Vec<Rect> {
+        let mut done = Vec::new();
+        // The rectangles that may still be extended downwards
+        let mut open: Vec<Rect> = Vec::new();
+
+        for y in 0..self.size.height.raw() {
+            let mut next_open = Vec::with_capacity(open.len());
+            for (min_x, max_x) in self.row_runs(y) {
+                let extendable = open
+                    .iter()
+                    .position(|rect| rect.min_x().raw() == min_x && rect.max_x().raw() == max_x);
+                let rect = match extendable {
+                    Some(index) => {
+                        let rect = open.remove(index);
+                        Rect::new(rect.min, (rect.max_x(), ArtworkSpaceUnit::from(y)))
+                    }
+                    None => Rect::new((min_x, y), (max_x, y)),
+                };
+                next_open.push(rect);
+            }
+            done.append(&mut open);
+            open = next_open;
+        }
+        done.append(&mut open);
+        done
+    }
+
+    /// Finds the horizontal runs of opaque pixels in a row.
+    ///
+    /// returns: The inclusive start and end of every run.
+    fn row_runs(&self, y: u32) -> Vec<(u32, u32)> {
+        let mut runs = Vec::new();
+        let mut run_start = None;
+        for x in 0..self.size.width.raw() {
+            match (run_start, self.is_opaque((x, y))) {
+                (None, true) => run_start = Some(x),
+                (Some(start), false) => {
+                    runs.push((start, x - 1));
+                    run_start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = run_start {
+            runs.push((start, self.size.width.raw() - 1));
+        }
+        runs
+    }
+
+    /// Calculates the convex hull of the opaque pixels.
+    ///
+    /// returns: The [`Polygon`]. This is empty if the mask is empty.
+    pub fn convex_hull(&self) -> Polygon {
+        Polygon::convex_hull_of_mask(self.size, |point| self.is_opaque(point))
+    }
+}
+
+#[cfg(test)]
+mod test_opacity_mask {
+    use super::OpacityMask;
+    use crate::geom_art::{Rect, Size};
+    use crate::sprite::{Cel, PaletteIndex, PaletteRef, Sprite, Tile, TileRef, TileSurface};
+    use crate::surface::Surface as _;
+    use ves_cache::SliceCache;
+
+    /// Creates a mask from a textual representation, where `#` is opaque.
+    fn mask(rows: &[&str]) -> OpacityMask {
+        let size = Size::new(rows[0].len() as u32, rows.len() as u32);
+        let mut mask = OpacityMask::new(size);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, chr) in row.chars().enumerate() {
+                mask.set_opaque((x as u32, y as u32), chr == '#');
+            }
+        }
+        mask
+    }
+
+    #[test]
+    fn test_basics() {
+        let mut mask = mask(&["#..", ".#.", "..#"]);
+        assert!(mask.is_opaque((0, 0)));
+        assert!(!mask.is_opaque((1, 0)));
+        assert!(mask.is_opaque((2, 2)));
+        assert!(!mask.is_opaque((3, 2)));
+        assert_eq!(3, mask.opaque_count());
+        assert!(!mask.is_empty());
+
+        mask.set_opaque((1, 1), false);
+        assert!(!mask.is_opaque((1, 1)));
+        assert_eq!(2, mask.opaque_count());
+
+        assert!(OpacityMask::new(Size::new(16, 16)).is_empty());
+    }
+
+    #[test]
+    fn test_outline_rects() {
+        let mask = mask(&[
+            "..##..", //
+            "..##..", //
+            "######", //
+            "#....#", //
+        ]);
+        let expected: Vec<Rect> = vec![
+            ((2, 0), (3, 1)).into(),
+            ((0, 2), (5, 2)).into(),
+            ((0, 3), (0, 3)).into(),
+            ((5, 3), (5, 3)).into(),
+        ];
+        assert_eq!(expected, mask.outline_rects());
+        assert_eq!(Some(((0, 0), (5, 3)).into()), mask.bounding_rect());
+    }
+
+    #[test]
+    fn test_empty() {
+        let mask = mask(&["....", "...."]);
+        assert!(mask.outline_rects().is_empty());
+        assert_eq!(None, mask.bounding_rect());
+        assert!(mask.convex_hull().is_empty());
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let mask = mask(&[
+            "..#..", //
+            ".###.", //
+            "#####", //
+        ]);
+        let hull = mask.convex_hull();
+        assert!(hull.contains((1, 1)));
+        assert!(!hull.contains((0, 1)));
+        assert!(!hull.contains((4, 0)));
+    }
+
+    #[test]
+    fn test_tile_opacity_mask() {
+        let mut surface = TileSurface::new(Size::new(3, 2));
+        surface.data_mut()[1] = PaletteIndex::new(4);
+        surface.data_mut()[5] = PaletteIndex::new(1);
+
+        assert_eq!(mask(&[".#.", "..#"]), Tile::new(surface).opacity_mask());
+    }
+
+    #[test]
+    fn test_cel_opacity_mask() {
+        let mut surface = TileSurface::new(Size::new(2, 2));
+        surface.data_mut()[0] = PaletteIndex::new(1);
+        let tiles = vec![Tile::new(surface)];
+        let tiles = SliceCache::new(&tiles);
+
+        let sprite = |x: u32, y: u32, h_flip, v_flip| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(0),
+                (x, y).into(),
+                h_flip,
+                v_flip,
+            )
+        };
+        let cel = Cel::new(vec![
+            sprite(0, 0, false, false),
+            sprite(2, 0, true, false),
+            sprite(1, 2, true, true),
+        ]);
+
+        let expected = mask(&[
+            "#..#", //
+            "....", //
+            "....", //
+            "..#.", //
+        ]);
+        assert_eq!(expected, cel.opacity_mask(&tiles));
+    }
+}
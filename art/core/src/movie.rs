@@ -1,4 +1,8 @@
+use crate::geom_art::{Point, Rect};
+use crate::sprite::{pack_tiles, Color, PaletteIndex, PaletteRef, TileRef};
 use crate::{Palette, Size, Sprite, Tile};
+use std::cell::OnceCell;
+use std::collections::BTreeMap;
 
 #[cfg_attr(
     feature = "serde_support",
@@ -20,10 +24,39 @@ impl FrameRate {
     }
 }
 
+/// Descriptive information about a [`Movie`], such that a movie file can still be identified once it has left the capture directory.
+/// All fields are optional.
 #[cfg_attr(
     feature = "serde_support",
     derive(serde::Serialize, serde::Deserialize)
 )]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct MovieMetadata {
+    /// The title, for instance the name of the captured scene.
+    pub title: Option<String>,
+    /// The game from which the movie has been captured.
+    pub game: Option<String>,
+    /// The author of the capture.
+    pub author: Option<String>,
+    /// Free-form notes.
+    pub notes: Option<String>,
+    /// Free-form properties that do not fit the other fields, ordered by key.
+    pub properties: BTreeMap<String, String>,
+}
+
+impl MovieMetadata {
+    /// Determines whether no metadata has been set.
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// A recording of the sprites on the screen, frame by frame.
+///
+/// With the `validate_refs` feature, deserialization fails for movies that do not pass [`Movie::validate_refs()`].
+///
+/// Binary formats (like bincode) use a versioned layout, which still reads movies that have been written before the metadata or the
+/// sprite identities were added.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Movie {
     screen_size: Size,
@@ -31,23 +64,168 @@ pub struct Movie {
     tiles: Vec<Tile>,
     frames: Vec<MovieFrame>,
     frame_rate: FrameRate,
+    metadata: MovieMetadata,
+    usage_index: UsageIndexCache,
+    /// For every frame, the identities of its sprites (see [`Movie::set_sprite_ids()`]).
+    sprite_ids: Option<Vec<Vec<SpriteId>>>,
+}
+
+/// The human-readable form of a [`Movie`]. This is only used for serialization, deserialization goes through [`UncheckedMovie`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Serialize)]
+#[serde(remote = "Movie")]
+struct MovieDef {
+    screen_size: Size,
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+    frames: Vec<MovieFrame>,
+    frame_rate: FrameRate,
+    metadata: MovieMetadata,
+    sprite_ids: Option<Vec<Vec<SpriteId>>>,
+}
+
+#[cfg(feature = "serde_support")]
+impl serde::Serialize for Movie {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            MovieDef::serialize(self, serializer)
+        } else {
+            let fields = (
+                &self.screen_size,
+                &self.palettes,
+                &self.tiles,
+                &self.frames,
+                &self.frame_rate,
+                &self.metadata,
+                &self.sprite_ids,
+            );
+            crate::compat::serialize::<Self, _, _>(&fields, serializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de> serde::Deserialize<'de> for Movie {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error as _;
+
+        if deserializer.is_human_readable() {
+            UncheckedMovie::deserialize(deserializer)?
+                .into_movie()
+                .map_err(D::Error::custom)
+        } else {
+            crate::compat::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for Movie {
+    // The original layout starts with the width of the screen
+    type Marker = u32;
+    const MARKER: u32 = u32::MAX;
+    // Version 2 added the sprite identities
+    const VERSION: u16 = 2;
+    type Fields = (
+        Size,
+        Vec<Palette>,
+        Vec<Tile>,
+        Vec<MovieFrame>,
+        FrameRate,
+        MovieMetadata,
+        Option<Vec<Vec<SpriteId>>>,
+    );
+
+    fn from_fields(
+        (screen_size, palettes, tiles, frames, frame_rate, metadata, sprite_ids): Self::Fields,
+    ) -> Result<Self, String> {
+        UncheckedMovie {
+            screen_size,
+            palettes,
+            tiles,
+            frames,
+            frame_rate,
+            metadata,
+            sprite_ids,
+        }
+        .into_movie()
+    }
+
+    fn from_earlier<'de, A: serde::de::SeqAccess<'de>>(
+        version: u16,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        use serde::de::Error as _;
+
+        if version != 1 {
+            return Err(A::Error::custom(format!(
+                "Unsupported layout version {version}."
+            )));
+        }
+        let (screen_size, palettes, tiles, frames, frame_rate, metadata) =
+            crate::compat::next_legacy(&mut seq)?;
+        UncheckedMovie {
+            screen_size,
+            palettes,
+            tiles,
+            frames,
+            frame_rate,
+            metadata,
+            sprite_ids: None,
+        }
+        .into_movie()
+        .map_err(A::Error::custom)
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        width: u32,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        use crate::compat::next_legacy;
+        use serde::de::Error as _;
+
+        let height: u32 = next_legacy(&mut seq)?;
+        UncheckedMovie {
+            screen_size: Size::new(width, height),
+            palettes: next_legacy(&mut seq)?,
+            tiles: next_legacy(&mut seq)?,
+            frames: next_legacy(&mut seq)?,
+            frame_rate: next_legacy(&mut seq)?,
+            metadata: MovieMetadata::default(),
+            sprite_ids: None,
+        }
+        .into_movie()
+        .map_err(A::Error::custom)
+    }
 }
 
 impl Movie {
     /// Creates a new instance.
+    ///
+    /// The pixel data of the tiles is moved into a single arena (see [`pack_tiles()`]).
     pub fn new(
         screen_size: Size,
         palettes: Vec<Palette>,
-        tiles: Vec<Tile>,
+        mut tiles: Vec<Tile>,
         frames: Vec<MovieFrame>,
         frame_rate: FrameRate,
     ) -> Self {
+        pack_tiles(&mut tiles);
         Self {
             screen_size,
             palettes,
             tiles,
             frames,
             frame_rate,
+            metadata: MovieMetadata::default(),
+            usage_index: Default::default(),
+            sprite_ids: None,
         }
     }
 
@@ -75,16 +253,905 @@ impl Movie {
     pub fn frame_rate(&self) -> FrameRate {
         self.frame_rate
     }
+
+    /// Retrieves the metadata.
+    pub fn metadata(&self) -> &MovieMetadata {
+        &self.metadata
+    }
+
+    /// Retrieves the metadata for editing.
+    pub fn metadata_mut(&mut self) -> &mut MovieMetadata {
+        &mut self.metadata
+    }
+
+    /// Retrieves the palettes as they are in effect in a frame, which means that the [`PaletteMutation`]s of that frame and all frames
+    /// before it have been applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_idx`: The position of the frame in the movie.
+    ///
+    /// returns: The palettes, in the same order as [`Movie::palettes()`].
+    pub fn palettes_at(&self, frame_idx: usize) -> Result<Vec<Palette>, String> {
+        let frames = self.frames.get(..=frame_idx).ok_or_else(|| {
+            format!(
+                "Frame index {} is out of range (the movie contains {} frames).",
+                frame_idx,
+                self.frames.len()
+            )
+        })?;
+
+        let mut palettes = self.palettes.clone();
+        for frame in frames {
+            frame.apply_palette_mutations(&mut palettes)?;
+        }
+        Ok(palettes)
+    }
+
+    /// Replaces the [`PaletteMutation`]s of a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_idx`: The position of the frame in the movie.
+    /// * `mutations`: The new mutations.
+    ///
+    /// returns: The previous mutations. Passing these to this function again undoes the replacement.
+    pub fn set_palette_mutations(
+        &mut self,
+        frame_idx: usize,
+        mutations: Vec<PaletteMutation>,
+    ) -> Result<Vec<PaletteMutation>, String> {
+        if frame_idx >= self.frames.len() {
+            return Err(format!(
+                "Frame index {} is out of range (the movie contains {} frames).",
+                frame_idx,
+                self.frames.len()
+            ));
+        }
+        for mutation in &mutations {
+            let valid = self
+                .palettes
+                .get(mutation.palette().value())
+                .is_some_and(|palette| usize::from(mutation.index().value()) < palette.len());
+            if !valid {
+                return Err(format!(
+                    "The mutation {:?} refers to an entry that does not exist in the movie.",
+                    mutation
+                ));
+            }
+        }
+
+        Ok(std::mem::replace(
+            &mut self.frames[frame_idx].palette_mutations,
+            mutations,
+        ))
+    }
+
+    /// Checks that all palette and tile references in the frames resolve.
+    ///
+    /// returns: `Ok` if all references resolve, otherwise `Err` with a description of the first reference that does not.
+    pub fn validate_refs(&self) -> Result<(), String> {
+        for frame in &self.frames {
+            for (i, mutation) in frame.palette_mutations().iter().enumerate() {
+                let palette = self.palettes.get(mutation.palette().value()).ok_or_else(|| {
+                    format!(
+                        "Palette mutation {} in frame {} refers to {}, but the movie only contains {} palettes.",
+                        i,
+                        frame.frame_number(),
+                        mutation.palette(),
+                        self.palettes.len()
+                    )
+                })?;
+                if usize::from(mutation.index().value()) >= palette.len() {
+                    return Err(format!(
+                        "Palette mutation {} in frame {} refers to entry {} of {}, but the palette only contains {} entries.",
+                        i,
+                        frame.frame_number(),
+                        mutation.index().value(),
+                        mutation.palette(),
+                        palette.len()
+                    ));
+                }
+            }
+            for (i, sprite) in frame.sprites().iter().enumerate() {
+                if sprite.palette().value() >= self.palettes.len() {
+                    return Err(format!(
+                        "Sprite {} in frame {} refers to {}, but the movie only contains {} palettes.",
+                        i,
+                        frame.frame_number(),
+                        sprite.palette(),
+                        self.palettes.len()
+                    ));
+                }
+                if sprite.tile().value() >= self.tiles.len() {
+                    return Err(format!(
+                        "Sprite {} in frame {} refers to {}, but the movie only contains {} tiles.",
+                        i,
+                        frame.frame_number(),
+                        sprite.tile(),
+                        self.tiles.len()
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counts the sprites that use a palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The palette.
+    ///
+    /// returns: The number of sprites and the number of frames that contain at least one of those sprites.
+    pub fn palette_usage(&self, palette: PaletteRef) -> (usize, usize) {
+        self.frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .sprites()
+                    .iter()
+                    .filter(|sprite| sprite.palette() == palette)
+                    .count()
+            })
+            .filter(|count| *count > 0)
+            .fold((0, 0), |(sprites, frames), count| {
+                (sprites + count, frames + 1)
+            })
+    }
+
+    /// Retrieves the reverse index from the palettes and tiles to the frames that use them.
+    ///
+    /// The index is built on the first call and cached until the movie is modified.
+    pub fn build_usage_index(&self) -> &UsageIndex {
+        self.usage_index.0.get_or_init(|| UsageIndex::build(self))
+    }
+
+    /// Retrieves the identities of the sprites in a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_idx`: The position of the frame in the movie.
+    ///
+    /// returns: The identities, in the order of the sprites, or `None` if the movie has not been tracked or the frame does not exist.
+    pub fn sprite_ids(&self, frame_idx: usize) -> Option<&[SpriteId]> {
+        self.sprite_ids
+            .as_ref()
+            .and_then(|ids| ids.get(frame_idx))
+            .map(Vec::as_slice)
+    }
+
+    /// Retrieves the identity of a sprite.
+    ///
+    /// # Arguments
+    ///
+    /// * `location`: The location of the sprite.
+    ///
+    /// returns: The identity or `None` if the movie has not been tracked or the sprite does not exist.
+    pub fn sprite_id(&self, location: SpriteLocation) -> Option<SpriteId> {
+        self.sprite_ids(location.frame)
+            .and_then(|ids| ids.get(location.sprite))
+            .copied()
+    }
+
+    /// Sets the identities of the sprites, usually as determined by [`track_sprites()`](crate::analysis::track_sprites).
+    ///
+    /// The identities are serialized along with the movie. They follow the sprites when these are replaced, reassigned to another
+    /// palette or removed (also by [`Movie::crop()`]), and when frames are removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `ids`: For every frame, the identities of its sprites (in the order of the sprites).
+    pub fn set_sprite_ids(&mut self, ids: Vec<Vec<SpriteId>>) -> Result<(), String> {
+        if ids.len() != self.frames.len() {
+            return Err(format!(
+                "Expected identities for {} frames, but got {}.",
+                self.frames.len(),
+                ids.len()
+            ));
+        }
+        for (frame_idx, (frame, ids)) in self.frames.iter().zip(&ids).enumerate() {
+            if ids.len() != frame.sprites.len() {
+                return Err(format!(
+                    "Frame {} contains {} sprites, but got {} identities.",
+                    frame_idx,
+                    frame.sprites.len(),
+                    ids.len()
+                ));
+            }
+        }
+        self.sprite_ids = Some(ids);
+        Ok(())
+    }
+
+    /// Finds the sprites with an identity, across all frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `id`: The identity.
+    ///
+    /// returns: The locations of the sprites, in the order of the frames. This is empty if the movie has not been tracked.
+    pub fn find_sprites_by_id(&self, id: SpriteId) -> Vec<SpriteLocation> {
+        let mut locations = Vec::new();
+        for (frame_idx, ids) in self.sprite_ids.iter().flatten().enumerate() {
+            for (sprite_idx, _) in ids.iter().enumerate().filter(|(_, other)| **other == id) {
+                locations.push(SpriteLocation {
+                    frame: frame_idx,
+                    sprite: sprite_idx,
+                });
+            }
+        }
+        locations
+    }
+
+    /// Reassigns all sprites that use a palette to another palette, across all frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: The palette that is currently used.
+    /// * `to`: The palette that should be used instead.
+    ///
+    /// returns: The number of sprites that have been reassigned.
+    pub fn reassign_palette(&mut self, from: PaletteRef, to: PaletteRef) -> Result<usize, String> {
+        if to.value() >= self.palettes.len() {
+            return Err(format!(
+                "Can not reassign to {}, since the movie only contains {} palettes.",
+                to,
+                self.palettes.len()
+            ));
+        }
+
+        self.usage_index = Default::default();
+        let mut count = 0;
+        for frame in &mut self.frames {
+            for sprite in frame
+                .sprites
+                .iter_mut()
+                .filter(|sprite| sprite.palette() == from)
+            {
+                sprite.set_palette(to);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Finds the sprites that show the same artwork as a sprite (the same tile, palette and flip flags), across all frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `sprite`: The sprite. Its position is not considered.
+    ///
+    /// returns: The locations of the sprites, in the order of the frames.
+    pub fn find_similar_sprites(&self, sprite: &Sprite) -> Vec<SpriteLocation> {
+        let mut locations = Vec::new();
+        for (frame_idx, frame) in self.frames.iter().enumerate() {
+            for (sprite_idx, other) in frame.sprites.iter().enumerate() {
+                if other.tile() == sprite.tile()
+                    && other.palette() == sprite.palette()
+                    && other.h_flip() == sprite.h_flip()
+                    && other.v_flip() == sprite.v_flip()
+                {
+                    locations.push(SpriteLocation {
+                        frame: frame_idx,
+                        sprite: sprite_idx,
+                    });
+                }
+            }
+        }
+        locations
+    }
+
+    /// Replaces sprites. Either all sprites are replaced or, if any of the replacements is invalid, none are.
+    ///
+    /// # Arguments
+    ///
+    /// * `replacements`: The locations of the sprites and the sprites that should replace them.
+    ///
+    /// returns: The previous sprites at the same locations. Passing these to this function again undoes the replacement.
+    pub fn replace_sprites(
+        &mut self,
+        replacements: Vec<(SpriteLocation, Sprite)>,
+    ) -> Result<Vec<(SpriteLocation, Sprite)>, String> {
+        for (location, sprite) in &replacements {
+            let exists = self
+                .frames
+                .get(location.frame)
+                .is_some_and(|frame| location.sprite < frame.sprites.len());
+            if !exists {
+                return Err(format!("There is no sprite at {:?}.", location));
+            }
+            if sprite.palette().value() >= self.palettes.len()
+                || sprite.tile().value() >= self.tiles.len()
+            {
+                return Err(format!(
+                    "The sprite for {:?} refers to {} and {}, but the movie only contains {} palettes and {} tiles.",
+                    location,
+                    sprite.palette(),
+                    sprite.tile(),
+                    self.palettes.len(),
+                    self.tiles.len()
+                ));
+            }
+        }
+
+        self.usage_index = Default::default();
+        Ok(replacements
+            .into_iter()
+            .map(|(location, sprite)| {
+                let target = &mut self.frames[location.frame].sprites[location.sprite];
+                (location, std::mem::replace(target, sprite))
+            })
+            .collect())
+    }
+
+    /// Removes sprites from all frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `predicate`: A function that determines whether a sprite should be removed.
+    ///
+    /// returns: The number of sprites that have been removed.
+    pub fn remove_sprites(&mut self, predicate: impl Fn(&Sprite) -> bool) -> usize {
+        self.usage_index = Default::default();
+        let mut count = 0;
+        for (frame_idx, frame) in self.frames.iter_mut().enumerate() {
+            let keep: Vec<bool> = frame
+                .sprites
+                .iter()
+                .map(|sprite| !predicate(sprite))
+                .collect();
+            let len = frame.sprites.len();
+            retain_by_mask(&mut frame.sprites, &keep);
+            if let Some(ids) = &mut self.sprite_ids {
+                retain_by_mask(&mut ids[frame_idx], &keep);
+            }
+            count += len - frame.sprites.len();
+        }
+        count
+    }
+
+    /// Crops the movie to a region of the screen.
+    ///
+    /// Sprites that lie entirely outside of the region are removed and the positions of the remaining sprites are rebased onto the
+    /// top-left corner of the region, which becomes the new screen. Since positions can not be negative, sprites that cross the left or
+    /// top edge of the region are removed as well. Sprites that wrap around the edge of the screen are treated the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `rect`: The region. This must lie within the screen.
+    ///
+    /// returns: The number of sprites that have been removed.
+    pub fn crop(&mut self, rect: Rect) -> Result<usize, String> {
+        let screen = self.screen_size;
+        if rect.max_x() >= screen.width || rect.max_y() >= screen.height {
+            return Err(format!(
+                "The region {:?} does not lie within the screen of {:?}.",
+                rect, screen
+            ));
+        }
+
+        // The cropped sprites are collected first, such that the movie remains unchanged when a sprite turns out to be invalid
+        let tiles = &self.tiles;
+        let new_size = rect.size();
+        let mut count = 0;
+        let mut cropped = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let mut sprites = Vec::with_capacity(frame.sprites.len());
+            let mut keep = Vec::with_capacity(frame.sprites.len());
+            for sprite in &frame.sprites {
+                if tiles.get(sprite.tile().value()).is_none() {
+                    return Err(format!("Unknown tile: {}.", sprite.tile()));
+                }
+                let position = sprite.position();
+                let x = crop_axis(
+                    position.x.raw(),
+                    rect.min_x().raw(),
+                    new_size.width.raw(),
+                    screen.width.raw(),
+                );
+                let y = crop_axis(
+                    position.y.raw(),
+                    rect.min_y().raw(),
+                    new_size.height.raw(),
+                    screen.height.raw(),
+                );
+                match (x, y) {
+                    (Some(x), Some(y)) => {
+                        let mut sprite = sprite.clone();
+                        sprite.set_position(Point::new(x, y));
+                        sprites.push(sprite);
+                        keep.push(true);
+                    }
+                    _ => {
+                        count += 1;
+                        keep.push(false);
+                    }
+                }
+            }
+            cropped.push((sprites, keep));
+        }
+
+        self.usage_index = Default::default();
+        for (frame_idx, (sprites, keep)) in cropped.into_iter().enumerate() {
+            self.frames[frame_idx].sprites = sprites;
+            if let Some(ids) = &mut self.sprite_ids {
+                retain_by_mask(&mut ids[frame_idx], &keep);
+            }
+        }
+        self.screen_size = new_size;
+        Ok(count)
+    }
+
+    /// Removes the frames that are identical to the frame before them: frames with the same sprites and without palette mutations.
+    /// Such frames occur when a game does not update the screen on every frame (like during lag frames).
+    ///
+    /// returns: The number of frames that have been removed.
+    pub fn dedup_frames(&mut self) -> usize {
+        self.usage_index = Default::default();
+        // A removed frame has the same sprites as the frame before it, which is therefore equal to the last frame that is kept
+        let keep: Vec<bool> = self
+            .frames
+            .iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                i == 0
+                    || !frame.palette_mutations.is_empty()
+                    || frame.sprites != self.frames[i - 1].sprites
+            })
+            .collect();
+        let len = self.frames.len();
+        retain_by_mask(&mut self.frames, &keep);
+        if let Some(ids) = &mut self.sprite_ids {
+            retain_by_mask(ids, &keep);
+        }
+        len - self.frames.len()
+    }
+
+    /// Scales the movie up by an integer factor.
+    ///
+    /// Every pixel of a tile becomes a square of `factor` by `factor` pixels, and the positions of the sprites and the screen size are
+    /// scaled accordingly (see also [`Tile::scaled()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `factor`: The factor. This must be greater than zero.
+    pub fn scale(&mut self, factor: u32) -> Result<(), String> {
+        let scale = |value: u32| {
+            value
+                .checked_mul(factor)
+                .filter(|_| factor > 0)
+                .ok_or_else(|| format!("Invalid scale factor: {}.", factor))
+        };
+        // Everything is checked before anything is changed, such that the movie remains unchanged on overflow
+        let screen_size = Size::new(
+            scale(self.screen_size.width.raw())?,
+            scale(self.screen_size.height.raw())?,
+        );
+        for sprite in self.frames.iter().flat_map(|frame| &frame.sprites) {
+            let position = sprite.position();
+            scale(position.x.raw())?;
+            scale(position.y.raw())?;
+        }
+
+        // The usage index and the sprite identities remain valid, since no sprites are added or removed
+        self.screen_size = screen_size;
+        let mut tiles: Vec<Tile> = self.tiles.iter().map(|tile| tile.scaled(factor)).collect();
+        pack_tiles(&mut tiles);
+        self.tiles = tiles;
+        for sprite in self.frames.iter_mut().flat_map(|frame| &mut frame.sprites) {
+            let position = sprite.position();
+            sprite.set_position(Point::new(
+                position.x.raw() * factor,
+                position.y.raw() * factor,
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A reverse index from the palettes and tiles of a [`Movie`] to the frames that use them (see [`Movie::build_usage_index()`]).
+///
+/// Frames are identified by their position in [`Movie::frames()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct UsageIndex {
+    /// For every palette, the positions of the frames that use it (in ascending order).
+    palettes: Vec<Vec<usize>>,
+    /// For every tile, the positions of the frames that use it (in ascending order).
+    tiles: Vec<Vec<usize>>,
+}
+
+impl UsageIndex {
+    /// Builds the index for a movie. References that do not resolve are ignored.
+    fn build(movie: &Movie) -> Self {
+        fn push(frames: Option<&mut Vec<usize>>, position: usize) {
+            if let Some(frames) = frames {
+                if frames.last() != Some(&position) {
+                    frames.push(position);
+                }
+            }
+        }
+
+        let mut palettes = vec![Vec::new(); movie.palettes.len()];
+        let mut tiles = vec![Vec::new(); movie.tiles.len()];
+        for (position, frame) in movie.frames.iter().enumerate() {
+            for sprite in frame.sprites() {
+                push(palettes.get_mut(sprite.palette().value()), position);
+                push(tiles.get_mut(sprite.tile().value()), position);
+            }
+        }
+        Self { palettes, tiles }
+    }
+
+    /// Retrieves the positions of the frames that use a palette (in ascending order).
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The palette.
+    ///
+    /// returns: The positions. This is empty if the palette is not used or does not exist.
+    pub fn palette_frames(&self, palette: PaletteRef) -> &[usize] {
+        self.palettes
+            .get(palette.value())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Retrieves the positions of the frames that use a tile (in ascending order).
+    ///
+    /// # Arguments
+    ///
+    /// * `tile`: The tile.
+    ///
+    /// returns: The positions. This is empty if the tile is not used or does not exist.
+    pub fn tile_frames(&self, tile: TileRef) -> &[usize] {
+        self.tiles
+            .get(tile.value())
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Creates an iterator over the palettes that are not used in any frame.
+    pub fn unreferenced_palettes(&self) -> impl Iterator<Item = PaletteRef> + '_ {
+        unreferenced(&self.palettes).map(PaletteRef::new)
+    }
+
+    /// Creates an iterator over the tiles that are not used in any frame.
+    pub fn unreferenced_tiles(&self) -> impl Iterator<Item = TileRef> + '_ {
+        unreferenced(&self.tiles).map(TileRef::new)
+    }
+}
+
+/// Creates an iterator over the indices of the entries that have no frames.
+fn unreferenced(frames: &[Vec<usize>]) -> impl Iterator<Item = usize> + '_ {
+    frames
+        .iter()
+        .enumerate()
+        .filter(|(_, frames)| frames.is_empty())
+        .map(|(index, _)| index)
+}
+
+/// The cache for the [`UsageIndex`] of a [`Movie`].
+///
+/// The cache does not take part in comparisons, since it is derived from the other fields.
+#[derive(Clone, Debug, Default)]
+struct UsageIndexCache(OnceCell<UsageIndex>);
+
+impl PartialEq for UsageIndexCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for UsageIndexCache {}
+
+/// Retains the values for which a mask is `true`.
+///
+/// # Arguments
+///
+/// * `values`: The values.
+/// * `keep`: For every value, whether it should be retained. Values beyond the end of the mask are retained.
+fn retain_by_mask<T>(values: &mut Vec<T>, keep: &[bool]) {
+    let mut keep = keep.iter();
+    // The values are visited in order, exactly once
+    values.retain(|_| keep.next().copied().unwrap_or(true));
+}
+
+/// Crops a sprite along a single axis (see [`Movie::crop()`]).
+///
+/// # Arguments
+///
+/// * `pos`: The position of the sprite.
+/// * `region_start`: The start of the region.
+/// * `region_len`: The length of the region.
+/// * `screen_len`: The length of the screen. Sprites wrap around at the edge of the screen.
+///
+/// returns: The position relative to the region or `None` if the sprite does not start inside of the region.
+fn crop_axis(pos: u32, region_start: u32, region_len: u32, screen_len: u32) -> Option<u32> {
+    let pos = pos % screen_len;
+    (region_start..region_start + region_len)
+        .contains(&pos)
+        .then(|| pos - region_start)
+}
+
+/// The serialized form of a [`Movie`], before the references have been validated.
+#[cfg(feature = "serde_support")]
+#[derive(serde::Deserialize)]
+struct UncheckedMovie {
+    screen_size: Size,
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+    frames: Vec<MovieFrame>,
+    frame_rate: FrameRate,
+    #[serde(default)]
+    metadata: MovieMetadata,
+    #[serde(default)]
+    sprite_ids: Option<Vec<Vec<SpriteId>>>,
+}
+
+#[cfg(feature = "serde_support")]
+impl UncheckedMovie {
+    /// Creates the [`Movie`].
+    ///
+    /// With the `validate_refs` feature, this fails for movies that do not pass [`Movie::validate_refs()`].
+    fn into_movie(self) -> Result<Movie, String> {
+        let mut movie = Movie::new(
+            self.screen_size,
+            self.palettes,
+            self.tiles,
+            self.frames,
+            self.frame_rate,
+        );
+        movie.metadata = self.metadata;
+        if let Some(ids) = self.sprite_ids {
+            movie.set_sprite_ids(ids)?;
+        }
+        #[cfg(feature = "validate_refs")]
+        movie.validate_refs()?;
+        Ok(movie)
+    }
+}
+
+/// The screen size, palettes and tiles of a [`Movie`], without the frames.
+///
+/// This type deserializes from a serialized [`Movie`]. Since the frames come last, deserialization with a sequential format like
+/// bincode stops before the frames, which saves reading and allocating the bulk of the data when only the graphics are needed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MovieAssets {
+    // NB: The fields must be in the same order as in Movie.
+    screen_size: Size,
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+}
+
+/// The human-readable form of [`MovieAssets`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Deserialize)]
+#[serde(remote = "MovieAssets")]
+struct MovieAssetsDef {
+    screen_size: Size,
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+}
+
+#[cfg(feature = "serde_support")]
+impl<'de> serde::Deserialize<'de> for MovieAssets {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            MovieAssetsDef::deserialize(deserializer)
+        } else {
+            crate::compat::deserialize(deserializer)
+        }
+    }
+}
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for MovieAssets {
+    // This reads the leading fields of the layout of a movie
+    type Marker = <Movie as crate::compat::BinaryLayout>::Marker;
+    const MARKER: Self::Marker = <Movie as crate::compat::BinaryLayout>::MARKER;
+    const VERSION: u16 = <Movie as crate::compat::BinaryLayout>::VERSION;
+    type Fields = (Size, Vec<Palette>, Vec<Tile>);
+
+    fn from_fields((screen_size, palettes, tiles): Self::Fields) -> Result<Self, String> {
+        Ok(Self {
+            screen_size,
+            palettes,
+            tiles,
+        })
+    }
+
+    fn from_earlier<'de, A: serde::de::SeqAccess<'de>>(
+        _version: u16,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        use serde::de::Error as _;
+
+        // Every version of the layout of a movie starts with these fields
+        let fields = crate::compat::next_legacy(&mut seq)?;
+        Self::from_fields(fields).map_err(A::Error::custom)
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        width: u32,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        use crate::compat::next_legacy;
+
+        let height: u32 = next_legacy(&mut seq)?;
+        Ok(Self {
+            screen_size: Size::new(width, height),
+            palettes: next_legacy(&mut seq)?,
+            tiles: next_legacy(&mut seq)?,
+        })
+    }
 }
 
+impl MovieAssets {
+    /// Retrieves the screen size.
+    pub fn screen_size(&self) -> Size {
+        self.screen_size
+    }
+
+    /// Retrieves the palettes.
+    pub fn palettes(&self) -> &[Palette] {
+        &self.palettes
+    }
+
+    /// Retrieves the tiles.
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+}
+
+impl From<Movie> for MovieAssets {
+    fn from(movie: Movie) -> Self {
+        Self {
+            screen_size: movie.screen_size,
+            palettes: movie.palettes,
+            tiles: movie.tiles,
+        }
+    }
+}
+
+/// The identity of a sprite across the frames of a [`Movie`] (see [`Movie::set_sprite_ids()`]).
+///
+/// Sprites in different frames with the same identity show the same object, for instance a character while it moves across the
+/// screen.
 #[cfg_attr(
     feature = "serde_support",
     derive(serde::Serialize, serde::Deserialize)
 )]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct SpriteId(usize);
+
+impl SpriteId {
+    /// Creates a new instance.
+    pub fn new(value: usize) -> Self {
+        Self(value)
+    }
+
+    /// Retrieves the value.
+    pub fn value(&self) -> usize {
+        self.0
+    }
+}
+
+/// The location of a sprite in a [`Movie`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SpriteLocation {
+    /// The position of the frame in the movie.
+    pub frame: usize,
+    /// The index of the sprite in the frame.
+    pub sprite: usize,
+}
+
+/// A change to a single entry of a [`Palette`].
+///
+/// Games often animate a palette by changing a single color (for instance to make something blink or flash). Rather than adding a new
+/// palette for every variation, a [`MovieFrame`] can record such changes. A mutation takes effect at the start of its frame (before the
+/// sprites are drawn) and remains in effect for all following frames, until another mutation changes the same entry.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PaletteMutation {
+    palette: PaletteRef,
+    index: PaletteIndex,
+    color: Color,
+}
+
+impl PaletteMutation {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The palette.
+    /// * `index`: The index of the entry in the palette.
+    /// * `color`: The new color of the entry.
+    pub fn new(palette: PaletteRef, index: PaletteIndex, color: Color) -> Self {
+        Self {
+            palette,
+            index,
+            color,
+        }
+    }
+
+    /// Retrieves the palette.
+    pub fn palette(&self) -> PaletteRef {
+        self.palette
+    }
+
+    /// Retrieves the index of the entry in the palette.
+    pub fn index(&self) -> PaletteIndex {
+        self.index
+    }
+
+    /// Retrieves the new color of the entry.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+}
+
+/// A frame of a [`Movie`].
+///
+/// Binary formats (like bincode) use a versioned layout, which still reads frames that have been written without palette mutations.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct MovieFrame {
     frame_number: u64,
     sprites: Vec<Sprite>,
+    palette_mutations: Vec<PaletteMutation>,
+}
+
+/// The human-readable form of a [`MovieFrame`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "MovieFrame")]
+struct MovieFrameDef {
+    frame_number: u64,
+    sprites: Vec<Sprite>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    palette_mutations: Vec<PaletteMutation>,
+}
+
+#[cfg(feature = "serde_support")]
+crate::compat::impl_serde!(MovieFrame, MovieFrameDef, |frame| (
+    &frame.frame_number,
+    &frame.sprites,
+    &frame.palette_mutations
+));
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for MovieFrame {
+    // The original layout starts with the frame number
+    type Marker = u64;
+    const MARKER: u64 = u64::MAX;
+    const VERSION: u16 = 1;
+    type Fields = (u64, Vec<Sprite>, Vec<PaletteMutation>);
+
+    fn from_fields(
+        (frame_number, sprites, palette_mutations): Self::Fields,
+    ) -> Result<Self, String> {
+        Ok(Self::with_palette_mutations(
+            frame_number,
+            sprites,
+            palette_mutations,
+        ))
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        frame_number: u64,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        Ok(Self::new(
+            frame_number,
+            crate::compat::next_legacy(&mut seq)?,
+        ))
+    }
 }
 
 impl MovieFrame {
@@ -93,6 +1160,20 @@ impl MovieFrame {
         Self {
             frame_number,
             sprites,
+            palette_mutations: Vec::new(),
+        }
+    }
+
+    /// Creates a new instance with [`PaletteMutation`]s.
+    pub fn with_palette_mutations(
+        frame_number: u64,
+        sprites: Vec<Sprite>,
+        palette_mutations: Vec<PaletteMutation>,
+    ) -> Self {
+        Self {
+            frame_number,
+            sprites,
+            palette_mutations,
         }
     }
 
@@ -105,4 +1186,951 @@ impl MovieFrame {
     pub fn sprites(&self) -> &[Sprite] {
         &self.sprites
     }
+
+    /// Retrieves the palette mutations.
+    pub fn palette_mutations(&self) -> &[PaletteMutation] {
+        &self.palette_mutations
+    }
+
+    /// Applies the [`PaletteMutation`]s of this frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `palettes`: The palettes as they are in effect in the previous frame.
+    pub fn apply_palette_mutations(&self, palettes: &mut [Palette]) -> Result<(), String> {
+        for mutation in &self.palette_mutations {
+            let entry = palettes
+                .get_mut(mutation.palette.value())
+                .filter(|palette| usize::from(mutation.index.value()) < palette.len())
+                .map(|palette| &mut palette[mutation.index])
+                .ok_or_else(|| {
+                    format!(
+                        "The mutation {:?} in frame {} refers to an entry that does not exist.",
+                        mutation, self.frame_number
+                    )
+                })?;
+            *entry = mutation.color;
+        }
+        Ok(())
+    }
+
+    /// Enforces a [`FrameCapacity`] on this frame.
+    ///
+    /// The parts of a split frame get consecutive frame numbers, starting with the number of this frame. The frames after it have to
+    /// be renumbered accordingly, which [`FrameCapacity::apply()`] takes care of.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: The [`FrameCapacity`].
+    ///
+    /// returns: The resulting frames (more than one if the frame has been split) and a warning if sprites have been dropped, or `Err`
+    ///          if the frame exceeds the capacity and the policy is [`OverflowPolicy::Error`].
+    pub fn apply_capacity(
+        mut self,
+        capacity: &FrameCapacity,
+    ) -> Result<(Vec<MovieFrame>, Option<CapacityWarning>), String> {
+        let max_sprites = capacity.max_sprites();
+        if self.sprites.len() <= max_sprites {
+            return Ok((vec![self], None));
+        }
+
+        match capacity.overflow() {
+            OverflowPolicy::Error => Err(format!(
+                "Frame {} contains {} sprites, but at most {} are allowed.",
+                self.frame_number,
+                self.sprites.len(),
+                max_sprites
+            )),
+            OverflowPolicy::Truncate => {
+                let warning = CapacityWarning {
+                    frame_number: self.frame_number,
+                    sprite_count: self.sprites.len(),
+                    dropped: self.sprites.len() - max_sprites,
+                };
+                self.sprites.truncate(max_sprites);
+                Ok((vec![self], Some(warning)))
+            }
+            OverflowPolicy::Split => {
+                let mut frames: Vec<_> = self
+                    .sprites
+                    .chunks(max_sprites)
+                    .zip(self.frame_number..)
+                    .map(|(sprites, frame_number)| MovieFrame::new(frame_number, sprites.to_vec()))
+                    .collect();
+                // The mutations only need to be applied once, at the start of the first part
+                frames[0].palette_mutations = self.palette_mutations;
+                Ok((frames, None))
+            }
+        }
+    }
+}
+
+/// The way in which [`MovieFrame::apply_capacity()`] handles frames with too many sprites.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum OverflowPolicy {
+    /// The frame is rejected.
+    #[default]
+    Error,
+    /// The sprites with the lowest priority (at the end of the frame) are dropped, which is reported with a [`CapacityWarning`].
+    Truncate,
+    /// The frame is split into consecutive frames with consecutive frame numbers. The first frame contains the sprites with the
+    /// highest priority.
+    Split,
+}
+
+/// A frame from which sprites have been dropped by [`MovieFrame::apply_capacity()`] (see [`OverflowPolicy::Truncate`]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct CapacityWarning {
+    /// The number of the frame.
+    pub frame_number: u64,
+    /// The number of sprites in the frame before any were dropped.
+    pub sprite_count: usize,
+    /// The number of sprites that have been dropped.
+    pub dropped: usize,
+}
+
+impl std::fmt::Display for CapacityWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Dropped {} of the {} sprites of frame {}.",
+            self.dropped, self.sprite_count, self.frame_number
+        )
+    }
+}
+
+/// The maximum number of sprites in a [`MovieFrame`] and the [`OverflowPolicy`] for frames that exceed it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FrameCapacity {
+    max_sprites: usize,
+    overflow: OverflowPolicy,
+}
+
+impl FrameCapacity {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_sprites`: The maximum number of sprites per frame. This must not be zero.
+    /// * `overflow`: The [`OverflowPolicy`].
+    pub fn new(max_sprites: usize, overflow: OverflowPolicy) -> Result<Self, String> {
+        if max_sprites == 0 {
+            return Err("The maximum number of sprites per frame must not be zero.".to_string());
+        }
+        Ok(Self {
+            max_sprites,
+            overflow,
+        })
+    }
+
+    /// Retrieves the maximum number of sprites per frame.
+    pub fn max_sprites(&self) -> usize {
+        self.max_sprites
+    }
+
+    /// Retrieves the [`OverflowPolicy`].
+    pub fn overflow(&self) -> OverflowPolicy {
+        self.overflow
+    }
+
+    /// Enforces this capacity on consecutive frames (see [`MovieFrame::apply_capacity()`]).
+    ///
+    /// Every part that is added by splitting a frame moves all later frames back by one frame number, such that the frame numbers
+    /// remain distinct.
+    ///
+    /// # Arguments
+    ///
+    /// * `frames`: The frames, in order.
+    ///
+    /// returns: The resulting frames and a warning for every frame from which sprites have been dropped, or `Err` if a frame exceeds
+    ///          the capacity and the policy is [`OverflowPolicy::Error`].
+    pub fn apply(
+        &self,
+        frames: Vec<MovieFrame>,
+    ) -> Result<(Vec<MovieFrame>, Vec<CapacityWarning>), String> {
+        let mut result = Vec::with_capacity(frames.len());
+        let mut warnings = Vec::new();
+        let mut shift = 0;
+        for mut frame in frames {
+            frame.frame_number += shift;
+            let (parts, warning) = frame.apply_capacity(self)?;
+            shift += parts.len() as u64 - 1;
+            result.extend(parts);
+            warnings.extend(warning);
+        }
+        Ok((result, warnings))
+    }
+}
+
+#[cfg(test)]
+mod test_movie {
+    use super::{FrameRate, Movie, MovieFrame, PaletteMutation, SpriteId, SpriteLocation};
+    use crate::geom_art::{Point, Rect, Size};
+    use crate::sprite::{
+        Color, Palette, PaletteIndex, PaletteRef, Sprite, Tile, TileRef, TileSurface,
+    };
+    use crate::surface::Surface as _;
+
+    fn movie(tile: usize, palette: usize) -> Movie {
+        let sprite = Sprite::new(
+            TileRef::new(tile),
+            PaletteRef::new(palette),
+            (0, 0).into(),
+            false,
+            false,
+        );
+        Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8)))],
+            vec![MovieFrame::new(7, vec![sprite])],
+            FrameRate::Ntsc,
+        )
+    }
+
+    #[test]
+    fn test_validate_refs() {
+        assert_eq!(Ok(()), movie(0, 0).validate_refs());
+        assert_eq!(
+            Err(
+                "Sprite 0 in frame 7 refers to tile #1, but the movie only contains 1 tiles."
+                    .to_string()
+            ),
+            movie(1, 0).validate_refs()
+        );
+        assert_eq!(
+            Err(
+                "Sprite 0 in frame 7 refers to palette #2, but the movie only contains 1 palettes."
+                    .to_string()
+            ),
+            movie(0, 2).validate_refs()
+        );
+    }
+
+    #[test]
+    fn test_validate_refs_palette_mutations() {
+        let with_mutation = |palette, index| {
+            let mut movie = movie(0, 0);
+            movie.frames[0].palette_mutations = vec![PaletteMutation::new(
+                PaletteRef::new(palette),
+                PaletteIndex::new(index),
+                Color::Transparent,
+            )];
+            movie
+        };
+
+        assert_eq!(Ok(()), with_mutation(0, 15).validate_refs());
+        assert_eq!(
+            Err("Palette mutation 0 in frame 7 refers to palette #1, but the movie only contains 1 palettes.".to_string()),
+            with_mutation(1, 0).validate_refs()
+        );
+        assert_eq!(
+            Err("Palette mutation 0 in frame 7 refers to entry 16 of palette #0, but the palette only contains 16 entries.".to_string()),
+            with_mutation(0, 16).validate_refs()
+        );
+    }
+
+    #[test]
+    fn test_palettes_at() {
+        let mutation = |index, color| {
+            PaletteMutation::new(PaletteRef::new(0), PaletteIndex::new(index), color)
+        };
+        let red = Color::new(255, 0, 0);
+        let blue = Color::new(0, 0, 255);
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(4, Color::Transparent)],
+            Vec::new(),
+            vec![
+                MovieFrame::new(0, Vec::new()),
+                MovieFrame::with_palette_mutations(1, Vec::new(), vec![mutation(1, red)]),
+                MovieFrame::new(2, Vec::new()),
+                MovieFrame::with_palette_mutations(
+                    3,
+                    Vec::new(),
+                    vec![mutation(1, blue), mutation(2, red)],
+                ),
+            ],
+            FrameRate::Ntsc,
+        );
+        let colors = |movie: &Movie, frame_idx| {
+            movie.palettes_at(frame_idx).unwrap()[0]
+                .iter()
+                .map(|(_, color)| *color)
+                .collect::<Vec<_>>()
+        };
+
+        let transparent = Color::Transparent;
+        assert_eq!(vec![transparent; 4], colors(&movie, 0));
+        // Mutations remain in effect in the following frames
+        assert_eq!(
+            vec![transparent, red, transparent, transparent],
+            colors(&movie, 1)
+        );
+        assert_eq!(
+            vec![transparent, red, transparent, transparent],
+            colors(&movie, 2)
+        );
+        assert_eq!(vec![transparent, blue, red, transparent], colors(&movie, 3));
+        assert!(movie.palettes_at(4).is_err());
+        // The palettes of the movie itself are not affected
+        assert_eq!(
+            &[Palette::new_filled(4, Color::Transparent)],
+            movie.palettes()
+        );
+
+        let previous = movie
+            .set_palette_mutations(1, vec![mutation(3, blue)])
+            .unwrap();
+        assert_eq!(vec![mutation(1, red)], previous);
+        assert_eq!(
+            vec![transparent, transparent, transparent, blue],
+            colors(&movie, 2)
+        );
+        assert!(movie
+            .set_palette_mutations(1, vec![mutation(4, blue)])
+            .is_err());
+        assert!(movie.set_palette_mutations(4, Vec::new()).is_err());
+        movie.set_palette_mutations(1, previous).unwrap();
+        assert_eq!(
+            vec![transparent, red, transparent, transparent],
+            colors(&movie, 2)
+        );
+    }
+
+    #[test]
+    fn test_reassign_palette() {
+        let sprite = |palette| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(palette),
+                (0, 0).into(),
+                false,
+                false,
+            )
+        };
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent); 3],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8)))],
+            vec![
+                MovieFrame::new(0, vec![sprite(0), sprite(1), sprite(1)]),
+                MovieFrame::new(1, vec![sprite(2)]),
+                MovieFrame::new(2, vec![sprite(1), sprite(2)]),
+            ],
+            FrameRate::Ntsc,
+        );
+        assert_eq!((3, 2), movie.palette_usage(PaletteRef::new(1)));
+        assert_eq!((2, 2), movie.palette_usage(PaletteRef::new(2)));
+
+        assert_eq!(
+            Ok(3),
+            movie.reassign_palette(PaletteRef::new(1), PaletteRef::new(2))
+        );
+        assert_eq!((0, 0), movie.palette_usage(PaletteRef::new(1)));
+        assert_eq!((5, 3), movie.palette_usage(PaletteRef::new(2)));
+        assert_eq!(Ok(()), movie.validate_refs());
+
+        assert!(movie
+            .reassign_palette(PaletteRef::new(2), PaletteRef::new(3))
+            .is_err());
+        assert_eq!((5, 3), movie.palette_usage(PaletteRef::new(2)));
+    }
+
+    #[test]
+    fn test_build_usage_index() {
+        let sprite = |tile, palette| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(palette),
+                (0, 0).into(),
+                false,
+                false,
+            )
+        };
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent); 3],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8))); 3],
+            vec![
+                MovieFrame::new(10, vec![sprite(0, 0), sprite(0, 1)]),
+                MovieFrame::new(11, vec![sprite(2, 1)]),
+                MovieFrame::new(12, vec![sprite(0, 1)]),
+            ],
+            FrameRate::Ntsc,
+        );
+
+        let index = movie.build_usage_index();
+        assert_eq!(&[0], index.palette_frames(PaletteRef::new(0)));
+        assert_eq!(&[0, 1, 2], index.palette_frames(PaletteRef::new(1)));
+        assert!(index.palette_frames(PaletteRef::new(5)).is_empty());
+        assert_eq!(&[0, 2], index.tile_frames(TileRef::new(0)));
+        assert_eq!(&[1], index.tile_frames(TileRef::new(2)));
+        assert_eq!(
+            vec![PaletteRef::new(2)],
+            index.unreferenced_palettes().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![TileRef::new(1)],
+            index.unreferenced_tiles().collect::<Vec<_>>()
+        );
+
+        // Modifying the movie invalidates the index
+        let cached = movie.build_usage_index().clone();
+        assert_eq!(movie.clone(), movie);
+        movie
+            .reassign_palette(PaletteRef::new(1), PaletteRef::new(2))
+            .unwrap();
+        assert_ne!(&cached, movie.build_usage_index());
+        assert_eq!(
+            &[0, 1, 2],
+            movie.build_usage_index().palette_frames(PaletteRef::new(2))
+        );
+    }
+
+    #[test]
+    fn test_crop() {
+        let sprite = |x: u32, y: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(0),
+                (x, y).into(),
+                false,
+                false,
+            )
+        };
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8)))],
+            vec![MovieFrame::new(
+                0,
+                vec![
+                    // Inside
+                    sprite(100, 50),
+                    // Crosses the bottom-right corner of the region
+                    sprite(124, 136),
+                    // Outside
+                    sprite(0, 0),
+                    sprite(100, 140),
+                    // Wraps around the right edge of the screen into the region
+                    sprite(252, 60),
+                ],
+            )],
+            FrameRate::Ntsc,
+        );
+        movie
+            .set_sprite_ids(vec![(0..5).map(SpriteId::new).collect()])
+            .unwrap();
+
+        assert_eq!(
+            Ok(3),
+            movie.crop(Rect::new_from_size((0, 40), Size::new(128, 100)))
+        );
+        assert_eq!(Size::new(128, 100), movie.screen_size());
+        let positions: Vec<Point> = movie.frames()[0]
+            .sprites()
+            .iter()
+            .map(Sprite::position)
+            .collect();
+        assert_eq!(vec![Point::new(100, 10), Point::new(124, 96)], positions);
+        // The identities follow the sprites
+        assert_eq!(
+            Some(&[SpriteId::new(0), SpriteId::new(1)][..]),
+            movie.sprite_ids(0)
+        );
+
+        assert!(movie
+            .crop(Rect::new_from_size((100, 0), Size::new(100, 10)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_crop_edge() {
+        let sprite = |x: u32, y: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(0),
+                (x, y).into(),
+                false,
+                false,
+            )
+        };
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8)))],
+            vec![MovieFrame::new(
+                0,
+                vec![
+                    // Straddle the left and the top edge of the region
+                    sprite(60, 70),
+                    sprite(80, 36),
+                    // Start on the left and the top edge of the region
+                    sprite(64, 70),
+                    sprite(80, 40),
+                ],
+            )],
+            FrameRate::Ntsc,
+        );
+
+        assert_eq!(
+            Ok(2),
+            movie.crop(Rect::new_from_size((64, 40), Size::new(64, 64)))
+        );
+        let positions: Vec<Point> = movie.frames()[0]
+            .sprites()
+            .iter()
+            .map(Sprite::position)
+            .collect();
+        assert_eq!(vec![Point::new(0, 30), Point::new(16, 0)], positions);
+    }
+
+    #[test]
+    fn test_crop_invalid_tile() {
+        // The first frame is valid, the second frame refers to a tile that does not exist
+        let mut movie = movie(0, 0);
+        let invalid = Sprite::new(
+            TileRef::new(1),
+            PaletteRef::new(0),
+            (0, 0).into(),
+            false,
+            false,
+        );
+        movie.frames.push(MovieFrame::new(8, vec![invalid]));
+        let original = movie.clone();
+
+        assert_eq!(
+            Err("Unknown tile: tile #1.".to_string()),
+            movie.crop(Rect::new_from_size((0, 0), Size::new(128, 100)))
+        );
+        assert_eq!(original, movie);
+    }
+
+    #[test]
+    fn test_dedup_frames() {
+        let sprite = |x: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(0),
+                (x, 0).into(),
+                false,
+                false,
+            )
+        };
+        let mut movie = movie(0, 0);
+        movie.frames = vec![
+            MovieFrame::new(0, vec![sprite(0)]),
+            MovieFrame::new(1, vec![sprite(0)]),
+            MovieFrame::new(2, vec![sprite(1)]),
+            // A palette mutation changes the frame, even if the sprites are the same
+            MovieFrame::with_palette_mutations(
+                3,
+                vec![sprite(1)],
+                vec![PaletteMutation::new(
+                    PaletteRef::new(0),
+                    PaletteIndex::new(1),
+                    Color::new(1, 2, 3),
+                )],
+            ),
+            MovieFrame::new(4, vec![sprite(1)]),
+            MovieFrame::new(5, vec![sprite(2)]),
+        ];
+        movie
+            .set_sprite_ids((0..6).map(|i| vec![SpriteId::new(i)]).collect())
+            .unwrap();
+
+        // Mutations persist, so the frame after a mutation looks the same as the frame with the mutation
+        assert_eq!(2, movie.dedup_frames());
+        let frame_numbers: Vec<u64> = movie
+            .frames()
+            .iter()
+            .map(MovieFrame::frame_number)
+            .collect();
+        assert_eq!(vec![0, 2, 3, 5], frame_numbers);
+        let ids: Vec<_> = (0..4)
+            .map(|frame_idx| movie.sprite_ids(frame_idx).unwrap()[0].value())
+            .collect();
+        assert_eq!(vec![0, 2, 3, 5], ids);
+        assert_eq!(0, movie.dedup_frames());
+    }
+
+    #[test]
+    fn test_scale() {
+        let mut movie = movie(0, 0);
+        movie.frames[0].sprites[0].set_position(Point::new(10, 20));
+        movie.tiles[0].surface_mut().data_mut()[1] = PaletteIndex::new(3);
+
+        assert_eq!(Ok(()), movie.scale(2));
+        assert_eq!(Size::new(512, 448), movie.screen_size());
+        assert_eq!(
+            Point::new(20, 40),
+            movie.frames()[0].sprites()[0].position()
+        );
+        let surface = movie.tiles()[0].surface();
+        assert_eq!(Size::new(16, 16), surface.size());
+        let data = surface.data();
+        assert_eq!(PaletteIndex::new(0), data[1]);
+        assert_eq!(PaletteIndex::new(3), data[2]);
+        assert_eq!(PaletteIndex::new(3), data[16 + 3]);
+        assert_eq!(PaletteIndex::new(0), data[4]);
+
+        assert!(movie.scale(0).is_err());
+        assert!(movie.scale(u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_scale_overflow() {
+        // The screen can be scaled, but the position of the sprite can not
+        let mut movie = movie(0, 0);
+        movie.frames[0].sprites[0].set_position(Point::new(0, u32::MAX / 2));
+        let original = movie.clone();
+
+        assert_eq!(Err("Invalid scale factor: 3.".to_string()), movie.scale(3));
+        assert_eq!(original, movie);
+    }
+
+    #[test]
+    fn test_replace_sprites() {
+        let sprite = |tile: usize, x: u32| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(0),
+                (x, 0).into(),
+                false,
+                false,
+            )
+        };
+        let tiles = vec![Tile::new(TileSurface::new(Size::new(8, 8))); 2];
+        let mut movie = Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            tiles,
+            vec![
+                MovieFrame::new(0, vec![sprite(0, 0), sprite(1, 8)]),
+                MovieFrame::new(1, vec![sprite(1, 16)]),
+            ],
+            FrameRate::Ntsc,
+        );
+        let original = movie.clone();
+
+        let locations = movie.find_similar_sprites(&sprite(1, 100));
+        assert_eq!(
+            vec![
+                SpriteLocation {
+                    frame: 0,
+                    sprite: 1
+                },
+                SpriteLocation {
+                    frame: 1,
+                    sprite: 0
+                }
+            ],
+            locations
+        );
+
+        let replacements = locations
+            .iter()
+            .map(|location| (*location, sprite(0, 50)))
+            .collect();
+        assert_eq!(
+            2,
+            movie.build_usage_index().tile_frames(TileRef::new(1)).len()
+        );
+        let previous = movie.replace_sprites(replacements).unwrap();
+        assert_eq!(&[sprite(0, 50)], movie.frames()[1].sprites());
+        // The usage index is rebuilt
+        assert!(movie
+            .build_usage_index()
+            .tile_frames(TileRef::new(1))
+            .is_empty());
+
+        // Undo
+        movie.replace_sprites(previous).unwrap();
+        assert_eq!(original, movie);
+
+        // Invalid replacements are rejected as a whole
+        let invalid = SpriteLocation {
+            frame: 1,
+            sprite: 1,
+        };
+        assert!(movie
+            .replace_sprites(vec![(locations[0], sprite(0, 0)), (invalid, sprite(0, 0))])
+            .is_err());
+        assert!(movie
+            .replace_sprites(vec![(locations[0], sprite(2, 0))])
+            .is_err());
+        assert_eq!(original, movie);
+    }
+
+    #[cfg(feature = "validate_refs")]
+    #[test]
+    fn test_deserialize_validates_refs() {
+        let valid = bincode::serialize(&movie(0, 0)).unwrap();
+        assert_eq!(movie(0, 0), bincode::deserialize::<Movie>(&valid).unwrap());
+
+        let invalid = bincode::serialize(&movie(3, 0)).unwrap();
+        assert!(bincode::deserialize::<Movie>(&invalid).is_err());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_serialize_metadata() {
+        let mut movie = movie(0, 0);
+        assert!(movie.metadata().is_empty());
+        movie.metadata_mut().title = Some("Intro".to_string());
+        movie
+            .metadata_mut()
+            .properties
+            .insert("emulator".to_string(), "Mesen-S".to_string());
+        assert!(!movie.metadata().is_empty());
+
+        let data = bincode::serialize(&movie).unwrap();
+        let deserialized: Movie = bincode::deserialize(&data).unwrap();
+        assert_eq!(movie.metadata(), deserialized.metadata());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_assets() {
+        use super::MovieAssets;
+
+        let movie = movie(0, 0);
+        let data = bincode::serialize(&movie).unwrap();
+
+        // Only the data in front of the frames is read (after the marker and the version of the layout)
+        let assets_len = bincode::serialized_size(&(
+            u32::MAX,
+            2u16,
+            movie.screen_size(),
+            movie.palettes(),
+            movie.tiles(),
+        ))
+        .unwrap() as usize;
+        let assets: MovieAssets = bincode::deserialize(&data[..assets_len]).unwrap();
+        assert_eq!(MovieAssets::from(movie), assets);
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_serialize_sprite_ids() {
+        let mut movie = movie(0, 0);
+        movie.set_sprite_ids(vec![vec![SpriteId::new(3)]]).unwrap();
+
+        let data = bincode::serialize(&movie).unwrap();
+        let deserialized: Movie = bincode::deserialize(&data).unwrap();
+        assert_eq!(Some(&[SpriteId::new(3)][..]), deserialized.sprite_ids(0));
+
+        let json = serde_json::to_string(&movie).unwrap();
+        let deserialized: Movie = serde_json::from_str(&json).unwrap();
+        assert_eq!(Some(&[SpriteId::new(3)][..]), deserialized.sprite_ids(0));
+
+        // The identities must match the sprites
+        let mut invalid: serde_json::Value = serde_json::from_str(&json).unwrap();
+        invalid["sprite_ids"] = serde_json::json!([[3, 4]]);
+        assert!(serde_json::from_value::<Movie>(invalid).is_err());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_version_1() {
+        use super::MovieAssets;
+
+        // The layout before the sprite identities were added
+        let movie = movie(0, 0);
+        let data = bincode::serialize(&(
+            u32::MAX,
+            1u16,
+            (
+                movie.screen_size(),
+                movie.palettes(),
+                movie.tiles(),
+                movie.frames(),
+                movie.frame_rate(),
+                movie.metadata(),
+            ),
+        ))
+        .unwrap();
+
+        let deserialized: Movie = bincode::deserialize(&data).unwrap();
+        assert_eq!(movie, deserialized);
+        assert_eq!(None, deserialized.sprite_ids(0));
+        assert_eq!(
+            MovieAssets::from(movie),
+            bincode::deserialize::<MovieAssets>(&data).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_original_layout() {
+        use super::MovieAssets;
+
+        // The layout before the metadata of movies, frames, palettes and tiles was added
+        let movie = movie(0, 0);
+        let tile = movie.tiles()[0].surface();
+        let frames: Vec<_> = movie
+            .frames()
+            .iter()
+            .map(|frame| (frame.frame_number(), frame.sprites()))
+            .collect();
+        let data = bincode::serialize(&(
+            movie.screen_size(),
+            vec![vec![Color::Transparent; 16]],
+            vec![(tile.data(), tile.size())],
+            frames,
+            movie.frame_rate(),
+        ))
+        .unwrap();
+
+        assert_eq!(movie, bincode::deserialize::<Movie>(&data).unwrap());
+        assert_eq!(
+            MovieAssets::from(movie),
+            bincode::deserialize::<MovieAssets>(&data).unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_movie_frame {
+    use super::{CapacityWarning, FrameCapacity, MovieFrame, OverflowPolicy, PaletteMutation};
+    use crate::sprite::{Color, PaletteIndex, PaletteRef, Sprite, TileRef};
+
+    fn frame(sprite_count: usize) -> MovieFrame {
+        let sprites = (0..sprite_count)
+            .map(|i| {
+                Sprite::new(
+                    TileRef::new(i),
+                    PaletteRef::new(0),
+                    (0, 0).into(),
+                    false,
+                    false,
+                )
+            })
+            .collect();
+        MovieFrame::new(5, sprites)
+    }
+
+    fn tiles(frame: &MovieFrame) -> Vec<usize> {
+        frame
+            .sprites()
+            .iter()
+            .map(|sprite| sprite.tile().value())
+            .collect()
+    }
+
+    #[test]
+    fn test_capacity_new() {
+        assert!(FrameCapacity::new(0, OverflowPolicy::Split).is_err());
+        let capacity = FrameCapacity::new(128, OverflowPolicy::Truncate).unwrap();
+        assert_eq!(128, capacity.max_sprites());
+        assert_eq!(OverflowPolicy::Truncate, capacity.overflow());
+    }
+
+    #[test]
+    fn test_apply_capacity() {
+        let capacity = |overflow| FrameCapacity::new(2, overflow).unwrap();
+
+        // Frames within the capacity are not affected by the policy
+        assert_eq!(
+            Ok((vec![frame(2)], None)),
+            frame(2).apply_capacity(&capacity(OverflowPolicy::Error))
+        );
+
+        assert_eq!(
+            Err("Frame 5 contains 5 sprites, but at most 2 are allowed.".to_string()),
+            frame(5).apply_capacity(&capacity(OverflowPolicy::Error))
+        );
+
+        let (frames, warning) = frame(5)
+            .apply_capacity(&capacity(OverflowPolicy::Truncate))
+            .unwrap();
+        let warning = warning.unwrap();
+        assert_eq!(
+            CapacityWarning {
+                frame_number: 5,
+                sprite_count: 5,
+                dropped: 3,
+            },
+            warning
+        );
+        assert_eq!(
+            "Dropped 3 of the 5 sprites of frame 5.",
+            warning.to_string()
+        );
+        assert_eq!(1, frames.len());
+        assert_eq!(vec![0, 1], tiles(&frames[0]));
+
+        let (frames, warning) = frame(5)
+            .apply_capacity(&capacity(OverflowPolicy::Split))
+            .unwrap();
+        assert_eq!(None, warning);
+        assert_eq!(
+            vec![vec![0, 1], vec![2, 3], vec![4]],
+            frames.iter().map(tiles).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![5, 6, 7],
+            frames
+                .iter()
+                .map(MovieFrame::frame_number)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_capacity_apply() {
+        let capacity = |overflow| FrameCapacity::new(2, overflow).unwrap();
+        let frames = || {
+            vec![
+                MovieFrame::new(3, frame(3).sprites().to_vec()),
+                MovieFrame::new(4, frame(1).sprites().to_vec()),
+                MovieFrame::new(10, frame(5).sprites().to_vec()),
+            ]
+        };
+        let frame_numbers = |frames: &[MovieFrame]| -> Vec<u64> {
+            frames.iter().map(MovieFrame::frame_number).collect()
+        };
+
+        assert_eq!(
+            Err("Frame 3 contains 3 sprites, but at most 2 are allowed.".to_string()),
+            capacity(OverflowPolicy::Error).apply(frames())
+        );
+
+        let (truncated, warnings) = capacity(OverflowPolicy::Truncate).apply(frames()).unwrap();
+        assert_eq!(vec![3, 4, 10], frame_numbers(&truncated));
+        assert_eq!(
+            vec![(3, 1), (10, 3)],
+            warnings
+                .iter()
+                .map(|warning| (warning.frame_number, warning.dropped))
+                .collect::<Vec<_>>()
+        );
+
+        // The frames after a split frame move back, such that all frame numbers are distinct
+        let (split, warnings) = capacity(OverflowPolicy::Split).apply(frames()).unwrap();
+        assert!(warnings.is_empty());
+        assert_eq!(vec![3, 4, 5, 11, 12, 13], frame_numbers(&split));
+        assert_eq!(
+            vec![2, 1, 1, 2, 2, 1],
+            split
+                .iter()
+                .map(|frame| frame.sprites().len())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_apply_capacity_palette_mutations() {
+        let mutation =
+            PaletteMutation::new(PaletteRef::new(0), PaletteIndex::new(1), Color::Transparent);
+        let frame =
+            MovieFrame::with_palette_mutations(5, frame(3).sprites().to_vec(), vec![mutation]);
+        let capacity = FrameCapacity::new(2, OverflowPolicy::Split).unwrap();
+
+        // The mutations are applied once, with the first part
+        let (frames, _) = frame.apply_capacity(&capacity).unwrap();
+        assert_eq!(&[mutation], frames[0].palette_mutations());
+        assert!(frames[1].palette_mutations().is_empty());
+    }
 }
@@ -0,0 +1,338 @@
+//! A module for post-processing movies after they have been extracted.
+//!
+//! A captured movie usually needs some work before its artwork can be used: duplicate frames, HUD elements and the parts of the
+//! screen that are not of interest are removed and the artwork may have to be scaled. Every such step is a [`FramePostProcessor`].
+//! A [`Pipeline`] runs a series of them, such that the tools can compose the steps from a configuration (see [`PassConfig`]) instead
+//! of calling every step on their own.
+
+use crate::analysis::strip_static_sprites;
+use crate::geom_art::{Rect, Size};
+use crate::movie::Movie;
+
+/// A processing step that modifies a movie.
+pub trait FramePostProcessor {
+    /// Retrieves the name of the step, for reporting.
+    fn name(&self) -> &str;
+
+    /// Applies the step to a movie.
+    ///
+    /// # Arguments
+    ///
+    /// * `movie`: The movie.
+    ///
+    /// returns: The number of things that have been changed (like removed frames or sprites), for reporting.
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String>;
+}
+
+impl<P> FramePostProcessor for Box<P>
+where
+    P: FramePostProcessor + ?Sized,
+{
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String> {
+        (**self).apply(movie)
+    }
+}
+
+/// Removes the frames that are identical to the frame before them (see [`Movie::dedup_frames()`]).
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct DedupFrames;
+
+impl FramePostProcessor for DedupFrames {
+    fn name(&self) -> &str {
+        "dedup"
+    }
+
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String> {
+        Ok(movie.dedup_frames())
+    }
+}
+
+/// Removes the sprites of the HUD, like score digits and lives counters. These are the sprites that occur unchanged in a large part
+/// of the frames (see [`strip_static_sprites()`]).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StripHud {
+    /// The minimum fraction of the frames in which a sprite has to occur to be considered part of the HUD.
+    pub min_fraction: f64,
+}
+
+impl FramePostProcessor for StripHud {
+    fn name(&self) -> &str {
+        "strip_hud"
+    }
+
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String> {
+        strip_static_sprites(movie, self.min_fraction)
+    }
+}
+
+/// Crops the movie to a region of the screen (see [`Movie::crop()`]).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Crop {
+    /// The region.
+    pub rect: Rect,
+}
+
+impl FramePostProcessor for Crop {
+    fn name(&self) -> &str {
+        "crop"
+    }
+
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String> {
+        movie.crop(self.rect)
+    }
+}
+
+/// Scales the movie up by an integer factor (see [`Movie::scale()`]).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Scale {
+    /// The factor.
+    pub factor: u32,
+}
+
+impl FramePostProcessor for Scale {
+    fn name(&self) -> &str {
+        "scale"
+    }
+
+    fn apply(&self, movie: &mut Movie) -> Result<usize, String> {
+        movie.scale(self.factor)?;
+        Ok(movie.tiles().len())
+    }
+}
+
+/// The configuration of a built-in [`FramePostProcessor`].
+///
+/// In serialized form, every pass is an object with a `pass` field that holds the name of the pass, for instance:
+///
+/// ```json
+/// [
+///     { "pass": "crop", "x": 0, "y": 16, "width": 256, "height": 192 },
+///     { "pass": "strip_hud", "min_fraction": 0.9 },
+///     { "pass": "dedup" }
+/// ]
+/// ```
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "pass", rename_all = "snake_case")
+)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PassConfig {
+    /// See [`DedupFrames`].
+    Dedup,
+    /// See [`StripHud`].
+    StripHud { min_fraction: f64 },
+    /// See [`Crop`].
+    Crop {
+        x: u32,
+        y: u32,
+        width: u32,
+        height: u32,
+    },
+    /// See [`Scale`].
+    Scale { factor: u32 },
+}
+
+impl PassConfig {
+    /// Creates the [`FramePostProcessor`] for this configuration.
+    pub fn build(&self) -> Box<dyn FramePostProcessor> {
+        match *self {
+            PassConfig::Dedup => Box::new(DedupFrames),
+            PassConfig::StripHud { min_fraction } => Box::new(StripHud { min_fraction }),
+            PassConfig::Crop {
+                x,
+                y,
+                width,
+                height,
+            } => Box::new(Crop {
+                rect: Rect::new_from_size((x, y), Size::new(width, height)),
+            }),
+            PassConfig::Scale { factor } => Box::new(Scale { factor }),
+        }
+    }
+}
+
+/// A series of [`FramePostProcessor`]s that are applied one after the other.
+#[derive(Default)]
+pub struct Pipeline {
+    passes: Vec<Box<dyn FramePostProcessor>>,
+}
+
+impl Pipeline {
+    /// Creates a new, empty instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new instance from the configuration of its passes.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The passes, in the order in which they should be applied.
+    pub fn from_config(config: &[PassConfig]) -> Self {
+        Self {
+            passes: config.iter().map(PassConfig::build).collect(),
+        }
+    }
+
+    /// Adds a pass at the end of the pipeline.
+    pub fn push(&mut self, pass: impl FramePostProcessor + 'static) {
+        self.passes.push(Box::new(pass));
+    }
+
+    /// Retrieves the number of passes.
+    pub fn len(&self) -> usize {
+        self.passes.len()
+    }
+
+    /// Determines whether the pipeline contains no passes.
+    pub fn is_empty(&self) -> bool {
+        self.passes.is_empty()
+    }
+
+    /// Applies all passes to a movie. Processing stops at the first pass that fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `movie`: The movie.
+    /// * `report`: A function that is called after every pass with the name of the pass and its result (see
+    ///   [`FramePostProcessor::apply()`]).
+    pub fn run(
+        &self,
+        movie: &mut Movie,
+        mut report: impl FnMut(&str, usize),
+    ) -> Result<(), String> {
+        for pass in &self.passes {
+            let count = pass
+                .apply(movie)
+                .map_err(|err| format!("Pass '{}' failed: {}", pass.name(), err))?;
+            report(pass.name(), count);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_pipeline {
+    use super::{PassConfig, Pipeline, Scale};
+    use crate::geom_art::{Point, Size};
+    use crate::movie::{FrameRate, Movie, MovieFrame};
+    use crate::sprite::{Color, Palette, PaletteRef, Sprite, Tile, TileRef, TileSurface};
+
+    fn movie() -> Movie {
+        let sprite = |x: u32, y: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(0),
+                (x, y).into(),
+                false,
+                false,
+            )
+        };
+        Movie::new(
+            Size::new(256, 224),
+            vec![Palette::new_filled(16, Color::Transparent)],
+            vec![Tile::new(TileSurface::new(Size::new(8, 8)))],
+            vec![
+                MovieFrame::new(0, vec![sprite(8, 8), sprite(100, 100)]),
+                MovieFrame::new(1, vec![sprite(8, 8), sprite(100, 100)]),
+                MovieFrame::new(2, vec![sprite(8, 8), sprite(104, 100)]),
+                MovieFrame::new(3, vec![sprite(8, 8), sprite(108, 100)]),
+            ],
+            FrameRate::Ntsc,
+        )
+    }
+
+    #[test]
+    fn test_run() {
+        let pipeline = Pipeline::from_config(&[
+            PassConfig::Dedup,
+            PassConfig::StripHud { min_fraction: 1.0 },
+            PassConfig::Crop {
+                x: 64,
+                y: 64,
+                width: 128,
+                height: 128,
+            },
+            PassConfig::Scale { factor: 2 },
+        ]);
+        assert_eq!(4, pipeline.len());
+
+        let mut movie = movie();
+        let mut reports = Vec::new();
+        pipeline
+            .run(&mut movie, |name, count| {
+                reports.push((name.to_string(), count))
+            })
+            .unwrap();
+
+        assert_eq!(
+            vec![
+                ("dedup".to_string(), 1),
+                ("strip_hud".to_string(), 3),
+                ("crop".to_string(), 0),
+                ("scale".to_string(), 1),
+            ],
+            reports
+        );
+        assert_eq!(Size::new(256, 256), movie.screen_size());
+        let positions: Vec<Point> = movie
+            .frames()
+            .iter()
+            .flat_map(MovieFrame::sprites)
+            .map(Sprite::position)
+            .collect();
+        assert_eq!(
+            vec![Point::new(72, 72), Point::new(80, 72), Point::new(88, 72)],
+            positions
+        );
+    }
+
+    #[test]
+    fn test_run_error() {
+        let mut pipeline = Pipeline::new();
+        assert!(pipeline.is_empty());
+        pipeline.push(Scale { factor: 0 });
+
+        let mut reports = 0;
+        assert_eq!(
+            Err("Pass 'scale' failed: Invalid scale factor: 0.".to_string()),
+            pipeline.run(&mut movie(), |_, _| reports += 1)
+        );
+        assert_eq!(0, reports);
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_config() {
+        let config: Vec<PassConfig> = serde_json::from_str(
+            r#"[
+                { "pass": "crop", "x": 0, "y": 16, "width": 256, "height": 192 },
+                { "pass": "strip_hud", "min_fraction": 0.9 },
+                { "pass": "dedup" },
+                { "pass": "scale", "factor": 3 }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                PassConfig::Crop {
+                    x: 0,
+                    y: 16,
+                    width: 256,
+                    height: 192
+                },
+                PassConfig::StripHud { min_fraction: 0.9 },
+                PassConfig::Dedup,
+                PassConfig::Scale { factor: 3 },
+            ],
+            config
+        );
+        assert!(serde_json::from_str::<Vec<PassConfig>>(r#"[{ "pass": "blur" }]"#).is_err());
+    }
+}
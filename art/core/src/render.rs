@@ -0,0 +1,223 @@
+//! A module for compositing sprites into a single image.
+//!
+//! Sprites are blitted in reverse order, such that the first sprite ends up on top. Which pixels are transparent is determined by the
+//! [`TransparencyPolicy`] (by default: pixels with palette index zero) and sprites that exceed the screen bounds wrap around to the
+//! other side.
+
+use crate::geom_art::{ArtworkSpaceUnit, Size};
+use crate::movie::Movie;
+use crate::sprite::{Color, Palette, PaletteRef, Sprite, Tile, TileRef, TransparencyPolicy};
+use crate::surface::{Surface, VecSurface};
+use std::ops::Index;
+use ves_cache::SliceCache;
+
+/// Options for [`render_frame()`] and [`render_sprites()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct RenderOptions {
+    /// The color for pixels that are not covered by any sprite.
+    pub background: Color,
+    /// The policy that determines which palette entries are transparent.
+    pub transparency: TransparencyPolicy,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            background: Color::Transparent,
+            transparency: TransparencyPolicy::default(),
+        }
+    }
+}
+
+/// Renders a frame of a [`Movie`].
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `frame_idx`: The index of the frame in the movie.
+/// * `options`: The [`RenderOptions`].
+///
+/// returns: A surface with the size of the movie screen.
+pub fn render_frame(
+    movie: &Movie,
+    frame_idx: usize,
+    options: &RenderOptions,
+) -> Result<VecSurface<Color>, String> {
+    let frame = movie.frames().get(frame_idx).ok_or_else(|| {
+        format!(
+            "Frame index {} is out of range (the movie contains {} frames).",
+            frame_idx,
+            movie.frames().len()
+        )
+    })?;
+
+    let palettes = movie.palettes_at(frame_idx)?;
+    render_sprites(
+        frame.sprites(),
+        &SliceCache::new(&palettes),
+        &SliceCache::new(movie.tiles()),
+        movie.screen_size(),
+        options,
+    )
+}
+
+/// Renders a sequence of sprites.
+///
+/// # Arguments
+///
+/// * `sprites`: The sprites. The first sprite has the highest priority.
+/// * `palettes`: The palettes.
+/// * `tiles`: The tiles.
+/// * `screen_size`: The size of the output surface.
+/// * `options`: The [`RenderOptions`].
+///
+/// returns: The output surface.
+pub fn render_sprites(
+    sprites: &[Sprite],
+    palettes: &impl Index<PaletteRef, Output = Palette>,
+    tiles: &impl Index<TileRef, Output = Tile>,
+    screen_size: Size,
+    options: &RenderOptions,
+) -> Result<VecSurface<Color>, String> {
+    let mut screen_surface =
+        VecSurface::<Color, ArtworkSpaceUnit>::new(screen_size, options.background);
+    let screen_data = screen_surface.data_mut();
+
+    // Reverse-iterate because the first sprites should be rendered on top
+    for sprite in sprites.iter().rev() {
+        let tile_surface = tiles[sprite.tile()].surface();
+        let src_data = tile_surface.data();
+        let src_size = tile_surface.size();
+        let palette = &palettes[sprite.palette()];
+
+        crate::surface::surface_iterate_2(
+            src_size,
+            src_size.as_rect(),
+            screen_size,
+            sprite.position(),
+            sprite.h_flip(),
+            sprite.v_flip(),
+            |_src_pos, src_idx, _dest_pos, dest_idx| {
+                let color = palette.color(src_data[src_idx], options.transparency);
+                if color != Color::Transparent {
+                    screen_data[dest_idx] = color;
+                }
+            },
+        )?;
+    }
+
+    Ok(screen_surface)
+}
+
+#[cfg(test)]
+mod test_render {
+    use super::{render_frame, RenderOptions};
+    use crate::geom_art::Size;
+    use crate::movie::{FrameRate, Movie, MovieFrame};
+    use crate::sprite::{
+        Color, Palette, PaletteIndex, PaletteRef, Sprite, Tile, TileRef, TileSurface,
+        TransparencyPolicy,
+    };
+    use crate::surface::{Offset, Surface};
+    use rgb::RGB8;
+
+    fn movie() -> Movie {
+        let palette = Palette::new(vec![
+            Color::Transparent,
+            Color::new(255, 0, 0),
+            Color::new(0, 255, 0),
+        ]);
+
+        // A 2x2 tile with a single pixel in the top-left corner
+        let mut corner = TileSurface::new(Size::new(2, 2));
+        corner.data_mut()[0] = PaletteIndex::new(1);
+        // A 2x2 tile that is completely filled
+        let mut filled = TileSurface::new(Size::new(2, 2));
+        filled.data_mut().fill(PaletteIndex::new(2));
+
+        let sprite = |tile: usize, x: u32, y: u32, h_flip, v_flip| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(0),
+                (x, y).into(),
+                h_flip,
+                v_flip,
+            )
+        };
+        let frame = MovieFrame::new(
+            0,
+            vec![
+                sprite(0, 1, 1, true, true),
+                sprite(1, 1, 1, false, false),
+                sprite(1, 7, 3, false, false),
+            ],
+        );
+
+        Movie::new(
+            Size::new(8, 4),
+            vec![palette],
+            vec![Tile::new(corner), Tile::new(filled)],
+            vec![frame],
+            FrameRate::Ntsc,
+        )
+    }
+
+    #[test]
+    fn test_render_frame() {
+        let surface = render_frame(&movie(), 0, &RenderOptions::default()).unwrap();
+        let pixel = |x: u32, y: u32| surface.data()[surface.offset((x, y)).unwrap()];
+
+        let red = Color::new(255, 0, 0);
+        let green = Color::new(0, 255, 0);
+        assert_eq!(Size::new(8, 4), surface.size());
+        assert_eq!(Color::Transparent, pixel(4, 0));
+        // The first sprite is on top, but only its (flipped) opaque pixel covers the second sprite
+        assert_eq!(green, pixel(1, 1));
+        assert_eq!(green, pixel(1, 2));
+        assert_eq!(red, pixel(2, 2));
+        // The third sprite wraps around both edges
+        assert_eq!(green, pixel(7, 3));
+        assert_eq!(green, pixel(0, 3));
+        assert_eq!(green, pixel(7, 0));
+        assert_eq!(green, pixel(0, 0));
+    }
+
+    #[test]
+    fn test_render_frame_background() {
+        let options = RenderOptions {
+            background: Color::new(1, 2, 3),
+            ..Default::default()
+        };
+        let surface = render_frame(&movie(), 0, &options).unwrap();
+        assert_eq!(
+            Color::new(1, 2, 3),
+            surface.data()[surface.offset((4, 0)).unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_render_frame_transparency() {
+        let pixel = |transparency, x: u32, y: u32| {
+            let options = RenderOptions {
+                transparency,
+                ..Default::default()
+            };
+            let surface = render_frame(&movie(), 0, &options).unwrap();
+            surface.data()[surface.offset((x, y)).unwrap()]
+        };
+
+        let red = Color::new(255, 0, 0);
+        let green = Color::new(0, 255, 0);
+        assert_eq!(green, pixel(TransparencyPolicy::Index0, 1, 2));
+        // The first palette entry is transparent by itself
+        assert_eq!(green, pixel(TransparencyPolicy::None, 1, 2));
+        let key = TransparencyPolicy::ColorKey(RGB8::new(0, 255, 0));
+        assert_eq!(Color::Transparent, pixel(key, 1, 2));
+        assert_eq!(red, pixel(key, 2, 2));
+    }
+
+    #[test]
+    fn test_render_frame_out_of_range() {
+        assert!(render_frame(&movie(), 1, &RenderOptions::default()).is_err());
+    }
+}
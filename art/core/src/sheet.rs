@@ -0,0 +1,522 @@
+//! A module for spritesheets (also known as atlases).
+//!
+//! A spritesheet is a single true-color image that contains the tiles of a library, along with a [`SheetMeta`] that describes where
+//! the tiles are located in the image and how they are composed into cels and animations. This is the format of choice for editing
+//! artwork in external tools, since any image editor can handle it. [`export_sheet()`] creates a spritesheet from an [`ArtLibrary`] and
+//! [`import_sheet()`] turns a spritesheet back into an [`ArtLibrary`].
+
+use crate::geom_art::{Point, Rect, Size};
+use crate::library::ArtLibrary;
+use crate::sprite::{
+    Animation, AnimationFrame, Cel, CelRef, PaletteRef, Sprite, SpriteSource, TileRef,
+};
+use crate::surface::Surface;
+use crate::true_color::{quantize, TrueColorSurface, TrueColorTile};
+use std::collections::HashMap;
+
+/// The location of a tile in a spritesheet.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SheetTile {
+    /// The X-coordinate of the top-left corner in the image.
+    pub x: u32,
+    /// The Y-coordinate of the top-left corner in the image.
+    pub y: u32,
+    /// The width.
+    pub width: u32,
+    /// The height.
+    pub height: u32,
+    /// The origin of the tile, relative to the top-left corner (see [`Tile::origin()`](crate::sprite::Tile::origin)).
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub origin: Option<Point>,
+}
+
+/// A sprite in a [`SheetCel`].
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SheetSprite {
+    /// The index of the tile in [`SheetMeta::tiles`].
+    pub tile: usize,
+    /// The X-coordinate of the tile inside the cel.
+    pub x: u32,
+    /// The Y-coordinate of the tile inside the cel.
+    pub y: u32,
+    /// Whether the tile is flipped horizontally.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub h_flip: bool,
+    /// Whether the tile is flipped vertically.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub v_flip: bool,
+    /// The origin of the sprite in a captured movie, if known.
+    #[cfg_attr(
+        feature = "serde_support",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub source: Option<SpriteSource>,
+}
+
+/// A cel (metasprite) in a spritesheet.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SheetCel {
+    /// The sprites. The first sprite has the highest priority.
+    pub sprites: Vec<SheetSprite>,
+}
+
+/// The metadata of a spritesheet.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SheetMeta {
+    /// The tiles.
+    pub tiles: Vec<SheetTile>,
+    /// The cels.
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub cels: Vec<SheetCel>,
+    /// The animations. Every animation is a sequence of indices into [`SheetMeta::cels`].
+    #[cfg_attr(feature = "serde_support", serde(default))]
+    pub animations: Vec<Vec<usize>>,
+}
+
+/// Options for [`export_sheet()`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ExportOptions {
+    /// The maximum width of the image. The image is wider if a single tile exceeds this width.
+    pub max_width: u32,
+    /// Whether the sources of the sprites are included in the metadata (see [`SheetSprite::source`]).
+    pub include_sources: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            max_width: 256,
+            include_sources: false,
+        }
+    }
+}
+
+/// Creates a spritesheet from an [`ArtLibrary`].
+///
+/// Every combination of a tile and a palette that is used by a cel results in one tile in the spritesheet. The tiles are laid out in
+/// rows, in the order in which they are first used.
+///
+/// # Arguments
+///
+/// * `library`: The library.
+/// * `options`: The [`ExportOptions`].
+///
+/// returns: The image and the metadata.
+pub fn export_sheet(
+    library: &ArtLibrary,
+    options: &ExportOptions,
+) -> Result<(TrueColorSurface, SheetMeta), String> {
+    let mut tile_indices: HashMap<(TileRef, PaletteRef), usize> = HashMap::new();
+    let mut meta = SheetMeta::default();
+    let (mut x, mut y, mut row_height, mut width) = (0, 0, 0, 0);
+
+    for cel in library.cels() {
+        let mut sheet_cel = SheetCel::default();
+        for (index, sprite) in cel.sprites().iter().enumerate() {
+            let key = (sprite.tile(), sprite.palette());
+            let tile = if let Some(tile) = tile_indices.get(&key) {
+                *tile
+            } else {
+                let tile = library
+                    .tiles()
+                    .get(sprite.tile().value())
+                    .ok_or_else(|| format!("Unknown tile: {}.", sprite.tile()))?;
+                let size = tile.surface().size();
+                let (tile_width, tile_height) = (size.width.raw(), size.height.raw());
+                // Start a new row if the tile does not fit
+                if x > 0 && x + tile_width > options.max_width {
+                    x = 0;
+                    y += row_height;
+                    row_height = 0;
+                }
+                let origin = tile.origin();
+                meta.tiles.push(SheetTile {
+                    x,
+                    y,
+                    width: tile_width,
+                    height: tile_height,
+                    origin: (origin != Point::new(0, 0)).then_some(origin),
+                });
+                x += tile_width;
+                width = width.max(x);
+                row_height = row_height.max(tile_height);
+
+                tile_indices.insert(key, meta.tiles.len() - 1);
+                meta.tiles.len() - 1
+            };
+
+            let position = sprite.position();
+            sheet_cel.sprites.push(SheetSprite {
+                tile,
+                x: position.x.raw(),
+                y: position.y.raw(),
+                h_flip: sprite.h_flip(),
+                v_flip: sprite.v_flip(),
+                source: options
+                    .include_sources
+                    .then(|| cel.sources().get(index).copied())
+                    .flatten(),
+            });
+        }
+        meta.cels.push(sheet_cel);
+    }
+
+    meta.animations = library
+        .animations()
+        .iter()
+        .map(|animation| {
+            animation
+                .as_ref()
+                .iter()
+                .map(|frame| frame.cel().value())
+                .collect()
+        })
+        .collect();
+
+    if meta.tiles.is_empty() {
+        return Err("The library does not contain any sprites.".to_string());
+    }
+
+    let mut image = TrueColorSurface::new(Size::new(width, y + row_height));
+    let image_width = width as usize;
+    let mut keys: Vec<_> = tile_indices.into_iter().collect();
+    keys.sort_unstable_by_key(|(_, index)| *index);
+    for ((tile_ref, palette_ref), index) in keys {
+        let sheet_tile = &meta.tiles[index];
+        let palette = library
+            .palettes()
+            .get(palette_ref.value())
+            .ok_or_else(|| format!("Unknown palette: {}.", palette_ref))?;
+        let tile_data = library.tiles()[tile_ref.value()].surface().data();
+        let tile_width = sheet_tile.width as usize;
+        for (row_index, row) in tile_data.chunks_exact(tile_width).enumerate() {
+            let start = (sheet_tile.y as usize + row_index) * image_width + sheet_tile.x as usize;
+            for (target, palette_index) in image.data_mut()[start..start + tile_width]
+                .iter_mut()
+                .zip(row)
+            {
+                if usize::from(palette_index.value()) >= palette.len() {
+                    return Err(format!(
+                        "{} has no entry {} (used by {}).",
+                        palette_ref,
+                        palette_index.value(),
+                        tile_ref
+                    ));
+                }
+                *target = palette[*palette_index];
+            }
+        }
+    }
+
+    Ok((image, meta))
+}
+
+/// Creates an [`ArtLibrary`] from a spritesheet.
+///
+/// The tiles are cut out of the image and quantized into a single palette (see [`quantize()`]). Cels without sprites are retained,
+/// since they may be part of an animation. The sources of the sprites are only retained if they are known for all sprites of a cel.
+///
+/// # Arguments
+///
+/// * `image`: The image.
+/// * `meta`: The metadata.
+/// * `palette_len`: The number of entries in the palette, including the transparent entry.
+pub fn import_sheet(
+    image: &TrueColorSurface,
+    meta: &SheetMeta,
+    palette_len: usize,
+) -> Result<ArtLibrary, String> {
+    let true_color_tiles = meta
+        .tiles
+        .iter()
+        .enumerate()
+        .map(|(index, tile)| cut_tile(image, tile).map_err(|err| format!("Tile {index}: {err}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (mut tiles, palette) = quantize(&true_color_tiles, palette_len)?;
+    for (tile, sheet_tile) in tiles.iter_mut().zip(&meta.tiles) {
+        tile.set_origin(sheet_tile.origin);
+    }
+
+    let cels = meta
+        .cels
+        .iter()
+        .enumerate()
+        .map(|(index, cel_meta)| {
+            let sprites = cel_meta
+                .sprites
+                .iter()
+                .map(|sprite| {
+                    if sprite.tile >= tiles.len() {
+                        return Err(format!(
+                            "Cel {index} refers to tile {}, but the sheet only contains {} tiles.",
+                            sprite.tile,
+                            tiles.len()
+                        ));
+                    }
+                    Ok(Sprite::new(
+                        TileRef::new(sprite.tile),
+                        PaletteRef::new(0),
+                        Point::new(sprite.x, sprite.y),
+                        sprite.h_flip,
+                        sprite.v_flip,
+                    ))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let mut cel = Cel::new(sprites);
+            if let Some(sources) = cel_sources(cel_meta) {
+                cel.set_sources(sources)?;
+            }
+            Ok(cel)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let animations = meta
+        .animations
+        .iter()
+        .enumerate()
+        .map(|(index, cel_indices)| {
+            let mut animation = Animation::default();
+            for cel in cel_indices {
+                if *cel >= cels.len() {
+                    return Err(format!(
+                        "Animation {index} refers to cel {cel}, but the sheet only contains {} cels.",
+                        cels.len()
+                    ));
+                }
+                animation
+                    .as_mut()
+                    .push(AnimationFrame::new(CelRef::new(*cel)));
+            }
+            Ok(animation)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    Ok(ArtLibrary::new(vec![palette], tiles, cels, animations))
+}
+
+/// Collects the sources of the sprites in a cel.
+///
+/// returns: The sources or `None` if the source of at least one sprite is unknown.
+fn cel_sources(cel: &SheetCel) -> Option<Vec<SpriteSource>> {
+    if cel.sprites.is_empty() {
+        return None;
+    }
+    cel.sprites.iter().map(|sprite| sprite.source).collect()
+}
+
+/// Cuts a tile out of a spritesheet image.
+fn cut_tile(image: &TrueColorSurface, tile: &SheetTile) -> Result<TrueColorTile, String> {
+    if tile.width == 0 || tile.height == 0 {
+        return Err("The tile is empty.".to_string());
+    }
+    let size = Size::new(tile.width, tile.height);
+    let rect = Rect::new_from_size(Point::new(tile.x, tile.y), size);
+    let image_size = image.size();
+    if rect.max_x() >= image_size.width || rect.max_y() >= image_size.height {
+        return Err(format!(
+            "The tile at ({}, {}) with size {}x{} exceeds the sheet of {}x{}.",
+            tile.x,
+            tile.y,
+            tile.width,
+            tile.height,
+            image_size.width.raw(),
+            image_size.height.raw()
+        ));
+    }
+
+    let mut surface = TrueColorSurface::new(size);
+    let (image_width, width) = (image_size.width.raw() as usize, tile.width as usize);
+    let image_data = image.data();
+    for (y, row) in surface.data_mut().chunks_exact_mut(width).enumerate() {
+        let start = (tile.y as usize + y) * image_width + tile.x as usize;
+        row.copy_from_slice(&image_data[start..start + width]);
+    }
+    Ok(TrueColorTile::new(surface))
+}
+
+#[cfg(test)]
+mod test_sheet {
+    use super::{
+        export_sheet, import_sheet, ExportOptions, SheetCel, SheetMeta, SheetSprite, SheetTile,
+    };
+    use crate::geom_art::{Point, Size};
+    use crate::library::ArtLibrary;
+    use crate::movie::{FrameRate, Movie, MovieFrame};
+    use crate::sprite::{
+        Color, Palette, PaletteIndex, PaletteRef, Sprite, SpriteSource, Tile, TileRef, TileSurface,
+    };
+    use crate::surface::Surface as _;
+    use crate::true_color::TrueColorSurface;
+    use rgb::RGB8;
+
+    const RED: Color = Color::Opaque(RGB8 { r: 255, g: 0, b: 0 });
+    const BLUE: Color = Color::Opaque(RGB8 { r: 0, g: 0, b: 255 });
+
+    /// Creates a 4x2 sheet with a 2x2 red tile on the left and a 2x1 blue tile in the top-right corner.
+    fn create_image() -> TrueColorSurface {
+        let mut image = TrueColorSurface::new(Size::new(4, 2));
+        #[rustfmt::skip]
+        image.data_mut().copy_from_slice(&[
+            RED, RED, BLUE, BLUE,
+            RED, RED, Color::Transparent, Color::Transparent,
+        ]);
+        image
+    }
+
+    fn tile(x: u32, y: u32, width: u32, height: u32) -> SheetTile {
+        SheetTile {
+            x,
+            y,
+            width,
+            height,
+            origin: None,
+        }
+    }
+
+    fn sprite(tile: usize, x: u32, y: u32) -> SheetSprite {
+        SheetSprite {
+            tile,
+            x,
+            y,
+            h_flip: false,
+            v_flip: false,
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_import() {
+        let mut meta = SheetMeta {
+            tiles: vec![tile(0, 0, 2, 2), tile(2, 0, 2, 1)],
+            cels: vec![SheetCel {
+                sprites: vec![sprite(1, 0, 0), sprite(0, 0, 1)],
+            }],
+            animations: vec![vec![0, 0]],
+        };
+        meta.tiles[1].origin = Some(Point::new(1, 0));
+
+        let library = import_sheet(&create_image(), &meta, 16).unwrap();
+
+        let palette = &library.palettes()[0];
+        assert_eq!(3, palette.len());
+        let colors = |tile: usize| -> Vec<Color> {
+            library.tiles()[tile]
+                .surface()
+                .data()
+                .iter()
+                .map(|index| palette[*index])
+                .collect()
+        };
+        assert_eq!(vec![RED; 4], colors(0));
+        assert_eq!(vec![BLUE; 2], colors(1));
+        assert_eq!(Point::new(1, 0), library.tiles()[1].origin());
+
+        let sprites = library.cels()[0].sprites();
+        assert_eq!(TileRef::new(1), sprites[0].tile());
+        assert_eq!(TileRef::new(0), sprites[1].tile());
+        assert_eq!(PaletteRef::new(0), sprites[1].palette());
+        assert_eq!(Point::new(0, 1), sprites[1].position());
+        assert_eq!(2, library.animations()[0].as_ref().len());
+        assert_ne!(PaletteIndex::new(0), library.tiles()[0].surface().data()[0]);
+    }
+
+    #[test]
+    fn test_import_invalid() {
+        let image = create_image();
+        let meta = |tiles, cels, animations| SheetMeta {
+            tiles,
+            cels,
+            animations,
+        };
+
+        // Tile outside of the image
+        assert!(import_sheet(&image, &meta(vec![tile(3, 0, 2, 1)], vec![], vec![]), 16).is_err());
+        // Empty tile
+        assert!(import_sheet(&image, &meta(vec![tile(0, 0, 0, 1)], vec![], vec![]), 16).is_err());
+        // Unknown tile
+        let cels = vec![SheetCel {
+            sprites: vec![sprite(1, 0, 0)],
+        }];
+        assert!(import_sheet(&image, &meta(vec![tile(0, 0, 2, 2)], cels, vec![]), 16).is_err());
+        // Unknown cel
+        assert!(import_sheet(&image, &meta(vec![], vec![], vec![vec![0]]), 16).is_err());
+    }
+
+    #[test]
+    fn test_export() {
+        let mut surface = TileSurface::new(Size::new(2, 1));
+        surface.data_mut()[1] = PaletteIndex::new(1);
+        let sprite = |palette: usize, x: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(palette),
+                Point::new(x, 0),
+                false,
+                false,
+            )
+        };
+        let movie = Movie::new(
+            Size::new(256, 224),
+            vec![
+                Palette::new(vec![Color::Transparent, RED]),
+                Palette::new(vec![Color::Transparent, BLUE]),
+            ],
+            vec![Tile::new(surface)],
+            vec![
+                MovieFrame::new(10, vec![sprite(0, 0), sprite(1, 2)]),
+                MovieFrame::new(11, vec![sprite(1, 4)]),
+            ],
+            FrameRate::Ntsc,
+        );
+        let library = ArtLibrary::from_movie(&movie);
+
+        let options = ExportOptions {
+            max_width: 3,
+            include_sources: true,
+        };
+        let (image, meta) = export_sheet(&library, &options).unwrap();
+
+        // One tile per combination of tile and palette, in separate rows due to the maximum width
+        assert_eq!(Size::new(2, 2), image.size());
+        assert_eq!(
+            &[Color::Transparent, RED, Color::Transparent, BLUE],
+            image.data()
+        );
+        assert_eq!(vec![tile(0, 0, 2, 1), tile(0, 1, 2, 1)], meta.tiles);
+        assert_eq!(1, meta.cels[1].sprites[0].tile);
+        assert_eq!(
+            Some(SpriteSource {
+                frame_number: 11,
+                oam_slot: 0
+            }),
+            meta.cels[1].sprites[0].source
+        );
+
+        // The sources survive the round trip
+        let imported = import_sheet(&image, &meta, 16).unwrap();
+        assert_eq!(library.cels()[0].sources(), imported.cels()[0].sources());
+
+        let (_, meta) = export_sheet(&library, &ExportOptions::default()).unwrap();
+        assert_eq!(None, meta.cels[1].sprites[0].source);
+        assert!(export_sheet(&ArtLibrary::default(), &options).is_err());
+    }
+}
@@ -10,7 +10,9 @@
 //! objects are referred to by index. The original object can only be retrieved via a lookup into a collection, which will usually be a
 //! global cache of some sort.
 
+use crate::arena::ArenaSlice;
 use crate::geom_art::{ArtworkSpaceUnit, Point, Size};
+use crate::mask::OpacityMask;
 use crate::Surface;
 
 #[cfg_attr(
@@ -82,9 +84,15 @@ macro_rules! primitive_wrapper {
 }
 
 macro_rules! ref_type {
-    ($(#[doc = $doc:expr])* $vis:vis $name:ident < $ty:ty >) => {
+    ($(#[doc = $doc:expr])* $vis:vis $name:ident < $ty:ty >, $label:literal) => {
         primitive_wrapper!($(#[doc = $doc])* $vis $name<$ty>);
 
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, concat!($label, " #{}"), self.0)
+            }
+        }
+
         impl ves_cache::AsIndex for $name {
             fn as_index(&self) -> usize {
                 self.0
@@ -106,23 +114,134 @@ primitive_wrapper!(
 
 ref_type!(
     /// A reference to a [`Palette`].
-    pub PaletteRef<usize>
+    pub PaletteRef<usize>, "palette"
 );
 
-/// A palette of colors.
+/// A policy that determines which entries of a [`Palette`] are transparent.
+///
+/// Palettes that come from the SNES (and palettes for the VES) reserve the first entry for transparency, but some sources use a
+/// "magic" color instead.
 #[cfg_attr(
     feature = "serde_support",
     derive(serde::Serialize, serde::Deserialize)
 )]
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum TransparencyPolicy {
+    /// The first entry (index 0) is transparent, regardless of its color.
+    #[default]
+    Index0,
+    /// All entries with this color are transparent.
+    ColorKey(rgb::RGB8),
+    /// No entry is made transparent by the policy. Entries that are [`Color::Transparent`] remain transparent, though.
+    None,
+}
+
+impl TransparencyPolicy {
+    /// Applies the policy to an entry of a palette.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index of the entry.
+    /// * `color`: The color of the entry.
+    ///
+    /// returns: The effective color.
+    pub fn apply(&self, index: PaletteIndex, color: Color) -> Color {
+        match (self, color) {
+            (Self::Index0, _) if index.value() == 0 => Color::Transparent,
+            (Self::ColorKey(key), Color::Opaque(rgb)) if rgb == *key => Color::Transparent,
+            _ => color,
+        }
+    }
+}
+
+/// A palette of colors.
+///
+/// Besides the colors, a palette can carry metadata for curated libraries, like a name. The metadata is informational only: it does
+/// not take part in comparisons and hashing, such that palettes with the same colors are still considered equal (and deduplicated).
+///
+/// In human-readable formats (like JSON or RON) the metadata is omitted when it is empty. Binary formats (like bincode) use a versioned
+/// layout, which still reads palettes that have been written without metadata.
+#[derive(Clone, Debug)]
 pub struct Palette {
     colors: Vec<Color>,
+    /// The name (for instance: "Mario day").
+    name: Option<String>,
+    /// Where the palette comes from (for instance: the game or the movie it was extracted from).
+    source: Option<String>,
+    /// Whether the palette is protected against changes by the tooling.
+    locked: bool,
+}
+
+impl PartialEq for Palette {
+    fn eq(&self, other: &Self) -> bool {
+        self.colors == other.colors
+    }
+}
+
+impl Eq for Palette {}
+
+impl std::hash::Hash for Palette {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.colors.hash(state);
+    }
+}
+
+/// The human-readable form of a [`Palette`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Palette")]
+struct PaletteDef {
+    colors: Vec<Color>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    locked: bool,
+}
+
+#[cfg(feature = "serde_support")]
+crate::compat::impl_serde!(Palette, PaletteDef, |palette| (
+    &palette.colors,
+    &palette.name,
+    &palette.source,
+    &palette.locked
+));
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for Palette {
+    // The original layout starts with the number of colors
+    type Marker = u64;
+    const MARKER: u64 = u64::MAX;
+    const VERSION: u16 = 1;
+    type Fields = (Vec<Color>, Option<String>, Option<String>, bool);
+
+    fn from_fields((colors, name, source, locked): Self::Fields) -> Result<Self, String> {
+        Ok(Self {
+            colors,
+            name,
+            source,
+            locked,
+        })
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        len: u64,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        Ok(Self::new(crate::compat::next_legacy_vec(len, &mut seq)?))
+    }
 }
 
 impl Palette {
     /// Creates a new instance from a `Vec`.
     pub fn new(colors: Vec<Color>) -> Self {
-        Self { colors }
+        Self {
+            colors,
+            name: None,
+            source: None,
+            locked: false,
+        }
     }
 
     /// Creates a new instance with the specified length and default value.
@@ -131,9 +250,37 @@ impl Palette {
     /// * `length`: The number of entries.
     /// * `default`: The default value.
     pub fn new_filled(length: usize, default: Color) -> Self {
-        Self {
-            colors: vec![default; length],
-        }
+        Self::new(vec![default; length])
+    }
+
+    /// Retrieves the name.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Sets the name.
+    pub fn set_name(&mut self, name: Option<String>) {
+        self.name = name;
+    }
+
+    /// Retrieves the source.
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Sets the source.
+    pub fn set_source(&mut self, source: Option<String>) {
+        self.source = source;
+    }
+
+    /// Determines whether the palette is protected against changes by the tooling.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
+    /// Sets whether the palette is protected against changes by the tooling.
+    pub fn set_locked(&mut self, locked: bool) {
+        self.locked = locked;
     }
 }
 
@@ -148,6 +295,16 @@ impl Palette {
         self.len() == 0
     }
 
+    /// Retrieves the effective color of an entry under a [`TransparencyPolicy`].
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index of the entry.
+    /// * `policy`: The policy.
+    pub fn color(&self, index: PaletteIndex, policy: TransparencyPolicy) -> Color {
+        policy.apply(index, self[index])
+    }
+
     /// Gets an immutable iterator over all slots.
     pub fn iter(&self) -> impl Iterator<Item = (PaletteIndex, &Color)> + '_ {
         self.colors
@@ -187,7 +344,7 @@ impl std::ops::IndexMut<PaletteIndex> for Palette {
 )]
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TileSurface {
-    data: Vec<PaletteIndex>,
+    data: ArenaSlice<PaletteIndex>,
     size: Size,
 }
 
@@ -195,7 +352,7 @@ impl TileSurface {
     pub fn new(size: Size) -> Self {
         let data_len = size.width * size.height;
         Self {
-            data: vec![PaletteIndex::new(0); data_len.into()],
+            data: ArenaSlice::from_vec(vec![PaletteIndex::new(0); data_len.into()]),
             size,
         }
     }
@@ -213,25 +370,131 @@ impl Surface<ArtworkSpaceUnit> for TileSurface {
     }
 
     fn data_mut(&mut self) -> &mut [Self::DataType] {
-        self.data.as_mut_slice()
+        self.data.make_mut()
     }
 }
 
-/// A tile. This is the smallest graphical element.
+/// The logical size of a [`Tile`].
+///
+/// The animation tooling treats all tiles of the same class as the same size, regardless of the size of their surface. This allows
+/// for aligning tiles of different sizes (like a 16x16 head on a 32x32 body) when they are assembled into a [`Cel`].
 #[cfg_attr(
     feature = "serde_support",
     derive(serde::Serialize, serde::Deserialize)
 )]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SizeClass {
+    Size8x8,
+    Size16x16,
+    Size32x32,
+    Size64x64,
+}
+
+impl SizeClass {
+    /// All size classes, from small to large.
+    pub const ALL: [SizeClass; 4] = [
+        SizeClass::Size8x8,
+        SizeClass::Size16x16,
+        SizeClass::Size32x32,
+        SizeClass::Size64x64,
+    ];
+
+    /// Retrieves the logical size.
+    pub fn size(&self) -> Size {
+        match self {
+            SizeClass::Size8x8 => Size::new_square(8),
+            SizeClass::Size16x16 => Size::new_square(16),
+            SizeClass::Size32x32 => Size::new_square(32),
+            SizeClass::Size64x64 => Size::new_square(64),
+        }
+    }
+
+    /// Finds the smallest size class that fits a size.
+    ///
+    /// # Parameters
+    /// * `size`: The size.
+    ///
+    /// # Returns
+    /// The size class or `None` if the size does not fit any class.
+    pub fn fitting(size: Size) -> Option<Self> {
+        Self::ALL.into_iter().find(|class| {
+            let class_size = class.size();
+            size.width <= class_size.width && size.height <= class_size.height
+        })
+    }
+}
+
+/// A tile. This is the smallest graphical element.
+///
+/// Besides the surface, a tile can carry metadata for the animation tooling. In human-readable formats (like JSON or RON) the metadata
+/// is omitted when it is empty. Binary formats (like bincode) use a versioned layout, which still reads tiles that have been written
+/// without metadata.
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Tile {
     /// The surface.
     surface: TileSurface,
+    /// The point inside the surface at which the tile is anchored when it is assembled with other tiles.
+    origin: Option<Point>,
+    /// The logical size.
+    size_class: Option<SizeClass>,
+}
+
+/// The human-readable form of a [`Tile`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Tile")]
+struct TileDef {
+    surface: TileSurface,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin: Option<Point>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    size_class: Option<SizeClass>,
+}
+
+#[cfg(feature = "serde_support")]
+crate::compat::impl_serde!(Tile, TileDef, |tile| (
+    &tile.surface,
+    &tile.origin,
+    &tile.size_class
+));
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for Tile {
+    // The original layout starts with the length of the data of the surface
+    type Marker = u64;
+    const MARKER: u64 = u64::MAX;
+    const VERSION: u16 = 1;
+    type Fields = (TileSurface, Option<Point>, Option<SizeClass>);
+
+    fn from_fields((surface, origin, size_class): Self::Fields) -> Result<Self, String> {
+        Ok(Self {
+            surface,
+            origin,
+            size_class,
+        })
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        len: u64,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        let data = crate::compat::next_legacy_vec(len, &mut seq)?;
+        let size = crate::compat::next_legacy(&mut seq)?;
+        Ok(Self::new(TileSurface {
+            data: ArenaSlice::from_vec(data),
+            size,
+        }))
+    }
 }
 
 impl Tile {
     /// Creates a new instance.
     pub fn new(surface: TileSurface) -> Self {
-        Self { surface }
+        Self {
+            surface,
+            origin: None,
+            size_class: None,
+        }
     }
 
     pub fn surface(&self) -> &TileSurface {
@@ -241,11 +504,179 @@ impl Tile {
     pub fn surface_mut(&mut self) -> &mut TileSurface {
         &mut self.surface
     }
+
+    /// Retrieves the origin. This is the point inside the surface at which the tile is anchored when it is assembled with other
+    /// tiles. If no origin has been set, the tile is anchored at the top-left corner.
+    pub fn origin(&self) -> Point {
+        self.origin.unwrap_or_else(|| Point::new(0, 0))
+    }
+
+    /// Sets the origin (see [`Tile::origin()`]).
+    pub fn set_origin(&mut self, origin: Option<Point>) {
+        self.origin = origin;
+    }
+
+    /// Retrieves the logical size class. If no size class has been set, the smallest class that fits the surface is used.
+    pub fn size_class(&self) -> Option<SizeClass> {
+        self.size_class
+            .or_else(|| SizeClass::fitting(self.surface.size()))
+    }
+
+    /// Sets the logical size class (see [`Tile::size_class()`]).
+    pub fn set_size_class(&mut self, size_class: Option<SizeClass>) {
+        self.size_class = size_class;
+    }
+
+    /// Creates a copy of the tile that is scaled up by an integer factor. Every pixel becomes a square of `factor` by `factor` pixels
+    /// and the origin is scaled accordingly. An explicit size class is not kept, since the scaled surface does not match it anymore.
+    ///
+    /// # Arguments
+    ///
+    /// * `factor`: The factor.
+    pub fn scaled(&self, factor: u32) -> Self {
+        let scale = |point: Point| Point::new(point.x.raw() * factor, point.y.raw() * factor);
+        let size = self.surface.size();
+        let scaled_size = scale(Point::new(size.width, size.height));
+        let mut surface = TileSurface::new(Size::new(scaled_size.x, scaled_size.y));
+
+        let src = self.surface.data();
+        let (src_width, width): (usize, usize) = (size.width.into(), scaled_size.x.into());
+        // The cast is OK here, because the factor is at most the scaled width
+        let factor = factor as usize;
+        for (idx, pixel) in surface.data_mut().iter_mut().enumerate() {
+            let (x, y) = (idx % width / factor, idx / width / factor);
+            *pixel = src[y * src_width + x];
+        }
+
+        Self {
+            surface,
+            origin: self.origin.map(scale),
+            size_class: None,
+        }
+    }
+
+    /// Creates a copy of the tile that is mirrored horizontally and/or vertically. The origin is mirrored along with the surface.
+    ///
+    /// # Arguments
+    ///
+    /// * `h_flip`: Whether to mirror horizontally.
+    /// * `v_flip`: Whether to mirror vertically.
+    pub fn flipped(&self, h_flip: bool, v_flip: bool) -> Self {
+        let size = self.surface.size();
+        let (width, height) = (size.width.raw(), size.height.raw());
+        let mirror = |value: u32, len: u32, flip: bool| {
+            if flip {
+                // The origin lies inside the surface, but do not rely on it
+                len.saturating_sub(1).saturating_sub(value)
+            } else {
+                value
+            }
+        };
+
+        let mut surface = TileSurface::new(size);
+        let src = self.surface.data();
+        let (width_usize, height_usize): (usize, usize) = (size.width.into(), size.height.into());
+        for (idx, pixel) in surface.data_mut().iter_mut().enumerate() {
+            let (x, y) = (idx % width_usize, idx / width_usize);
+            let src_x = if h_flip { width_usize - 1 - x } else { x };
+            let src_y = if v_flip { height_usize - 1 - y } else { y };
+            *pixel = src[src_y * width_usize + src_x];
+        }
+
+        Self {
+            surface,
+            origin: self.origin.map(|origin| {
+                Point::new(
+                    mirror(origin.x.raw(), width, h_flip),
+                    mirror(origin.y.raw(), height, v_flip),
+                )
+            }),
+            size_class: self.size_class,
+        }
+    }
+
+    /// Determines the canonical form of the tile, which is the same for all four orientations of the same artwork. This allows for
+    /// storing a tile only once, regardless of the orientations in which it is used.
+    ///
+    /// The canonical form is the orientation with the smallest pixel data (compared by palette index, row by row). If multiple
+    /// orientations are equal (because the tile is symmetric), the one with the fewest flips is used.
+    ///
+    /// returns: The canonical form and the flips that turn the canonical form back into this tile (see [`Tile::flipped()`]).
+    pub fn canonical_form(&self) -> (Self, FlipState) {
+        FlipState::ALL
+            .into_iter()
+            .map(|flip| (self.flipped(flip.h_flip, flip.v_flip), flip))
+            .min_by(|(a, _), (b, _)| a.surface.data().cmp(b.surface.data()))
+            // There are always four candidates
+            .unwrap()
+    }
+
+    /// Creates an [`OpacityMask`] for this tile.
+    ///
+    /// The first entry in a palette is reserved for transparency, so all pixels with a [`PaletteIndex`] other than zero are opaque.
+    pub fn opacity_mask(&self) -> OpacityMask {
+        let size = self.surface.size();
+        let mut mask = OpacityMask::new(size);
+        for y in 0..size.height.raw() {
+            for x in 0..size.width.raw() {
+                let idx = usize::try_from(y * size.width.raw() + x).unwrap();
+                if self.surface.data[idx].value() != 0 {
+                    mask.set_opaque((x, y), true);
+                }
+            }
+        }
+        mask
+    }
+}
+
+/// The orientation of a [`Tile`], in the form of the flips relative to another orientation (see [`Tile::canonical_form()`]).
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct FlipState {
+    /// Whether the tile is flipped horizontally.
+    pub h_flip: bool,
+    /// Whether the tile is flipped vertically.
+    pub v_flip: bool,
+}
+
+impl FlipState {
+    /// All orientations, ordered by the number of flips.
+    pub const ALL: [FlipState; 4] = [
+        FlipState::new(false, false),
+        FlipState::new(true, false),
+        FlipState::new(false, true),
+        FlipState::new(true, true),
+    ];
+
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `h_flip`: Whether the tile is flipped horizontally.
+    /// * `v_flip`: Whether the tile is flipped vertically.
+    pub const fn new(h_flip: bool, v_flip: bool) -> Self {
+        Self { h_flip, v_flip }
+    }
+}
+
+/// Moves the pixel data of tiles into a single, contiguous arena.
+///
+/// This saves an allocation per tile and keeps the data of the tiles close together, which speeds up rendering many tiles (like during
+/// playback). The tiles remain independent: modifying the surface of a tile afterwards moves its data out of the arena again.
+///
+/// # Arguments
+///
+/// * `tiles`: The tiles.
+pub fn pack_tiles(tiles: &mut [Tile]) {
+    crate::arena::pack(tiles.iter_mut().map(|tile| &mut tile.surface.data));
 }
 
 ref_type!(
     /// A reference to a [`Tile`].
-    pub TileRef<usize>
+    pub TileRef<usize>, "tile"
 );
 
 /// A sprite. This is basically a [`Tile`] inside a container (like a [`Cel`]) with some extra properties like position and flipping flags.
@@ -294,36 +725,213 @@ impl Sprite {
         self.palette
     }
 
+    /// Sets the [`PaletteRef`].
+    pub fn set_palette(&mut self, palette: PaletteRef) {
+        self.palette = palette;
+    }
+
     /// Retrieves the position.
     pub fn position(&self) -> Point {
         self.position
     }
 
+    /// Sets the position.
+    pub fn set_position(&mut self, position: Point) {
+        self.position = position;
+    }
+
     /// Retrieves the horizontal-flip flag.
     pub fn h_flip(&self) -> bool {
         self.h_flip
     }
 
+    /// Sets the horizontal-flip flag.
+    pub fn set_h_flip(&mut self, h_flip: bool) {
+        self.h_flip = h_flip;
+    }
+
     /// Retrieves the vertical-flip flag.
     pub fn v_flip(&self) -> bool {
         self.v_flip
     }
+
+    /// Sets the vertical-flip flag.
+    pub fn set_v_flip(&mut self, v_flip: bool) {
+        self.v_flip = v_flip;
+    }
 }
 
-/// A cel. This is a composition of zero or more [`Sprite`]s that together form one image.
+/// The origin of a [`Sprite`] in a captured movie. This allows for tracing artwork back to the original emulator frame, for instance
+/// to capture it again.
 #[cfg_attr(
     feature = "serde_support",
     derive(serde::Serialize, serde::Deserialize)
 )]
-#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SpriteSource {
+    /// The number of the frame in the movie (see [`MovieFrame::frame_number()`](crate::movie::MovieFrame::frame_number)).
+    pub frame_number: u64,
+    /// The OAM slot of the sprite in that frame.
+    pub oam_slot: usize,
+}
+
+/// A cel. This is a composition of zero or more [`Sprite`]s that together form one image.
+///
+/// A cel can keep track of the [`SpriteSource`] of its sprites. The sources are informational only: they do not take part in
+/// comparisons and hashing, such that identical cels from different frames are still considered equal.
+#[derive(Clone, Debug)]
 pub struct Cel {
     /// The sprites.
     sprites: Vec<Sprite>,
+    /// The sources of the sprites, in the same order as the sprites. This is empty if the sources are unknown.
+    sources: Vec<SpriteSource>,
+}
+
+impl PartialEq for Cel {
+    fn eq(&self, other: &Self) -> bool {
+        self.sprites == other.sprites
+    }
+}
+
+impl Eq for Cel {}
+
+impl std::hash::Hash for Cel {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sprites.hash(state);
+    }
+}
+
+/// The human-readable form of a [`Cel`].
+#[cfg(feature = "serde_support")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(remote = "Cel")]
+struct CelDef {
+    sprites: Vec<Sprite>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sources: Vec<SpriteSource>,
+}
+
+#[cfg(feature = "serde_support")]
+crate::compat::impl_serde!(Cel, CelDef, |cel| (&cel.sprites, &cel.sources));
+
+#[cfg(feature = "serde_support")]
+impl crate::compat::BinaryLayout for Cel {
+    // The original layout starts with the number of sprites
+    type Marker = u64;
+    const MARKER: u64 = u64::MAX;
+    const VERSION: u16 = 1;
+    type Fields = (Vec<Sprite>, Vec<SpriteSource>);
+
+    fn from_fields((sprites, sources): Self::Fields) -> Result<Self, String> {
+        let mut cel = Self::new(sprites);
+        cel.set_sources(sources)?;
+        Ok(cel)
+    }
+
+    fn from_legacy<'de, A: serde::de::SeqAccess<'de>>(
+        len: u64,
+        mut seq: A,
+    ) -> Result<Self, A::Error> {
+        Ok(Self::new(crate::compat::next_legacy_vec(len, &mut seq)?))
+    }
+}
+
+impl Cel {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `sprites`: The sprites.
+    pub fn new(sprites: Vec<Sprite>) -> Self {
+        Self {
+            sprites,
+            sources: Vec::new(),
+        }
+    }
+
+    /// Retrieves the sprites.
+    pub fn sprites(&self) -> &[Sprite] {
+        &self.sprites
+    }
+
+    /// Retrieves the sources of the sprites, in the same order as the sprites. This is empty if the sources are unknown.
+    pub fn sources(&self) -> &[SpriteSource] {
+        &self.sources
+    }
+
+    /// Sets the sources of the sprites.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources`: The sources, in the same order as the sprites. This must either be empty or contain one source per sprite.
+    pub fn set_sources(&mut self, sources: Vec<SpriteSource>) -> Result<(), String> {
+        if !sources.is_empty() && sources.len() != self.sprites.len() {
+            return Err(format!(
+                "Expected {} sprite sources, but got {}.",
+                self.sprites.len(),
+                sources.len()
+            ));
+        }
+        self.sources = sources;
+        Ok(())
+    }
+
+    /// Creates an [`OpacityMask`] for this cel.
+    ///
+    /// The mask starts at the origin of the cel and covers all of its sprites.
+    ///
+    /// # Arguments
+    ///
+    /// * `tiles`: The tiles.
+    pub fn opacity_mask(
+        &self,
+        tiles: &impl std::ops::Index<TileRef, Output = Tile>,
+    ) -> OpacityMask {
+        let tile_masks: Vec<(&Sprite, OpacityMask)> = self
+            .sprites
+            .iter()
+            .map(|sprite| (sprite, tiles[sprite.tile()].opacity_mask()))
+            .collect();
+
+        let mut width = 0;
+        let mut height = 0;
+        for (sprite, tile_mask) in &tile_masks {
+            let pos = sprite.position();
+            let size = tile_mask.size();
+            width = u32::max(width, (pos.x + size.width).raw());
+            height = u32::max(height, (pos.y + size.height).raw());
+        }
+
+        let mut mask = OpacityMask::new(Size::new(width, height));
+        for (sprite, tile_mask) in tile_masks {
+            let pos = sprite.position();
+            let size = tile_mask.size();
+            for y in 0..size.height.raw() {
+                for x in 0..size.width.raw() {
+                    if !tile_mask.is_opaque((x, y)) {
+                        continue;
+                    }
+                    let dest_x = if sprite.h_flip() {
+                        size.width.raw() - 1 - x
+                    } else {
+                        x
+                    };
+                    let dest_y = if sprite.v_flip() {
+                        size.height.raw() - 1 - y
+                    } else {
+                        y
+                    };
+                    mask.set_opaque((pos.x.raw() + dest_x, pos.y.raw() + dest_y), true);
+                }
+            }
+        }
+        mask
+    }
 }
 
 ref_type!(
     /// A reference to a [`Cel`].
-    pub CelRef<usize>
+    pub CelRef<usize>, "cel"
 );
 
 /// A single frame in an [`Animation`].
@@ -345,6 +953,11 @@ impl AnimationFrame {
     pub fn new(cel: CelRef) -> Self {
         Self { cel }
     }
+
+    /// Retrieves the [`CelRef`].
+    pub fn cel(&self) -> CelRef {
+        self.cel
+    }
 }
 
 /// An animation. This is a sequence of [`AnimationFrame`]s.
@@ -358,7 +971,7 @@ pub struct Animation {
 }
 
 impl AsRef<Vec<AnimationFrame>> for Animation {
-    fn as_ref(& self) -> &Vec<AnimationFrame> {
+    fn as_ref(&self) -> &Vec<AnimationFrame> {
         &self.frames
     }
 }
@@ -408,9 +1021,31 @@ mod test_palette_index {
     }
 }
 
+#[cfg(test)]
+mod test_refs {
+    use super::{CelRef, PaletteRef, TileRef};
+
+    #[test]
+    fn test_display() {
+        assert_eq!("palette #3", PaletteRef::new(3).to_string());
+        assert_eq!("tile #0", TileRef::new(0).to_string());
+        assert_eq!("cel #12", CelRef::new(12).to_string());
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut refs = vec![TileRef::new(5), TileRef::new(1), TileRef::new(3)];
+        refs.sort();
+        assert_eq!(
+            vec![TileRef::new(1), TileRef::new(3), TileRef::new(5)],
+            refs
+        );
+    }
+}
+
 #[cfg(test)]
 mod test_palette {
-    use super::{Color, Palette};
+    use super::{Color, Palette, PaletteIndex, TransparencyPolicy};
 
     macro_rules! assert_eq_colors {
         ($pal:ident, $($col:expr),*) => {
@@ -457,4 +1092,299 @@ mod test_palette {
         let result = super::catch_unwind_silent(move || pal[4u8.into()]);
         assert!(result.is_err());
     }
+    #[test]
+    fn test_metadata() {
+        let mut pal = Palette::new_filled(2, Color::Transparent);
+        assert_eq!(None, pal.name());
+        assert_eq!(None, pal.source());
+        assert!(!pal.is_locked());
+
+        pal.set_name(Some("Mario day".to_string()));
+        pal.set_source(Some("yoshi_run".to_string()));
+        pal.set_locked(true);
+        assert_eq!(Some("Mario day"), pal.name());
+        assert_eq!(Some("yoshi_run"), pal.source());
+        assert!(pal.is_locked());
+    }
+
+    #[test]
+    fn test_eq_ignores_metadata() {
+        use super::PaletteRef;
+        use std::borrow::Cow;
+        use std::hash::{BuildHasher as _, RandomState};
+
+        let plain = Palette::new_filled(2, Color::Transparent);
+        let mut named = plain.clone();
+        named.set_name(Some("Mario day".to_string()));
+        named.set_locked(true);
+        assert_eq!(plain, named);
+
+        let hasher = RandomState::new();
+        assert_eq!(hasher.hash_one(&plain), hasher.hash_one(&named));
+
+        let mut cache = ves_cache::VecCacheMut::<Palette, PaletteRef>::new();
+        let first = cache.offer(Cow::Owned(plain));
+        assert_eq!(first, cache.offer(Cow::Owned(named)));
+        assert_eq!(1, cache.len());
+    }
+
+    #[test]
+    fn test_transparency_policy() {
+        let magenta = Color::new(255, 0, 255);
+        let red = Color::new(255, 0, 0);
+        let pal = Palette::new(vec![red, magenta, Color::Transparent]);
+        let colors = |policy| -> Vec<Color> {
+            (0..3)
+                .map(|index| pal.color(PaletteIndex::new(index), policy))
+                .collect()
+        };
+
+        assert_eq!(TransparencyPolicy::Index0, TransparencyPolicy::default());
+        assert_eq!(
+            vec![Color::Transparent, magenta, Color::Transparent],
+            colors(TransparencyPolicy::Index0)
+        );
+        assert_eq!(
+            vec![red, Color::Transparent, Color::Transparent],
+            colors(TransparencyPolicy::ColorKey(rgb::RGB8::new(255, 0, 255)))
+        );
+        assert_eq!(
+            vec![red, magenta, Color::Transparent],
+            colors(TransparencyPolicy::None)
+        );
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_serde_metadata() {
+        let plain = Palette::new(vec![Color::Transparent]);
+        let mut named = plain.clone();
+        named.set_name(Some("Mario underwater".to_string()));
+
+        // Empty metadata is omitted in human-readable formats
+        assert_eq!(
+            r#"{"colors":["Transparent"]}"#,
+            serde_json::to_string(&plain).unwrap()
+        );
+        assert_eq!(
+            r#"{"colors":["Transparent"],"name":"Mario underwater"}"#,
+            serde_json::to_string(&named).unwrap()
+        );
+        for palette in [&plain, &named] {
+            let json = serde_json::to_string(palette).unwrap();
+            assert_eq!(palette, &serde_json::from_str::<Palette>(&json).unwrap());
+            let data = bincode::serialize(palette).unwrap();
+            let deserialized = bincode::deserialize::<Palette>(&data).unwrap();
+            assert_eq!(palette, &deserialized);
+            assert_eq!(palette.name(), deserialized.name());
+        }
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_original_layout() {
+        // Palettes used to be written as only the colors
+        let colors = vec![Color::Transparent; 4];
+        let data = bincode::serialize(&colors).unwrap();
+        assert_eq!(
+            Palette::new(colors),
+            bincode::deserialize::<Palette>(&data).unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_tile {
+    use super::{FlipState, PaletteIndex, SizeClass, Tile, TileSurface};
+    use crate::geom_art::{Point, Size};
+    use crate::surface::Surface as _;
+
+    /// Creates a tile with the provided pixels, row by row.
+    fn tile(width: u32, pixels: &[u8]) -> Tile {
+        let height = pixels.len() as u32 / width;
+        let mut surface = TileSurface::new(Size::new(width, height));
+        for (pixel, value) in surface.data_mut().iter_mut().zip(pixels) {
+            *pixel = PaletteIndex::new(*value);
+        }
+        Tile::new(surface)
+    }
+
+    #[test]
+    fn test_flipped() {
+        let mut original = tile(3, &[1, 2, 3, 4, 5, 6]);
+        original.set_origin(Some(Point::new(0, 1)));
+        original.set_size_class(Some(SizeClass::Size16x16));
+
+        let mut expected = tile(3, &[3, 2, 1, 6, 5, 4]);
+        expected.set_origin(Some(Point::new(2, 1)));
+        expected.set_size_class(Some(SizeClass::Size16x16));
+        assert_eq!(expected, original.flipped(true, false));
+
+        let mut expected = tile(3, &[4, 5, 6, 1, 2, 3]);
+        expected.set_origin(Some(Point::new(0, 0)));
+        expected.set_size_class(Some(SizeClass::Size16x16));
+        assert_eq!(expected, original.flipped(false, true));
+
+        let mut expected = tile(3, &[6, 5, 4, 3, 2, 1]);
+        expected.set_origin(Some(Point::new(2, 0)));
+        expected.set_size_class(Some(SizeClass::Size16x16));
+        assert_eq!(expected, original.flipped(true, true));
+
+        assert_eq!(original, original.flipped(false, false));
+    }
+
+    #[test]
+    fn test_canonical_form() {
+        // Non-square tiles in both directions, with pixels that are all different
+        for original in [tile(3, &[1, 2, 3, 4, 5, 6]), tile(2, &[6, 1, 5, 2, 4, 3])] {
+            let (canonical, _) = original.canonical_form();
+            for flip in FlipState::ALL {
+                let oriented = original.flipped(flip.h_flip, flip.v_flip);
+                let (oriented_canonical, oriented_flip) = oriented.canonical_form();
+
+                // All orientations share the same canonical form, which can be turned back into the orientation
+                assert_eq!(canonical, oriented_canonical);
+                assert_eq!(
+                    oriented,
+                    canonical.flipped(oriented_flip.h_flip, oriented_flip.v_flip)
+                );
+            }
+        }
+
+        assert_eq!(
+            (tile(3, &[1, 2, 3, 4, 5, 6]), FlipState::new(true, true)),
+            tile(3, &[6, 5, 4, 3, 2, 1]).canonical_form()
+        );
+    }
+
+    #[test]
+    fn test_canonical_form_symmetric() {
+        // Symmetric tiles keep the orientation with the fewest flips
+        let symmetric = tile(3, &[2, 1, 2, 2, 1, 2]);
+        assert_eq!(
+            (symmetric.clone(), FlipState::default()),
+            symmetric.canonical_form()
+        );
+
+        let h_symmetric = tile(2, &[3, 3, 1, 1, 2, 2]);
+        assert_eq!(
+            (tile(2, &[2, 2, 1, 1, 3, 3]), FlipState::new(false, true)),
+            h_symmetric.canonical_form()
+        );
+    }
+
+    #[test]
+    fn test_size_class_fitting() {
+        assert_eq!(
+            Some(SizeClass::Size8x8),
+            SizeClass::fitting(Size::new(8, 8))
+        );
+        assert_eq!(
+            Some(SizeClass::Size16x16),
+            SizeClass::fitting(Size::new(9, 4))
+        );
+        assert_eq!(
+            Some(SizeClass::Size64x64),
+            SizeClass::fitting(Size::new(16, 64))
+        );
+        assert_eq!(None, SizeClass::fitting(Size::new(65, 1)));
+    }
+
+    #[test]
+    fn test_metadata() {
+        let mut tile = Tile::new(TileSurface::new(Size::new(12, 16)));
+        assert_eq!(Point::new(0, 0), tile.origin());
+        assert_eq!(Some(SizeClass::Size16x16), tile.size_class());
+
+        tile.set_origin(Some(Point::new(6, 15)));
+        tile.set_size_class(Some(SizeClass::Size32x32));
+        assert_eq!(Point::new(6, 15), tile.origin());
+        assert_eq!(Some(SizeClass::Size32x32), tile.size_class());
+        assert_ne!(Tile::new(TileSurface::new(Size::new(12, 16))), tile);
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_serde_metadata() {
+        let plain = Tile::new(TileSurface::new(Size::new(1, 1)));
+        let mut anchored = plain.clone();
+        anchored.set_origin(Some(Point::new(0, 1)));
+
+        // Empty metadata is omitted in human-readable formats
+        let json = serde_json::to_string(&plain).unwrap();
+        assert!(!json.contains("origin"));
+        assert!(!json.contains("size_class"));
+        assert!(serde_json::to_string(&anchored)
+            .unwrap()
+            .contains(r#""origin":{"x":0,"y":1}"#));
+
+        for tile in [&plain, &anchored] {
+            let json = serde_json::to_string(tile).unwrap();
+            assert_eq!(tile, &serde_json::from_str::<Tile>(&json).unwrap());
+            let data = bincode::serialize(tile).unwrap();
+            assert_eq!(tile, &bincode::deserialize::<Tile>(&data).unwrap());
+        }
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_original_layout() {
+        // Tiles used to be written as only the surface
+        let original = tile(2, &[1, 2, 3, 4, 5, 6]);
+        let surface = original.surface();
+        let data = bincode::serialize(&(surface.data(), surface.size())).unwrap();
+        assert_eq!(original, bincode::deserialize::<Tile>(&data).unwrap());
+    }
+
+    #[cfg(feature = "serde_support")]
+    #[test]
+    fn test_deserialize_unsupported_version() {
+        let mut data = bincode::serialize(&tile(1, &[1])).unwrap();
+        // The version follows the marker
+        data[8..10].copy_from_slice(&2u16.to_le_bytes());
+        assert!(bincode::deserialize::<Tile>(&data).is_err());
+    }
+}
+
+#[cfg(all(test, feature = "serde_support"))]
+mod test_cel {
+    use super::{Cel, PaletteRef, Sprite, SpriteSource, TileRef};
+
+    fn sprites() -> Vec<Sprite> {
+        vec![Sprite::new(
+            TileRef::new(1),
+            PaletteRef::new(0),
+            (0, 0).into(),
+            false,
+            true,
+        )]
+    }
+
+    #[test]
+    fn test_deserialize_original_layout() {
+        // Cels used to be written as only the sprites
+        let data = bincode::serialize(&sprites()).unwrap();
+        let cel = bincode::deserialize::<Cel>(&data).unwrap();
+        assert_eq!(sprites(), cel.sprites());
+        assert!(cel.sources().is_empty());
+    }
+
+    #[test]
+    fn test_deserialize_sources() {
+        let source = SpriteSource {
+            frame_number: 3,
+            oam_slot: 12,
+        };
+        let mut cel = Cel::new(sprites());
+        cel.set_sources(vec![source]).unwrap();
+        let data = bincode::serialize(&cel).unwrap();
+        assert_eq!(
+            &[source],
+            bincode::deserialize::<Cel>(&data).unwrap().sources()
+        );
+
+        // The number of sources must match the number of sprites
+        let invalid = bincode::serialize(&(u64::MAX, 1u16, sprites(), vec![source; 2])).unwrap();
+        assert!(bincode::deserialize::<Cel>(&invalid).is_err());
+    }
 }
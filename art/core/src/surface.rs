@@ -1,10 +1,14 @@
 //! A module for working with 2-dimensional surfaces.
 
 use std::fmt::Debug;
-use std::ops::{Add, Rem, Sub};
+use std::marker::PhantomData;
+use std::ops::{Add, Mul, Rem, Sub};
 use ves_geom::{FiniteRange, One, Point, Rect, Size};
 
 /// A 2-dimensional surface.
+///
+/// This trait is object safe, such that different kinds of surfaces can be stored in one collection (for instance:
+/// `Vec<Box<dyn Surface<T, DataType = D>>>`). Use [`OffsetSurface`] for trait objects that also need [`Offset`].
 pub trait Surface<T> {
     type DataType;
 
@@ -24,7 +28,157 @@ pub trait Offset {
     type Input;
 
     /// Returns the offset for the provided value.
-    fn offset(&self, value: impl Into<Self::Input>) -> Option<usize>;
+    ///
+    /// This is a convenience wrapper around [`Offset::offset_of()`], which is not available on trait objects.
+    #[inline(always)]
+    fn offset(&self, value: impl Into<Self::Input>) -> Option<usize>
+    where
+        Self: Sized,
+    {
+        self.offset_of(value.into())
+    }
+
+    /// Returns the offset for the provided value.
+    fn offset_of(&self, value: Self::Input) -> Option<usize>;
+}
+
+/// A [`Surface`] that implements [`Offset`] for its points.
+///
+/// This trait is implemented for all such surfaces and allows for using both traits through a single trait object (for instance:
+/// `&dyn OffsetSurface<T, DataType = D>`).
+pub trait OffsetSurface<T>: Surface<T> + Offset<Input = Point<T>> {}
+
+impl<T, S> OffsetSurface<T> for S where S: Surface<T> + Offset<Input = Point<T>> + ?Sized {}
+
+/// A [`Surface`] that stores its data on the heap.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VecSurface<D, T = crate::geom_art::ArtworkSpaceUnit> {
+    size: Size<T>,
+    data: Vec<D>,
+}
+
+impl<D, T> VecSurface<D, T>
+where
+    D: Clone,
+    T: Copy + Mul<Output = T> + Into<usize>,
+{
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `size`: The size.
+    /// * `value`: The initial value for every element.
+    pub fn new(size: Size<T>, value: D) -> Self {
+        let len: usize = (size.width * size.height).into();
+        Self {
+            size,
+            data: vec![value; len],
+        }
+    }
+}
+
+impl<D, T> Surface<T> for VecSurface<D, T>
+where
+    T: Copy,
+{
+    type DataType = D;
+
+    fn size(&self) -> Size<T> {
+        self.size
+    }
+
+    fn data(&self) -> &[Self::DataType] {
+        &self.data
+    }
+
+    fn data_mut(&mut self) -> &mut [Self::DataType] {
+        &mut self.data
+    }
+}
+
+impl<D, T> Offset for VecSurface<D, T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Mul<Output = T> + Into<usize>,
+{
+    type Input = Point<T>;
+
+    fn offset_of(&self, value: Self::Input) -> Option<usize> {
+        if value.x >= self.size.width || value.y >= self.size.height {
+            None
+        } else {
+            Some((value.y * self.size.width + value.x).into())
+        }
+    }
+}
+
+/// A [`Surface`] of `W`x`H` pixels that stores its data in an array, such that it does not require any allocation.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArraySurface<D, const W: usize, const H: usize, T = crate::geom_art::ArtworkSpaceUnit> {
+    data: [[D; W]; H],
+    unit: PhantomData<T>,
+}
+
+impl<D, const W: usize, const H: usize, T> ArraySurface<D, W, H, T>
+where
+    D: Copy,
+{
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `value`: The initial value for every element.
+    pub fn new(value: D) -> Self {
+        Self {
+            data: [[value; W]; H],
+            unit: PhantomData,
+        }
+    }
+}
+
+impl<D, const W: usize, const H: usize, T> Default for ArraySurface<D, W, H, T>
+where
+    D: Copy + Default,
+{
+    fn default() -> Self {
+        Self::new(D::default())
+    }
+}
+
+impl<D, const W: usize, const H: usize, T> Surface<T> for ArraySurface<D, W, H, T>
+where
+    T: From<u32>,
+{
+    type DataType = D;
+
+    #[inline(always)]
+    fn size(&self) -> Size<T> {
+        Size::new(W as u32, H as u32)
+    }
+
+    #[inline(always)]
+    fn data(&self) -> &[Self::DataType] {
+        self.data.as_flattened()
+    }
+
+    #[inline(always)]
+    fn data_mut(&mut self) -> &mut [Self::DataType] {
+        self.data.as_flattened_mut()
+    }
+}
+
+impl<D, const W: usize, const H: usize, T> Offset for ArraySurface<D, W, H, T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Mul<Output = T> + Into<usize> + From<u32>,
+{
+    type Input = Point<T>;
+
+    #[inline(always)]
+    fn offset_of(&self, value: Self::Input) -> Option<usize> {
+        let size = self.size();
+        if value.x >= size.width || value.y >= size.height {
+            None
+        } else {
+            Some((value.y * size.width + value.x).into())
+        }
+    }
 }
 
 /// An [`Iterator`] factory for index offsets of a [`Surface`] axis (x or y).
@@ -43,19 +197,6 @@ pub trait SurfaceAxisIterFactory<T> {
     fn new_iter(min: T, max: T, limit: T) -> Result<Self::IterType, String>;
 }
 
-fn check_min_max<T>(min: T, max: T) -> Result<(), String>
-where
-    T: Copy + PartialEq + PartialOrd,
-{
-    if min == max {
-        Err(String::from("Min and max are equal."))
-    } else if min > max {
-        Err(String::from("Min is greater than max."))
-    } else {
-        Ok(())
-    }
-}
-
 /// A [`SurfaceAxisIterFactory`] with ascending iteration order and in which bounds are not checked.
 struct AscendingUnchecked;
 
@@ -66,8 +207,7 @@ where
     type IterType = FiniteRange<T>;
 
     fn new_iter(min: T, max: T, _limit: T) -> Result<Self::IterType, String> {
-        check_min_max(min, max)?;
-        Ok(FiniteRange::new(min, max))
+        FiniteRange::try_new(min, max).map_err(|_| String::from("Min is greater than max."))
     }
 }
 
@@ -745,3 +885,42 @@ mod test_fn_surface_iterate_2;
 
 #[cfg(test)]
 mod test_surface_iter;
+
+#[cfg(test)]
+mod test_trait_objects {
+    use super::{ArraySurface, OffsetSurface, Surface, VecSurface};
+    use crate::geom_art::{ArtworkSpaceUnit, Point, Size};
+    use crate::sprite::{PaletteIndex, TileSurface};
+
+    #[test]
+    fn test_surface() {
+        let surfaces: Vec<Box<dyn Surface<ArtworkSpaceUnit, DataType = PaletteIndex>>> = vec![
+            Box::new(TileSurface::new(Size::new(8, 16))),
+            Box::new(ArraySurface::<_, 4, 2>::new(PaletteIndex::new(1))),
+            Box::new(VecSurface::new(Size::new(3, 3), PaletteIndex::new(2))),
+        ];
+
+        let sizes: Vec<_> = surfaces.iter().map(|surface| surface.size()).collect();
+        assert_eq!(
+            vec![Size::new(8, 16), Size::new(4, 2), Size::new(3, 3)],
+            sizes
+        );
+        assert_eq!(PaletteIndex::new(2), surfaces[2].data()[8]);
+    }
+
+    #[test]
+    fn test_offset_surface() {
+        let mut array = ArraySurface::<u8, 4, 2>::new(0);
+        let mut vec = VecSurface::new(Size::new(3, 3), 0u8);
+        let surfaces: [&mut dyn OffsetSurface<ArtworkSpaceUnit, DataType = u8>; 2] =
+            [&mut array, &mut vec];
+
+        for surface in surfaces {
+            let offset = surface.offset_of(Point::new(1, 1)).unwrap();
+            surface.data_mut()[offset] = 7;
+            assert_eq!(None, surface.offset_of(Point::new(5, 0)));
+        }
+        assert_eq!(7, array.data()[5]);
+        assert_eq!(7, vec.data()[4]);
+    }
+}
@@ -1,10 +1,10 @@
 //! Unit tests for `surface_iterate_2()`.
 
 use super::surface_iterate_2;
-use super::Surface;
-use crate::geom_art::{ArtworkSpaceUnit, Point, Rect};
+use super::{ArraySurface, Surface};
+use crate::geom_art::{Point, Rect};
 
-crate::sized_surface!(Surfy, u8, ArtworkSpaceUnit, 12, 8, 0);
+type Surfy = ArraySurface<u8, 12, 8>;
 
 macro_rules! data {
         ($($elt:expr)*) => {
@@ -34,7 +34,7 @@ const EMPTY_DATA: [u8; 12 * 8] = data![
 ];
 
 fn create_source() -> Surfy {
-    let mut src = Surfy::new();
+    let mut src = Surfy::new(0);
     assert_eq!(&EMPTY_DATA, src.data());
 
     src.data_mut().copy_from_slice(&SOURCE_DATA);
@@ -87,7 +87,7 @@ fn copy_data(
 #[test]
 fn test_full_copy_no_flip() {
     let src = create_source();
-    let mut dest = Surfy::new();
+    let mut dest = Surfy::new(0);
     let src_spec = source_spec!(Rect::new_from_size((0, 0), src.size()));
     let dest_point = (0, 0).into();
     copy_data(&src, &mut dest, src_spec, dest_point);
@@ -110,7 +110,7 @@ fn test_full_copy_hflip() {
     ];
 
     let src = create_source();
-    let mut dest = Surfy::new();
+    let mut dest = Surfy::new(0);
     let src_spec = source_spec!(Rect::new_from_size((0, 0), src.size()), @hflip);
     let dest_point = (0, 0).into();
     copy_data(&src, &mut dest, src_spec, dest_point);
@@ -133,7 +133,7 @@ fn test_full_copy_vflip() {
     ];
 
     let src = create_source();
-    let mut dest = Surfy::new();
+    let mut dest = Surfy::new(0);
     let src_spec = source_spec!(Rect::new_from_size((0, 0), src.size()), @vflip);
     let dest_point = (0, 0).into();
     copy_data(&src, &mut dest, src_spec, dest_point);
@@ -156,7 +156,7 @@ fn test_full_copy_hflip_vflip() {
     ];
 
     let src = create_source();
-    let mut dest = Surfy::new();
+    let mut dest = Surfy::new(0);
     let src_spec = source_spec!(Rect::new_from_size((0, 0), src.size()), @hflip, @vflip);
     let dest_point = (0, 0).into();
     copy_data(&src, &mut dest, src_spec, dest_point);
@@ -181,7 +181,7 @@ fn test_partial_no_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))));
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -203,7 +203,7 @@ fn test_partial_no_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))), @hflip);
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -225,7 +225,7 @@ fn test_partial_no_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))), @vflip);
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -247,7 +247,7 @@ fn test_partial_no_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))), @hflip, @vflip);
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -273,7 +273,7 @@ fn test_partial_h_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((10, 4), (13, 7))));
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -295,7 +295,7 @@ fn test_partial_h_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))));
         let dest_point = (10, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -321,7 +321,7 @@ fn test_partial_v_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 6), (4, 9))));
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -343,7 +343,7 @@ fn test_partial_v_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))));
         let dest_point = (6, 6).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -369,7 +369,7 @@ fn test_partial_hv_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((10, 6), (13, 9))));
         let dest_point = (6, 3).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -391,7 +391,7 @@ fn test_partial_hv_wrap() {
         ];
 
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_spec = source_spec!(Rect::from(((1, 4), (4, 7))));
         let dest_point = (10, 6).into();
         copy_data(&src, &mut dest, src_spec, dest_point);
@@ -1,9 +1,9 @@
 //! Unit tests for `SurfaceIter`.
 
 use crate::geom_art::{ArtworkSpaceUnit, Rect};
-use crate::surface::Surface;
+use crate::surface::{ArraySurface, Surface};
 
-crate::sized_surface!(Surfy, u8, ArtworkSpaceUnit, 12, 8, 0);
+type Surfy = ArraySurface<u8, 12, 8>;
 
 macro_rules! data {
         ($($elt:expr)*) => {
@@ -88,7 +88,7 @@ fn copy_data(
 }
 
 fn create_source() -> Surfy {
-    let mut src = Surfy::new();
+    let mut src = Surfy::new(0);
     assert_eq!(&EMPTY_DATA, src.data());
 
     src.data_mut().copy_from_slice(&SOURCE_DATA);
@@ -102,7 +102,7 @@ fn test_full_copy_no_flip() {
     // No flipping
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()));
         let dest_iter = surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()));
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -112,7 +112,7 @@ fn test_full_copy_no_flip() {
     // H-flip on both
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @hflip);
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @hflip);
@@ -123,7 +123,7 @@ fn test_full_copy_no_flip() {
     // V-flip on both
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @vflip);
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @vflip);
@@ -134,7 +134,7 @@ fn test_full_copy_no_flip() {
     // H-flip and v-flip on both
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter =
             surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @hflip, @vflip);
         let dest_iter =
@@ -161,7 +161,7 @@ fn test_full_copy_hflip() {
     // H-flip on src
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @hflip);
         let dest_iter = surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()));
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -171,7 +171,7 @@ fn test_full_copy_hflip() {
     // H-flip on dest
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()));
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @hflip);
@@ -197,7 +197,7 @@ fn test_full_copy_vflip() {
     // V-flip on src
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @vflip);
         let dest_iter = surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()));
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -207,7 +207,7 @@ fn test_full_copy_vflip() {
     // V-flip on dest
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()));
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @vflip);
@@ -233,7 +233,7 @@ fn test_full_copy_hflip_vflip() {
     // H-flip and v-flip on src
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter =
             surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @hflip, @vflip);
         let dest_iter = surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()));
@@ -244,7 +244,7 @@ fn test_full_copy_hflip_vflip() {
     // H-flip and v-flip on dest
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()));
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @hflip, @vflip);
@@ -255,7 +255,7 @@ fn test_full_copy_hflip_vflip() {
     // H-flip on src and v-flip on dest
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @hflip);
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @vflip);
@@ -266,7 +266,7 @@ fn test_full_copy_hflip_vflip() {
     // H-flip on dest and v-flip on src
     {
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), Rect::new_from_size((0, 0), src.size()), @vflip);
         let dest_iter =
             surface_iter!(dest.size(), Rect::new_from_size((0, 0), dest.size()), @hflip);
@@ -291,7 +291,7 @@ fn test_partial_no_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into());
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -311,7 +311,7 @@ fn test_partial_no_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into(), @hflip);
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -331,7 +331,7 @@ fn test_partial_no_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into(), @vflip);
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -351,7 +351,7 @@ fn test_partial_no_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into(), @hflip, @vflip);
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -375,7 +375,7 @@ fn test_partial_h_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((10, 4), (13, 7)).into());
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -395,7 +395,7 @@ fn test_partial_h_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into());
         let dest_iter = surface_iter!(dest.size(), ((10, 3), (13, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -419,7 +419,7 @@ fn test_partial_v_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 6), (4, 9)).into());
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -439,7 +439,7 @@ fn test_partial_v_wrap() {
             0 0 0 0 0 0 2 2 3 3 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into());
         let dest_iter = surface_iter!(dest.size(), ((6, 6), (9, 9)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -463,7 +463,7 @@ fn test_partial_hv_wrap() {
             0 0 0 0 0 0 0 0 0 0 0 0
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((10, 6), (13, 9)).into());
         let dest_iter = surface_iter!(dest.size(), ((6, 3), (9, 6)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
@@ -483,10 +483,29 @@ fn test_partial_hv_wrap() {
             3 3 0 0 0 0 0 0 0 0 2 2
         ];
         let src = create_source();
-        let mut dest = Surfy::new();
+        let mut dest = Surfy::new(0);
         let src_iter = surface_iter!(src.size(), ((1, 4), (4, 7)).into());
         let dest_iter = surface_iter!(dest.size(), ((10, 6), (13, 9)).into());
         copy_data(&src, &mut dest, src_iter, dest_iter);
         assert_eq!(&EXPECTED, dest.data());
     }
 }
+
+/// Test with a selection that is a single column or a single row wide.
+#[test]
+fn test_single_line() {
+    let src = create_source();
+    let src_data = src.data();
+
+    // Column 4, rows 2 to 5
+    let column: Vec<u8> = surface_iter!(src.size(), ((4, 2), (4, 5)).into())
+        .map(|idx| src_data[idx])
+        .collect();
+    assert_eq!(vec![2, 3, 2, 3], column);
+
+    // Row 7, columns 2 to 5, backwards
+    let row: Vec<u8> = surface_iter!(src.size(), ((2, 7), (5, 7)).into(), @hflip)
+        .map(|idx| src_data[idx])
+        .collect();
+    assert_eq!(vec![1, 4, 1, 1], row);
+}
@@ -0,0 +1,333 @@
+//! A module for artwork that does not use palettes.
+//!
+//! A [`TrueColorTile`] stores the color of every pixel directly, which is useful for sources that are not palette-based, like
+//! screenshots or mockups. Targets that require palettes (like VROM) only accept a [`Tile`], so true-color tiles have to be quantized
+//! with [`quantize()`] before they are exported.
+
+use crate::geom_art::{ArtworkSpaceUnit, Size};
+use crate::mask::OpacityMask;
+use crate::sprite::{Color, Palette, PaletteIndex, Tile, TileSurface};
+use crate::Surface;
+use rgb::RGB8;
+use std::collections::HashMap;
+
+/// The maximum number of entries in a palette, since a [`PaletteIndex`] is a `u8`.
+const MAX_PALETTE_LEN: usize = u8::MAX as usize + 1;
+
+/// A surface with a [`Color`] for every pixel.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TrueColorSurface {
+    data: Vec<Color>,
+    size: Size,
+}
+
+impl TrueColorSurface {
+    /// Creates a new instance in which all pixels are transparent.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The size.
+    pub fn new(size: Size) -> Self {
+        let data_len = size.width * size.height;
+        Self {
+            data: vec![Color::Transparent; data_len.into()],
+            size,
+        }
+    }
+}
+
+impl Surface<ArtworkSpaceUnit> for TrueColorSurface {
+    type DataType = Color;
+
+    fn size(&self) -> Size {
+        self.size
+    }
+
+    fn data(&self) -> &[Self::DataType] {
+        self.data.as_slice()
+    }
+
+    fn data_mut(&mut self) -> &mut [Self::DataType] {
+        self.data.as_mut_slice()
+    }
+}
+
+/// A tile that stores the color of every pixel directly, rather than an index into a palette.
+#[cfg_attr(
+    feature = "serde_support",
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct TrueColorTile {
+    /// The surface.
+    surface: TrueColorSurface,
+}
+
+impl TrueColorTile {
+    /// Creates a new instance.
+    pub fn new(surface: TrueColorSurface) -> Self {
+        Self { surface }
+    }
+
+    pub fn surface(&self) -> &TrueColorSurface {
+        &self.surface
+    }
+
+    pub fn surface_mut(&mut self) -> &mut TrueColorSurface {
+        &mut self.surface
+    }
+
+    /// Creates an [`OpacityMask`] for this tile.
+    pub fn opacity_mask(&self) -> OpacityMask {
+        let size = self.surface.size();
+        let mut mask = OpacityMask::new(size);
+        for y in 0..size.height.raw() {
+            for x in 0..size.width.raw() {
+                let idx = usize::try_from(y * size.width.raw() + x).unwrap();
+                if self.surface.data[idx] != Color::Transparent {
+                    mask.set_opaque((x, y), true);
+                }
+            }
+        }
+        mask
+    }
+}
+
+/// Converts true-color tiles into tiles that share a single palette.
+///
+/// The first entry in the palette is reserved for transparency. If the tiles contain more opaque colors than the palette can hold,
+/// the colors are reduced with the median cut algorithm and every pixel is mapped onto the nearest color in the palette.
+///
+/// # Arguments
+///
+/// * `tiles`: The true-color tiles.
+/// * `palette_len`: The number of entries in the palette, including the transparent entry. This must be between 2 and 256.
+///
+/// returns: The tiles (in the same order as the input) and the palette.
+pub fn quantize(
+    tiles: &[TrueColorTile],
+    palette_len: usize,
+) -> Result<(Vec<Tile>, Palette), String> {
+    if !(2..=MAX_PALETTE_LEN).contains(&palette_len) {
+        return Err(format!(
+            "Invalid palette length: {palette_len} (expected 2 to {MAX_PALETTE_LEN})."
+        ));
+    }
+
+    let mut histogram: HashMap<RGB8, usize> = HashMap::new();
+    for tile in tiles {
+        for color in tile.surface.data() {
+            if let Color::Opaque(rgb) = color {
+                *histogram.entry(*rgb).or_default() += 1;
+            }
+        }
+    }
+
+    let mut colors: Vec<(RGB8, usize)> = histogram.into_iter().collect();
+    // Sort for deterministic output
+    colors.sort_unstable_by_key(|(rgb, _)| (rgb.r, rgb.g, rgb.b));
+    let opaque_colors = median_cut(colors, palette_len - 1);
+
+    let mut palette_colors = Vec::with_capacity(opaque_colors.len() + 1);
+    palette_colors.push(Color::Transparent);
+    palette_colors.extend(opaque_colors.iter().map(|rgb| Color::Opaque(*rgb)));
+
+    let mut lookup: HashMap<RGB8, PaletteIndex> = HashMap::new();
+    let tiles = tiles
+        .iter()
+        .map(|tile| {
+            let mut surface = TileSurface::new(tile.surface.size());
+            for (target, color) in surface.data_mut().iter_mut().zip(tile.surface.data()) {
+                if let Color::Opaque(rgb) = color {
+                    *target = *lookup
+                        .entry(*rgb)
+                        .or_insert_with(|| nearest(&opaque_colors, *rgb));
+                }
+            }
+            Tile::new(surface)
+        })
+        .collect();
+
+    Ok((tiles, Palette::new(palette_colors)))
+}
+
+/// Reduces a set of colors with the median cut algorithm.
+///
+/// # Arguments
+///
+/// * `colors`: The distinct colors and the number of pixels that have that color.
+/// * `max_colors`: The maximum number of colors in the output.
+fn median_cut(colors: Vec<(RGB8, usize)>, max_colors: usize) -> Vec<RGB8> {
+    if colors.len() <= max_colors {
+        return colors.into_iter().map(|(rgb, _)| rgb).collect();
+    }
+
+    let mut boxes = vec![colors];
+    while boxes.len() < max_colors {
+        // Split the box with the widest range in any channel
+        let (index, channel, _) = match boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, colors)| colors.len() > 1)
+            .map(|(index, colors)| {
+                let (channel, range) = widest_channel(colors);
+                (index, channel, range)
+            })
+            .max_by_key(|(_, _, range)| *range)
+        {
+            Some(split) => split,
+            None => break,
+        };
+
+        let mut colors = boxes.swap_remove(index);
+        colors.sort_unstable_by_key(|(rgb, _)| channel_value(rgb, channel));
+        let total: usize = colors.iter().map(|(_, count)| count).sum();
+        let mut seen = 0;
+        // Split at the weighted median, but keep at least one color on either side
+        let median = colors
+            .iter()
+            .position(|(_, count)| {
+                seen += count;
+                seen * 2 >= total
+            })
+            .unwrap_or(0)
+            .clamp(0, colors.len() - 2);
+        let upper = colors.split_off(median + 1);
+        boxes.push(colors);
+        boxes.push(upper);
+    }
+
+    let mut out: Vec<RGB8> = boxes.iter().map(|colors| average(colors)).collect();
+    out.sort_unstable_by_key(|rgb| (rgb.r, rgb.g, rgb.b));
+    out.dedup();
+    out
+}
+
+fn channel_value(rgb: &RGB8, channel: usize) -> u8 {
+    match channel {
+        0 => rgb.r,
+        1 => rgb.g,
+        _ => rgb.b,
+    }
+}
+
+/// Determines the channel with the widest range of values.
+///
+/// returns: The channel (0 for red, 1 for green and 2 for blue) and its range.
+fn widest_channel(colors: &[(RGB8, usize)]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let values = colors.iter().map(|(rgb, _)| channel_value(rgb, channel));
+            let min = values.clone().min().unwrap_or(0);
+            let max = values.max().unwrap_or(0);
+            (channel, max - min)
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap()
+}
+
+/// Calculates the average color, weighted by the number of pixels.
+fn average(colors: &[(RGB8, usize)]) -> RGB8 {
+    let total: usize = colors.iter().map(|(_, count)| count).sum();
+    let channel = |channel: usize| {
+        let sum: usize = colors
+            .iter()
+            .map(|(rgb, count)| usize::from(channel_value(rgb, channel)) * count)
+            .sum();
+        ((sum + total / 2) / total) as u8
+    };
+    RGB8::new(channel(0), channel(1), channel(2))
+}
+
+/// Finds the nearest color in a palette.
+///
+/// returns: The index in the palette, taking the transparent entry into account.
+fn nearest(palette: &[RGB8], rgb: RGB8) -> PaletteIndex {
+    let distance = |other: &RGB8| {
+        let diff = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        diff(rgb.r, other.r) + diff(rgb.g, other.g) + diff(rgb.b, other.b)
+    };
+    let index = palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, other)| distance(other))
+        .map(|(index, _)| index)
+        .unwrap();
+    PaletteIndex::new((index + 1) as u8)
+}
+
+#[cfg(test)]
+mod test_true_color {
+    use super::{quantize, TrueColorSurface, TrueColorTile};
+    use crate::geom_art::Size;
+    use crate::sprite::{Color, PaletteIndex};
+    use crate::surface::Surface as _;
+
+    fn tile(colors: &[Color]) -> TrueColorTile {
+        let mut surface = TrueColorSurface::new(Size::new(colors.len() as u32, 1));
+        surface.data_mut().copy_from_slice(colors);
+        TrueColorTile::new(surface)
+    }
+
+    fn indices(tile: &crate::sprite::Tile) -> Vec<u8> {
+        tile.surface()
+            .data()
+            .iter()
+            .map(PaletteIndex::value)
+            .collect()
+    }
+
+    #[test]
+    fn test_opacity_mask() {
+        let tile = tile(&[Color::new(1, 2, 3), Color::Transparent]);
+        let mask = tile.opacity_mask();
+        assert!(mask.is_opaque((0, 0)));
+        assert!(!mask.is_opaque((1, 0)));
+    }
+
+    #[test]
+    fn test_quantize_exact() {
+        let red = Color::new(255, 0, 0);
+        let blue = Color::new(0, 0, 255);
+        let tiles = [
+            tile(&[red, Color::Transparent, blue]),
+            tile(&[blue, blue, Color::Transparent]),
+        ];
+
+        let (out, palette) = quantize(&tiles, 16).unwrap();
+        assert_eq!(3, palette.len());
+        assert_eq!(Color::Transparent, palette[PaletteIndex::new(0)]);
+        assert_eq!(blue, palette[PaletteIndex::new(1)]);
+        assert_eq!(red, palette[PaletteIndex::new(2)]);
+        assert_eq!(vec![2, 0, 1], indices(&out[0]));
+        assert_eq!(vec![1, 1, 0], indices(&out[1]));
+    }
+
+    #[test]
+    fn test_quantize_reduce() {
+        let tiles = [tile(&[
+            Color::new(0, 0, 0),
+            Color::new(4, 4, 4),
+            Color::new(250, 0, 0),
+            Color::new(254, 0, 0),
+            Color::Transparent,
+        ])];
+
+        let (out, palette) = quantize(&tiles, 3).unwrap();
+        assert_eq!(3, palette.len());
+        assert_eq!(Color::new(2, 2, 2), palette[PaletteIndex::new(1)]);
+        assert_eq!(Color::new(252, 0, 0), palette[PaletteIndex::new(2)]);
+        assert_eq!(vec![1, 1, 2, 2, 0], indices(&out[0]));
+    }
+
+    #[test]
+    fn test_quantize_invalid_palette_len() {
+        assert!(quantize(&[], 1).is_err());
+        assert!(quantize(&[], 257).is_err());
+        assert!(quantize(&[], 256).is_ok());
+    }
+}
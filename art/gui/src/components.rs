@@ -1,13 +1,25 @@
 pub mod animations;
+pub mod crop;
 pub mod cursor;
 pub mod entities;
+pub mod entity_timeline;
+pub mod hud;
+pub mod import;
+pub mod info;
 pub mod mouse;
 pub mod movie;
+pub mod palette_heatmap;
+pub mod palette_mutations;
+pub mod palette_reassign;
+pub mod parity;
+pub mod pipeline_editor;
 pub mod selection;
 pub mod sprite;
 pub mod sprite_table;
 pub mod sprite_details;
+pub mod view_export;
 pub mod window;
+pub mod wrap;
 
 use crate::egui;
 
@@ -22,3 +34,22 @@ use crate::egui;
 pub fn zoom_vec2(ui: &egui::Ui, factor: f32) -> egui::Vec2 {
     (factor / ui.ctx().pixels_per_point()) * ui.available_size()
 }
+
+/// Makes a custom widget accessible: the label is exposed to the accessibility output of egui and a focus outline is drawn while
+/// the widget has the keyboard focus. Built-in widgets do both by themselves.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `response`: The response of the widget. The widget must sense clicks, otherwise it can not receive the focus.
+/// * `label`: The label that describes the widget.
+pub fn accessible(ui: &egui::Ui, response: &egui::Response, label: &str) {
+    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Other, label));
+    if response.has_focus() {
+        ui.painter().rect_stroke(
+            response.rect.expand(1.0),
+            0.0,
+            ui.visuals().selection.stroke,
+        );
+    }
+}
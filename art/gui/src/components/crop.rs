@@ -0,0 +1,77 @@
+use crate::components::movie::Movie;
+use crate::egui;
+use ves_art_core::geom_art::{Rect, Size};
+
+/// A tool for cropping a movie to a region of the screen, for instance to focus on a single character.
+#[derive(Default)]
+pub struct CropTool {
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    result: Option<Result<usize, String>>,
+}
+
+impl CropTool {
+    /// Shows the tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    ///
+    /// returns: `true` if the movie has been cropped.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) -> bool {
+        let screen_size = movie.movie().screen_size();
+        let (screen_width, screen_height) = (screen_size.width.raw(), screen_size.height.raw());
+        // Default to the whole screen and keep the region within the screen (the movie may have been replaced since the last time)
+        if self.width == 0 || self.height == 0 {
+            self.width = screen_width;
+            self.height = screen_height;
+        }
+        self.x = self.x.min(screen_width - 1);
+        self.y = self.y.min(screen_height - 1);
+        self.width = self.width.min(screen_width - self.x);
+        self.height = self.height.min(screen_height - self.y);
+
+        egui::Grid::new("crop_grid").num_columns(2).show(ui, |ui| {
+            ui.label("X");
+            ui.add(egui::DragValue::new(&mut self.x).clamp_range(0..=screen_width - 1));
+            ui.end_row();
+            ui.label("Y");
+            ui.add(egui::DragValue::new(&mut self.y).clamp_range(0..=screen_height - 1));
+            ui.end_row();
+            ui.label("Width");
+            ui.add(egui::DragValue::new(&mut self.width).clamp_range(1..=screen_width - self.x));
+            ui.end_row();
+            ui.label("Height");
+            ui.add(egui::DragValue::new(&mut self.height).clamp_range(1..=screen_height - self.y));
+            ui.end_row();
+        });
+
+        let mut cropped = false;
+        if ui.button("Crop").clicked() {
+            let rect = Rect::new_from_size((self.x, self.y), Size::new(self.width, self.height));
+            let result = movie.crop(rect);
+            if result.is_ok() {
+                // The region is the whole screen from now on
+                self.x = 0;
+                self.y = 0;
+                cropped = true;
+            }
+            self.result = Some(result);
+        }
+
+        match &self.result {
+            None => {}
+            Some(Ok(count)) => {
+                ui.label(format!("Removed {} sprites.", count));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+
+        cropped
+    }
+}
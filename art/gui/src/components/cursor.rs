@@ -30,6 +30,14 @@ impl Cursor {
         self.position = 0;
     }
 
+    /// Moves the cursor to a position. Positions beyond the upper bound are clamped to the upper bound.
+    ///
+    /// # Parameters
+    /// * `position`: The new position.
+    pub fn move_to(&mut self, position: usize) {
+        self.position = position.min(self.length - 1);
+    }
+
     /// Moves the cursor backward at most the provided number of steps.
     ///
     /// # Parameters
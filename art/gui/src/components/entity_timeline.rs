@@ -0,0 +1,228 @@
+use crate::components::movie::Movie;
+use crate::egui;
+use ves_art_core::movie::SpriteId;
+use ves_art_core::sprite::TileRef;
+
+/// The width of a timeline strip.
+const STRIP_WIDTH: f32 = 256.0;
+/// The height of a timeline strip.
+const STRIP_HEIGHT: f32 = 12.0;
+/// The color for the frames in which an entity is present.
+const PRESENT_COLOR: egui::Color32 = egui::Color32::from_rgb(0, 160, 255);
+/// The color of the markers for the frames in which an entity changes its tile.
+const TILE_CHANGE_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 220, 0);
+/// The color of the marker for the current frame.
+const CURRENT_FRAME_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 255, 255);
+
+/// The appearances of a single entity (a tracked sprite).
+struct EntityRow {
+    id: SpriteId,
+    /// The positions of the frames in which the entity is present (in ascending order), together with the tile it shows.
+    frames: Vec<(usize, TileRef)>,
+}
+
+impl EntityRow {
+    /// Determines the sequence of tiles that the entity shows, leaving out repetitions of the same tile.
+    fn cels(&self) -> Vec<TileRef> {
+        let mut cels: Vec<TileRef> = Vec::new();
+        for (_, tile) in &self.frames {
+            if cels.last() != Some(tile) {
+                cels.push(*tile);
+            }
+        }
+        cels
+    }
+}
+
+/// A timeline that shows, per entity, in which frames of the movie it is present and when it changes its tile.
+///
+/// Entities are the sprites of the movie, tracked across the frames (see
+/// [`track_sprites()`](ves_art_core::analysis::track_sprites)). Every entity gets a timeline strip that covers the entire movie, in
+/// which tile changes are marked, such that the cel sequence of an animation can be seen at a glance. Clicking on a strip jumps to the
+/// frame under the pointer.
+pub struct EntityTimeline {
+    max_distance: u32,
+    min_frames: usize,
+}
+
+impl Default for EntityTimeline {
+    fn default() -> Self {
+        Self {
+            max_distance: 16,
+            min_frames: 2,
+        }
+    }
+}
+
+impl EntityTimeline {
+    /// Shows the timeline.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) {
+        ui.horizontal(|ui| {
+            ui.label("Max. distance");
+            ui.add(egui::DragValue::new(&mut self.max_distance).clamp_range(0..=64));
+            if ui.button("Track sprites").clicked() {
+                movie.track_sprites(self.max_distance);
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Min. frames");
+            ui.add(egui::DragValue::new(&mut self.min_frames).clamp_range(1..=usize::MAX));
+        });
+
+        let frame_count = movie.movie().frames().len();
+        if frame_count == 0 || movie.movie().sprite_ids(0).is_none() {
+            ui.label("The sprites have not been tracked yet.");
+            return;
+        }
+
+        let rows: Vec<EntityRow> = entity_rows(movie.movie())
+            .into_iter()
+            .filter(|row| row.frames.len() >= self.min_frames)
+            .collect();
+        let current_position = movie.current_frame().map(|frame| frame.frame_nr());
+
+        let mut jump_target = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("entity_timeline_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for row in &rows {
+                        ui.label(format!("#{}", row.id.value()));
+                        let response = timeline_strip(ui, row, frame_count, current_position);
+                        super::accessible(
+                            ui,
+                            &response,
+                            &format!("Timeline of entity #{}", row.id.value()),
+                        );
+                        let cels: Vec<String> = row
+                            .cels()
+                            .iter()
+                            .map(|tile| tile.value().to_string())
+                            .collect();
+                        let response = response.on_hover_text(format!(
+                            "Present in {} frames, showing tiles {}.",
+                            row.frames.len(),
+                            cels.join(", ")
+                        ));
+                        if response.clicked() {
+                            jump_target = match response.interact_pointer_pos() {
+                                Some(pos) => {
+                                    let offset = (pos.x - response.rect.left()) / STRIP_WIDTH;
+                                    // The cast is OK here, because the offset has been clamped to the strip
+                                    let position =
+                                        (offset.clamp(0.0, 1.0) * frame_count as f32) as usize;
+                                    Some(position.min(frame_count - 1))
+                                }
+                                // Clicked with the keyboard: jump to the next frame in which the entity is present
+                                None => row
+                                    .frames
+                                    .iter()
+                                    .map(|(position, _)| *position)
+                                    .find(|position| {
+                                        current_position.map_or(true, |current| *position > current)
+                                    })
+                                    .or_else(|| row.frames.first().map(|(position, _)| *position)),
+                            };
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(position) = jump_target {
+            movie.jump_to(position);
+        }
+    }
+}
+
+/// Collects the appearances of all entities of a movie.
+///
+/// # Arguments
+///
+/// * `movie`: The movie. Movies that have not been tracked have no entities.
+///
+/// returns: The entities, in the order of their identities.
+fn entity_rows(movie: &ves_art_core::movie::Movie) -> Vec<EntityRow> {
+    let mut rows: Vec<EntityRow> = Vec::new();
+    for (position, frame) in movie.frames().iter().enumerate() {
+        let ids = match movie.sprite_ids(position) {
+            Some(ids) => ids,
+            None => continue,
+        };
+        for (id, sprite) in ids.iter().zip(frame.sprites()) {
+            // New identities are assigned in ascending order
+            while rows.len() <= id.value() {
+                rows.push(EntityRow {
+                    id: SpriteId::new(rows.len()),
+                    frames: Vec::new(),
+                });
+            }
+            rows[id.value()].frames.push((position, sprite.tile()));
+        }
+    }
+    rows
+}
+
+/// Shows a timeline strip for a single entity.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `row`: The entity.
+/// * `frame_count`: The number of frames in the movie.
+/// * `current_position`: The position of the current frame, if any.
+///
+/// returns: The response of the strip.
+fn timeline_strip(
+    ui: &mut egui::Ui,
+    row: &EntityRow,
+    frame_count: usize,
+    current_position: Option<usize>,
+) -> egui::Response {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(STRIP_WIDTH, STRIP_HEIGHT), egui::Sense::click());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+
+    // Combine frames into columns, such that a column is at least one point wide
+    let columns = frame_count.min(STRIP_WIDTH as usize);
+    let column_width = STRIP_WIDTH / columns as f32;
+    let column_rect = |column: usize, top: f32| {
+        let x = rect.left() + column as f32 * column_width;
+        egui::Rect::from_min_max(
+            egui::pos2(x, top),
+            egui::pos2(x + column_width, rect.bottom()),
+        )
+    };
+
+    let mut previous: Option<TileRef> = None;
+    for &(position, tile) in &row.frames {
+        let column = position * columns / frame_count;
+        painter.rect_filled(column_rect(column, rect.top()), 0.0, PRESENT_COLOR);
+        // Mark the frames in which the tile changes, without marking the reappearance of the same tile after a gap
+        if let Some(previous) = previous {
+            if previous != tile {
+                let top = rect.top() + STRIP_HEIGHT / 2.0;
+                painter.rect_filled(column_rect(column, top), 0.0, TILE_CHANGE_COLOR);
+            }
+        }
+        previous = Some(tile);
+    }
+
+    if let Some(position) = current_position {
+        let x = rect.left()
+            + (position * columns / frame_count) as f32 * column_width
+            + column_width / 2.0;
+        painter.line_segment(
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+            egui::Stroke::new(1.0, CURRENT_FRAME_COLOR),
+        );
+    }
+
+    response
+}
@@ -0,0 +1,128 @@
+use crate::egui;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// The number of UI frames over which the UI frame time is averaged.
+const FRAME_TIME_SAMPLES: usize = 60;
+
+/// The number of bytes per texel. Textures are uploaded as RGBA with 8 bits per component.
+const BYTES_PER_TEXEL: usize = 4;
+
+/// Statistics about the textures that are in use.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct TextureStats {
+    count: usize,
+    bytes: usize,
+}
+
+impl TextureStats {
+    /// Adds a texture to the statistics.
+    ///
+    /// # Arguments
+    ///
+    /// * `size`: The size of the texture in texels.
+    pub fn add(&mut self, size: [usize; 2]) {
+        self.count += 1;
+        self.bytes += size[0] * size[1] * BYTES_PER_TEXEL;
+    }
+}
+
+/// The values that are shown in the [`PerformanceHud`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct HudStats {
+    /// The textures that are in use.
+    pub textures: TextureStats,
+    /// The number of tiles that are held in memory.
+    pub tiles: usize,
+    /// The time it took to build the current movie frame, if any.
+    pub frame_build_time: Option<Duration>,
+}
+
+/// An overlay that shows memory usage and timing information.
+#[derive(Default)]
+pub struct PerformanceHud {
+    frame_times: VecDeque<Duration>,
+}
+
+impl PerformanceHud {
+    /// Records the time that was spent on the previous UI frame. This should be called exactly once per update.
+    ///
+    /// # Arguments
+    ///
+    /// * `cpu_usage`: The CPU time of the previous UI frame in seconds, as reported by the integration.
+    pub fn record_frame_time(&mut self, cpu_usage: Option<f32>) {
+        if let Some(cpu_usage) = cpu_usage {
+            if self.frame_times.len() == FRAME_TIME_SAMPLES {
+                self.frame_times.pop_front();
+            }
+            self.frame_times
+                .push_back(Duration::from_secs_f32(cpu_usage));
+        }
+    }
+
+    /// Calculates the average UI frame time over the last [`FRAME_TIME_SAMPLES`] frames.
+    fn average_frame_time(&self) -> Option<Duration> {
+        let count = u32::try_from(self.frame_times.len())
+            .ok()
+            .filter(|count| *count > 0)?;
+        Some(self.frame_times.iter().sum::<Duration>() / count)
+    }
+
+    /// Shows the overlay in the bottom-right corner.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: The context.
+    /// * `stats`: The values to show.
+    pub fn show(&self, ctx: &egui::Context, stats: &HudStats) {
+        let frame_time = self.average_frame_time();
+
+        egui::Area::new("performance_hud")
+            .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-8.0, -8.0))
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    egui::Grid::new("performance_hud_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Textures");
+                            ui.monospace(format!(
+                                "{} ({})",
+                                stats.textures.count,
+                                format_bytes(stats.textures.bytes)
+                            ));
+                            ui.end_row();
+
+                            ui.label("Tiles");
+                            ui.monospace(format!("{}", stats.tiles));
+                            ui.end_row();
+
+                            ui.label("Frame build time");
+                            ui.monospace(format_duration(stats.frame_build_time));
+                            ui.end_row();
+
+                            ui.label("UI frame time");
+                            ui.monospace(format_duration(frame_time));
+                            ui.end_row();
+                        });
+                });
+            });
+    }
+}
+
+fn format_duration(duration: Option<Duration>) -> String {
+    match duration {
+        Some(duration) => format!("{:.2} ms", duration.as_secs_f64() * 1000.0),
+        None => "-".to_string(),
+    }
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const KIB: usize = 1024;
+    const MIB: usize = KIB * 1024;
+    if bytes >= MIB {
+        format!("{:.1} MiB", bytes as f64 / MIB as f64)
+    } else {
+        format!("{:.1} KiB", bytes as f64 / KIB as f64)
+    }
+}
@@ -0,0 +1,258 @@
+use crate::egui;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use ves_art_core::movie::Movie;
+
+/// The emulators from which frame dumps can be imported.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DumpBackend {
+    /// Mesen-S, with one JSON file per frame (see `emu_scripts` in `ves-art-snes`).
+    MesenS,
+}
+
+impl DumpBackend {
+    /// All backends.
+    pub const ALL: [DumpBackend; 1] = [DumpBackend::MesenS];
+
+    /// Retrieves the display name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            DumpBackend::MesenS => "Mesen-S (SNES)",
+        }
+    }
+
+    /// Retrieves the extension of the frame dump files.
+    fn file_extension(&self) -> &'static str {
+        match self {
+            DumpBackend::MesenS => "json",
+        }
+    }
+
+    /// Creates a movie from frame dump files.
+    ///
+    /// # Arguments
+    ///
+    /// * `files`: The frame dump files.
+    /// * `progress`: A function that is called right before a file is processed. The arguments are the index of the file and its path.
+    fn create_movie(
+        &self,
+        files: &[PathBuf],
+        progress: impl FnMut(usize, &Path),
+    ) -> Result<Movie, String> {
+        match self {
+            DumpBackend::MesenS => ves_art_snes::create_movie_with_progress(files.iter(), progress)
+                .map_err(|err| format!("{:#}", err)),
+        }
+    }
+}
+
+/// A message from the import thread.
+enum ImportMessage {
+    /// A file is about to be processed.
+    Progress {
+        index: usize,
+        count: usize,
+        path: PathBuf,
+    },
+    /// The import has finished.
+    Done(Result<Movie, String>),
+}
+
+enum ImportState {
+    Idle,
+    Running {
+        receiver: Receiver<ImportMessage>,
+        progress: Option<(usize, usize, PathBuf)>,
+    },
+    Failed(String),
+    Finished(String),
+}
+
+/// A dialog for creating a movie from emulator frame dumps.
+///
+/// The import runs on a separate thread, such that the UI stays responsive. The movie is written to the output file and handed to
+/// the caller, such that it can be curated right away.
+pub struct ImportDialog {
+    backend: DumpBackend,
+    in_dir: String,
+    out_path: String,
+    state: ImportState,
+}
+
+impl Default for ImportDialog {
+    fn default() -> Self {
+        Self {
+            backend: DumpBackend::MesenS,
+            in_dir: String::new(),
+            out_path: "movie.bincode".to_string(),
+            state: ImportState::Idle,
+        }
+    }
+}
+
+impl ImportDialog {
+    /// Shows the dialog.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    ///
+    /// returns: The imported movie, once the import has finished successfully.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<Movie> {
+        let movie = self.poll();
+        let running = matches!(self.state, ImportState::Running { .. });
+
+        ui.add_enabled_ui(!running, |ui| {
+            egui::Grid::new("import_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Emulator");
+                    egui::ComboBox::from_id_source("import_backend")
+                        .selected_text(self.backend.name())
+                        .show_ui(ui, |ui| {
+                            for backend in DumpBackend::ALL {
+                                ui.selectable_value(&mut self.backend, backend, backend.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Dump directory");
+                    ui.text_edit_singleline(&mut self.in_dir);
+                    ui.end_row();
+
+                    ui.label("Movie file");
+                    ui.text_edit_singleline(&mut self.out_path);
+                    ui.end_row();
+                });
+
+            if ui.button("Import").clicked() {
+                self.start();
+            }
+        });
+
+        match &self.state {
+            ImportState::Idle => {}
+            ImportState::Running { progress, .. } => {
+                let (fraction, text) = match progress {
+                    Some((index, count, path)) => (
+                        *index as f32 / *count as f32,
+                        format!("{}/{}: {}", index + 1, count, path.display()),
+                    ),
+                    None => (0.0, "Collecting files...".to_string()),
+                };
+                ui.add(egui::ProgressBar::new(fraction).text(text));
+                // Keep polling the import thread
+                ui.ctx().request_repaint();
+            }
+            ImportState::Failed(err) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+            ImportState::Finished(message) => {
+                ui.label(message);
+            }
+        }
+
+        movie
+    }
+
+    /// Starts the import thread.
+    fn start(&mut self) {
+        let (sender, receiver) = channel();
+        let backend = self.backend;
+        let in_dir = PathBuf::from(&self.in_dir);
+        let out_path = PathBuf::from(&self.out_path);
+        std::thread::spawn(move || {
+            let result = import(backend, &in_dir, &out_path, &sender);
+            // The receiver is gone if the dialog has been dropped, in which case nobody is interested in the result
+            let _ = sender.send(ImportMessage::Done(result));
+        });
+
+        self.state = ImportState::Running {
+            receiver,
+            progress: None,
+        };
+    }
+
+    /// Processes the messages from the import thread.
+    ///
+    /// returns: The imported movie, once the import has finished successfully.
+    fn poll(&mut self) -> Option<Movie> {
+        let (receiver, progress) = match &mut self.state {
+            ImportState::Running { receiver, progress } => (receiver, progress),
+            _ => return None,
+        };
+
+        while let Ok(message) = receiver.try_recv() {
+            match message {
+                ImportMessage::Progress { index, count, path } => {
+                    *progress = Some((index, count, path));
+                }
+                ImportMessage::Done(Ok(movie)) => {
+                    self.state = ImportState::Finished(format!(
+                        "Imported {} frames into {}.",
+                        movie.frames().len(),
+                        self.out_path
+                    ));
+                    return Some(movie);
+                }
+                ImportMessage::Done(Err(err)) => {
+                    self.state = ImportState::Failed(err);
+                    return None;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Creates a movie from the frame dumps in a directory and writes it to a file.
+///
+/// # Arguments
+///
+/// * `backend`: The emulator that created the frame dumps.
+/// * `in_dir`: The directory with the frame dump files.
+/// * `out_path`: The movie file.
+/// * `sender`: The channel for reporting progress.
+fn import(
+    backend: DumpBackend,
+    in_dir: &Path,
+    out_path: &Path,
+    sender: &Sender<ImportMessage>,
+) -> Result<Movie, String> {
+    let mut files = std::fs::read_dir(in_dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|err| format!("Could not read directory {}: {}", in_dir.display(), err))?;
+    files.retain(|path| {
+        path.extension()
+            .map_or(false, |ext| ext == backend.file_extension())
+    });
+    files.sort();
+    if files.is_empty() {
+        return Err(format!(
+            "Could not find any .{} files in {}.",
+            backend.file_extension(),
+            in_dir.display()
+        ));
+    }
+
+    let count = files.len();
+    let movie = backend.create_movie(&files, |index, path| {
+        let _ = sender.send(ImportMessage::Progress {
+            index,
+            count,
+            path: path.to_path_buf(),
+        });
+    })?;
+
+    let file = File::create(out_path)
+        .map_err(|err| format!("Could not create {}: {}", out_path.display(), err))?;
+    bincode::serialize_into(BufWriter::new(file), &movie)
+        .map_err(|err| format!("Could not write {}: {}", out_path.display(), err))?;
+    Ok(movie)
+}
@@ -0,0 +1,245 @@
+use super::movie::Movie;
+use crate::egui;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use ves_art_core::manifest::Manifest;
+use ves_art_core::movie::MovieMetadata;
+
+/// The number of characters of a hash that are shown. The full hash is shown on hover.
+const SHORT_HASH_LEN: usize = 12;
+
+/// A window with information about the loaded movie.
+///
+/// The metadata of the movie can be edited and saved along with the movie. Movie files do not record how they were created, so that
+/// information comes from the reproducibility manifest that can be written alongside the movie (see `movie create --manifest` in
+/// `ves-art-snes-cli`).
+pub struct MovieInfo {
+    new_key: String,
+    new_value: String,
+    save_path: String,
+    save_result: Option<Result<PathBuf, String>>,
+    manifest_path: String,
+    manifest: Option<Result<Manifest, String>>,
+}
+
+impl Default for MovieInfo {
+    fn default() -> Self {
+        Self {
+            new_key: String::new(),
+            new_value: String::new(),
+            save_path: "movie.bincode".to_string(),
+            save_result: None,
+            manifest_path: String::new(),
+            manifest: None,
+        }
+    }
+}
+
+impl MovieInfo {
+    /// Shows the window contents.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) {
+        self.show_metadata(ui, movie.metadata_mut());
+
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.save_path);
+            if ui.button("Save movie").clicked() {
+                let path = PathBuf::from(&self.save_path);
+                self.save_result = Some(save_movie(movie.movie(), &path).map(|_| path));
+            }
+        });
+        match &self.save_result {
+            None => {}
+            Some(Ok(path)) => {
+                ui.label(format!("Saved {}.", path.display()));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+
+        ui.separator();
+        let movie = movie.movie();
+        egui::Grid::new("movie_info_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let size = movie.screen_size();
+                ui.label("Screen size");
+                ui.monospace(format!("{} x {}", size.width.raw(), size.height.raw()));
+                ui.end_row();
+                ui.label("Frame rate");
+                ui.monospace(format!(
+                    "{:?} ({} fps)",
+                    movie.frame_rate(),
+                    movie.frame_rate().fps()
+                ));
+                ui.end_row();
+                ui.label("Frames");
+                ui.monospace(format!("{}", movie.frames().len()));
+                ui.end_row();
+                ui.label("Palettes");
+                ui.monospace(format!("{}", movie.palettes().len()));
+                ui.end_row();
+                ui.label("Tiles");
+                ui.monospace(format!("{}", movie.tiles().len()));
+                ui.end_row();
+            });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("Manifest");
+            ui.text_edit_singleline(&mut self.manifest_path);
+            if ui.button("Load").clicked() {
+                self.manifest = Some(read_manifest(Path::new(&self.manifest_path)));
+            }
+        });
+
+        match &self.manifest {
+            None => {
+                ui.label("No manifest loaded.");
+            }
+            Some(Ok(manifest)) => show_manifest(ui, manifest),
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+    }
+}
+
+impl MovieInfo {
+    /// Shows the editor for the metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `metadata`: The metadata.
+    fn show_metadata(&mut self, ui: &mut egui::Ui, metadata: &mut MovieMetadata) {
+        egui::Grid::new("movie_info_metadata_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                edit_optional(ui, "Title", &mut metadata.title);
+                edit_optional(ui, "Game", &mut metadata.game);
+                edit_optional(ui, "Author", &mut metadata.author);
+                edit_optional(ui, "Notes", &mut metadata.notes);
+
+                let mut removed = None;
+                for (key, value) in metadata.properties.iter_mut() {
+                    ui.label(key.as_str());
+                    ui.horizontal(|ui| {
+                        ui.text_edit_singleline(value);
+                        if ui.small_button("Remove").clicked() {
+                            removed = Some(key.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+                if let Some(key) = removed {
+                    metadata.properties.remove(&key);
+                }
+
+                ui.text_edit_singleline(&mut self.new_key);
+                ui.horizontal(|ui| {
+                    ui.text_edit_singleline(&mut self.new_value);
+                    let can_add = !self.new_key.is_empty();
+                    if ui
+                        .add_enabled(can_add, egui::Button::new("Add property"))
+                        .clicked()
+                    {
+                        metadata.properties.insert(
+                            std::mem::take(&mut self.new_key),
+                            std::mem::take(&mut self.new_value),
+                        );
+                    }
+                });
+                ui.end_row();
+            });
+    }
+}
+
+/// Shows a text field for an optional value. An empty text means that the value is not set.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `label`: The label.
+/// * `value`: The value.
+fn edit_optional(ui: &mut egui::Ui, label: &str, value: &mut Option<String>) {
+    ui.label(label);
+    let mut text = value.clone().unwrap_or_default();
+    if ui.text_edit_singleline(&mut text).changed() {
+        *value = (!text.is_empty()).then_some(text);
+    }
+    ui.end_row();
+}
+
+/// Writes a movie into a file.
+fn save_movie(movie: &ves_art_core::movie::Movie, path: &Path) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|err| format!("Could not create {}: {}", path.display(), err))?;
+    bincode::serialize_into(BufWriter::new(file), movie)
+        .map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
+
+/// Shows the contents of a manifest.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `manifest`: The manifest.
+fn show_manifest(ui: &mut egui::Ui, manifest: &Manifest) {
+    egui::Grid::new("movie_info_manifest_grid")
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Created by");
+            ui.monospace(manifest.tool.as_str());
+            ui.end_row();
+            ui.label("Extractor version");
+            ui.monospace(manifest.extractor_version.as_str());
+            ui.end_row();
+            ui.label("Input format");
+            ui.monospace(manifest.format.as_str());
+            ui.end_row();
+            ui.label("Output");
+            short_hash(ui, &manifest.output.sha256);
+            ui.end_row();
+        });
+
+    ui.label(format!("Passes: {}", manifest.passes.len()));
+    for pass in &manifest.passes {
+        ui.monospace(format!("{:?}", pass));
+    }
+
+    ui.label(format!("Input files: {}", manifest.inputs.len()));
+    egui::ScrollArea::vertical()
+        .max_height(200.0)
+        .show(ui, |ui| {
+            egui::Grid::new("movie_info_inputs_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    for input in &manifest.inputs {
+                        ui.monospace(input.path.as_str());
+                        short_hash(ui, &input.sha256);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Shows the start of a hash.
+fn short_hash(ui: &mut egui::Ui, hash: &str) {
+    let short = hash.get(..SHORT_HASH_LEN).unwrap_or(hash);
+    ui.monospace(short).on_hover_text(hash);
+}
+
+/// Reads a manifest from a JSON file.
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    let file =
+        File::open(path).map_err(|err| format!("Could not open {}: {}", path.display(), err))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("Could not read {}: {}", path.display(), err))
+}
@@ -9,9 +9,8 @@ impl MouseInteractionTracker {
     pub fn update(&mut self, response: &egui::Response) -> Option<MouseInteraction> {
         if response.clicked() {
             self.drag_state.reset();
-            // Since the response was clicked, this *must* be Some, hence the unwrap.
-            let pos = response.interact_pointer_pos().unwrap();
-            Some(MouseInteraction::Click(pos))
+            // A focused widget can also be clicked with the keyboard, in which case there is no position
+            response.interact_pointer_pos().map(MouseInteraction::Click)
         } else {
             self.drag_state.update(response).map(MouseInteraction::Drag)
         }
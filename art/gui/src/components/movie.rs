@@ -1,19 +1,24 @@
 use super::sprite::Sprite;
 use crate::components::cursor::Cursor;
 use crate::components::mouse::MouseInteractionTracker;
-use crate::components::selection::{Selectable, SelectionState};
+use crate::components::selection::{Selectable, SelectionRange, SelectionState};
+use crate::components::wrap::wrap_sprite;
 use crate::egui;
 use crate::egui::ImageData;
 use crate::ToEgui as _;
 use std::time::{Duration, Instant};
+use ves_art_core::movie::{SpriteLocation, UsageIndex};
 use ves_cache::SliceCache;
-use ves_geom::RectIntersection;
 
 struct MovieFrame<'a> {
     sprites: &'a [Selectable<Sprite>],
 }
 
-const ZOOM: f32 = 2.0;
+/// The zoom factor of the movie window.
+pub const ZOOM: f32 = 2.0;
+
+/// The color for highlighting the sprite under the mouse cursor.
+const HOVER_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 255, 0);
 
 impl<'a> MovieFrame<'a> {
     /// Creates a new instance.
@@ -21,12 +26,15 @@ impl<'a> MovieFrame<'a> {
         Self { sprites }
     }
 
+    /// Shows the frame.
+    ///
+    /// returns: The transformation from frame coordinates to screen coordinates.
     pub fn show(
         &self,
         ui: &mut egui::Ui,
         screen_size: ves_art_core::geom_art::Size,
         viewport: egui::Rect,
-    ) {
+    ) -> egui::emath::RectTransform {
         // TODO: It seems like the UI adds spacing of an extra 8px when an image is exactly on the edge, causing the scrollbars to resize
         //       when a sprite wraps around.
 
@@ -42,8 +50,6 @@ impl<'a> MovieFrame<'a> {
         );
         let transform = egui::emath::RectTransform::from_to(from_rect, to_rect);
 
-        let intersect_pos = screen_size.as_rect().max;
-
         // Collect all selection states with their rects and render them after all the images have
         // been added, since we want the selection boxes to appear over all sprites.
         let mut states_with_rect = Vec::with_capacity(self.sprites.len());
@@ -51,40 +57,134 @@ impl<'a> MovieFrame<'a> {
         self.sprites.iter().rev().for_each(|selectable_sprite| {
             let state = &selectable_sprite.state;
             let sprite = &selectable_sprite.item;
-            let sprite_rect = sprite.rect();
-            match sprite_rect.intersect_point(intersect_pos) {
-                // No intersections; this means the sprite fits entirely on the screen
-                RectIntersection::None => {
-                    let rect = transform.transform_rect(sprite_rect.to_egui());
-                    ui.put(rect, sprite.to_image(rect.size()));
-                    states_with_rect.push((state, rect));
-                }
-                // Treat all other cases generically
-                intersection => {
-                    intersection.for_each(|rect| {
-                        let egui_dest_rect = ves_art_core::geom_art::Rect::new_from_size(
-                            (
-                                rect.min_x() % screen_size.width,
-                                rect.min_y() % screen_size.height,
-                            ),
-                            rect.size(),
-                        )
-                        .to_egui();
-
-                        let dest_rect = transform.transform_rect(egui_dest_rect);
-                        let image = egui::Image::new(sprite.texture(), dest_rect.size())
-                            .uv(sprite.partial_uv(rect));
-
-                        ui.put(dest_rect, image);
-                        states_with_rect.push((state, dest_rect));
-                    });
-                }
+            for part in wrap_sprite(sprite.rect(), screen_size, sprite.hflip(), sprite.vflip()) {
+                let dest_rect = transform.transform_rect(part.dest.to_egui());
+                let [min_u, min_v, max_u, max_v] = part.uv;
+                let uv =
+                    egui::Rect::from_min_max(egui::pos2(min_u, min_v), egui::pos2(max_u, max_v));
+                let image = egui::Image::new(sprite.texture(), dest_rect.size()).uv(uv);
+
+                ui.put(dest_rect, image);
+                states_with_rect.push((state, dest_rect));
             }
         });
 
         for (state, rect) in states_with_rect {
             state.show(ui, rect, ZOOM);
         }
+
+        transform
+    }
+
+    /// Finds the sprite with the highest priority that has an opaque pixel under a position.
+    ///
+    /// # Arguments
+    ///
+    /// * `pos`: The position in screen coordinates.
+    /// * `screen_size`: The size of the movie screen.
+    /// * `transform`: The transformation from frame coordinates to screen coordinates.
+    ///
+    /// returns: The index of the sprite or `None` if there is no opaque pixel under the position.
+    pub fn sprite_at(
+        &self,
+        pos: egui::Pos2,
+        screen_size: ves_art_core::geom_art::Size,
+        transform: &egui::emath::RectTransform,
+    ) -> Option<usize> {
+        let frame_pos = transform.inverse().transform_pos(pos);
+        if frame_pos.x < 0.0 || frame_pos.y < 0.0 {
+            return None;
+        }
+        let point = ves_art_core::geom_art::Point::new(frame_pos.x as u32, frame_pos.y as u32);
+        // The first sprite is rendered on top
+        self.sprites
+            .iter()
+            .position(|selectable_sprite| selectable_sprite.item.is_opaque_at(point, screen_size))
+    }
+
+    /// Highlights the opaque pixels of a sprite.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `index`: The index of the sprite.
+    /// * `screen_size`: The size of the movie screen.
+    /// * `transform`: The transformation from frame coordinates to screen coordinates.
+    pub fn highlight(
+        &self,
+        ui: &egui::Ui,
+        index: usize,
+        screen_size: ves_art_core::geom_art::Size,
+        transform: &egui::emath::RectTransform,
+    ) {
+        let stroke = egui::Stroke::new(ui.ctx().pixels_per_point(), HOVER_COLOR);
+        for rect in self.sprites[index].item.outline_rects() {
+            let rect = ves_art_core::geom_art::Rect::new_from_size(
+                (
+                    rect.min_x() % screen_size.width,
+                    rect.min_y() % screen_size.height,
+                ),
+                rect.size(),
+            );
+            ui.painter()
+                .rect_stroke(transform.transform_rect(rect.to_egui()), 0.0, stroke);
+        }
+    }
+}
+
+/// Handles the keyboard input while the movie frame has the focus. The left and right arrow keys select the previous or next sprite
+/// (the modifiers work the same as for clicking), escape clears the selection, page up and page down step through the frames and
+/// home and end jump to the first or last frame.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `sprites`: The sprites of the current frame.
+/// * `selection`: The selection range of the sprites.
+/// * `control_messages`: The sink for the playback control messages.
+fn handle_keyboard(
+    ui: &egui::Ui,
+    sprites: &mut [Selectable<Sprite>],
+    selection: &mut SelectionRange,
+    control_messages: &mut Vec<MovieControlMessage>,
+) {
+    let (previous, next, clear, step_backward, step_forward, start, end) = {
+        let input = ui.input();
+        (
+            input.key_pressed(egui::Key::ArrowLeft),
+            input.key_pressed(egui::Key::ArrowRight),
+            input.key_pressed(egui::Key::Escape),
+            input.key_pressed(egui::Key::PageUp),
+            input.key_pressed(egui::Key::PageDown),
+            input.key_pressed(egui::Key::Home),
+            input.key_pressed(egui::Key::End),
+        )
+    };
+
+    let count = sprites.len();
+    if count > 0 && previous != next {
+        let index = match (selection.root(), next) {
+            (Some(root), true) => (root + 1) % count,
+            (Some(root), false) => (root + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        selection.update(ui, index, sprites, |sprite| &mut sprite.state);
+    }
+    if clear {
+        sprites.iter_mut().for_each(|sprite| sprite.state.unselect());
+    }
+
+    let message = match (step_backward, step_forward, start, end) {
+        (true, false, _, _) => Some(MovieControlMessage::SkipBackward(1)),
+        (false, true, _, _) => Some(MovieControlMessage::SkipForward(1)),
+        (_, _, true, false) => Some(MovieControlMessage::Jump(JumpMessage::Start)),
+        (_, _, false, true) => Some(MovieControlMessage::Jump(JumpMessage::End)),
+        _ => None,
+    };
+    if let Some(message) = message {
+        control_messages.push(MovieControlMessage::Pause);
+        control_messages.push(message);
     }
 }
 
@@ -126,8 +226,16 @@ pub struct Movie {
     playback_state: PlaybackState,
     playback_repeat: bool,
     current_frame: Option<CurrentFrame>,
+    /// Whether the current frame has to be rebuilt, even if the position has not changed.
+    frame_dirty: bool,
+    frame_build_time: Option<Duration>,
     control_messages: Vec<MovieControlMessage>,
     mouse_tracker: MouseInteractionTracker,
+    selection: SelectionRange,
+    /// The sprite edits that can be undone, most recent last. Every entry contains the sprites that undo the edit.
+    undo_stack: Vec<Vec<(SpriteLocation, ves_art_core::sprite::Sprite)>>,
+    /// The sprite edits that can be redone, most recent last. Every entry contains the sprites that redo the edit.
+    redo_stack: Vec<Vec<(SpriteLocation, ves_art_core::sprite::Sprite)>>,
 }
 
 impl Movie {
@@ -146,8 +254,13 @@ impl Movie {
             playback_state: PlaybackState::Paused,
             playback_repeat: false,
             current_frame: None,
+            frame_dirty: false,
+            frame_build_time: None,
             control_messages: Vec::with_capacity(16),
             mouse_tracker: Default::default(),
+            selection: Default::default(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
         }
     }
 
@@ -194,18 +307,24 @@ impl Movie {
 
     fn render_frame(&mut self, ctx: &egui::Context) -> bool {
         let pos = self.frame_cursor.position();
-        // Only render the frame if the position has changed
+        // Only render the frame if the position has changed (or the movie has been modified)
         if let Some(last_pos) = self
             .current_frame
             .as_ref()
             .map(|current_frame| current_frame.frame_nr())
         {
-            if pos == last_pos {
+            if pos == last_pos && !self.frame_dirty {
                 return false;
             }
         }
 
-        let palettes = SliceCache::new(self.movie.palettes());
+        let build_start = Instant::now();
+        // Apply the palette mutations up to this frame, such that palette animations are visible during playback
+        let frame_palettes = self
+            .movie
+            .palettes_at(pos)
+            .unwrap_or_else(|_| self.movie.palettes().to_vec());
+        let palettes = SliceCache::new(&frame_palettes);
         let tiles = SliceCache::new(self.movie.tiles());
         let movie_frame = &self.movie.frames()[pos];
 
@@ -242,6 +361,8 @@ impl Movie {
         }
 
         self.current_frame = Some(CurrentFrame::new(pos, sprites));
+        self.frame_dirty = false;
+        self.frame_build_time = Some(build_start.elapsed());
 
         true
     }
@@ -265,6 +386,7 @@ impl Movie {
                 JumpMessage::End => {
                     self.frame_cursor.move_forward(usize::MAX);
                 }
+                JumpMessage::Frame(position) => self.frame_cursor.move_to(position),
             },
             MovieControlMessage::SetRepeat(val) => {
                 self.playback_repeat = val;
@@ -274,14 +396,14 @@ impl Movie {
 
     pub fn show(&mut self, ui: &mut egui::Ui) {
         ui.vertical(|ui| {
-            if let Some(current_frame) = self.current_frame.as_ref() {
+            if let Some(current_frame) = self.current_frame.as_mut() {
                 let frame_nr = current_frame.frame_nr();
                 ui.horizontal(|ui| {
                     ui.label("Frame nr");
                     ui.label(format!("{}", frame_nr));
                 });
 
-                let sprites = current_frame.sprites();
+                let sprites = current_frame.sprites_mut();
                 let screen_size = self.movie.screen_size();
                 let movie_frame_size = screen_size.to_egui() * ZOOM;
 
@@ -297,7 +419,8 @@ impl Movie {
                                 // Make sure the movie canvas doesn't shrink too far
                                 ui.set_min_size(movie_frame_size);
 
-                                MovieFrame::new(sprites).show(ui, screen_size, viewport);
+                                let movie_frame = MovieFrame::new(sprites);
+                                let transform = movie_frame.show(ui, screen_size, viewport);
 
                                 // This also "steals" the interaction of the parent, which in this
                                 // case causes the ScrollArea not to scroll on drag (which is what
@@ -307,12 +430,37 @@ impl Movie {
                                     ui.id(),
                                     egui::Sense::click_and_drag(),
                                 );
+                                super::accessible(ui, &response, "Movie frame");
+
+                                // Highlight the sprite under the cursor
+                                let hovered_sprite = response.hover_pos().and_then(|pos| {
+                                    movie_frame.sprite_at(pos, screen_size, &transform)
+                                });
+                                if let Some(index) = hovered_sprite {
+                                    movie_frame.highlight(ui, index, screen_size, &transform);
+                                }
 
                                 use crate::components::mouse::{DragEvent, MouseInteraction};
 
                                 if let Some(event) = self.mouse_tracker.update(&response) {
                                     match event {
-                                        MouseInteraction::Click(_) => { /* do nothing for now */ }
+                                        MouseInteraction::Click(pos) => {
+                                            let clicked_sprite =
+                                                movie_frame.sprite_at(pos, screen_size, &transform);
+                                            if let Some(index) = clicked_sprite {
+                                                self.selection.update(
+                                                    ui,
+                                                    index,
+                                                    sprites,
+                                                    |sprite| &mut sprite.state,
+                                                );
+                                            } else if !ui.input().modifiers.ctrl {
+                                                // Clicking on an empty spot clears the selection
+                                                sprites
+                                                    .iter_mut()
+                                                    .for_each(|sprite| sprite.state.unselect());
+                                            }
+                                        }
                                         MouseInteraction::Drag(event) => match event {
                                             DragEvent::Start(_) => {}
                                             DragEvent::Update(rect) => {
@@ -329,6 +477,15 @@ impl Movie {
                                         },
                                     }
                                 }
+
+                                if response.has_focus() {
+                                    handle_keyboard(
+                                        ui,
+                                        sprites,
+                                        &mut self.selection,
+                                        &mut self.control_messages,
+                                    );
+                                }
                             });
                     },
                 );
@@ -345,16 +502,268 @@ impl Movie {
         });
     }
 
+    /// Retrieves the underlying [`Movie`](ves_art_core::movie::Movie).
+    pub fn movie(&self) -> &ves_art_core::movie::Movie {
+        &self.movie
+    }
+
+    /// Retrieves the metadata of the movie for editing. The metadata does not affect the frames.
+    pub fn metadata_mut(&mut self) -> &mut ves_art_core::movie::MovieMetadata {
+        self.movie.metadata_mut()
+    }
+
+    /// Reassigns all sprites that use a palette to another palette, across the whole movie (see
+    /// [`Movie::reassign_palette()`](ves_art_core::movie::Movie::reassign_palette)).
+    ///
+    /// # Arguments
+    ///
+    /// * `from`: The palette that is currently used.
+    /// * `to`: The palette that should be used instead.
+    ///
+    /// returns: The number of sprites that have been reassigned.
+    pub fn reassign_palette(
+        &mut self,
+        from: ves_art_core::sprite::PaletteRef,
+        to: ves_art_core::sprite::PaletteRef,
+    ) -> Result<usize, String> {
+        let count = self.movie.reassign_palette(from, to)?;
+        self.frame_dirty = true;
+        self.clear_history();
+        Ok(count)
+    }
+
+    /// Crops the movie to a region of the screen (see [`Movie::crop()`](ves_art_core::movie::Movie::crop)).
+    ///
+    /// # Arguments
+    ///
+    /// * `rect`: The region.
+    ///
+    /// returns: The number of sprites that have been removed.
+    pub fn crop(&mut self, rect: ves_art_core::geom_art::Rect) -> Result<usize, String> {
+        let count = self.movie.crop(rect)?;
+        self.frame_dirty = true;
+        self.clear_history();
+        Ok(count)
+    }
+
+    /// Applies a post-processing [`Pipeline`](ves_art_core::postprocess::Pipeline) to the whole movie. The pipeline runs on a copy,
+    /// such that the movie remains untouched if any of the passes fails.
+    ///
+    /// Since the passes may remove frames, the cursor moves to the frame that shows the same moment of the capture (or the last
+    /// frame before it).
+    ///
+    /// # Arguments
+    ///
+    /// * `pipeline`: The pipeline.
+    ///
+    /// returns: The name and the result of every pass (see
+    /// [`FramePostProcessor::apply()`](ves_art_core::postprocess::FramePostProcessor::apply)).
+    pub fn apply_pipeline(
+        &mut self,
+        pipeline: &ves_art_core::postprocess::Pipeline,
+    ) -> Result<Vec<(String, usize)>, String> {
+        let mut movie = self.movie.clone();
+        let mut reports = Vec::with_capacity(pipeline.len());
+        pipeline.run(&mut movie, |name, count| {
+            reports.push((name.to_string(), count))
+        })?;
+        if movie.frames().is_empty() {
+            return Err("The pipeline has removed all frames.".to_string());
+        }
+
+        let frame_number = self
+            .movie
+            .frames()
+            .get(self.frame_cursor.position())
+            .map_or(0, |frame| frame.frame_number());
+        let position = matching_frame(&movie, frame_number);
+        self.frame_cursor = Cursor::new(movie.frames().len());
+        self.frame_cursor.move_to(position);
+        self.movie = movie;
+        // The sprite indices of the selection may no longer be valid
+        self.current_frame = None;
+        self.frame_dirty = true;
+        self.clear_history();
+        Ok(reports)
+    }
+
+    /// Tracks the sprites across the frames and stores their identities on the movie (see
+    /// [`assign_sprite_ids()`](ves_art_core::analysis::assign_sprite_ids)).
+    ///
+    /// # Arguments
+    ///
+    /// * `max_distance`: The maximum distance that a sprite can move from one frame to the next.
+    ///
+    /// returns: The number of distinct identities.
+    pub fn track_sprites(&mut self, max_distance: u32) -> usize {
+        ves_art_core::analysis::assign_sprite_ids(&mut self.movie, max_distance)
+    }
+
+    /// Replaces the palette mutations of a frame (see
+    /// [`Movie::set_palette_mutations()`](ves_art_core::movie::Movie::set_palette_mutations)).
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The position of the frame in the movie.
+    /// * `mutations`: The new mutations.
+    pub fn set_palette_mutations(
+        &mut self,
+        position: usize,
+        mutations: Vec<ves_art_core::movie::PaletteMutation>,
+    ) -> Result<(), String> {
+        self.movie.set_palette_mutations(position, mutations)?;
+        // The mutations also affect all following frames
+        self.frame_dirty = true;
+        Ok(())
+    }
+
+    /// Edits a sprite of the current frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index of the sprite in the current frame.
+    /// * `edited`: The edited sprite. Only the position, the palette and the flip flags are applied.
+    /// * `all_frames`: Whether to apply the edit to all sprites that show the same artwork in all frames (see
+    ///   [`Movie::find_similar_sprites()`](ves_art_core::movie::Movie::find_similar_sprites)). In this case, the sprites are moved
+    ///   by the same distance as the edited sprite.
+    ///
+    /// returns: The number of sprites that have been edited.
+    pub fn edit_sprite(
+        &mut self,
+        index: usize,
+        edited: &ves_art_core::sprite::Sprite,
+        all_frames: bool,
+    ) -> Result<usize, String> {
+        let frame = self
+            .current_frame
+            .as_ref()
+            .map(|current_frame| current_frame.frame_nr())
+            .ok_or_else(|| "No movie frame available.".to_string())?;
+        let original = self
+            .movie
+            .frames()
+            .get(frame)
+            .and_then(|movie_frame| movie_frame.sprites().get(index))
+            .ok_or_else(|| format!("There is no sprite {} in the current frame.", index))?
+            .clone();
+        let locations = if all_frames {
+            self.movie.find_similar_sprites(&original)
+        } else {
+            vec![SpriteLocation {
+                frame,
+                sprite: index,
+            }]
+        };
+
+        // Move all sprites by the same distance, wrapping around the edges of the screen
+        let screen_size = self.movie.screen_size();
+        let (width, height) = (screen_size.width.raw(), screen_size.height.raw());
+        let shift = |value: u32, from: u32, to: u32, screen: u32| {
+            (value % screen + to % screen + screen - from % screen) % screen
+        };
+        let replacements: Vec<_> = locations
+            .into_iter()
+            .map(|location| {
+                let mut sprite =
+                    self.movie.frames()[location.frame].sprites()[location.sprite].clone();
+                let (position, from, to) =
+                    (sprite.position(), original.position(), edited.position());
+                sprite.set_position(ves_art_core::geom_art::Point::new(
+                    shift(position.x.raw(), from.x.raw(), to.x.raw(), width),
+                    shift(position.y.raw(), from.y.raw(), to.y.raw(), height),
+                ));
+                sprite.set_palette(edited.palette());
+                sprite.set_h_flip(edited.h_flip());
+                sprite.set_v_flip(edited.v_flip());
+                (location, sprite)
+            })
+            .collect();
+
+        let count = replacements.len();
+        let undo = self.movie.replace_sprites(replacements)?;
+        self.undo_stack.push(undo);
+        self.redo_stack.clear();
+        self.frame_dirty = true;
+        Ok(count)
+    }
+
+    /// Determines whether there is a sprite edit that can be undone.
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    /// Determines whether there is a sprite edit that can be redone.
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Undoes the most recent sprite edit.
+    pub fn undo(&mut self) -> Result<(), String> {
+        if let Some(sprites) = self.undo_stack.pop() {
+            let redo = self.movie.replace_sprites(sprites)?;
+            self.redo_stack.push(redo);
+            self.frame_dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Redoes the most recently undone sprite edit.
+    pub fn redo(&mut self) -> Result<(), String> {
+        if let Some(sprites) = self.redo_stack.pop() {
+            let undo = self.movie.replace_sprites(sprites)?;
+            self.undo_stack.push(undo);
+            self.frame_dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Discards the sprite edit history. This is necessary after operations that move or remove sprites.
+    fn clear_history(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Pauses playback and moves to a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `position`: The position of the frame in the movie.
+    pub fn jump_to(&mut self, position: usize) {
+        self.control_messages
+            .push(MovieControlMessage::Jump(JumpMessage::Frame(position)));
+        self.control_messages.push(MovieControlMessage::Pause);
+    }
+
+    /// Retrieves the [`UsageIndex`] of the movie (see
+    /// [`Movie::build_usage_index()`](ves_art_core::movie::Movie::build_usage_index)).
+    pub fn usage(&self) -> &UsageIndex {
+        self.movie.build_usage_index()
+    }
+
+    /// Retrieves the current frame, if any.
+    pub fn current_frame(&self) -> Option<&CurrentFrame> {
+        self.current_frame.as_ref()
+    }
+
+    /// Retrieves the time it took to build the current frame (including the creation of the textures), if any.
+    pub fn frame_build_time(&self) -> Option<Duration> {
+        self.frame_build_time
+    }
+
     pub fn sprites(&self) -> Option<&[Selectable<Sprite>]> {
         self.current_frame
             .as_ref()
             .map(|current_frame| current_frame.sprites())
     }
 
-    pub fn sprites_mut(&mut self) -> Option<&mut [Selectable<Sprite>]> {
+    /// Retrieves the sprites of the current frame mutably, together with the [`UsageIndex`] of the movie.
+    pub fn sprites_mut_with_usage(
+        &mut self,
+    ) -> Option<(&mut [Selectable<Sprite>], &UsageIndex)> {
+        let usage = self.movie.build_usage_index();
         self.current_frame
             .as_mut()
-            .map(|current_frame| current_frame.sprites_mut())
+            .map(|current_frame| (current_frame.sprites_mut(), usage))
     }
 }
 
@@ -362,6 +771,8 @@ impl Movie {
 enum JumpMessage {
     Start,
     End,
+    /// Jump to the frame at the provided position.
+    Frame(usize),
 }
 
 #[derive(Clone, Debug)]
@@ -374,6 +785,23 @@ enum MovieControlMessage {
     SetRepeat(bool),
 }
 
+/// Finds the frame of a movie that shows a moment of the capture. If the movie does not contain that moment (for instance because
+/// it has been removed as a duplicate), the last frame before it is used.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `frame_number`: The frame number of the moment in the capture (see
+///   [`MovieFrame::frame_number()`](ves_art_core::movie::MovieFrame::frame_number)).
+///
+/// returns: The position of the frame in the movie.
+pub fn matching_frame(movie: &ves_art_core::movie::Movie, frame_number: u64) -> usize {
+    movie
+        .frames()
+        .partition_point(|frame| frame.frame_number() <= frame_number)
+        .saturating_sub(1)
+}
+
 struct MovieControls<Sink> {
     playback_state: PlaybackState,
     playback_repeat: bool,
@@ -394,13 +822,18 @@ impl<Sink> MovieControls<Sink>
 where
     Sink: FnMut(MovieControlMessage),
 {
+    /// Adds a button. The icon is shown, the label is used as tooltip and is exposed to the accessibility output (instead of the
+    /// icon).
     fn add_button(
         &mut self,
         ui: &mut egui::Ui,
         icon: &'static str,
+        label: &'static str,
         on_click_fn: impl FnOnce(&mut Sink),
     ) {
-        if ui.button(icon).clicked() {
+        let response = ui.button(icon).on_hover_text(label);
+        response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Button, label));
+        if response.clicked() {
             on_click_fn(&mut self.sink);
         }
     }
@@ -409,35 +842,52 @@ where
         &mut self,
         ui: &mut egui::Ui,
         icon: &'static str,
+        label: &'static str,
         message: MovieControlMessage,
     ) {
-        self.add_button(ui, icon, |sink| sink(message));
+        self.add_button(ui, icon, label, |sink| sink(message));
     }
 
     fn show(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            self.add_button_simple(ui, "⏮", MovieControlMessage::Jump(JumpMessage::Start));
-            self.add_button(ui, "<", |sink| {
+            self.add_button_simple(
+                ui,
+                "⏮",
+                "First frame",
+                MovieControlMessage::Jump(JumpMessage::Start),
+            );
+            self.add_button(ui, "<", "Previous frame", |sink| {
                 sink(MovieControlMessage::Pause);
                 sink(MovieControlMessage::SkipBackward(1));
             });
             if let PlaybackState::Playing(_) = self.playback_state {
-                self.add_button_simple(ui, "⏸", MovieControlMessage::Pause);
+                self.add_button_simple(ui, "⏸", "Pause", MovieControlMessage::Pause);
             } else {
-                self.add_button_simple(ui, "▶", MovieControlMessage::Play);
+                self.add_button_simple(ui, "▶", "Play", MovieControlMessage::Play);
             }
-            self.add_button(ui, "⏹", |sink| {
+            self.add_button(ui, "⏹", "Stop", |sink| {
                 sink(MovieControlMessage::Pause);
                 sink(MovieControlMessage::Jump(JumpMessage::Start));
             });
-            self.add_button(ui, ">", |sink| {
+            self.add_button(ui, ">", "Next frame", |sink| {
                 sink(MovieControlMessage::Pause);
                 sink(MovieControlMessage::SkipForward(1));
             });
-            self.add_button_simple(ui, "⏭", MovieControlMessage::Jump(JumpMessage::End));
+            self.add_button_simple(
+                ui,
+                "⏭",
+                "Last frame",
+                MovieControlMessage::Jump(JumpMessage::End),
+            );
+            let repeat_label = if self.playback_repeat {
+                "Repeat (on)"
+            } else {
+                "Repeat (off)"
+            };
             self.add_button_simple(
                 ui,
                 "🔁",
+                repeat_label,
                 MovieControlMessage::SetRepeat(!self.playback_repeat),
             );
         });
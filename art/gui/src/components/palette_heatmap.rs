@@ -0,0 +1,151 @@
+use crate::components::movie::Movie;
+use crate::egui;
+use ves_art_core::sprite::PaletteRef;
+
+/// The width of a timeline strip.
+const STRIP_WIDTH: f32 = 256.0;
+/// The height of a timeline strip.
+const STRIP_HEIGHT: f32 = 12.0;
+/// The color for the frames in which a palette is used.
+const USED_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 128, 0);
+/// The color of the marker for the current frame.
+const CURRENT_FRAME_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 255, 255);
+
+/// A heatmap that shows, per palette, in which frames of the movie it is used.
+///
+/// Every palette gets a timeline strip that covers the entire movie. If the movie has more frames than the strip is wide, multiple
+/// frames are combined into a single column and the intensity of the column reflects the fraction of those frames that use the
+/// palette. Clicking on a strip jumps to the first frame that uses the palette.
+#[derive(Default)]
+pub struct PaletteHeatmap;
+
+impl PaletteHeatmap {
+    /// Shows the heatmap.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) {
+        let frame_count = movie.movie().frames().len();
+        if movie.movie().palettes().is_empty() {
+            ui.label("The movie does not contain any palettes.");
+            return;
+        }
+        let current_position = movie.current_frame().map(|frame| frame.frame_nr());
+        let labels: Vec<String> = movie
+            .movie()
+            .palettes()
+            .iter()
+            .enumerate()
+            .map(|(index, palette)| match palette.name() {
+                Some(name) => format!("{} ({})", index, name),
+                None => format!("{}", index),
+            })
+            .collect();
+
+        let mut jump_target = None;
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            egui::Grid::new("palette_heatmap_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    let usage = movie.usage();
+                    for (index, label) in labels.iter().enumerate() {
+                        let frames = usage.palette_frames(PaletteRef::new(index));
+                        ui.label(label);
+                        let response = timeline_strip(ui, frames, frame_count, current_position);
+                        super::accessible(ui, &response, &format!("Usage of palette {}", label));
+                        let response = match (frames.first(), frames.last()) {
+                            (Some(first), Some(last)) => response.on_hover_text(format!(
+                                "Used in {} frames, first in frame {}, last in frame {}.",
+                                frames.len(),
+                                first,
+                                last
+                            )),
+                            _ => response.on_hover_text("Not used in any frame."),
+                        };
+                        if response.clicked() {
+                            jump_target = frames.first().copied();
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if let Some(position) = jump_target {
+            movie.jump_to(position);
+        }
+    }
+}
+
+/// Shows a timeline strip for a single palette.
+///
+/// # Arguments
+///
+/// * `ui`: The UI.
+/// * `frames`: The positions of the frames that use the palette, in ascending order.
+/// * `frame_count`: The number of frames in the movie.
+/// * `current_position`: The position of the current frame, if any.
+///
+/// returns: The response of the strip.
+fn timeline_strip(
+    ui: &mut egui::Ui,
+    frames: &[usize],
+    frame_count: usize,
+    current_position: Option<usize>,
+) -> egui::Response {
+    let (rect, response) =
+        ui.allocate_exact_size(egui::vec2(STRIP_WIDTH, STRIP_HEIGHT), egui::Sense::click());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+    if frame_count == 0 {
+        return response;
+    }
+
+    // Combine frames into columns, such that a column is at least one point wide
+    let columns = frame_count.min(STRIP_WIDTH as usize);
+    let mut counts = vec![0usize; columns];
+    for position in frames {
+        counts[position * columns / frame_count] += 1;
+    }
+
+    let column_width = STRIP_WIDTH / columns as f32;
+    for (column, count) in counts.into_iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        // The number of frames in a column differs by at most one between columns
+        let first = (column * frame_count + columns - 1) / columns;
+        let end = ((column + 1) * frame_count + columns - 1) / columns;
+        let intensity = count as f32 / (end - first) as f32;
+        let x = rect.left() + column as f32 * column_width;
+        let column_rect = egui::Rect::from_min_max(
+            egui::pos2(x, rect.top()),
+            egui::pos2(x + column_width, rect.bottom()),
+        );
+        // Keep a minimum intensity, such that single frames remain visible
+        let alpha = (64.0 + 191.0 * intensity) as u8;
+        painter.rect_filled(
+            column_rect,
+            0.0,
+            egui::Color32::from_rgba_unmultiplied(
+                USED_COLOR.r(),
+                USED_COLOR.g(),
+                USED_COLOR.b(),
+                alpha,
+            ),
+        );
+    }
+
+    if let Some(position) = current_position {
+        let x = rect.left()
+            + (position * columns / frame_count) as f32 * column_width
+            + column_width / 2.0;
+        painter.line_segment(
+            [egui::pos2(x, rect.top()), egui::pos2(x, rect.bottom())],
+            egui::Stroke::new(1.0, CURRENT_FRAME_COLOR),
+        );
+    }
+
+    response
+}
@@ -0,0 +1,255 @@
+use crate::components::movie::Movie;
+use crate::egui;
+use ves_art_core::movie::PaletteMutation;
+use ves_art_core::sprite::{Color, PaletteIndex, PaletteRef};
+
+/// The size of a color swatch.
+const SWATCH_SIZE: f32 = 12.0;
+
+/// A single row of the panel.
+struct MutationRow {
+    /// The position of the frame in the movie.
+    position: usize,
+    frame_number: u64,
+    /// The index of the mutation in the frame.
+    index: usize,
+    mutation: PaletteMutation,
+    /// The color of the entry before the mutation.
+    old_color: Color,
+}
+
+/// A change to the mutations of a frame.
+enum MutationEdit {
+    SetColor(usize, usize, Color),
+    Remove(usize, usize),
+    Add(usize, PaletteMutation),
+}
+
+/// A panel that lists the palette mutations of the movie (see [`PaletteMutation`]).
+///
+/// Every mutation is shown together with the color that it replaces. The rows of the current frame are highlighted and clicking on a
+/// frame number jumps to that frame. The colors of the mutations can be edited, mutations can be removed and new mutations can be
+/// added to the current frame. The movie window reflects the changes right away.
+#[derive(Default)]
+pub struct PaletteMutations {
+    /// The palette for a new mutation.
+    palette: usize,
+    /// The entry for a new mutation.
+    entry: u8,
+    /// The color for a new mutation.
+    rgb: [u8; 3],
+    error: Option<String>,
+}
+
+impl PaletteMutations {
+    /// Shows the panel.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) {
+        let current_position = movie.current_frame().map(|frame| frame.frame_nr());
+        let rows = mutation_rows(movie.movie());
+        let mut edit = None;
+
+        if rows.is_empty() {
+            ui.label("The movie does not contain any palette mutations.");
+        } else {
+            ui.horizontal(|ui| {
+                let position = current_position.unwrap_or(0);
+                let previous = rows
+                    .iter()
+                    .rev()
+                    .map(|row| row.position)
+                    .find(|row_position| *row_position < position);
+                let next = rows
+                    .iter()
+                    .map(|row| row.position)
+                    .find(|row_position| *row_position > position);
+                for (label, target) in
+                    [("⏴ Previous mutation", previous), ("Next mutation ⏵", next)]
+                {
+                    if ui
+                        .add_enabled(target.is_some(), egui::Button::new(label))
+                        .clicked()
+                    {
+                        if let Some(position) = target {
+                            movie.jump_to(position);
+                        }
+                    }
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .max_height(240.0)
+                .show(ui, |ui| {
+                    egui::Grid::new("palette_mutations_grid")
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("Frame");
+                            ui.label("Palette");
+                            ui.label("Index");
+                            ui.label("Old → new");
+                            ui.label("");
+                            ui.end_row();
+
+                            for row in &rows {
+                                let current = current_position == Some(row.position);
+                                if ui
+                                    .selectable_label(current, format!("{}", row.frame_number))
+                                    .on_hover_text(format!("Position {}", row.position))
+                                    .clicked()
+                                {
+                                    movie.jump_to(row.position);
+                                }
+                                ui.label(format!("{}", row.mutation.palette().value()));
+                                ui.label(format!("{}", row.mutation.index().value()));
+                                ui.horizontal(|ui| {
+                                    swatch(ui, row.old_color);
+                                    ui.label("→");
+                                    swatch(ui, row.mutation.color());
+                                });
+                                ui.horizontal(|ui| {
+                                    let mut rgb = match row.mutation.color() {
+                                        Color::Opaque(rgb) => [rgb.r, rgb.g, rgb.b],
+                                        Color::Transparent => [0, 0, 0],
+                                    };
+                                    if ui.color_edit_button_srgb(&mut rgb).changed() {
+                                        edit = Some(MutationEdit::SetColor(
+                                            row.position,
+                                            row.index,
+                                            Color::new(rgb[0], rgb[1], rgb[2]),
+                                        ));
+                                    }
+                                    if ui.small_button("Remove").clicked() {
+                                        edit = Some(MutationEdit::Remove(row.position, row.index));
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+
+        ui.separator();
+        let palettes = movie.movie().palettes();
+        match (current_position, palettes.len()) {
+            (Some(position), palette_count) if palette_count > 0 => {
+                // The movie may have been replaced since the last time
+                self.palette = self.palette.min(palette_count - 1);
+                // The cast is OK here, because the value has been limited to the range of a u8
+                let max_entry = palettes[self.palette].len().saturating_sub(1).min(0xff) as u8;
+                self.entry = self.entry.min(max_entry);
+                ui.horizontal(|ui| {
+                    ui.label("Palette");
+                    ui.add(
+                        egui::DragValue::new(&mut self.palette).clamp_range(0..=palette_count - 1),
+                    );
+                    ui.label("Index");
+                    ui.add(egui::DragValue::new(&mut self.entry).clamp_range(0..=max_entry));
+                    ui.color_edit_button_srgb(&mut self.rgb);
+                    if ui.button("Add to current frame").clicked() {
+                        edit = Some(MutationEdit::Add(
+                            position,
+                            PaletteMutation::new(
+                                PaletteRef::new(self.palette),
+                                PaletteIndex::new(self.entry),
+                                Color::new(self.rgb[0], self.rgb[1], self.rgb[2]),
+                            ),
+                        ));
+                    }
+                });
+            }
+            _ => {
+                ui.label("Mutations can only be added to a frame of a movie with palettes.");
+            }
+        }
+
+        if let Some(edit) = edit {
+            self.error = apply_edit(movie, edit).err();
+        }
+        if let Some(err) = &self.error {
+            ui.colored_label(egui::Color32::RED, err);
+        }
+    }
+}
+
+/// Collects the palette mutations of all frames, together with the colors that they replace.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+fn mutation_rows(movie: &ves_art_core::movie::Movie) -> Vec<MutationRow> {
+    let mut palettes = movie.palettes().to_vec();
+    let mut rows = Vec::new();
+    for (position, frame) in movie.frames().iter().enumerate() {
+        for (index, mutation) in frame.palette_mutations().iter().enumerate() {
+            // Mutations of entries that do not exist are still listed, such that they can be removed
+            let entry = palettes
+                .get_mut(mutation.palette().value())
+                .filter(|palette| usize::from(mutation.index().value()) < palette.len())
+                .map(|palette| &mut palette[mutation.index()]);
+            let old_color = match entry {
+                Some(entry) => std::mem::replace(entry, mutation.color()),
+                None => Color::Transparent,
+            };
+            rows.push(MutationRow {
+                position,
+                frame_number: frame.frame_number(),
+                index,
+                mutation: *mutation,
+                old_color,
+            });
+        }
+    }
+    rows
+}
+
+/// Applies a [`MutationEdit`] to the movie.
+fn apply_edit(movie: &mut Movie, edit: MutationEdit) -> Result<(), String> {
+    let position = match edit {
+        MutationEdit::SetColor(position, ..)
+        | MutationEdit::Remove(position, _)
+        | MutationEdit::Add(position, _) => position,
+    };
+    let mut mutations = movie
+        .movie()
+        .frames()
+        .get(position)
+        .ok_or_else(|| format!("There is no frame at position {}.", position))?
+        .palette_mutations()
+        .to_vec();
+
+    match edit {
+        MutationEdit::SetColor(_, index, color) => {
+            let mutation = &mut mutations[index];
+            *mutation = PaletteMutation::new(mutation.palette(), mutation.index(), color);
+        }
+        MutationEdit::Remove(_, index) => {
+            mutations.remove(index);
+        }
+        MutationEdit::Add(_, mutation) => mutations.push(mutation),
+    }
+    movie.set_palette_mutations(position, mutations)
+}
+
+/// Shows a single color. Transparent colors are shown as an outline.
+fn swatch(ui: &mut egui::Ui, color: Color) {
+    let (rect, _) =
+        ui.allocate_exact_size(egui::vec2(SWATCH_SIZE, SWATCH_SIZE), egui::Sense::hover());
+    match color {
+        Color::Opaque(rgb) => {
+            ui.painter()
+                .rect_filled(rect, 0.0, egui::Color32::from_rgb(rgb.r, rgb.g, rgb.b));
+        }
+        Color::Transparent => {
+            ui.painter().rect_stroke(
+                rect.shrink(0.5),
+                0.0,
+                egui::Stroke::new(1.0, egui::Color32::GRAY),
+            );
+        }
+    }
+}
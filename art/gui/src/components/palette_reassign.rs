@@ -0,0 +1,135 @@
+use crate::components::movie::Movie;
+use crate::egui;
+use ves_art_core::sprite::{Color, Palette, PaletteRef};
+
+/// The size of a single color in the palette preview.
+const SWATCH_SIZE: f32 = 12.0;
+
+/// A tool for reassigning all sprites that use one palette to another palette, across the whole movie.
+///
+/// This is useful for consolidating palettes, for instance after an optimization has proposed to merge two palettes that only differ
+/// in colors that are not used.
+#[derive(Default)]
+pub struct PaletteReassignment {
+    from: usize,
+    to: usize,
+    result: Option<Result<usize, String>>,
+}
+
+impl PaletteReassignment {
+    /// Shows the tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) {
+        let palettes = movie.movie().palettes();
+        if palettes.is_empty() {
+            ui.label("The movie does not contain any palettes.");
+            return;
+        }
+        // The movie may have been replaced since the last time
+        self.from = self.from.min(palettes.len() - 1);
+        self.to = self.to.min(palettes.len() - 1);
+
+        let mut changed = false;
+        egui::Grid::new("palette_reassign_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("From");
+                changed |= palette_combo(ui, "palette_reassign_from", &mut self.from, palettes);
+                ui.end_row();
+                ui.label("");
+                palette_swatches(ui, &palettes[self.from]);
+                ui.end_row();
+
+                ui.label("To");
+                changed |= palette_combo(ui, "palette_reassign_to", &mut self.to, palettes);
+                ui.end_row();
+                ui.label("");
+                palette_swatches(ui, &palettes[self.to]);
+                ui.end_row();
+            });
+        if changed {
+            self.result = None;
+        }
+
+        let (from, to) = (PaletteRef::new(self.from), PaletteRef::new(self.to));
+        let (sprites, frames) = movie.movie().palette_usage(from);
+        ui.label(format!(
+            "{} sprites in {} frames use {}.",
+            sprites, frames, from
+        ));
+
+        let enabled = from != to && sprites > 0;
+        if ui
+            .add_enabled(enabled, egui::Button::new("Reassign"))
+            .clicked()
+        {
+            self.result = Some(movie.reassign_palette(from, to));
+        }
+
+        match &self.result {
+            None => {}
+            Some(Ok(count)) => {
+                ui.label(format!("Reassigned {} sprites to {}.", count, to));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+    }
+}
+
+/// Shows a combo box for selecting a palette.
+///
+/// returns: `true` if the selection has changed.
+fn palette_combo(
+    ui: &mut egui::Ui,
+    id_source: &str,
+    selected: &mut usize,
+    palettes: &[Palette],
+) -> bool {
+    let label = |index: usize| match palettes[index].name() {
+        Some(name) => format!("{} ({})", index, name),
+        None => format!("{}", index),
+    };
+
+    let before = *selected;
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(label(*selected))
+        .show_ui(ui, |ui| {
+            for index in 0..palettes.len() {
+                ui.selectable_value(selected, index, label(index));
+            }
+        });
+    before != *selected
+}
+
+/// Shows the colors of a palette. Transparent entries are shown as an outline.
+fn palette_swatches(ui: &mut egui::Ui, palette: &Palette) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing = egui::vec2(1.0, 1.0);
+        for (_, color) in palette.iter() {
+            let (rect, _) =
+                ui.allocate_exact_size(egui::vec2(SWATCH_SIZE, SWATCH_SIZE), egui::Sense::hover());
+            match color {
+                Color::Opaque(rgb) => {
+                    ui.painter().rect_filled(
+                        rect,
+                        0.0,
+                        egui::Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+                    );
+                }
+                Color::Transparent => {
+                    ui.painter().rect_stroke(
+                        rect.shrink(0.5),
+                        0.0,
+                        egui::Stroke::new(1.0, egui::Color32::GRAY),
+                    );
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,200 @@
+use super::hud::TextureStats;
+use super::selection::Selectable;
+use super::sprite::Sprite;
+use crate::egui;
+use crate::egui::ImageData;
+use crate::ToEgui as _;
+use ves_art_core::color::reduce_to_5bit;
+use ves_core_render::movie::MovieScene;
+use ves_core_render::SCREEN_BUFFER_WIDTH;
+
+/// The color that marks the pixels that differ between both render paths.
+const MISMATCH_COLOR: egui::Color32 = egui::Color32::from_rgb(255, 0, 255);
+
+/// A comparison between the egui render path and the core render path (`ves-core-render`) for a single movie frame.
+pub struct RenderParity {
+    frame_nr: usize,
+    result: Result<ParityImages, String>,
+}
+
+struct ParityImages {
+    gui: egui::TextureHandle,
+    core: egui::TextureHandle,
+    diff: egui::TextureHandle,
+    size: egui::Vec2,
+    mismatches: usize,
+}
+
+impl RenderParity {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: The context for loading the textures.
+    /// * `movie`: The movie.
+    /// * `frame_nr`: The frame number.
+    /// * `sprites`: The GUI sprites for the frame.
+    ///
+    /// returns: The [`RenderParity`].
+    pub fn create(
+        ctx: &egui::Context,
+        movie: &ves_art_core::movie::Movie,
+        frame_nr: usize,
+        sprites: &[Selectable<Sprite>],
+    ) -> Self {
+        let screen_size = movie.screen_size();
+        let width: usize = screen_size.width.raw().try_into().unwrap();
+        let height: usize = screen_size.height.raw().try_into().unwrap();
+
+        let result = MovieScene::from_movie_frame(movie, frame_nr)
+            .and_then(|scene| scene.render())
+            .map(|screen_buffer| {
+                let gui_image = compose_gui_image(sprites, [width, height]);
+                let core_image = crop_screen_buffer(&screen_buffer, [width, height]);
+
+                let mut diff_image = egui::ColorImage::new([width, height], egui::Color32::BLACK);
+                let mut mismatches = 0;
+                for ((gui, core), diff) in gui_image
+                    .pixels
+                    .iter()
+                    .zip(&core_image.pixels)
+                    .zip(&mut diff_image.pixels)
+                {
+                    if !same_color(*gui, *core) {
+                        *diff = MISMATCH_COLOR;
+                        mismatches += 1;
+                    }
+                }
+
+                ParityImages {
+                    gui: ctx.load_texture("parity_gui", ImageData::Color(gui_image)),
+                    core: ctx.load_texture("parity_core", ImageData::Color(core_image)),
+                    diff: ctx.load_texture("parity_diff", ImageData::Color(diff_image)),
+                    size: screen_size.to_egui(),
+                    mismatches,
+                }
+            });
+
+        Self { frame_nr, result }
+    }
+
+    /// Retrieves the frame number.
+    pub fn frame_nr(&self) -> usize {
+        self.frame_nr
+    }
+
+    /// Adds the textures of the comparison to the [`TextureStats`].
+    pub fn add_texture_stats(&self, stats: &mut TextureStats) {
+        if let Ok(images) = &self.result {
+            // The GUI, core and diff images all have the size of the screen
+            let size = [images.size.x as usize, images.size.y as usize];
+            for _ in 0..3 {
+                stats.add(size);
+            }
+        }
+    }
+
+    pub fn show(&self, ui: &mut egui::Ui) {
+        match &self.result {
+            Err(err) => {
+                ui.label(format!("Could not render frame {}: {}", self.frame_nr, err));
+            }
+            Ok(images) => {
+                ui.vertical(|ui| {
+                    if images.mismatches == 0 {
+                        ui.label("Both render paths are identical.");
+                    } else {
+                        ui.colored_label(
+                            MISMATCH_COLOR,
+                            format!("{} pixels differ.", images.mismatches),
+                        );
+                    }
+
+                    ui.horizontal(|ui| {
+                        for (title, texture) in [
+                            ("GUI", &images.gui),
+                            ("Core", &images.core),
+                            ("Diff", &images.diff),
+                        ] {
+                            ui.vertical(|ui| {
+                                ui.label(title);
+                                ui.image(texture, images.size);
+                            });
+                        }
+                    });
+                });
+            }
+        }
+    }
+}
+
+/// Composes the frame from the sprite images in the same way as the movie view does (including wrapping around the screen edges).
+fn compose_gui_image(sprites: &[Selectable<Sprite>], size: [usize; 2]) -> egui::ColorImage {
+    let [width, height] = size;
+    let mut image = egui::ColorImage::new(size, egui::Color32::TRANSPARENT);
+
+    // Reverse-iterate because the first sprites should be rendered on top
+    for sprite in sprites.iter().rev().map(|selectable| &selectable.item) {
+        let src = sprite.image();
+        let [src_width, src_height] = src.size;
+        let rect = sprite.rect();
+        let min_x: usize = rect.min_x().raw().try_into().unwrap();
+        let min_y: usize = rect.min_y().raw().try_into().unwrap();
+
+        for y in 0..src_height {
+            let src_y = if sprite.vflip() {
+                src_height - 1 - y
+            } else {
+                y
+            };
+            for x in 0..src_width {
+                let src_x = if sprite.hflip() { src_width - 1 - x } else { x };
+                let color = src.pixels[src_y * src_width + src_x];
+                if color.a() == 0 {
+                    continue;
+                }
+                let dest_x = (min_x + x) % width;
+                let dest_y = (min_y + y) % height;
+                image.pixels[dest_y * width + dest_x] = color;
+            }
+        }
+    }
+
+    image
+}
+
+/// Converts (the top-left part of) a screen buffer from `ves-core-render` into an image.
+fn crop_screen_buffer(screen_buffer: &[u8], size: [usize; 2]) -> egui::ColorImage {
+    let [width, height] = size;
+    let buffer_width = SCREEN_BUFFER_WIDTH as usize;
+    let buffer_height = screen_buffer.len() / 4 / buffer_width;
+
+    let mut image = egui::ColorImage::new(size, egui::Color32::TRANSPARENT);
+    for y in 0..height.min(buffer_height) {
+        for x in 0..width.min(buffer_width) {
+            let i = 4 * (y * buffer_width + x);
+            let rgba = &screen_buffer[i..i + 4];
+            image.pixels[y * width + x] =
+                egui::Color32::from_rgba_unmultiplied(rgba[0], rgba[1], rgba[2], rgba[3]);
+        }
+    }
+    image
+}
+
+/// Compares two colors at the resolution of the core (5 bits per component).
+fn same_color(a: egui::Color32, b: egui::Color32) -> bool {
+    match (a.a(), b.a()) {
+        (0, 0) => true,
+        (0, _) | (_, 0) => false,
+        _ => {
+            let reduce = |color: egui::Color32| {
+                (
+                    reduce_to_5bit(color.r()),
+                    reduce_to_5bit(color.g()),
+                    reduce_to_5bit(color.b()),
+                )
+            };
+            reduce(a) == reduce(b)
+        }
+    }
+}
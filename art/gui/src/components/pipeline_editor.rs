@@ -0,0 +1,341 @@
+use crate::components::movie::{matching_frame, Movie};
+use crate::egui;
+use crate::egui::ImageData;
+use ves_art_core::postprocess::{PassConfig, Pipeline};
+use ves_art_core::render::{render_frame, RenderOptions};
+use ves_art_core::sprite::Color;
+use ves_art_core::surface::Surface as _;
+
+/// The passes that can be added, with their default configuration.
+const PASS_TEMPLATES: [PassConfig; 4] = [
+    PassConfig::Dedup,
+    PassConfig::StripHud { min_fraction: 0.9 },
+    PassConfig::Crop {
+        x: 0,
+        y: 0,
+        width: 256,
+        height: 224,
+    },
+    PassConfig::Scale { factor: 2 },
+];
+
+/// The action that the user has chosen for a pass.
+enum PassAction {
+    MoveUp(usize),
+    MoveDown(usize),
+    Remove(usize),
+}
+
+/// An editor for a post-processing [`Pipeline`]. Passes can be added, removed, reordered and configured, and the effect of the
+/// pipeline on the current frame can be previewed before it is applied to the whole movie.
+#[derive(Default)]
+pub struct PipelineEditor {
+    passes: Vec<PassConfig>,
+    /// The index in [`PASS_TEMPLATES`] of the pass that is added next.
+    template: usize,
+    preview: Option<PipelinePreview>,
+    result: Option<Result<Vec<(String, usize)>, String>>,
+}
+
+impl PipelineEditor {
+    /// Shows the editor.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    ///
+    /// returns: `true` if the pipeline has been applied to the movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &mut Movie) -> bool {
+        let mut changed = self.show_passes(ui);
+
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_source("pipeline_template")
+                .selected_text(pass_name(&PASS_TEMPLATES[self.template]))
+                .show_ui(ui, |ui| {
+                    for (index, template) in PASS_TEMPLATES.iter().enumerate() {
+                        ui.selectable_value(&mut self.template, index, pass_name(template));
+                    }
+                });
+            if ui.button("Add pass").clicked() {
+                self.passes.push(PASS_TEMPLATES[self.template]);
+                changed = true;
+            }
+        });
+        if changed {
+            // The preview and the result belong to the previous configuration
+            self.preview = None;
+            self.result = None;
+        }
+
+        ui.separator();
+        let mut applied = false;
+        ui.horizontal(|ui| {
+            let enabled = !self.passes.is_empty();
+            if ui
+                .add_enabled(enabled, egui::Button::new("Preview current frame"))
+                .clicked()
+            {
+                self.preview = Some(PipelinePreview::create(ui.ctx(), movie, &self.passes));
+            }
+            if ui
+                .add_enabled(enabled, egui::Button::new("Apply to movie"))
+                .clicked()
+            {
+                let result = movie.apply_pipeline(&Pipeline::from_config(&self.passes));
+                applied = result.is_ok();
+                self.preview = None;
+                self.result = Some(result);
+            }
+        });
+
+        if let Some(preview) = &self.preview {
+            preview.show(ui);
+        }
+
+        match &self.result {
+            None => {}
+            Some(Ok(reports)) => {
+                for (name, count) in reports {
+                    ui.label(format!("Pass '{}' changed {} items.", name, count));
+                }
+            }
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+
+        applied
+    }
+
+    /// Shows the list of passes with their options.
+    ///
+    /// returns: `true` if the passes have been changed.
+    fn show_passes(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.passes.is_empty() {
+            ui.label("The pipeline does not contain any passes.");
+            return false;
+        }
+
+        let mut changed = false;
+        let mut action = None;
+        let count = self.passes.len();
+        egui::Grid::new("pipeline_grid")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (index, pass) in self.passes.iter_mut().enumerate() {
+                    ui.label(format!("{}. {}", index + 1, pass_name(pass)));
+                    changed |= show_options(ui, pass);
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(index > 0, egui::Button::new("Up")).clicked() {
+                            action = Some(PassAction::MoveUp(index));
+                        }
+                        if ui
+                            .add_enabled(index + 1 < count, egui::Button::new("Down"))
+                            .clicked()
+                        {
+                            action = Some(PassAction::MoveDown(index));
+                        }
+                        if ui.button("Remove").clicked() {
+                            action = Some(PassAction::Remove(index));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+
+        match action {
+            None => {}
+            Some(PassAction::MoveUp(index)) => self.passes.swap(index - 1, index),
+            Some(PassAction::MoveDown(index)) => self.passes.swap(index, index + 1),
+            Some(PassAction::Remove(index)) => {
+                self.passes.remove(index);
+            }
+        }
+        changed || action.is_some()
+    }
+}
+
+/// Retrieves the name of a pass, as it appears in the configuration files of the command line tool.
+fn pass_name(pass: &PassConfig) -> &'static str {
+    match pass {
+        PassConfig::Dedup => "dedup",
+        PassConfig::StripHud { .. } => "strip_hud",
+        PassConfig::Crop { .. } => "crop",
+        PassConfig::Scale { .. } => "scale",
+    }
+}
+
+/// Shows the options of a pass.
+///
+/// returns: `true` if any of the options has been changed.
+fn show_options(ui: &mut egui::Ui, pass: &mut PassConfig) -> bool {
+    let before = *pass;
+    ui.horizontal(|ui| match pass {
+        PassConfig::Dedup => {
+            ui.label("No options");
+        }
+        PassConfig::StripHud { min_fraction } => {
+            ui.label("Min. fraction");
+            ui.add(
+                egui::DragValue::new(min_fraction)
+                    .clamp_range(0.0..=1.0)
+                    .speed(0.01),
+            );
+        }
+        PassConfig::Crop {
+            x,
+            y,
+            width,
+            height,
+        } => {
+            for (label, value, min) in [("X", x, 0), ("Y", y, 0), ("W", width, 1), ("H", height, 1)]
+            {
+                ui.label(label);
+                ui.add(egui::DragValue::new(value).clamp_range(min..=u32::from(u16::MAX)));
+            }
+        }
+        PassConfig::Scale { factor } => {
+            ui.label("Factor");
+            ui.add(egui::DragValue::new(factor).clamp_range(1..=8));
+        }
+    });
+    before != *pass
+}
+
+/// The current frame of a movie before and after running a pipeline on a copy of the movie.
+struct PipelinePreview {
+    frame_nr: usize,
+    result: Result<PreviewImages, String>,
+}
+
+struct PreviewImages {
+    before: (egui::TextureHandle, egui::Vec2),
+    after: (egui::TextureHandle, egui::Vec2),
+    frame_count: (usize, usize),
+    sprite_count: (usize, usize),
+    reports: Vec<(String, usize)>,
+}
+
+impl PipelinePreview {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`: The context for loading the textures.
+    /// * `movie`: The movie.
+    /// * `passes`: The configuration of the pipeline.
+    fn create(ctx: &egui::Context, movie: &Movie, passes: &[PassConfig]) -> Self {
+        let frame_nr = movie
+            .current_frame()
+            .map_or(0, |current_frame| current_frame.frame_nr());
+        let result = Self::create_images(ctx, movie.movie(), frame_nr, passes);
+        Self { frame_nr, result }
+    }
+
+    fn create_images(
+        ctx: &egui::Context,
+        movie: &ves_art_core::movie::Movie,
+        frame_nr: usize,
+        passes: &[PassConfig],
+    ) -> Result<PreviewImages, String> {
+        let frame_number = movie
+            .frames()
+            .get(frame_nr)
+            .ok_or_else(|| "No movie frame available.".to_string())?
+            .frame_number();
+
+        // The passes may depend on all frames (like the detection of the HUD), so the pipeline runs on the whole movie
+        let mut processed = movie.clone();
+        let mut reports = Vec::with_capacity(passes.len());
+        Pipeline::from_config(passes).run(&mut processed, |name, count| {
+            reports.push((name.to_string(), count))
+        })?;
+        if processed.frames().is_empty() {
+            return Err("The pipeline has removed all frames.".to_string());
+        }
+        let processed_nr = matching_frame(&processed, frame_number);
+
+        Ok(PreviewImages {
+            before: load_frame(ctx, "pipeline_before", movie, frame_nr)?,
+            after: load_frame(ctx, "pipeline_after", &processed, processed_nr)?,
+            frame_count: (movie.frames().len(), processed.frames().len()),
+            sprite_count: (
+                movie.frames()[frame_nr].sprites().len(),
+                processed.frames()[processed_nr].sprites().len(),
+            ),
+            reports,
+        })
+    }
+
+    fn show(&self, ui: &mut egui::Ui) {
+        match &self.result {
+            Err(err) => {
+                ui.colored_label(
+                    egui::Color32::RED,
+                    format!("Could not preview frame {}: {}", self.frame_nr, err),
+                );
+            }
+            Ok(images) => {
+                for (name, count) in &images.reports {
+                    ui.label(format!("Pass '{}' would change {} items.", name, count));
+                }
+                ui.horizontal(|ui| {
+                    for (title, (texture, size), frames, sprites) in [
+                        (
+                            "Before",
+                            &images.before,
+                            images.frame_count.0,
+                            images.sprite_count.0,
+                        ),
+                        (
+                            "After",
+                            &images.after,
+                            images.frame_count.1,
+                            images.sprite_count.1,
+                        ),
+                    ] {
+                        ui.vertical(|ui| {
+                            ui.label(format!(
+                                "{} ({} frames, {} sprites)",
+                                title, frames, sprites
+                            ));
+                            ui.image(texture, *size);
+                        });
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// Renders a frame of a movie into a texture.
+///
+/// returns: The texture and its size.
+fn load_frame(
+    ctx: &egui::Context,
+    name: &str,
+    movie: &ves_art_core::movie::Movie,
+    frame_nr: usize,
+) -> Result<(egui::TextureHandle, egui::Vec2), String> {
+    let frame = render_frame(movie, frame_nr, &RenderOptions::default())?;
+    let size = frame.size();
+    // The casts are OK here, because the screen size is far below the limits of both types
+    let (width, height) = (size.width.raw() as usize, size.height.raw() as usize);
+    let pixels = frame
+        .data()
+        .iter()
+        .map(|color| match color {
+            Color::Opaque(rgb) => egui::Color32::from_rgb(rgb.r, rgb.g, rgb.b),
+            Color::Transparent => egui::Color32::TRANSPARENT,
+        })
+        .collect();
+    let image = egui::ColorImage {
+        size: [width, height],
+        pixels,
+    };
+    Ok((
+        ctx.load_texture(name, ImageData::Color(image)),
+        egui::vec2(width as f32, height as f32),
+    ))
+}
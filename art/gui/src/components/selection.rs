@@ -69,6 +69,11 @@ pub struct SelectionRange {
 }
 
 impl SelectionRange {
+    /// Retrieves the index of the item from which range selections start (the last item that was clicked without shift).
+    pub fn root(&self) -> Option<usize> {
+        self.selection_root
+    }
+
     /// Updates the selection.
     ///
     /// # Arguments
@@ -1,5 +1,7 @@
 use crate::egui;
 use std::ops::Index;
+use ves_art_core::mask::OpacityMask;
+use ves_art_core::sprite::FlipState;
 use ves_art_core::surface::Surface;
 
 pub const DEFAULT_UV: egui::Rect =
@@ -27,14 +29,19 @@ fn correct_uv(rect: egui::Rect, hflip: bool, vflip: bool) -> egui::Rect {
 
 pub struct Sprite {
     sprite: ves_art_core::sprite::Sprite,
+    palette_name: Option<String>,
     rect: ves_art_core::geom_art::Rect,
+    image: egui::ColorImage,
+    mask: OpacityMask,
     texture: egui::TextureHandle,
     hflip: bool,
     vflip: bool,
+    /// The flips that turn the tile into its canonical form, in which it is stored in art libraries.
+    stored_flip: FlipState,
 }
 
 impl Sprite {
-    /// Creates a [`Sprite`] from a [`art_extractor_core::sprite::Sprite`].
+    /// Creates a [`Sprite`] from a [`ves_art_core::sprite::Sprite`].
     ///
     /// # Arguments
     ///
@@ -54,16 +61,22 @@ impl Sprite {
         let tile = &tiles[sprite.tile()];
         let color_image = Self::create_color_image(palette, tile);
 
-        let texture = texture_factory(color_image);
+        let texture = texture_factory(color_image.clone());
         let rect =
             ves_art_core::geom_art::Rect::new_from_size(sprite.position(), tile.surface().size());
+        // Flips are involutions, so the flips from the canonical form back to the tile also lead from the tile to the canonical form
+        let (_, stored_flip) = tile.canonical_form();
 
         Self {
             sprite: sprite.clone(),
+            palette_name: palette.name().map(str::to_string),
             rect,
+            image: color_image,
+            mask: tile.opacity_mask(),
             texture,
             hflip: sprite.h_flip(),
             vflip: sprite.v_flip(),
+            stored_flip,
         }
     }
 
@@ -72,6 +85,81 @@ impl Sprite {
         &self.sprite
     }
 
+    /// Retrieves the name of the palette of the sprite, if it has one.
+    pub fn palette_name(&self) -> Option<&str> {
+        self.palette_name.as_deref()
+    }
+
+    /// Retrieves the [`ColorImage`](egui::ColorImage) from which the texture was created.
+    pub fn image(&self) -> &egui::ColorImage {
+        &self.image
+    }
+
+    /// Retrieves the [`OpacityMask`] of the (unflipped) tile.
+    pub fn mask(&self) -> &OpacityMask {
+        &self.mask
+    }
+
+    /// Determines whether this sprite has an opaque pixel at a position on the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `point`: The position on the screen.
+    /// * `screen_size`: The size of the screen. Sprites wrap around the edges of the screen.
+    ///
+    /// returns: `true` if the pixel is opaque.
+    pub fn is_opaque_at(
+        &self,
+        point: ves_art_core::geom_art::Point,
+        screen_size: ves_art_core::geom_art::Size,
+    ) -> bool {
+        let (screen_width, screen_height) = (screen_size.width.raw(), screen_size.height.raw());
+        if point.x.raw() >= screen_width || point.y.raw() >= screen_height {
+            return false;
+        }
+
+        let local = |pos: u32, min: u32, screen: u32| (pos + screen - min % screen) % screen;
+        let x = local(point.x.raw(), self.rect.min_x().raw(), screen_width);
+        let y = local(point.y.raw(), self.rect.min_y().raw(), screen_height);
+
+        let size = self.mask.size();
+        let (width, height) = (size.width.raw(), size.height.raw());
+        if x >= width || y >= height {
+            return false;
+        }
+
+        let x = if self.hflip { width - 1 - x } else { x };
+        let y = if self.vflip { height - 1 - y } else { y };
+        self.mask.is_opaque((x, y))
+    }
+
+    /// Calculates the outline of the opaque pixels of this sprite.
+    ///
+    /// returns: The outline rectangles (see [`OpacityMask::outline_rects()`]), positioned and flipped like the sprite.
+    pub fn outline_rects(&self) -> Vec<ves_art_core::geom_art::Rect> {
+        let size = self.mask.size();
+        let (width, height) = (size.width.raw(), size.height.raw());
+        let (min_x, min_y) = (self.rect.min_x().raw(), self.rect.min_y().raw());
+        self.mask
+            .outline_rects()
+            .into_iter()
+            .map(|rect| {
+                let (mut x1, mut x2) = (rect.min_x().raw(), rect.max_x().raw());
+                let (mut y1, mut y2) = (rect.min_y().raw(), rect.max_y().raw());
+                if self.hflip {
+                    (x1, x2) = (width - 1 - x2, width - 1 - x1);
+                }
+                if self.vflip {
+                    (y1, y2) = (height - 1 - y2, height - 1 - y1);
+                }
+                ves_art_core::geom_art::Rect::new(
+                    (min_x + x1, min_y + y1),
+                    (min_x + x2, min_y + y2),
+                )
+            })
+            .collect()
+    }
+
     /// Retrieves the [`TextureHandle`](egui::TextureHandle).
     pub fn texture(&self) -> &egui::TextureHandle {
         &self.texture
@@ -83,13 +171,11 @@ impl Sprite {
     }
 
     /// Retrieves the horizontal flipping flag.
-    #[allow(unused)]
     pub fn hflip(&self) -> bool {
         self.hflip
     }
 
     /// Retrieves the vertical flipping flag.
-    #[allow(unused)]
     pub fn vflip(&self) -> bool {
         self.vflip
     }
@@ -105,35 +191,26 @@ impl Sprite {
         egui::Image::new(&self.texture, size).uv(correct_uv(DEFAULT_UV, self.hflip, self.vflip))
     }
 
-    /// Calculates the UV [`egui::Rect`] for a section of this [`Sprite`].
+    /// Retrieves the flips that turn the tile into the orientation in which it is stored in art libraries (see
+    /// [`Tile::canonical_form()`](ves_art_core::sprite::Tile::canonical_form)).
+    pub fn stored_flip(&self) -> FlipState {
+        self.stored_flip
+    }
+
+    /// Create an [`egui::Image`] of the tile in the orientation in which it is stored in art libraries, regardless of the flip flags
+    /// of this [`Sprite`].
     ///
     /// # Arguments
     ///
-    /// * `rect`: The rectangle that defines the section.
+    /// * `size`: The size for the output image.
     ///
-    /// returns: A [`egui::Rect`] that represents the UV values of the underlying texture.
-    pub fn partial_uv(&self, rect: &ves_art_core::geom_art::Rect) -> egui::Rect {
-        use crate::ToEgui as _;
-
-        let egui_sprite_rect = self.rect.to_egui();
-        let egui_rect = rect.to_egui();
-        let width = egui_sprite_rect.width();
-        let height = egui_sprite_rect.height();
-        let mut u_x = (egui_rect.min.x - egui_sprite_rect.min.x) / width;
-        let mut u_y = (egui_rect.min.y - egui_sprite_rect.min.y) / height;
-        let mut v_x = (egui_rect.max.x - egui_sprite_rect.min.x) / width;
-        let mut v_y = (egui_rect.max.y - egui_sprite_rect.min.y) / height;
-
-        if self.hflip {
-            u_x = 1.0 - u_x;
-            v_x = 1.0 - v_x;
-        }
-        if self.vflip {
-            u_y = 1.0 - u_y;
-            v_y = 1.0 - v_y;
-        }
-
-        egui::Rect::from_min_max(egui::pos2(u_x, u_y), egui::pos2(v_x, v_y))
+    /// returns: An [`egui::Image`].
+    pub fn to_stored_image(&self, size: egui::Vec2) -> egui::Image {
+        egui::Image::new(&self.texture, size).uv(correct_uv(
+            DEFAULT_UV,
+            self.stored_flip.h_flip,
+            self.stored_flip.v_flip,
+        ))
     }
 
     fn create_color_image(
@@ -1,20 +1,70 @@
 use crate::components::sprite::Sprite;
+use crate::components::sprite_table::appearance_text;
 use crate::egui;
 use crate::ToEgui as _;
 
 const ZOOM: f32 = 2.0;
 
+/// An action that has been requested in the [`SpriteDetails`].
+pub enum SpriteDetailsAction {
+    /// Jump to the frame at the provided position.
+    Jump(usize),
+    /// Apply the edited sprite (see [`Movie::edit_sprite()`](crate::components::movie::Movie::edit_sprite)).
+    Edit {
+        sprite: ves_art_core::sprite::Sprite,
+        all_frames: bool,
+    },
+}
+
+/// The state of the sprite that is being edited in the [`SpriteDetails`].
+#[derive(Default)]
+pub struct SpriteEdit {
+    /// The sprite as it is in the movie. The edit is discarded when the selected sprite changes.
+    original: Option<ves_art_core::sprite::Sprite>,
+    edited: Option<ves_art_core::sprite::Sprite>,
+    all_frames: bool,
+}
+
 pub struct SpriteDetails<'a> {
     index: usize,
     sprite: &'a Sprite,
+    /// The positions of the frames that use the tile of the sprite.
+    tile_frames: &'a [usize],
+    /// The number of palettes in the movie.
+    palette_count: usize,
+    edit: &'a mut SpriteEdit,
 }
 
 impl<'a> SpriteDetails<'a> {
-    pub fn new(index: usize, sprite: &'a Sprite) -> Self {
-        Self {  index, sprite }
+    pub fn new(
+        index: usize,
+        sprite: &'a Sprite,
+        tile_frames: &'a [usize],
+        palette_count: usize,
+        edit: &'a mut SpriteEdit,
+    ) -> Self {
+        Self {
+            index,
+            sprite,
+            tile_frames,
+            palette_count,
+            edit,
+        }
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui) {
+    /// Shows the widget.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The [`Ui`](egui::Ui).
+    ///
+    /// returns: The action that has been requested, if any.
+    pub fn show(&mut self, ui: &mut egui::Ui) -> Option<SpriteDetailsAction> {
+        let mut action = None;
+        if self.edit.original.as_ref() != Some(self.sprite.sprite()) {
+            self.edit.original = Some(self.sprite.sprite().clone());
+            self.edit.edited = Some(self.sprite.sprite().clone());
+        }
         ui.vertical(|ui| {
             let from_rect = egui::Rect::from_min_size(egui::Pos2::ZERO, ui.available_size());
             let to_rect = egui::Rect::from_min_size(egui::Pos2::ZERO, super::zoom_vec2(ui, ZOOM));
@@ -35,7 +85,12 @@ impl<'a> SpriteDetails<'a> {
                     ui.label(format!("{}", sprite.sprite().tile().value()));
                     ui.end_row();
                     ui.label("Palette");
-                    ui.label(format!("{}", sprite.sprite().palette().value()));
+                    match sprite.palette_name() {
+                        Some(name) => {
+                            ui.label(format!("{} ({})", sprite.sprite().palette().value(), name))
+                        }
+                        None => ui.label(format!("{}", sprite.sprite().palette().value())),
+                    };
                     ui.end_row();
                     ui.label("Position");
                     ui.label(format!("{:?}", sprite.sprite().position()));
@@ -46,8 +101,83 @@ impl<'a> SpriteDetails<'a> {
                     ui.label("V-flip");
                     ui.label(format!("{:?}", sprite.sprite().v_flip()));
                     ui.end_row();
+                    ui.label("Stored as");
+                    let stored_flip = sprite.stored_flip();
+                    ui.horizontal(|ui| {
+                        ui.add(sprite.to_stored_image(rect.size() / 2.0));
+                        ui.label(match (stored_flip.h_flip, stored_flip.v_flip) {
+                            (false, false) => "Unflipped",
+                            (true, false) => "H-flipped",
+                            (false, true) => "V-flipped",
+                            (true, true) => "H- and V-flipped",
+                        });
+                    });
+                    ui.end_row();
+                });
+
+            ui.separator();
+            if let Some(edited) = self.edit.edited.as_mut() {
+                let position = edited.position();
+                let (mut x, mut y) = (position.x.raw(), position.y.raw());
+                let mut palette = edited.palette().value();
+                let (mut h_flip, mut v_flip) = (edited.h_flip(), edited.v_flip());
+                egui::Grid::new("sprite_edit")
+                    .spacing(egui::vec2(10.0, 5.0))
+                    .show(ui, |ui| {
+                        ui.label("X");
+                        ui.add(egui::DragValue::new(&mut x));
+                        ui.end_row();
+                        ui.label("Y");
+                        ui.add(egui::DragValue::new(&mut y));
+                        ui.end_row();
+                        ui.label("Palette");
+                        ui.add(
+                            egui::DragValue::new(&mut palette)
+                                .clamp_range(0..=self.palette_count.saturating_sub(1)),
+                        );
+                        ui.end_row();
+                        ui.label("Flip");
+                        ui.horizontal(|ui| {
+                            ui.checkbox(&mut h_flip, "H");
+                            ui.checkbox(&mut v_flip, "V");
+                        });
+                        ui.end_row();
+                    });
+                edited.set_position(ves_art_core::geom_art::Point::new(x, y));
+                edited.set_palette(ves_art_core::sprite::PaletteRef::new(palette));
+                edited.set_h_flip(h_flip);
+                edited.set_v_flip(v_flip);
+
+                ui.checkbox(&mut self.edit.all_frames, "Apply to the same artwork in all frames");
+                let changed = self.edit.original.as_ref() != Some(&*edited);
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(changed, egui::Button::new("Apply")).clicked() {
+                        action = Some(SpriteDetailsAction::Edit {
+                            sprite: edited.clone(),
+                            all_frames: self.edit.all_frames,
+                        });
+                    }
+                    if ui.add_enabled(changed, egui::Button::new("Reset")).clicked() {
+                        *edited = sprite.sprite().clone();
+                    }
                 });
+            }
+            ui.separator();
 
+            ui.label(appearance_text(sprite.sprite().tile(), self.tile_frames));
+            ui.horizontal(|ui| {
+                let first = self.tile_frames.first().copied();
+                let first_button = egui::Button::new("Jump to first appearance");
+                if ui.add_enabled(first.is_some(), first_button).clicked() {
+                    action = first.map(SpriteDetailsAction::Jump);
+                }
+                let last = self.tile_frames.last().copied();
+                let last_button = egui::Button::new("Jump to last appearance");
+                if ui.add_enabled(last.is_some(), last_button).clicked() {
+                    action = last.map(SpriteDetailsAction::Jump);
+                }
+            });
         });
+        action
     }
 }
@@ -3,13 +3,86 @@ use crate::components::sprite::Sprite;
 use crate::egui;
 use crate::egui::Sense;
 use crate::ToEgui as _;
+use ves_art_core::movie::UsageIndex;
 
 const ZOOM: f32 = 2.0;
 
+/// The way in which the sprites are shown.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Mode {
+    /// A grid of images.
+    #[default]
+    Grid,
+    /// A sortable list with the properties of the sprites.
+    List,
+}
+
+/// The columns of the list.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
+enum Column {
+    #[default]
+    Index,
+    Tile,
+    Palette,
+    Size,
+    Position,
+    Flags,
+}
+
+impl Column {
+    const ALL: [Column; 6] = [
+        Column::Index,
+        Column::Tile,
+        Column::Palette,
+        Column::Size,
+        Column::Position,
+        Column::Flags,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Column::Index => "Index",
+            Column::Tile => "Tile",
+            Column::Palette => "Palette",
+            Column::Size => "Size",
+            Column::Position => "Position",
+            Column::Flags => "Flags",
+        }
+    }
+
+    /// Compares two sprites by the value in this column.
+    ///
+    /// # Arguments
+    ///
+    /// * `a`: The index and the first sprite.
+    /// * `b`: The index and the second sprite.
+    fn compare(&self, a: (usize, &Sprite), b: (usize, &Sprite)) -> std::cmp::Ordering {
+        let key = |(idx, sprite): (usize, &Sprite)| {
+            let rect = sprite.rect();
+            let value = match self {
+                Column::Index => 0,
+                Column::Tile => sprite.sprite().tile().value() as u64,
+                Column::Palette => sprite.sprite().palette().value() as u64,
+                Column::Size => u64::from(rect.width().raw()) * u64::from(rect.height().raw()),
+                Column::Position => {
+                    u64::from(rect.min_y().raw()) << 32 | u64::from(rect.min_x().raw())
+                }
+                Column::Flags => u64::from(sprite.hflip()) | u64::from(sprite.vflip()) << 1,
+            };
+            // Sprites with the same value remain in their original order
+            (value, idx)
+        };
+        key(a).cmp(&key(b))
+    }
+}
+
 #[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 #[must_use = "You should call .store()"]
 struct State {
     selection: SelectionRange,
+    mode: Mode,
+    sort_column: Column,
+    sort_descending: bool,
 }
 
 impl State {
@@ -25,17 +98,39 @@ impl State {
 
 pub struct SpriteTable<'a> {
     sprites: &'a mut [Selectable<Sprite>],
+    usage: &'a UsageIndex,
     columns: usize,
 }
 
 impl<'a> SpriteTable<'a> {
-    pub fn new(sprites: &'a mut [Selectable<Sprite>], columns: usize) -> Self {
-        Self { sprites, columns }
+    pub fn new(
+        sprites: &'a mut [Selectable<Sprite>],
+        usage: &'a UsageIndex,
+        columns: usize,
+    ) -> Self {
+        Self {
+            sprites,
+            usage,
+            columns,
+        }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui) {
         let mut state = State::load(ui.ctx()).unwrap_or_default();
 
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut state.mode, Mode::Grid, "Grid");
+            ui.selectable_value(&mut state.mode, Mode::List, "List");
+        });
+        match state.mode {
+            Mode::Grid => self.show_grid(ui, &mut state),
+            Mode::List => self.show_list(ui, &mut state),
+        }
+
+        state.store(ui.ctx());
+    }
+
+    fn show_grid(&mut self, ui: &mut egui::Ui, state: &mut State) {
         egui::Grid::new("sprite_table")
             .spacing(egui::vec2(4.0, 4.0))
             .show(ui, |ui| {
@@ -54,7 +149,11 @@ impl<'a> SpriteTable<'a> {
                         let egui_sprite_rect = sprite.rect().to_egui();
 
                         let rect = transform.transform_rect(egui_sprite_rect);
-                        let response = ui.add(sprite.to_image(rect.size()).sense(Sense::click()));
+                        let tile = sprite.sprite().tile();
+                        let response = ui
+                            .add(sprite.to_image(rect.size()).sense(Sense::click()))
+                            .on_hover_text(appearance_text(tile, self.usage.tile_frames(tile)));
+                        super::accessible(ui, &response, &format!("Sprite {}", idx));
                         if response.clicked() {
                             clicked_sprite_idx = Some(idx);
                         }
@@ -71,7 +170,142 @@ impl<'a> SpriteTable<'a> {
                         .update(ui, clicked_idx, self.sprites, |sprite| &mut sprite.state);
                 }
             });
+    }
 
-        state.store(ui.ctx());
+    /// Shows the sprites as a list. Clicking on a column header sorts the list by that column (clicking again reverses the order).
+    /// The arrow keys move the selection while the mouse is over the list or one of its rows has the keyboard focus; the modifiers work
+    /// the same as for clicking.
+    fn show_list(&mut self, ui: &mut egui::Ui, state: &mut State) {
+        let mut order: Vec<usize> = (0..self.sprites.len()).collect();
+        order.sort_by(|a, b| {
+            let ordering = state
+                .sort_column
+                .compare((*a, &self.sprites[*a].item), (*b, &self.sprites[*b].item));
+            if state.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let mut clicked_sprite_idx = None;
+        let mut row_focused = false;
+        let response = egui::Grid::new("sprite_list")
+            .num_columns(Column::ALL.len())
+            .striped(true)
+            .show(ui, |ui| {
+                for column in Column::ALL {
+                    let label = match (state.sort_column == column, state.sort_descending) {
+                        (true, false) => format!("{} ⬆", column.name()),
+                        (true, true) => format!("{} ⬇", column.name()),
+                        (false, _) => column.name().to_string(),
+                    };
+                    if ui.button(label).clicked() {
+                        if state.sort_column == column {
+                            state.sort_descending = !state.sort_descending;
+                        } else {
+                            state.sort_column = column;
+                            state.sort_descending = false;
+                        }
+                    }
+                }
+                ui.end_row();
+
+                for &idx in &order {
+                    let selectable_sprite = &self.sprites[idx];
+                    let sprite = &selectable_sprite.item;
+                    let rect = sprite.rect();
+                    let tile = sprite.sprite().tile();
+                    let response = ui
+                        .selectable_label(selectable_sprite.state.selected(), idx.to_string())
+                        .on_hover_text(appearance_text(tile, self.usage.tile_frames(tile)));
+                    if response.clicked() {
+                        clicked_sprite_idx = Some(idx);
+                    }
+                    row_focused |= response.has_focus();
+                    ui.label(tile.value().to_string());
+                    ui.label(match sprite.palette_name() {
+                        Some(name) => format!("{} ({})", sprite.sprite().palette().value(), name),
+                        None => sprite.sprite().palette().value().to_string(),
+                    });
+                    ui.label(format!("{}x{}", rect.width().raw(), rect.height().raw()));
+                    ui.label(format!("{}, {}", rect.min_x().raw(), rect.min_y().raw()));
+                    ui.label(flags_text(sprite));
+                    ui.end_row();
+                }
+            })
+            .response;
+
+        if clicked_sprite_idx.is_none() && (response.hovered() || row_focused) {
+            let (down, up) = {
+                let input = ui.input();
+                (
+                    input.key_pressed(egui::Key::ArrowDown),
+                    input.key_pressed(egui::Key::ArrowUp),
+                )
+            };
+            let step = match (down, up) {
+                (true, false) => Some(1),
+                (false, true) => Some(-1),
+                _ => None,
+            };
+            clicked_sprite_idx =
+                step.and_then(|step| neighbour(&order, state.selection.root(), step));
+        }
+
+        if let Some(clicked_idx) = clicked_sprite_idx {
+            state
+                .selection
+                .update(ui, clicked_idx, self.sprites, |sprite| &mut sprite.state);
+        }
+    }
+}
+
+/// Describes the flip flags of a sprite.
+fn flags_text(sprite: &Sprite) -> &'static str {
+    match (sprite.hflip(), sprite.vflip()) {
+        (false, false) => "-",
+        (true, false) => "H",
+        (false, true) => "V",
+        (true, true) => "HV",
+    }
+}
+
+/// Finds the neighbour of an item in a sorted list.
+///
+/// # Arguments
+///
+/// * `order`: The indices of the items, in the order of the list.
+/// * `current`: The index of the current item. If this is `None` or not in the list, the first item is returned.
+/// * `step`: The direction (`1` for the next item and `-1` for the previous item).
+///
+/// returns: The index of the neighbour or `None` if the list is empty. The result is clamped to the list.
+fn neighbour(order: &[usize], current: Option<usize>, step: isize) -> Option<usize> {
+    let position = current.and_then(|current| order.iter().position(|idx| *idx == current));
+    let position = match position {
+        Some(position) => position
+            .saturating_add_signed(step)
+            .min(order.len().saturating_sub(1)),
+        None => 0,
+    };
+    order.get(position).copied()
+}
+
+/// Describes the first and last appearance of a tile in the movie.
+///
+/// # Arguments
+///
+/// * `tile`: The tile.
+/// * `frames`: The positions of the frames that use the tile (see [`UsageIndex::tile_frames()`]).
+pub fn appearance_text(tile: ves_art_core::sprite::TileRef, frames: &[usize]) -> String {
+    match (frames.first(), frames.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} appears in {} frames, first in frame {}, last in frame {}.",
+            tile,
+            frames.len(),
+            first,
+            last
+        ),
+        _ => format!("{} does not appear in any frame.", tile),
     }
 }
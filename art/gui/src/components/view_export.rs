@@ -0,0 +1,142 @@
+use super::movie::{Movie, ZOOM};
+use crate::egui;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use ves_art_core::render::{render_frame, RenderOptions};
+use ves_art_core::sprite::Color;
+use ves_art_core::surface::Surface as _;
+
+/// The color of the selection overlay (the same as in the movie window).
+const SELECTION_COLOR: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+/// A tool for exporting the current view of the movie window as a PNG image.
+///
+/// The image is composited by the core renderer ([`render_frame()`]) rather than read back from the GPU, such that the result does
+/// not depend on the graphics driver or the UI scaling.
+pub struct ViewExport {
+    path: String,
+    zoom: u32,
+    overlays: bool,
+    result: Option<Result<PathBuf, String>>,
+}
+
+impl Default for ViewExport {
+    fn default() -> Self {
+        Self {
+            path: "view.png".to_string(),
+            // The cast is OK here, because the zoom of the movie window is a whole number
+            zoom: ZOOM as u32,
+            overlays: false,
+            result: None,
+        }
+    }
+}
+
+impl ViewExport {
+    /// Shows the tool.
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`: The UI.
+    /// * `movie`: The movie.
+    pub fn show(&mut self, ui: &mut egui::Ui, movie: &Movie) {
+        egui::Grid::new("view_export_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("File");
+                ui.text_edit_singleline(&mut self.path);
+                ui.end_row();
+                ui.label("Zoom");
+                ui.add(egui::DragValue::new(&mut self.zoom).clamp_range(1..=8));
+                ui.end_row();
+                ui.label("Overlays");
+                ui.checkbox(&mut self.overlays, "Include selection");
+                ui.end_row();
+            });
+
+        if ui.button("Export view as PNG").clicked() {
+            let path = PathBuf::from(&self.path);
+            self.result = Some(export_view(movie, self.zoom, self.overlays, &path).map(|_| path));
+        }
+
+        match &self.result {
+            None => {}
+            Some(Ok(path)) => {
+                ui.label(format!("Exported {}.", path.display()));
+            }
+            Some(Err(err)) => {
+                ui.colored_label(egui::Color32::RED, err);
+            }
+        }
+    }
+}
+
+/// Exports the current frame of a movie as a PNG image.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `zoom`: The zoom factor. Every pixel of the frame becomes a square of `zoom` by `zoom` pixels.
+/// * `overlays`: Whether to draw the selection overlay.
+/// * `path`: The path of the PNG image.
+fn export_view(movie: &Movie, zoom: u32, overlays: bool, path: &Path) -> Result<(), String> {
+    let current_frame = movie
+        .current_frame()
+        .ok_or_else(|| "No movie frame available.".to_string())?;
+    let frame = render_frame(
+        movie.movie(),
+        current_frame.frame_nr(),
+        &RenderOptions::default(),
+    )?;
+
+    let size = frame.size();
+    let (width, height) = (size.width.raw(), size.height.raw());
+    let (out_width, out_height) = (width * zoom, height * zoom);
+    let mut pixels = vec![[0u8; 4]; (out_width * out_height) as usize];
+    let mut fill = |x: u32, y: u32, rgba: [u8; 4]| {
+        for dy in 0..zoom {
+            let row = ((y * zoom + dy) * out_width) as usize;
+            let start = row + (x * zoom) as usize;
+            pixels[start..start + zoom as usize].fill(rgba);
+        }
+    };
+
+    for (index, color) in frame.data().iter().enumerate() {
+        if let Color::Opaque(rgb) = color {
+            // The casts are OK here, because the index lies within the frame
+            let (x, y) = (index as u32 % width, index as u32 / width);
+            fill(x, y, [rgb.r, rgb.g, rgb.b, 0xff]);
+        }
+    }
+
+    if overlays {
+        for selectable in current_frame.sprites() {
+            if !selectable.state.selected() {
+                continue;
+            }
+            // Outline the sprite rectangle; sprites that exceed the screen wrap around, like in the movie window
+            let rect = selectable.item.rect();
+            let (min_x, min_y) = (rect.min_x().raw(), rect.min_y().raw());
+            let (max_x, max_y) = (rect.max_x().raw(), rect.max_y().raw());
+            for x in min_x..=max_x {
+                fill(x % width, min_y % height, SELECTION_COLOR);
+                fill(x % width, max_y % height, SELECTION_COLOR);
+            }
+            for y in min_y..=max_y {
+                fill(min_x % width, y % height, SELECTION_COLOR);
+                fill(max_x % width, y % height, SELECTION_COLOR);
+            }
+        }
+    }
+
+    let file = File::create(path)
+        .map_err(|err| format!("Could not create {}: {}", path.display(), err))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), out_width, out_height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(pixels.concat().as_slice()))
+        .map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
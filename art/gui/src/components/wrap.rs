@@ -0,0 +1,175 @@
+//! Wrapping sprites around the edges of the screen.
+//!
+//! Like on the original hardware, a sprite that extends past the right or bottom edge of the screen continues at the opposite edge.
+//! To draw such a sprite, it is split into the parts that lie on either side of the edges. Every part is drawn with the matching
+//! section of the texture of the sprite. This module contains that logic without any dependency on egui, such that it can be tested
+//! on its own.
+
+use ves_art_core::geom_art::{Rect, Size};
+use ves_geom::RectIntersection;
+
+/// A part of a sprite that is drawn on the screen.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WrappedPart {
+    /// The region of the screen.
+    pub dest: Rect,
+    /// The section of the texture of the sprite: the minimum U, minimum V, maximum U and maximum V (see [`section_uv()`]).
+    pub uv: [f32; 4],
+}
+
+/// Splits a sprite into the parts that are drawn on the screen.
+///
+/// # Arguments
+///
+/// * `rect`: The rectangle of the sprite. Positions beyond the screen wrap around.
+/// * `screen_size`: The size of the screen.
+/// * `hflip`: Whether the sprite is flipped horizontally.
+/// * `vflip`: Whether the sprite is flipped vertically.
+///
+/// returns: One part if the sprite fits on the screen, otherwise two or four parts.
+pub fn wrap_sprite(rect: Rect, screen_size: Size, hflip: bool, vflip: bool) -> Vec<WrappedPart> {
+    // Move the sprite onto the screen first, such that only the right and bottom edges have to be considered
+    let wrapped = Rect::new_from_size(
+        (
+            rect.min_x() % screen_size.width,
+            rect.min_y() % screen_size.height,
+        ),
+        rect.size(),
+    );
+    let part = |section: &Rect| WrappedPart {
+        dest: Rect::new_from_size(
+            (
+                section.min_x() % screen_size.width,
+                section.min_y() % screen_size.height,
+            ),
+            section.size(),
+        ),
+        uv: section_uv(section, &wrapped, hflip, vflip),
+    };
+
+    match wrapped.intersect_point(screen_size.as_rect().max) {
+        RectIntersection::None => vec![part(&wrapped)],
+        intersection => intersection.iter().map(part).collect(),
+    }
+}
+
+/// Calculates the UV coordinates of a section of a sprite.
+///
+/// # Arguments
+///
+/// * `section`: The section.
+/// * `sprite_rect`: The rectangle of the sprite. It must contain the section.
+/// * `hflip`: Whether the sprite is flipped horizontally.
+/// * `vflip`: Whether the sprite is flipped vertically.
+///
+/// returns: The minimum U, minimum V, maximum U and maximum V. For a flipped axis, the minimum is larger than the maximum, such that
+///          the texture is mirrored.
+pub fn section_uv(section: &Rect, sprite_rect: &Rect, hflip: bool, vflip: bool) -> [f32; 4] {
+    let [mut min_u, mut min_v, mut max_u, mut max_v] = section.normalized_within(sprite_rect);
+    if hflip {
+        min_u = 1.0 - min_u;
+        max_u = 1.0 - max_u;
+    }
+    if vflip {
+        min_v = 1.0 - min_v;
+        max_v = 1.0 - max_v;
+    }
+    [min_u, min_v, max_u, max_v]
+}
+
+#[cfg(test)]
+mod test_wrap_sprite {
+    use super::{wrap_sprite, WrappedPart};
+    use ves_art_core::geom_art::{Rect, Size};
+
+    fn screen_size() -> Size {
+        Size::new(256, 224)
+    }
+
+    fn part(x: u32, y: u32, width: u32, height: u32, uv: [f32; 4]) -> WrappedPart {
+        WrappedPart {
+            dest: Rect::new_from_size((x, y), Size::new(width, height)),
+            uv,
+        }
+    }
+
+    fn sprite(x: u32, y: u32) -> Rect {
+        Rect::new_from_size((x, y), Size::new(16, 16))
+    }
+
+    #[test]
+    fn test_inside() {
+        assert_eq!(
+            vec![part(8, 8, 16, 16, [0.0, 0.0, 1.0, 1.0])],
+            wrap_sprite(sprite(8, 8), screen_size(), false, false)
+        );
+        assert_eq!(
+            vec![part(8, 8, 16, 16, [1.0, 1.0, 0.0, 0.0])],
+            wrap_sprite(sprite(8, 8), screen_size(), true, true)
+        );
+        // A sprite that touches the edges does not have to be split
+        assert_eq!(
+            vec![part(240, 208, 16, 16, [0.0, 0.0, 1.0, 1.0])],
+            wrap_sprite(sprite(240, 208), screen_size(), false, false)
+        );
+    }
+
+    #[test]
+    fn test_right_edge() {
+        assert_eq!(
+            vec![
+                part(252, 8, 4, 16, [0.0, 0.0, 0.25, 1.0]),
+                part(0, 8, 12, 16, [0.25, 0.0, 1.0, 1.0]),
+            ],
+            wrap_sprite(sprite(252, 8), screen_size(), false, false)
+        );
+        // When flipped, the part at the right edge of the screen shows the right side of the texture
+        assert_eq!(
+            vec![
+                part(252, 8, 4, 16, [1.0, 0.0, 0.75, 1.0]),
+                part(0, 8, 12, 16, [0.75, 0.0, 0.0, 1.0]),
+            ],
+            wrap_sprite(sprite(252, 8), screen_size(), true, false)
+        );
+    }
+
+    #[test]
+    fn test_bottom_edge() {
+        assert_eq!(
+            vec![
+                part(8, 220, 16, 4, [0.0, 1.0, 1.0, 0.75]),
+                part(8, 0, 16, 12, [0.0, 0.75, 1.0, 0.0]),
+            ],
+            wrap_sprite(sprite(8, 220), screen_size(), false, true)
+        );
+    }
+
+    #[test]
+    fn test_corner() {
+        assert_eq!(
+            vec![
+                part(248, 216, 8, 8, [0.0, 0.0, 0.5, 0.5]),
+                part(0, 216, 8, 8, [0.5, 0.0, 1.0, 0.5]),
+                part(248, 0, 8, 8, [0.0, 0.5, 0.5, 1.0]),
+                part(0, 0, 8, 8, [0.5, 0.5, 1.0, 1.0]),
+            ],
+            wrap_sprite(sprite(248, 216), screen_size(), false, false)
+        );
+    }
+
+    #[test]
+    fn test_beyond_screen() {
+        // Sprites that start beyond the screen wrap around as a whole
+        assert_eq!(
+            vec![part(44, 8, 16, 16, [0.0, 0.0, 1.0, 1.0])],
+            wrap_sprite(sprite(300, 8), screen_size(), false, false)
+        );
+        assert_eq!(
+            vec![
+                part(252, 8, 4, 16, [0.0, 0.0, 0.25, 1.0]),
+                part(0, 8, 12, 16, [0.25, 0.0, 1.0, 1.0]),
+            ],
+            wrap_sprite(sprite(508, 232), screen_size(), false, false)
+        );
+    }
+}
@@ -2,27 +2,55 @@ mod components;
 mod model;
 
 use crate::components::animations::Animations;
+use crate::components::crop::CropTool;
 use crate::components::entities::Entities;
+use crate::components::entity_timeline::EntityTimeline;
+use crate::components::hud::{HudStats, PerformanceHud};
+use crate::components::import::ImportDialog;
+use crate::components::info::MovieInfo;
 use crate::components::movie::Movie;
+use crate::components::palette_heatmap::PaletteHeatmap;
+use crate::components::palette_mutations::PaletteMutations;
+use crate::components::palette_reassign::PaletteReassignment;
+use crate::components::parity::RenderParity;
+use crate::components::pipeline_editor::PipelineEditor;
 use crate::components::selection::SelectionState;
-use crate::components::sprite_details::SpriteDetails;
+use crate::components::sprite_details::{SpriteDetails, SpriteDetailsAction, SpriteEdit};
 use crate::components::sprite_table::SpriteTable;
+use crate::components::view_export::ViewExport;
 use crate::components::window::Window;
 use eframe::{egui, epi};
 use log::info;
 use std::time::Instant;
 use ves_art_core::geom_art::ArtworkSpaceUnit;
+use ves_geom::{BoundingBox, IntoF32 as _};
 use crate::model::entities::Entity;
 
 #[derive(Default)]
 struct ArtDirectorApp {
     movie: Option<Movie>,
     entities: model::entities::Entities,
+    parity_enabled: bool,
+    parity: Option<RenderParity>,
+    hud_enabled: bool,
+    hud: PerformanceHud,
+    import: Option<ImportDialog>,
+    info: MovieInfo,
+    palette_heatmap: PaletteHeatmap,
+    palette_mutations: PaletteMutations,
+    entity_timeline: EntityTimeline,
+    palette_reassignment: PaletteReassignment,
+    crop: CropTool,
+    pipeline_editor: PipelineEditor,
+    view_export: ViewExport,
+    sprite_edit: SpriteEdit,
+    sprite_edit_error: Option<String>,
 }
 
 impl epi::App for ArtDirectorApp {
     fn update(&mut self, ctx: &egui::Context, frame: &epi::Frame) {
         let current_instant = Instant::now();
+        self.hud.record_frame_time(frame.info().cpu_usage);
 
         if let Some(ref mut movie) = self.movie {
             if movie.update(ctx, current_instant) {
@@ -60,14 +88,44 @@ impl epi::App for ArtDirectorApp {
 
         egui::TopBottomPanel::top("main_menu").show(ctx, |ui| {
             ui.horizontal(|ui| {
+                if ui.button("Import from emulator dump…").clicked() && self.import.is_none() {
+                    self.import = Some(ImportDialog::default());
+                }
+                if let Some(movie) = &mut self.movie {
+                    if ui.add_enabled(movie.can_undo(), egui::Button::new("Undo")).clicked() {
+                        self.sprite_edit_error = movie.undo().err();
+                    }
+                    if ui.add_enabled(movie.can_redo(), egui::Button::new("Redo")).clicked() {
+                        self.sprite_edit_error = movie.redo().err();
+                    }
+                }
+
                 // Mini menu icons
                 ui.with_layout(egui::Layout::right_to_left(), |ui| {
                     egui::global_dark_light_mode_switch(ui);
+                    ui.checkbox(&mut self.hud_enabled, "Performance HUD");
                 });
             })
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            if let Some(import) = &mut self.import {
+                let mut open = true;
+                let imported = egui::Window::new("Import from emulator dump")
+                    .open(&mut open)
+                    .auto_sized()
+                    .collapsible(false)
+                    .show(ui.ctx(), |ui| import.show(ui))
+                    .and_then(|response| response.inner.flatten());
+                if let Some(core_movie) = imported {
+                    self.movie = Some(Movie::new(core_movie));
+                    self.parity = None;
+                }
+                if !open {
+                    self.import = None;
+                }
+            }
+
             Window::new("Movie").show(ui.ctx(), |ui| match &mut self.movie {
                 None => {
                     ui.label("No movie loaded.");
@@ -77,23 +135,41 @@ impl epi::App for ArtDirectorApp {
                 }
             });
 
+            Window::new("Info").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.info.show(ui, movie);
+                }
+            });
+
             Window::new("Sprites").show(ui.ctx(), |ui| {
-                match self.movie.as_mut().and_then(|movie| movie.sprites_mut()) {
+                match self
+                    .movie
+                    .as_mut()
+                    .and_then(|movie| movie.sprites_mut_with_usage())
+                {
                     None => {
                         ui.label("No movie loaded.");
                     }
-                    Some(sprites) => {
-                        SpriteTable::new(sprites, 8).show(ui);
+                    Some((sprites, usage)) => {
+                        SpriteTable::new(sprites, usage, 8).show(ui);
                     }
                 }
             });
 
+            let mut action = None;
             Window::new("Sprite Details").show(ui.ctx(), |ui| {
-                match self.movie.as_ref().and_then(|movie| movie.sprites()) {
+                let movie_with_sprites = self
+                    .movie
+                    .as_ref()
+                    .and_then(|movie| movie.sprites().map(|sprites| (movie, sprites)));
+                match movie_with_sprites {
                     None => {
                         ui.label("No movie loaded.");
                     }
-                    Some(sprites) => {
+                    Some((movie, sprites)) => {
                         let selected_sprites: Vec<_> = sprites
                             .iter()
                             .enumerate()
@@ -105,14 +181,157 @@ impl epi::App for ArtDirectorApp {
                             }
                             1 => {
                                 let (index, sprite) = selected_sprites[0];
-                                SpriteDetails::new(index, &sprite.item).show(ui);
+                                let tile_frames =
+                                    movie.usage().tile_frames(sprite.item.sprite().tile());
+                                let palette_count = movie.movie().palettes().len();
+                                action = SpriteDetails::new(
+                                    index,
+                                    &sprite.item,
+                                    tile_frames,
+                                    palette_count,
+                                    &mut self.sprite_edit,
+                                )
+                                .show(ui)
+                                .map(|action| (index, action));
                             }
-                            _ => {
-                                ui.label("Multiple sprites selected.");
+                            count => {
+                                ui.label(format!("{} sprites selected.", count));
+                                let bounds: BoundingBox<ArtworkSpaceUnit> = selected_sprites
+                                    .iter()
+                                    .map(|(_, sprite)| sprite.item.rect())
+                                    .collect();
+                                if let Some(rect) = bounds.rect() {
+                                    ui.label(format!(
+                                        "Bounds: {}, {} to {}, {} ({} x {})",
+                                        rect.min_x().raw(),
+                                        rect.min_y().raw(),
+                                        rect.max_x().raw(),
+                                        rect.max_y().raw(),
+                                        rect.width().raw(),
+                                        rect.height().raw()
+                                    ));
+                                }
                             }
                         };
                     }
                 }
+                if let Some(error) = &self.sprite_edit_error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+            if let (Some((index, action)), Some(movie)) = (action, &mut self.movie) {
+                match action {
+                    SpriteDetailsAction::Jump(position) => movie.jump_to(position),
+                    SpriteDetailsAction::Edit { sprite, all_frames } => {
+                        self.sprite_edit_error =
+                            movie.edit_sprite(index, &sprite, all_frames).err();
+                    }
+                }
+            }
+
+            Window::new("Palette Usage").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.palette_heatmap.show(ui, movie);
+                }
+            });
+
+            Window::new("Palette Mutations").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.palette_mutations.show(ui, movie);
+                }
+            });
+
+            Window::new("Entity Timeline").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.entity_timeline.show(ui, movie);
+                }
+            });
+
+            Window::new("Palette Reassignment").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.palette_reassignment.show(ui, movie);
+                }
+            });
+
+            Window::new("Crop").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    if self.crop.show(ui, movie) {
+                        self.parity = None;
+                    }
+                }
+            });
+
+            Window::new("Pipeline").show(ui.ctx(), |ui| match &mut self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    if self.pipeline_editor.show(ui, movie) {
+                        self.parity = None;
+                    }
+                }
+            });
+
+            Window::new("Export View").show(ui.ctx(), |ui| match &self.movie {
+                None => {
+                    ui.label("No movie loaded.");
+                }
+                Some(movie) => {
+                    self.view_export.show(ui, movie);
+                }
+            });
+
+            Window::new("Render Parity").show(ui.ctx(), |ui| {
+                ui.checkbox(&mut self.parity_enabled, "Compare with core renderer");
+                if !self.parity_enabled {
+                    self.parity = None;
+                    return;
+                }
+
+                let movie = match &self.movie {
+                    Some(movie) => movie,
+                    None => {
+                        ui.label("No movie loaded.");
+                        return;
+                    }
+                };
+                let current_frame = match movie.current_frame() {
+                    Some(current_frame) => current_frame,
+                    None => {
+                        ui.label("No movie frame available.");
+                        return;
+                    }
+                };
+
+                // Only recompute the comparison when the frame has changed
+                let frame_nr = current_frame.frame_nr();
+                if self.parity.as_ref().map(|parity| parity.frame_nr()) != Some(frame_nr) {
+                    self.parity = Some(RenderParity::create(
+                        ui.ctx(),
+                        movie.movie(),
+                        frame_nr,
+                        current_frame.sprites(),
+                    ));
+                }
+
+                if let Some(parity) = &self.parity {
+                    parity.show(ui);
+                }
             });
 
             let ents = &mut self.entities;
@@ -135,6 +354,10 @@ impl epi::App for ArtDirectorApp {
             });
         });
 
+        if self.hud_enabled {
+            self.hud.show(ctx, &self.hud_stats());
+        }
+
         // Resize the native window to be just the size we need it to be:
         frame.set_window_size(ctx.used_size());
     }
@@ -144,21 +367,22 @@ impl epi::App for ArtDirectorApp {
     }
 }
 
-trait IntoF32 {
-    fn into_f32(self) -> f32;
-}
-
-impl IntoF32 for u32 {
-    #[inline(always)]
-    fn into_f32(self) -> f32 {
-        u16::try_from(self).unwrap().into()
-    }
-}
-
-impl IntoF32 for ArtworkSpaceUnit {
-    #[inline(always)]
-    fn into_f32(self) -> f32 {
-        self.raw().into_f32()
+impl ArtDirectorApp {
+    /// Collects the values for the [`PerformanceHud`].
+    fn hud_stats(&self) -> HudStats {
+        let mut stats = HudStats::default();
+        if let Some(movie) = &self.movie {
+            stats.tiles = movie.movie().tiles().len();
+            stats.frame_build_time = movie.frame_build_time();
+            // Every sprite has its own texture
+            for sprite in movie.sprites().unwrap_or_default() {
+                stats.textures.add(sprite.item.image().size);
+            }
+        }
+        if let Some(parity) = &self.parity {
+            parity.add_texture_stats(&mut stats.textures);
+        }
+        stats
     }
 }
 
@@ -175,11 +399,9 @@ impl ToEgui for ves_art_core::geom_art::Rect {
 
     #[inline(always)]
     fn to_egui(&self) -> Self::Out {
-        // We have to convert from an inclusive (integer-based) to an exclusive (float-based) space, hence the +1
-        egui::Rect::from_min_max(
-            egui::pos2(self.min_x().into_f32(), self.min_y().into_f32()),
-            egui::pos2(self.max_x().into_f32() + 1.0, self.max_y().into_f32() + 1.0),
-        )
+        // egui uses an exclusive (float-based) space
+        let (min, size) = self.to_exclusive();
+        egui::Rect::from_min_size(egui::pos2(min.x.into_f32(), min.y.into_f32()), size.to_egui())
     }
 }
 
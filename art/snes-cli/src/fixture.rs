@@ -0,0 +1,158 @@
+//! Generation of rendering test fixtures for `ves-core-render`.
+//!
+//! A fixture consists of the following files:
+//! * `<name>.rs`: A self-contained test that sets up the OAM table and palettes, renders the scene and compares the result.
+//! * `fixtures/<name>.tiles.bincode`: The tiles that are used by the scene.
+//! * `fixtures/<name>.png`: The expected output of the renderer.
+
+use crate::output::Output;
+use anyhow::{anyhow, Result};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use ves_art_core::movie::Movie;
+use ves_core_render::movie::MovieScene;
+use ves_core_render::{SCREEN_BUFFER_HEIGHT, SCREEN_BUFFER_WIDTH};
+
+fn generate_test_source(name: &str, scene: &MovieScene) -> Result<String> {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "//! Generated by `ves-art-snes-cli movie fixture`. Do not edit."
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "use ves_core_render::{{Palette, Scene, SCREEN_BUFFER_LEN}};"
+    )?;
+    writeln!(
+        out,
+        "use ves_proto_common::gpu::{{OamTableEntry, ScreenControl}};"
+    )?;
+    writeln!(out)?;
+    writeln!(out, "#[test]")?;
+    writeln!(out, "fn test_{name}() {{")?;
+    writeln!(out, "    let tiles: Vec<ves_art_core::sprite::Tile> =")?;
+    writeln!(
+        out,
+        "        bincode::deserialize(include_bytes!(\"fixtures/{name}.tiles.bincode\")).unwrap();"
+    )?;
+    writeln!(out)?;
+    writeln!(out, "    let oam: Vec<OamTableEntry> = [")?;
+    for entry in scene.oam() {
+        writeln!(out, "        0x{:016X}u64,", u64::from(entry))?;
+    }
+    writeln!(out, "    ]")?;
+    writeln!(out, "    .into_iter()")?;
+    writeln!(out, "    .map(OamTableEntry::from)")?;
+    writeln!(out, "    .collect();")?;
+    writeln!(out)?;
+    writeln!(out, "    let palettes: Vec<Palette> = [")?;
+    for palette in scene.palettes() {
+        let colors: Vec<String> = palette
+            .colors
+            .iter()
+            .map(|color| format!("0x{:04X}", u16::from(color)))
+            .collect();
+        writeln!(out, "        [")?;
+        // 11 values per line matches the layout of rustfmt
+        for chunk in colors.chunks(11) {
+            writeln!(out, "            {},", chunk.join(", "))?;
+        }
+        writeln!(out, "        ],")?;
+    }
+    writeln!(out, "    ]")?;
+    writeln!(out, "    .into_iter()")?;
+    writeln!(out, "    .map(|colors: [u16; 16]| Palette {{")?;
+    writeln!(out, "        colors: colors.map(Into::into),")?;
+    writeln!(out, "    }})")?;
+    writeln!(out, "    .collect();")?;
+    writeln!(out)?;
+    writeln!(out, "    let scene = Scene {{")?;
+    writeln!(out, "        tiles: &tiles,")?;
+    writeln!(out, "        oam: &oam,")?;
+    writeln!(out, "        palettes: &palettes,")?;
+    writeln!(out, "        screen_control: ScreenControl::default(),")?;
+    writeln!(out, "    }};")?;
+    writeln!(out, "    let mut actual = vec![0; SCREEN_BUFFER_LEN];")?;
+    writeln!(
+        out,
+        "    ves_core_render::render(&scene, &mut actual, None).unwrap();"
+    )?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "    let png_data = include_bytes!(\"fixtures/{name}.png\");"
+    )?;
+    writeln!(
+        out,
+        "    let decoder = png::Decoder::new(std::io::Cursor::new(&png_data[..]));"
+    )?;
+    writeln!(out, "    let mut reader = decoder.read_info().unwrap();")?;
+    writeln!(
+        out,
+        "    let mut expected = vec![0; reader.output_buffer_size()];"
+    )?;
+    writeln!(out, "    reader.next_frame(&mut expected).unwrap();")?;
+    writeln!(out)?;
+    writeln!(out, "    assert!(")?;
+    writeln!(out, "        expected == actual,")?;
+    writeln!(out, "        \"Rendered output differs from {name}.png.\"")?;
+    writeln!(out, "    );")?;
+    writeln!(out, "}}")?;
+    Ok(out)
+}
+
+fn write_png(path: &Path, screen_buffer: &[u8]) -> Result<()> {
+    let file = BufWriter::new(File::create(path)?);
+    let mut encoder = png::Encoder::new(file, SCREEN_BUFFER_WIDTH, SCREEN_BUFFER_HEIGHT);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(screen_buffer)?;
+    Ok(())
+}
+
+/// Generates a rendering test fixture from a movie frame.
+///
+/// # Arguments
+///
+/// * `movie`: The movie.
+/// * `frame_index`: The index of the frame in the movie.
+/// * `name`: The name of the fixture. This must be a valid Rust identifier.
+/// * `out_dir`: The output directory (normally the `tests` directory of `ves-core-render`).
+/// * `output`: The [`Output`].
+pub fn create_fixture(
+    movie: &Movie,
+    frame_index: usize,
+    name: &str,
+    out_dir: &Path,
+    output: &Output,
+) -> Result<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(anyhow!(
+            "The fixture name '{name}' is not a valid identifier."
+        ));
+    }
+
+    let scene = MovieScene::from_movie_frame(movie, frame_index).map_err(anyhow::Error::msg)?;
+    let screen_buffer = scene.render().map_err(anyhow::Error::msg)?;
+
+    let fixtures_dir = out_dir.join("fixtures");
+    std::fs::create_dir_all(&fixtures_dir)?;
+
+    let tiles_path = fixtures_dir.join(format!("{name}.tiles.bincode"));
+    output.info(format!("Writing tiles: {}", tiles_path.display()));
+    bincode::serialize_into(BufWriter::new(File::create(&tiles_path)?), scene.tiles())?;
+
+    let png_path = fixtures_dir.join(format!("{name}.png"));
+    output.info(format!("Writing expected output: {}", png_path.display()));
+    write_png(&png_path, &screen_buffer)?;
+
+    let test_path = out_dir.join(format!("{name}.rs"));
+    output.info(format!("Writing test: {}", test_path.display()));
+    std::fs::write(&test_path, generate_test_source(name, &scene)?)?;
+
+    Ok(())
+}
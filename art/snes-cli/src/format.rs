@@ -0,0 +1,131 @@
+//! Detection of the format of the input files of `movie create`.
+//!
+//! Only frames exported by the Mesen-S capture script are supported. Other dumps (such as Mesen2
+//! saves, raw binary manifests or NES ROMs) are not recognized.
+
+use anyhow::Context as _;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+
+/// The keys that every Mesen-S frame contains (see the capture script).
+const MESEN_S_KEYS: [&[u8]; 4] = [
+    b"\"cgram\"",
+    b"\"oam\"",
+    b"\"obj_name_base_table\"",
+    b"\"obj_name_select_table\"",
+];
+
+/// The format of a dump.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum DumpFormat {
+    /// SNES frames, exported as JSON by the Mesen-S capture script.
+    MesenS,
+}
+
+impl Display for DumpFormat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DumpFormat::MesenS => f.write_str("Mesen-S"),
+        }
+    }
+}
+
+impl DumpFormat {
+    /// Determines the format from the contents of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `contents`: The contents of the file.
+    ///
+    /// returns: The format or `None` if it is not recognized.
+    pub fn sniff(contents: &[u8]) -> Option<Self> {
+        let start = contents
+            .iter()
+            .position(|byte| !byte.is_ascii_whitespace())
+            .unwrap_or(contents.len());
+        let json = &contents[start..];
+        let is_mesen_s = json.starts_with(b"{")
+            && MESEN_S_KEYS
+                .iter()
+                .all(|key| json.windows(key.len()).any(|window| window == *key));
+        is_mesen_s.then_some(DumpFormat::MesenS)
+    }
+
+    /// Determines the format of a file.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The path of the file.
+    pub fn detect(path: &Path) -> anyhow::Result<Self> {
+        let contents =
+            std::fs::read(path).with_context(|| format!("Could not read {}.", path.display()))?;
+        Self::sniff(&contents).with_context(|| {
+            format!(
+                "Could not determine the format of {}. Use --platform to specify it.",
+                path.display()
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_dump_format {
+    use super::DumpFormat;
+    use crate::test_util::TempDir;
+
+    const MESEN_S: &[u8] =
+        br#"{ "cgram": [], "oam": [], "obj_name_base_table": 0, "obj_name_select_table": 0 }"#;
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(Some(DumpFormat::MesenS), DumpFormat::sniff(MESEN_S));
+
+        let mut padded = b"\n  \t".to_vec();
+        padded.extend_from_slice(MESEN_S);
+        assert_eq!(Some(DumpFormat::MesenS), DumpFormat::sniff(&padded));
+
+        // A key is missing
+        assert_eq!(None, DumpFormat::sniff(br#"{ "cgram": [], "oam": [] }"#));
+        // Not an object
+        assert_eq!(
+            None,
+            DumpFormat::sniff(
+                br#"["cgram", "oam", "obj_name_base_table", "obj_name_select_table"]"#
+            )
+        );
+        // An NES ROM
+        assert_eq!(None, DumpFormat::sniff(b"NES\x1a\x02\x01"));
+        assert_eq!(None, DumpFormat::sniff(b""));
+    }
+
+    #[test]
+    fn test_detect() {
+        let dir = TempDir::new("detect");
+
+        // Only the contents count, not the extension
+        let mesen_s = dir.file("frame_1.bin");
+        std::fs::write(&mesen_s, MESEN_S).unwrap();
+        assert_eq!(DumpFormat::MesenS, DumpFormat::detect(&mesen_s).unwrap());
+
+        for (name, contents) in [
+            ("frame_2.json", &b"{}"[..]),
+            ("game.nes", b"NES\x1a\x02\x01"),
+        ] {
+            let unknown = dir.file(name);
+            std::fs::write(&unknown, contents).unwrap();
+            assert_eq!(
+                format!(
+                    "Could not determine the format of {}. Use --platform to specify it.",
+                    unknown.display()
+                ),
+                DumpFormat::detect(&unknown).unwrap_err().to_string()
+            );
+        }
+
+        let missing = dir.file("missing.json");
+        assert_eq!(
+            format!("Could not read {}.", missing.display()),
+            DumpFormat::detect(&missing).unwrap_err().to_string()
+        );
+    }
+}
@@ -1,11 +1,39 @@
+use crate::format::DumpFormat;
+use crate::manifest::{create_manifest, write_manifest};
+use crate::output::{Output, Verbosity};
+use anyhow::Context as _;
 use clap::{Args, Parser, Subcommand};
+use std::cell::Cell;
 use std::fs::File;
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use ves_art_core::color::ColorTransform;
+use ves_art_core::geom_art::{Rect, Size};
+use ves_art_core::library::ArtLibrary;
+use ves_art_core::movie::{FrameCapacity, Movie, OverflowPolicy};
+use ves_art_core::postprocess::{PassConfig, Pipeline, StripHud};
+use ves_art_core::sheet::ExportOptions;
+use ves_art_snes::doctor::diagnose_capture;
 
-/// Tool for generating input for Art Extractor from SNES data.
+mod fixture;
+mod format;
+mod manifest;
+mod output;
+mod sheet;
+#[cfg(test)]
+mod test_util;
+
+/// Tool for generating input for the VES art tools from SNES data.
 #[derive(Parser, Debug)]
 #[clap(version)]
 struct SnesCli {
+    /// Only print errors.
+    #[clap(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+    /// Print details about every processed file.
+    #[clap(short, long, global = true, action = clap::ArgAction::Count)]
+    verbose: u8,
     #[clap(subcommand)]
     command: CliCommand,
 }
@@ -13,6 +41,17 @@ struct SnesCli {
 #[derive(Subcommand, Debug)]
 enum CliCommand {
     Movie(MovieArgs),
+    Library(LibraryArgs),
+    Doctor(DoctorArgs),
+}
+
+/// Validates a directory of Mesen-S input files before a movie is created from them. Reports missing frame numbers, inconsistent
+/// OBJ SIZE SELECT values, truncated files and unsupported format versions.
+#[derive(Args, Debug)]
+struct DoctorArgs {
+    /// The directory with the files (extracted from Mesen-S).
+    #[clap(name = "DIR")]
+    dir: String,
 }
 
 /// Commands related to movies.
@@ -22,10 +61,12 @@ struct MovieArgs {
     command: MovieCommand,
 }
 
-/// Creates a movie from Mesen-S input files.
+/// Creates a movie from Mesen-S input files, prints information about a movie or generates test fixtures from a movie.
 #[derive(Subcommand, Debug)]
 enum MovieCommand {
     Create(MovieCreateArgs),
+    Info(MovieInfoArgs),
+    Fixture(MovieFixtureArgs),
 }
 
 /// Creates a movie from Mesen-S input files.
@@ -34,50 +75,702 @@ struct MovieCreateArgs {
     /// The target output file.
     #[clap(name = "out", short = 'o')]
     out_path: String,
+    /// The format of the input files. Only Mesen-S frames are supported. By default, the format is
+    /// verified against the contents of the first file.
+    #[clap(long, value_enum)]
+    platform: Option<DumpFormat>,
+    /// Crops the movie to a region of the screen. Sprites outside of the region or across its left or top edge are removed.
+    #[clap(long, value_name = "X,Y,WIDTH,HEIGHT", value_parser = parse_region)]
+    crop: Option<Rect>,
+    /// A JSON file with the post-processing passes that are applied to the movie, in order (after --crop). For example:
+    /// `[{ "pass": "strip_hud", "min_fraction": 0.9 }, { "pass": "dedup" }]`. The available passes are `dedup`, `strip_hud`
+    /// (`min_fraction`), `crop` (`x`, `y`, `width`, `height`) and `scale` (`factor`).
+    #[clap(long, value_name = "FILE")]
+    passes: Option<String>,
+    /// The maximum number of sprites per frame (see --overflow).
+    #[clap(long, default_value_t = ves_art_snes::OAM_SIZE)]
+    max_sprites: usize,
+    /// Determines what happens to frames with more sprites than --max-sprites: `error` rejects them, `truncate` drops the sprites
+    /// with the lowest priority (with a warning for every frame) and `split` splits them into consecutive frames. Splitting moves
+    /// the later frames back, such that the frame numbers remain distinct.
+    #[clap(long, value_name = "POLICY", default_value = "error", value_parser = parse_overflow)]
+    overflow: OverflowPolicy,
+    /// Also writes a reproducibility manifest to this JSON file: the input files with their SHA-256 hashes, the version of the
+    /// extractor and the post-processing passes.
+    #[clap(long, value_name = "FILE")]
+    manifest: Option<String>,
     /// The files to use as input (extracted from Mesen-S).
     #[clap(name = "FILES", last = true)]
     in_paths: Vec<String>,
 }
 
-fn create_movie(in_paths: &[impl AsRef<str>], out_path: &str) -> anyhow::Result<()> {
-    let iter = in_paths
-        .iter()
-        .map(|in_path| {
-            let mut path = PathBuf::new();
-            path.push(in_path.as_ref());
-            path
+/// Prints the metadata of a movie and statistics about its contents.
+#[derive(Args, Debug)]
+struct MovieInfoArgs {
+    /// The movie file (created with `movie create`).
+    #[clap(name = "FILE")]
+    in_path: String,
+}
+
+/// Generates a rendering test fixture for `ves-core-render` from a movie frame.
+#[derive(Args, Debug)]
+struct MovieFixtureArgs {
+    /// The output directory (normally the `tests` directory of `ves-core-render`).
+    #[clap(name = "out", short = 'o')]
+    out_dir: String,
+    /// The index of the frame in the movie.
+    #[clap(long, default_value_t = 0)]
+    frame: usize,
+    /// The name of the fixture (used for the test name and the file names).
+    #[clap(long)]
+    name: String,
+    /// The movie file (created with `movie create`).
+    #[clap(name = "FILE")]
+    in_path: String,
+}
+
+/// Commands related to art libraries.
+#[derive(Args, Debug)]
+struct LibraryArgs {
+    #[clap(subcommand)]
+    command: LibraryCommand,
+}
+
+/// Creates, merges or imports art libraries.
+#[derive(Subcommand, Debug)]
+enum LibraryCommand {
+    Create(LibraryCreateArgs),
+    Merge(LibraryMergeArgs),
+    ExportSheet(LibraryExportSheetArgs),
+    ImportSheet(LibraryImportSheetArgs),
+}
+
+/// Creates an art library from movie files.
+#[derive(Args, Debug)]
+struct LibraryCreateArgs {
+    /// The target output file.
+    #[clap(name = "out", short = 'o')]
+    out_path: String,
+    /// Removes the sprites that occur unchanged in at least this fraction of the frames of a movie (like HUD elements and score
+    /// digits).
+    #[clap(long, value_name = "FRACTION")]
+    strip_static: Option<f64>,
+    /// The movie files to use as input (created with `movie create`).
+    #[clap(name = "FILES", last = true)]
+    in_paths: Vec<String>,
+}
+
+/// Merges multiple art libraries into one.
+#[derive(Args, Debug)]
+struct LibraryMergeArgs {
+    /// The target output file.
+    #[clap(name = "out", short = 'o')]
+    out_path: String,
+    /// The library files to merge.
+    #[clap(name = "FILES", last = true)]
+    in_paths: Vec<String>,
+}
+
+/// Creates a spritesheet (a PNG image with a JSON metadata file) from an art library, for instance for editing the artwork in an
+/// external tool.
+#[derive(Args, Debug)]
+struct LibraryExportSheetArgs {
+    /// The target image file (PNG).
+    #[clap(name = "out", short = 'o')]
+    out_path: String,
+    /// The target metadata file (JSON).
+    #[clap(long = "meta")]
+    meta_path: String,
+    /// The maximum width of the image.
+    #[clap(long, default_value_t = ExportOptions::default().max_width)]
+    max_width: u32,
+    /// Includes the source of every sprite (frame number and OAM slot) in the metadata, such that exported cels can be traced back
+    /// to the original emulator frames.
+    #[clap(long)]
+    provenance: bool,
+    /// Applies a gamma adjustment to the colors of the image (values above 1 darken the mid-tones). Transformed images are meant for
+    /// viewing only, since importing them loses the original colors.
+    #[clap(long, value_name = "GAMMA")]
+    gamma: Option<f32>,
+    /// Scales the color saturation of the image (0 is grayscale, 1 leaves the colors unchanged).
+    #[clap(long, value_name = "FACTOR")]
+    saturation: Option<f32>,
+    /// Approximates the colors of an NTSC TV. The --gamma and --saturation options override the respective parts of this preset.
+    #[clap(long)]
+    tv: bool,
+    /// The library file.
+    #[clap(name = "FILE")]
+    in_path: String,
+}
+
+impl LibraryExportSheetArgs {
+    /// Creates the [`ExportOptions`] from the arguments.
+    fn export_options(&self) -> ExportOptions {
+        ExportOptions {
+            max_width: self.max_width,
+            include_sources: self.provenance,
+        }
+    }
+
+    /// Creates the [`ColorTransform`] from the arguments.
+    ///
+    /// returns: The transform or an error if the gamma or the saturation is invalid.
+    fn color_transform(&self) -> anyhow::Result<ColorTransform> {
+        let preset = if self.tv {
+            ColorTransform::TV
+        } else {
+            ColorTransform::RAW
+        };
+        ColorTransform::new(
+            self.gamma.unwrap_or_else(|| preset.gamma()),
+            self.saturation.unwrap_or_else(|| preset.saturation()),
+        )
+        .map_err(anyhow::Error::msg)
+    }
+}
+
+/// Creates an art library from a spritesheet (a PNG image with a JSON metadata file), for instance after editing the artwork in an
+/// external tool.
+#[derive(Args, Debug)]
+struct LibraryImportSheetArgs {
+    /// The target output file.
+    #[clap(name = "out", short = 'o')]
+    out_path: String,
+    /// The number of entries in the palette of the library, including the transparent entry. The colors in the image are reduced to
+    /// fit the palette.
+    #[clap(long, default_value_t = 16)]
+    palette_len: usize,
+    /// The spritesheet image (PNG).
+    #[clap(name = "SHEET")]
+    sheet_path: String,
+    /// The spritesheet metadata (JSON).
+    #[clap(name = "META")]
+    meta_path: String,
+}
+
+/// Parses a screen region in the format `X,Y,WIDTH,HEIGHT`.
+fn parse_region(value: &str) -> Result<Rect, String> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| format!("Invalid region '{}': {}", value, err))?;
+    match parts[..] {
+        [x, y, width, height] if width > 0 && height > 0 => {
+            Ok(Rect::new_from_size((x, y), Size::new(width, height)))
+        }
+        _ => Err(format!(
+            "Invalid region '{}' (expected X,Y,WIDTH,HEIGHT with a non-zero size).",
+            value
+        )),
+    }
+}
+
+/// Parses an [`OverflowPolicy`].
+fn parse_overflow(value: &str) -> Result<OverflowPolicy, String> {
+    match value {
+        "error" => Ok(OverflowPolicy::Error),
+        "truncate" => Ok(OverflowPolicy::Truncate),
+        "split" => Ok(OverflowPolicy::Split),
+        _ => Err(format!(
+            "Invalid overflow policy '{}' (expected error, truncate or split).",
+            value
+        )),
+    }
+}
+
+/// Reads the configuration of post-processing passes from a JSON file (see [`PassConfig`]).
+fn read_passes(in_path: &str) -> anyhow::Result<Vec<PassConfig>> {
+    let file = File::open(in_path).with_context(|| format!("Could not open {}.", in_path))?;
+    serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Could not parse {}.", in_path))
+}
+
+/// Runs a post-processing pipeline on a movie and reports the result of every pass.
+fn run_pipeline(pipeline: &Pipeline, movie: &mut Movie, output: &Output) -> anyhow::Result<()> {
+    pipeline
+        .run(movie, |name, count| {
+            output.info(format!("Pass '{}' changed {} items.", name, count))
         })
-        // Below is just a kind of hacky way to show the progress. It presumes that each element in the iterator is consumed and immediately
-        // processed (which is not specified by ves_art_snes::create_movie()... it might collect all paths first and then process them
-        // all, in which case this output is more or less bogus.
-        .enumerate()
-        .map(|(i, path)| {
-            println!(
-                "Processing file {}/{}: {}",
-                i,
-                in_paths.len(),
-                path.display()
-            );
-            path
+        .map_err(anyhow::Error::msg)
+}
+
+/// Deserializes a bincode file.
+fn read_bincode<T: serde::de::DeserializeOwned>(in_path: &str) -> anyhow::Result<T> {
+    let file = File::open(in_path).with_context(|| format!("Could not open {}.", in_path))?;
+    bincode::deserialize_from(BufReader::new(file))
+        .with_context(|| format!("Could not deserialize {}.", in_path))
+}
+
+/// Serializes a value into a bincode file.
+fn write_bincode(value: &impl serde::Serialize, out_path: &str) -> anyhow::Result<()> {
+    let file = File::create(out_path).with_context(|| format!("Could not create {}.", out_path))?;
+    bincode::serialize_into(BufWriter::new(file), value)
+        .with_context(|| format!("Could not write {}.", out_path))
+}
+
+impl MovieCreateArgs {
+    /// Creates the [`FrameCapacity`] from the arguments.
+    ///
+    /// returns: The capacity or an error if the maximum number of sprites is zero.
+    fn capacity(&self) -> anyhow::Result<FrameCapacity> {
+        FrameCapacity::new(self.max_sprites, self.overflow).map_err(anyhow::Error::msg)
+    }
+}
+
+fn create_movie(args: &MovieCreateArgs, output: &Output) -> anyhow::Result<()> {
+    let in_paths = &args.in_paths;
+    let out_path = &args.out_path;
+    let capacity = args.capacity()?;
+    let format = match (args.platform, in_paths.first()) {
+        (Some(format), _) => format,
+        (None, Some(in_path)) => {
+            let format = DumpFormat::detect(Path::new(in_path))?;
+            output.detail(None, format!("Detected input format: {}", format));
+            format
+        }
+        (None, None) => anyhow::bail!("No input files specified."),
+    };
+
+    let mut pass_configs = Vec::new();
+    if let Some(rect) = args.crop {
+        pass_configs.push(PassConfig::Crop {
+            x: rect.min_x().raw(),
+            y: rect.min_y().raw(),
+            width: rect.width().raw(),
+            height: rect.height().raw(),
         });
+    }
+    if let Some(passes) = &args.passes {
+        pass_configs.extend(read_passes(passes)?);
+    }
+    let pipeline = Pipeline::from_config(&pass_configs);
 
-    let movie = ves_art_snes::create_movie(iter)?;
+    let progress_bar = output.progress_bar(in_paths.len(), "Processing files");
+    let result = ves_art_snes::create_movie_with_capacity(
+        in_paths.iter().map(PathBuf::from),
+        &capacity,
+        |i, path| {
+            progress_bar.set_position(i as u64);
+            progress_bar.set_message(path.display().to_string());
+            output.detail(
+                Some(&progress_bar),
+                format!(
+                    "Processing file {}/{}: {}",
+                    i + 1,
+                    in_paths.len(),
+                    path.display()
+                ),
+            );
+        },
+    );
+    progress_bar.finish_and_clear();
+    let (mut movie, warnings) = result?;
+    for warning in warnings {
+        output.warning(warning.to_string());
+    }
 
-    println!("Writing output file: {}", out_path);
-    let bincode_file = File::create(out_path)?;
-    bincode::serialize_into(bincode_file, &movie)?;
+    run_pipeline(&pipeline, &mut movie, output)?;
 
+    output.info(format!(
+        "Movie contains {} frames, {} palettes and {} tiles.",
+        movie.frames().len(),
+        movie.palettes().len(),
+        movie.tiles().len()
+    ));
+    output.info(format!("Writing output file: {}", out_path));
+    write_bincode(&movie, out_path)?;
+
+    if let Some(manifest_path) = &args.manifest {
+        output.info(format!("Writing manifest: {}", manifest_path));
+        let manifest = create_manifest(in_paths, format, &pass_configs, out_path)?;
+        write_manifest(&manifest, manifest_path)?;
+    }
     Ok(())
 }
 
-fn main() -> anyhow::Result<()> {
-    let cli_args: SnesCli = SnesCli::parse();
+fn movie_info(in_path: &str, output: &Output) -> anyhow::Result<()> {
+    let movie: Movie = read_bincode(in_path)?;
 
-    match cli_args.command {
-        CliCommand::Movie(cmd) => match cmd.command {
-            MovieCommand::Create(args) => create_movie(&args.in_paths, &args.out_path)?,
+    let metadata = movie.metadata();
+    let fields = [
+        ("Title", &metadata.title),
+        ("Game", &metadata.game),
+        ("Author", &metadata.author),
+        ("Notes", &metadata.notes),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            output.info(format!("{}: {}", name, value));
+        }
+    }
+    for (key, value) in &metadata.properties {
+        output.info(format!("{}: {}", key, value));
+    }
+    if metadata.is_empty() {
+        output.info("The movie has no metadata.");
+    }
+
+    let screen_size = movie.screen_size();
+    output.info(format!(
+        "Screen size: {} x {}",
+        screen_size.width.raw(),
+        screen_size.height.raw()
+    ));
+    output.info(format!(
+        "Frame rate: {:?} ({} fps)",
+        movie.frame_rate(),
+        movie.frame_rate().fps()
+    ));
+    output.info(format!(
+        "Movie contains {} frames, {} palettes and {} tiles.",
+        movie.frames().len(),
+        movie.palettes().len(),
+        movie.tiles().len()
+    ));
+    Ok(())
+}
+
+fn create_fixture(
+    in_path: &str,
+    frame: usize,
+    name: &str,
+    out_dir: &str,
+    output: &Output,
+) -> anyhow::Result<()> {
+    output.info(format!("Reading movie: {}", in_path));
+    let movie: Movie = read_bincode(in_path)?;
+    fixture::create_fixture(&movie, frame, name, Path::new(out_dir), output)
+}
+
+/// Merges the art libraries that are loaded from the input files.
+///
+/// # Arguments
+///
+/// * `in_paths`: The input files.
+/// * `description`: A description of the input files for the progress output.
+/// * `load`: A function that loads an art library from an input file.
+/// * `output`: The [`Output`].
+fn merge_inputs(
+    in_paths: &[impl AsRef<str>],
+    description: &'static str,
+    load: impl Fn(&str) -> anyhow::Result<ArtLibrary>,
+    output: &Output,
+) -> anyhow::Result<ArtLibrary> {
+    let mut library = ArtLibrary::default();
+    let progress_bar = output.progress_bar(in_paths.len(), description);
+    for (i, in_path) in in_paths.iter().enumerate() {
+        let in_path = in_path.as_ref();
+        progress_bar.set_message(in_path.to_string());
+        output.detail(
+            Some(&progress_bar),
+            format!("{} {}/{}: {}", description, i + 1, in_paths.len(), in_path),
+        );
+        match load(in_path) {
+            Ok(other) => library.merge(&other),
+            Err(err) => {
+                progress_bar.abandon();
+                return Err(err);
+            }
+        }
+        progress_bar.inc(1);
+    }
+    progress_bar.finish_and_clear();
+    Ok(library)
+}
+
+fn create_library(
+    in_paths: &[impl AsRef<str>],
+    strip_static: Option<f64>,
+    out_path: &str,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let mut pipeline = Pipeline::new();
+    if let Some(min_fraction) = strip_static {
+        pipeline.push(StripHud { min_fraction });
+    }
+
+    let stripped = Cell::new(0);
+    let library = merge_inputs(
+        in_paths,
+        "Processing movies",
+        |in_path| {
+            let mut movie: Movie = read_bincode(in_path)?;
+            pipeline
+                .run(&mut movie, |_, count| stripped.set(stripped.get() + count))
+                .map_err(anyhow::Error::msg)?;
+            Ok(ArtLibrary::from_movie(&movie))
         },
+        output,
+    )?;
+    if strip_static.is_some() {
+        output.info(format!("Removed {} static sprites.", stripped.get()));
+    }
+
+    write_library(library, out_path, output)
+}
+
+fn merge_libraries(
+    in_paths: &[impl AsRef<str>],
+    out_path: &str,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let library = merge_inputs(in_paths, "Processing libraries", read_bincode, output)?;
+
+    write_library(library, out_path, output)
+}
+
+fn export_sheet(args: &LibraryExportSheetArgs, output: &Output) -> anyhow::Result<()> {
+    output.info(format!("Reading library: {}", args.in_path));
+    let library: ArtLibrary = read_bincode(&args.in_path)?;
+    let options = args.export_options();
+    let transform = args.color_transform()?;
+
+    output.info(format!("Writing spritesheet: {}", args.out_path));
+    sheet::export_sheet(
+        &library,
+        Path::new(&args.out_path),
+        Path::new(&args.meta_path),
+        &options,
+        &transform,
+    )
+}
+
+fn import_sheet(
+    sheet_path: &str,
+    meta_path: &str,
+    palette_len: usize,
+    out_path: &str,
+    output: &Output,
+) -> anyhow::Result<()> {
+    output.info(format!("Reading spritesheet: {}", sheet_path));
+    let library = sheet::import_sheet(Path::new(sheet_path), Path::new(meta_path), palette_len)?;
+
+    write_library(library, out_path, output)
+}
+
+fn doctor(dir: &str, output: &Output) -> anyhow::Result<()> {
+    let mut in_paths = Vec::new();
+    for entry in std::fs::read_dir(dir).with_context(|| format!("Could not read {}.", dir))? {
+        let path = entry
+            .with_context(|| format!("Could not read {}.", dir))?
+            .path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            in_paths.push(path);
+        }
+    }
+    in_paths.sort();
+    if in_paths.is_empty() {
+        anyhow::bail!("{} does not contain any JSON files.", dir);
     }
 
+    let progress_bar = output.progress_bar(in_paths.len(), "Checking files");
+    let report = diagnose_capture(in_paths.iter(), |i, path| {
+        progress_bar.set_position(i as u64);
+        progress_bar.set_message(path.display().to_string());
+        output.detail(
+            Some(&progress_bar),
+            format!(
+                "Checking file {}/{}: {}",
+                i + 1,
+                in_paths.len(),
+                path.display()
+            ),
+        );
+    });
+    progress_bar.finish_and_clear();
+
+    match report.frame_range {
+        Some((first, last)) => output.info(format!(
+            "Found {} valid frames (frame {} to {}).",
+            report.frame_count, first, last
+        )),
+        None => output.info("Found no valid frames."),
+    }
+    for issue in &report.issues {
+        output.warning(issue.to_string());
+    }
+    if !report.issues.is_empty() {
+        anyhow::bail!("Found {} problems in {}.", report.issues.len(), dir);
+    }
+    output.info("No problems found.");
     Ok(())
 }
+
+/// Applies the optimization passes to the provided library and writes it to the output file.
+fn write_library(mut library: ArtLibrary, out_path: &str, output: &Output) -> anyhow::Result<()> {
+    library.dedup();
+    library.remove_unreferenced();
+
+    output.info(format!(
+        "Library contains {} palettes, {} tiles, {} cels and {} animations.",
+        library.palettes().len(),
+        library.tiles().len(),
+        library.cels().len(),
+        library.animations().len()
+    ));
+
+    output.info(format!("Writing output file: {}", out_path));
+    write_bincode(&library, out_path)
+}
+
+fn run(command: CliCommand, output: &Output) -> anyhow::Result<()> {
+    match command {
+        CliCommand::Movie(cmd) => match cmd.command {
+            MovieCommand::Create(args) => create_movie(&args, output),
+            MovieCommand::Info(args) => movie_info(&args.in_path, output),
+            MovieCommand::Fixture(args) => {
+                create_fixture(&args.in_path, args.frame, &args.name, &args.out_dir, output)
+            }
+        },
+        CliCommand::Library(cmd) => match cmd.command {
+            LibraryCommand::Create(args) => {
+                create_library(&args.in_paths, args.strip_static, &args.out_path, output)
+            }
+            LibraryCommand::Merge(args) => merge_libraries(&args.in_paths, &args.out_path, output),
+            LibraryCommand::ExportSheet(args) => export_sheet(&args, output),
+            LibraryCommand::ImportSheet(args) => import_sheet(
+                &args.sheet_path,
+                &args.meta_path,
+                args.palette_len,
+                &args.out_path,
+                output,
+            ),
+        },
+        CliCommand::Doctor(args) => doctor(&args.dir, output),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli_args: SnesCli = SnesCli::parse();
+    let output = Output::new(Verbosity::from_flags(cli_args.quiet, cli_args.verbose));
+
+    match run(cli_args.command, &output) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            output.error(&err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_snes_cli {
+    use super::{
+        CliCommand, LibraryCommand, LibraryExportSheetArgs, MovieCommand, MovieCreateArgs, SnesCli,
+    };
+    use clap::Parser as _;
+    use ves_art_core::color::ColorTransform;
+    use ves_art_core::movie::{FrameCapacity, OverflowPolicy};
+    use ves_art_core::sheet::ExportOptions;
+
+    fn parse_movie_create(args: &[&str]) -> MovieCreateArgs {
+        let mut cli_args = vec!["ves-art-snes-cli", "movie", "create", "-o", "movie.bin"];
+        cli_args.extend(args);
+        cli_args.extend(["--", "frame_1.json"]);
+        let cli = SnesCli::try_parse_from(cli_args).unwrap();
+        match cli.command {
+            CliCommand::Movie(movie) => match movie.command {
+                MovieCommand::Create(args) => args,
+                command => panic!("Unexpected command: {:?}", command),
+            },
+            command => panic!("Unexpected command: {:?}", command),
+        }
+    }
+
+    fn parse_export_sheet(args: &[&str]) -> LibraryExportSheetArgs {
+        let mut cli_args = vec!["ves-art-snes-cli", "library", "export-sheet"];
+        cli_args.extend(["-o", "sheet.png", "--meta", "sheet.json"]);
+        cli_args.extend(args);
+        cli_args.push("library.bin");
+        let cli = SnesCli::try_parse_from(cli_args).unwrap();
+        match cli.command {
+            CliCommand::Library(library) => match library.command {
+                LibraryCommand::ExportSheet(args) => args,
+                command => panic!("Unexpected command: {:?}", command),
+            },
+            command => panic!("Unexpected command: {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_movie_create_capacity() {
+        let args = parse_movie_create(&[]);
+        assert_eq!(vec!["frame_1.json"], args.in_paths);
+        assert_eq!(ves_art_snes::default_capacity(), args.capacity().unwrap());
+
+        let args = parse_movie_create(&["--max-sprites", "32", "--overflow", "split"]);
+        assert_eq!(
+            FrameCapacity::new(32, OverflowPolicy::Split).unwrap(),
+            args.capacity().unwrap()
+        );
+        let args = parse_movie_create(&["--overflow", "truncate"]);
+        assert_eq!(
+            OverflowPolicy::Truncate,
+            args.capacity().unwrap().overflow()
+        );
+
+        assert!(parse_movie_create(&["--max-sprites", "0"])
+            .capacity()
+            .is_err());
+        assert!(SnesCli::try_parse_from([
+            "ves-art-snes-cli",
+            "movie",
+            "create",
+            "-o",
+            "movie.bin",
+            "--overflow",
+            "wrap"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_export_sheet_defaults() {
+        let args = parse_export_sheet(&[]);
+        assert_eq!("sheet.png", args.out_path);
+        assert_eq!("sheet.json", args.meta_path);
+        assert_eq!("library.bin", args.in_path);
+        assert_eq!(ExportOptions::default(), args.export_options());
+        assert_eq!(ColorTransform::RAW, args.color_transform().unwrap());
+    }
+
+    #[test]
+    fn test_export_sheet_options() {
+        let args = parse_export_sheet(&["--max-width", "64", "--provenance"]);
+        assert_eq!(
+            ExportOptions {
+                max_width: 64,
+                include_sources: true,
+            },
+            args.export_options()
+        );
+    }
+
+    #[test]
+    fn test_export_sheet_color_transform() {
+        let args = parse_export_sheet(&["--tv"]);
+        assert_eq!(ColorTransform::TV, args.color_transform().unwrap());
+
+        // The explicit values override the preset
+        let args = parse_export_sheet(&["--tv", "--saturation", "0.5"]);
+        assert_eq!(
+            ColorTransform::new(ColorTransform::TV.gamma(), 0.5).unwrap(),
+            args.color_transform().unwrap()
+        );
+        let args = parse_export_sheet(&["--gamma", "2"]);
+        assert_eq!(
+            ColorTransform::new(2.0, 1.0).unwrap(),
+            args.color_transform().unwrap()
+        );
+
+        let args = parse_export_sheet(&["--gamma", "0"]);
+        assert_eq!(
+            "Invalid gamma: 0 (expected a positive value).",
+            args.color_transform().unwrap_err().to_string()
+        );
+    }
+}
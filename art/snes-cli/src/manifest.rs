@@ -0,0 +1,145 @@
+//! Reproducibility manifests for `movie create` (see [`Manifest`]).
+//!
+//! The passes are recorded in the same format as the `--passes` file (including `--crop`, which is the first pass), such that they
+//! can be fed back into the tool as-is.
+
+use crate::format::DumpFormat;
+use anyhow::Context as _;
+use sha2::{Digest, Sha256};
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::BufWriter;
+use ves_art_core::manifest::{HashedFile, Manifest};
+use ves_art_core::postprocess::PassConfig;
+
+/// Creates a manifest. This hashes all input files and the output file.
+///
+/// # Arguments
+///
+/// * `in_paths`: The input files.
+/// * `format`: The format of the input files.
+/// * `passes`: The post-processing passes, in order.
+/// * `out_path`: The output file. It must have been written already.
+pub fn create_manifest(
+    in_paths: &[impl AsRef<str>],
+    format: DumpFormat,
+    passes: &[PassConfig],
+    out_path: &str,
+) -> anyhow::Result<Manifest> {
+    let mut inputs = in_paths
+        .iter()
+        .map(|in_path| hash_file(in_path.as_ref()))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    // The movie does not depend on the order of the input files, so neither should the manifest
+    inputs.sort_by(|a, b| a.path.cmp(&b.path));
+
+    Ok(Manifest {
+        tool: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+        extractor_version: ves_art_snes::VERSION.to_string(),
+        format: format.to_string(),
+        passes: passes.to_vec(),
+        inputs,
+        output: hash_file(out_path)?,
+    })
+}
+
+/// Writes a manifest into a JSON file.
+///
+/// # Arguments
+///
+/// * `manifest`: The manifest.
+/// * `out_path`: The path to the file.
+pub fn write_manifest(manifest: &Manifest, out_path: &str) -> anyhow::Result<()> {
+    let file = File::create(out_path).with_context(|| format!("Could not create {}.", out_path))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), manifest)
+        .with_context(|| format!("Could not write {}.", out_path))
+}
+
+/// Reads a file and calculates its hash.
+///
+/// # Arguments
+///
+/// * `path`: The path to the file.
+fn hash_file(path: &str) -> anyhow::Result<HashedFile> {
+    let mut file = File::open(path).with_context(|| format!("Could not open {}.", path))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).with_context(|| format!("Could not read {}.", path))?;
+
+    let mut sha256 = String::with_capacity(64);
+    for byte in hasher.finalize() {
+        // Writing to a String never fails
+        write!(sha256, "{:02x}", byte).unwrap();
+    }
+    Ok(HashedFile {
+        path: path.to_string(),
+        sha256,
+    })
+}
+
+#[cfg(test)]
+mod test_manifest {
+    use super::{create_manifest, write_manifest};
+    use crate::format::DumpFormat;
+    use crate::test_util::TempDir;
+    use ves_art_core::manifest::{HashedFile, Manifest};
+    use ves_art_core::postprocess::PassConfig;
+
+    const SHA256_ABC: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+    const SHA256_EMPTY: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn test_create_and_write() {
+        let dir = TempDir::new("manifest");
+        let path = |name: &str| dir.file(name).to_str().unwrap().to_string();
+
+        let in_paths = [path("frame_2.json"), path("frame_1.json")];
+        std::fs::write(&in_paths[0], b"").unwrap();
+        std::fs::write(&in_paths[1], b"abc").unwrap();
+        let out_path = path("movie.bin");
+        std::fs::write(&out_path, b"abc").unwrap();
+        let passes = [PassConfig::Dedup, PassConfig::Scale { factor: 2 }];
+
+        let manifest = create_manifest(&in_paths, DumpFormat::MesenS, &passes, &out_path).unwrap();
+        assert_eq!("Mesen-S", manifest.format);
+        assert_eq!(ves_art_snes::VERSION, manifest.extractor_version);
+        assert_eq!(passes.to_vec(), manifest.passes);
+        // Sorted by path
+        assert_eq!(
+            vec![
+                HashedFile {
+                    path: in_paths[1].clone(),
+                    sha256: SHA256_ABC.to_string(),
+                },
+                HashedFile {
+                    path: in_paths[0].clone(),
+                    sha256: SHA256_EMPTY.to_string(),
+                },
+            ],
+            manifest.inputs
+        );
+        assert_eq!(
+            HashedFile {
+                path: out_path,
+                sha256: SHA256_ABC.to_string(),
+            },
+            manifest.output
+        );
+
+        let manifest_path = path("manifest.json");
+        write_manifest(&manifest, &manifest_path).unwrap();
+        let written: Manifest =
+            serde_json::from_slice(&std::fs::read(&manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest, written);
+    }
+
+    #[test]
+    fn test_create_missing_input() {
+        let dir = TempDir::new("manifest_missing");
+        let in_path = dir.file("missing.json").to_str().unwrap().to_string();
+        let out_path = dir.file("movie.bin").to_str().unwrap().to_string();
+        std::fs::write(&out_path, b"abc").unwrap();
+
+        let err = create_manifest(&[&in_path], DumpFormat::MesenS, &[], &out_path).unwrap_err();
+        assert_eq!(format!("Could not open {}.", in_path), err.to_string());
+    }
+}
@@ -0,0 +1,111 @@
+//! Console output of the CLI: messages, progress bars and errors.
+
+use indicatif::{ProgressBar, ProgressStyle};
+
+/// The amount of output.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Verbosity {
+    /// Only errors.
+    Quiet,
+    /// Errors, progress bars and the main steps.
+    Normal,
+    /// Everything, including details about every processed file.
+    Verbose,
+}
+
+impl Verbosity {
+    /// Determines the verbosity from the command line flags.
+    ///
+    /// # Arguments
+    ///
+    /// * `quiet`: The `-q` flag.
+    /// * `verbose`: The number of `-v` flags.
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        if quiet {
+            Verbosity::Quiet
+        } else if verbose > 0 {
+            Verbosity::Verbose
+        } else {
+            Verbosity::Normal
+        }
+    }
+}
+
+/// Writes output to the console, according to the [`Verbosity`].
+#[derive(Clone, Debug)]
+pub struct Output {
+    verbosity: Verbosity,
+}
+
+impl Output {
+    /// Creates a new instance.
+    pub fn new(verbosity: Verbosity) -> Self {
+        Self { verbosity }
+    }
+
+    /// Prints a message about one of the main steps.
+    pub fn info(&self, message: impl AsRef<str>) {
+        if self.verbosity >= Verbosity::Normal {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// Creates a progress bar.
+    ///
+    /// The progress bar is hidden in quiet mode. In verbose mode, [`Output::detail()`] prints above the progress bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `len`: The total number of steps.
+    /// * `description`: A description of the task, which is shown in front of the progress bar.
+    pub fn progress_bar(&self, len: usize, description: &'static str) -> ProgressBar {
+        if self.verbosity == Verbosity::Quiet {
+            return ProgressBar::hidden();
+        }
+
+        let progress_bar = ProgressBar::new(len as u64);
+        progress_bar.set_style(
+            ProgressStyle::with_template("{prefix} [{bar:40}] {pos}/{len} {wide_msg}")
+                // The unwrap is OK here, because the template is static
+                .unwrap()
+                .progress_chars("=> "),
+        );
+        progress_bar.set_prefix(description);
+        progress_bar
+    }
+
+    /// Prints details about a step, like the file that is being processed.
+    ///
+    /// # Arguments
+    ///
+    /// * `progress_bar`: The progress bar that is currently shown, if any.
+    /// * `message`: The message.
+    pub fn detail(&self, progress_bar: Option<&ProgressBar>, message: impl AsRef<str>) {
+        if self.verbosity < Verbosity::Verbose {
+            return;
+        }
+
+        match progress_bar {
+            // Printing through the progress bar prevents the message from being overwritten
+            Some(progress_bar) if !progress_bar.is_hidden() => {
+                progress_bar.println(message.as_ref())
+            }
+            _ => println!("{}", message.as_ref()),
+        }
+    }
+
+    /// Prints a problem that does not stop the current command.
+    pub fn warning(&self, message: impl AsRef<str>) {
+        if self.verbosity >= Verbosity::Normal {
+            eprintln!("warning: {}", message.as_ref());
+        }
+    }
+
+    /// Prints an error, including the chain of errors that caused it.
+    pub fn error(&self, error: &anyhow::Error) {
+        eprintln!("error: {}", error);
+        for cause in error.chain().skip(1) {
+            eprintln!("  caused by: {}", cause);
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Exporting and importing spritesheets (see [`ves_art_core::sheet`]).
+//!
+//! A spritesheet consists of the following files:
+//! * A PNG image with the tiles. Fully transparent pixels are transparent, all other pixels are opaque.
+//! * A JSON file with the [`SheetMeta`].
+
+use anyhow::{anyhow, Context as _, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use ves_art_core::color::ColorTransform;
+use ves_art_core::geom_art::Size;
+use ves_art_core::library::ArtLibrary;
+use ves_art_core::sheet::{ExportOptions, SheetMeta};
+use ves_art_core::sprite::Color;
+use ves_art_core::surface::Surface as _;
+use ves_art_core::true_color::TrueColorSurface;
+
+/// Reads a PNG image into a [`TrueColorSurface`].
+fn read_png(path: &Path) -> Result<TrueColorSurface> {
+    let file = File::open(path).with_context(|| format!("Could not open {}.", path.display()))?;
+    let mut decoder = png::Decoder::new(BufReader::new(file));
+    // Expand palettes and low bit depths, such that we only have to deal with 8 bits per channel
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder
+        .read_info()
+        .with_context(|| format!("Could not decode {}.", path.display()))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .with_context(|| format!("Could not decode {}.", path.display()))?;
+    let samples = info.color_type.samples();
+    if info.bit_depth != png::BitDepth::Eight || !(1..=4).contains(&samples) {
+        return Err(anyhow!(
+            "Unsupported image format in {}: {:?} with bit depth {:?}.",
+            path.display(),
+            info.color_type,
+            info.bit_depth
+        ));
+    }
+
+    let to_color = |pixel: &[u8]| -> Color {
+        match pixel {
+            [value] => Color::new(*value, *value, *value),
+            [_, 0] | [_, _, _, 0] => Color::Transparent,
+            [value, _] => Color::new(*value, *value, *value),
+            [r, g, b] | [r, g, b, _] => Color::new(*r, *g, *b),
+            // The slices are created by chunks_exact() with the number of samples of the color type
+            _ => unreachable!(),
+        }
+    };
+
+    let mut surface = TrueColorSurface::new(Size::new(info.width, info.height));
+    for (y, row) in buffer
+        .chunks_exact(info.line_size)
+        .take(info.height as usize)
+        .enumerate()
+    {
+        let width = info.width as usize;
+        let target = &mut surface.data_mut()[y * width..(y + 1) * width];
+        for (color, pixel) in target.iter_mut().zip(row.chunks_exact(samples)) {
+            *color = to_color(pixel);
+        }
+    }
+    Ok(surface)
+}
+
+/// Writes a [`TrueColorSurface`] to a PNG image.
+///
+/// # Arguments
+///
+/// * `path`: The PNG image.
+/// * `image`: The image.
+/// * `transform`: The [`ColorTransform`] that is applied to the colors.
+fn write_png(path: &Path, image: &TrueColorSurface, transform: &ColorTransform) -> Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("Could not create {}.", path.display()))?;
+    let size = image.size();
+    let mut encoder = png::Encoder::new(BufWriter::new(file), size.width.raw(), size.height.raw());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let data: Vec<u8> = image
+        .data()
+        .iter()
+        .flat_map(|color| match transform.apply(*color) {
+            Color::Opaque(rgb) => [rgb.r, rgb.g, rgb.b, 0xff],
+            Color::Transparent => [0, 0, 0, 0],
+        })
+        .collect();
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&data))
+        .with_context(|| format!("Could not write {}.", path.display()))
+}
+
+/// Creates a spritesheet from an [`ArtLibrary`].
+///
+/// # Arguments
+///
+/// * `library`: The library.
+/// * `image_path`: The PNG image.
+/// * `meta_path`: The JSON file with the [`SheetMeta`].
+/// * `options`: The [`ExportOptions`].
+/// * `transform`: The [`ColorTransform`] that is applied to the colors of the image. The metadata is not affected.
+pub fn export_sheet(
+    library: &ArtLibrary,
+    image_path: &Path,
+    meta_path: &Path,
+    options: &ExportOptions,
+    transform: &ColorTransform,
+) -> Result<()> {
+    let (image, meta) = ves_art_core::sheet::export_sheet(library, options)
+        .map_err(|err| anyhow!("Could not create spritesheet: {}", err))?;
+    write_png(image_path, &image, transform)?;
+
+    let meta_file = File::create(meta_path)
+        .with_context(|| format!("Could not create {}.", meta_path.display()))?;
+    serde_json::to_writer_pretty(BufWriter::new(meta_file), &meta)
+        .with_context(|| format!("Could not write {}.", meta_path.display()))
+}
+
+/// Creates an [`ArtLibrary`] from a spritesheet.
+///
+/// # Arguments
+///
+/// * `image_path`: The PNG image.
+/// * `meta_path`: The JSON file with the [`SheetMeta`].
+/// * `palette_len`: The number of entries in the palette, including the transparent entry.
+pub fn import_sheet(image_path: &Path, meta_path: &Path, palette_len: usize) -> Result<ArtLibrary> {
+    let meta_file = File::open(meta_path)
+        .with_context(|| format!("Could not open {}.", meta_path.display()))?;
+    let meta: SheetMeta = serde_json::from_reader(BufReader::new(meta_file))
+        .with_context(|| format!("Could not parse {}.", meta_path.display()))?;
+    let image = read_png(image_path)?;
+
+    ves_art_core::sheet::import_sheet(&image, &meta, palette_len).map_err(|err| {
+        anyhow!(
+            "Could not import spritesheet {}: {}",
+            image_path.display(),
+            err
+        )
+    })
+}
+
+#[cfg(test)]
+mod test_sheet {
+    use super::{export_sheet, import_sheet};
+    use crate::test_util::TempDir;
+    use ves_art_core::color::ColorTransform;
+    use ves_art_core::geom_art::{Point, Size};
+    use ves_art_core::library::ArtLibrary;
+    use ves_art_core::movie::{FrameRate, Movie, MovieFrame};
+    use ves_art_core::sheet::ExportOptions;
+    use ves_art_core::sprite::{
+        Color, Palette, PaletteIndex, PaletteRef, Sprite, Tile, TileRef, TileSurface,
+    };
+    use ves_art_core::surface::Surface as _;
+
+    /// Creates a library with a single 2x1 tile, which is used with two palettes.
+    fn create_library() -> ArtLibrary {
+        let mut surface = TileSurface::new(Size::new(2, 1));
+        surface.data_mut()[1] = PaletteIndex::new(1);
+        let sprite = |palette: usize, x: u32| {
+            Sprite::new(
+                TileRef::new(0),
+                PaletteRef::new(palette),
+                Point::new(x, 0),
+                false,
+                false,
+            )
+        };
+        let movie = Movie::new(
+            Size::new(256, 224),
+            vec![
+                Palette::new(vec![Color::Transparent, Color::new(255, 0, 0)]),
+                Palette::new(vec![Color::Transparent, Color::new(0, 0, 255)]),
+            ],
+            vec![Tile::new(surface)],
+            vec![
+                MovieFrame::new(10, vec![sprite(0, 0), sprite(1, 2)]),
+                MovieFrame::new(11, vec![sprite(1, 4)]),
+            ],
+            FrameRate::Ntsc,
+        );
+        ArtLibrary::from_movie(&movie)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = TempDir::new("sheet_round_trip");
+        let library = create_library();
+        let options = ExportOptions::default();
+        export_sheet(
+            &library,
+            &dir.file("sheet.png"),
+            &dir.file("sheet.json"),
+            &options,
+            &ColorTransform::RAW,
+        )
+        .unwrap();
+
+        let imported = import_sheet(&dir.file("sheet.png"), &dir.file("sheet.json"), 16).unwrap();
+        assert_eq!(library.cels().len(), imported.cels().len());
+        assert_eq!(library.animations().len(), imported.animations().len());
+
+        // Exporting the imported library results in the same spritesheet
+        export_sheet(
+            &imported,
+            &dir.file("again.png"),
+            &dir.file("again.json"),
+            &options,
+            &ColorTransform::RAW,
+        )
+        .unwrap();
+        let read = |name: &str| std::fs::read(dir.file(name)).unwrap();
+        assert_eq!(read("sheet.png"), read("again.png"));
+        assert_eq!(read("sheet.json"), read("again.json"));
+
+        // The transform only affects the image
+        export_sheet(
+            &library,
+            &dir.file("tv.png"),
+            &dir.file("tv.json"),
+            &options,
+            &ColorTransform::TV,
+        )
+        .unwrap();
+        assert_ne!(read("sheet.png"), read("tv.png"));
+        assert_eq!(read("sheet.json"), read("tv.json"));
+    }
+
+    #[test]
+    fn test_export_errors() {
+        let dir = TempDir::new("sheet_export_errors");
+        let image_path = dir.file("sheet.png");
+        let meta_path = dir.file("sheet.json");
+
+        let err = export_sheet(
+            &ArtLibrary::default(),
+            &image_path,
+            &meta_path,
+            &ExportOptions::default(),
+            &ColorTransform::RAW,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("Could not create spritesheet: "));
+
+        let missing_dir = dir.file("missing");
+        let err = export_sheet(
+            &create_library(),
+            &missing_dir.join("sheet.png"),
+            &meta_path,
+            &ExportOptions::default(),
+            &ColorTransform::RAW,
+        )
+        .unwrap_err();
+        assert_eq!(
+            format!(
+                "Could not create {}.",
+                missing_dir.join("sheet.png").display()
+            ),
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let dir = TempDir::new("sheet_import_errors");
+        let image_path = dir.file("sheet.png");
+        let meta_path = dir.file("sheet.json");
+        export_sheet(
+            &create_library(),
+            &image_path,
+            &meta_path,
+            &ExportOptions::default(),
+            &ColorTransform::RAW,
+        )
+        .unwrap();
+        let error = |image_path, meta_path| {
+            import_sheet(image_path, meta_path, 16)
+                .unwrap_err()
+                .to_string()
+        };
+
+        let missing = dir.file("missing.json");
+        assert_eq!(
+            format!("Could not open {}.", missing.display()),
+            error(&image_path, &missing)
+        );
+
+        let invalid = dir.file("invalid.json");
+        std::fs::write(&invalid, b"{ \"tiles\": ").unwrap();
+        assert_eq!(
+            format!("Could not parse {}.", invalid.display()),
+            error(&image_path, &invalid)
+        );
+
+        // The metadata refers to a tile that does not exist
+        let unknown_tile = dir.file("unknown_tile.json");
+        std::fs::write(
+            &unknown_tile,
+            br#"{ "tiles": [], "cels": [{ "sprites": [{ "tile": 0, "x": 0, "y": 0, "h_flip": false, "v_flip": false }] }], "animations": [] }"#,
+        )
+        .unwrap();
+        assert!(error(&image_path, &unknown_tile).starts_with(&format!(
+            "Could not import spritesheet {}: ",
+            image_path.display()
+        )));
+
+        let not_png = dir.file("not_png.png");
+        std::fs::write(&not_png, b"GIF89a").unwrap();
+        assert_eq!(
+            format!("Could not decode {}.", not_png.display()),
+            error(&not_png, &meta_path)
+        );
+    }
+}
@@ -0,0 +1,37 @@
+//! Utilities for the tests.
+
+use std::path::PathBuf;
+
+/// A directory for the files of a test, which is removed when it is dropped.
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// Creates an empty directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the test. Together with the process ID, this makes the directory unique.
+    pub fn new(name: &str) -> Self {
+        let path =
+            std::env::temp_dir().join(format!("ves-art-snes-cli-{}-{}", std::process::id(), name));
+        // Leftovers of an earlier run are not interesting
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+        Self(path)
+    }
+
+    /// Retrieves the path of a file in the directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the file.
+    pub fn file(&self, name: &str) -> PathBuf {
+        self.0.join(name)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
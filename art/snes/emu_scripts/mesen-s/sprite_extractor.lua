@@ -462,6 +462,8 @@ function grabSpriteData()
 
   local state_ppu = emu.getState().ppu;
 
+  frame_record.format_version = 1; -- Must match FORMAT_VERSION in mesen.rs
+
   frame_record.frame_nr = state_ppu.frameCount;
   frame_record.obj_size_select = state_ppu.oamMode;
 
@@ -0,0 +1,353 @@
+//! Validation of Mesen-S captures.
+//!
+//! Extracting a movie from a long capture session takes a while and fails on the first broken file. The functions in this module scan
+//! all files up front and report every problem at once.
+
+use crate::mesen::{Frame, CGRAM_LEN, FORMAT_VERSION, OAM_LEN, OBJ_NAME_TABLE_LEN};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// A problem with a capture.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CaptureIssue {
+    /// A file could not be read or does not contain a valid frame.
+    Invalid { path: PathBuf, reason: String },
+    /// A file ends prematurely or one of its tables is too short. This usually means that the emulator was stopped while the file
+    /// was being written.
+    Truncated { path: PathBuf, reason: String },
+    /// A file has been written by an unsupported version of the capture script.
+    VersionMismatch { path: PathBuf, version: u32 },
+    /// Multiple files contain the same frame number.
+    DuplicateFrame {
+        frame_nr: u64,
+        paths: (PathBuf, PathBuf),
+    },
+    /// A range of frame numbers (inclusive) is not present in the capture.
+    MissingFrames { first: u64, last: u64 },
+    /// Not all frames use the same `OBJ SIZE SELECT`. Every entry contains a value, the number of frames that use it and the first of
+    /// those frames.
+    InconsistentObjSizeSelect { modes: Vec<(u8, usize, u64)> },
+}
+
+impl Display for CaptureIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureIssue::Invalid { path, reason } => {
+                write!(f, "{} is invalid: {}", path.display(), reason)
+            }
+            CaptureIssue::Truncated { path, reason } => {
+                write!(f, "{} is truncated: {}", path.display(), reason)
+            }
+            CaptureIssue::VersionMismatch { path, version } => write!(
+                f,
+                "{} has format version {} (expected {}).",
+                path.display(),
+                version,
+                FORMAT_VERSION
+            ),
+            CaptureIssue::DuplicateFrame { frame_nr, paths } => write!(
+                f,
+                "Frame {} is contained in both {} and {}.",
+                frame_nr,
+                paths.0.display(),
+                paths.1.display()
+            ),
+            CaptureIssue::MissingFrames { first, last } if first == last => {
+                write!(f, "Frame {} is missing.", first)
+            }
+            CaptureIssue::MissingFrames { first, last } => {
+                write!(f, "Frames {} to {} are missing.", first, last)
+            }
+            CaptureIssue::InconsistentObjSizeSelect { modes } => {
+                let modes: Vec<String> = modes
+                    .iter()
+                    .map(|(value, count, first)| {
+                        format!("{} in {} frames (first in frame {})", value, count, first)
+                    })
+                    .collect();
+                write!(
+                    f,
+                    "The frames use different OBJ SIZE SELECT values: {}.",
+                    modes.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// The result of [`diagnose_capture()`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CaptureReport {
+    /// The number of valid frames.
+    pub frame_count: usize,
+    /// The lowest and highest frame number, if there is at least one valid frame.
+    pub frame_range: Option<(u64, u64)>,
+    /// The problems that were found.
+    pub issues: Vec<CaptureIssue>,
+}
+
+/// The relevant properties of a single valid frame.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct FrameSummary {
+    frame_nr: u64,
+    obj_size_select: u8,
+}
+
+/// Checks the contents of a single capture file.
+///
+/// # Arguments
+///
+/// * `path`: The path of the file (only used in the issue).
+/// * `contents`: The contents of the file.
+fn check_frame(path: &Path, contents: &[u8]) -> Result<FrameSummary, CaptureIssue> {
+    let frame: Frame = serde_json::from_slice(contents).map_err(|err| {
+        let path = path.to_path_buf();
+        let reason = err.to_string();
+        if err.is_eof() {
+            CaptureIssue::Truncated { path, reason }
+        } else {
+            CaptureIssue::Invalid { path, reason }
+        }
+    })?;
+
+    if frame.format_version != FORMAT_VERSION {
+        return Err(CaptureIssue::VersionMismatch {
+            path: path.to_path_buf(),
+            version: frame.format_version,
+        });
+    }
+
+    let tables = [
+        ("cgram", frame.cgram.len(), CGRAM_LEN),
+        ("oam", frame.oam.len(), OAM_LEN),
+        (
+            "obj_name_base_table",
+            frame.obj_name_base_table.len(),
+            OBJ_NAME_TABLE_LEN,
+        ),
+        (
+            "obj_name_select_table",
+            frame.obj_name_select_table.len(),
+            OBJ_NAME_TABLE_LEN,
+        ),
+    ];
+    for (name, len, expected) in tables {
+        if len != expected {
+            return Err(CaptureIssue::Truncated {
+                path: path.to_path_buf(),
+                reason: format!(
+                    "{} contains {:#x} bytes (expected {:#x}).",
+                    name, len, expected
+                ),
+            });
+        }
+    }
+
+    Ok(FrameSummary {
+        frame_nr: frame.frame_nr,
+        obj_size_select: frame.obj_size_select,
+    })
+}
+
+/// Creates a [`CaptureReport`] from the results of the individual files.
+///
+/// # Arguments
+///
+/// * `results`: The results of [`check_frame()`], together with the paths of the files.
+fn create_report(results: Vec<(PathBuf, Result<FrameSummary, CaptureIssue>)>) -> CaptureReport {
+    let mut report = CaptureReport::default();
+    let mut frames: BTreeMap<u64, PathBuf> = BTreeMap::new();
+    let mut modes: BTreeMap<u8, (usize, u64)> = BTreeMap::new();
+
+    for (path, result) in results {
+        let summary = match result {
+            Ok(summary) => summary,
+            Err(issue) => {
+                report.issues.push(issue);
+                continue;
+            }
+        };
+
+        if let Some(other) = frames.get(&summary.frame_nr) {
+            report.issues.push(CaptureIssue::DuplicateFrame {
+                frame_nr: summary.frame_nr,
+                paths: (other.clone(), path),
+            });
+            continue;
+        }
+        frames.insert(summary.frame_nr, path);
+
+        let (count, first) = modes
+            .entry(summary.obj_size_select)
+            .or_insert((0, summary.frame_nr));
+        *count += 1;
+        *first = (*first).min(summary.frame_nr);
+    }
+
+    let mut previous: Option<u64> = None;
+    for &frame_nr in frames.keys() {
+        if let Some(previous) = previous {
+            if frame_nr > previous + 1 {
+                report.issues.push(CaptureIssue::MissingFrames {
+                    first: previous + 1,
+                    last: frame_nr - 1,
+                });
+            }
+        }
+        previous = Some(frame_nr);
+    }
+
+    if modes.len() > 1 {
+        report.issues.push(CaptureIssue::InconsistentObjSizeSelect {
+            modes: modes
+                .into_iter()
+                .map(|(value, (count, first))| (value, count, first))
+                .collect(),
+        });
+    }
+
+    report.frame_count = frames.len();
+    report.frame_range = frames
+        .keys()
+        .next()
+        .zip(frames.keys().next_back())
+        .map(|(first, last)| (*first, *last));
+    report
+}
+
+/// Validates the files of a Mesen-S capture and reports the progress.
+///
+/// # Arguments
+///
+/// * `files`: The Mesen-S export files.
+/// * `progress`: A function that is called right before a file is processed. The arguments are the index of the file and its path.
+///
+/// returns: The [`CaptureReport`].
+pub fn diagnose_capture(
+    files: impl ExactSizeIterator<Item = impl AsRef<Path>>,
+    mut progress: impl FnMut(usize, &Path),
+) -> CaptureReport {
+    let mut results = Vec::with_capacity(files.len());
+    for (i, file) in files.enumerate() {
+        let file = file.as_ref();
+        progress(i, file);
+        let result = std::fs::read(file)
+            .map_err(|err| CaptureIssue::Invalid {
+                path: file.to_path_buf(),
+                reason: err.to_string(),
+            })
+            .and_then(|contents| check_frame(file, &contents));
+        results.push((file.to_path_buf(), result));
+    }
+    create_report(results)
+}
+
+#[cfg(test)]
+mod test_diagnose_capture {
+    use super::{check_frame, create_report, diagnose_capture, CaptureIssue, FrameSummary};
+    use std::path::{Path, PathBuf};
+
+    fn frame_json(version: Option<u32>, frame_nr: u64, oam_len: usize) -> String {
+        let version = version
+            .map(|version| format!("\"format_version\": {},", version))
+            .unwrap_or_default();
+        format!(
+            "{{{} \"frame_nr\": {}, \"obj_size_select\": 0, \"cgram\": {:?}, \"oam\": {:?}, \"obj_name_base_table\": {:?}, \"obj_name_select_table\": {:?}}}",
+            version,
+            frame_nr,
+            vec![0; 0x200],
+            vec![0; oam_len],
+            vec![0; 0x2000],
+            vec![0; 0x2000]
+        )
+    }
+
+    fn summary(frame_nr: u64, obj_size_select: u8) -> Result<FrameSummary, CaptureIssue> {
+        Ok(FrameSummary {
+            frame_nr,
+            obj_size_select,
+        })
+    }
+
+    #[test]
+    fn test_check_frame() {
+        let path = Path::new("frame_1.json");
+        assert_eq!(
+            Ok(FrameSummary {
+                frame_nr: 1,
+                obj_size_select: 0
+            }),
+            check_frame(path, frame_json(None, 1, 0x220).as_bytes())
+        );
+        assert!(matches!(
+            check_frame(path, frame_json(Some(2), 1, 0x220).as_bytes()),
+            Err(CaptureIssue::VersionMismatch { version: 2, .. })
+        ));
+        assert!(matches!(
+            check_frame(path, frame_json(Some(1), 1, 0x100).as_bytes()),
+            Err(CaptureIssue::Truncated { .. })
+        ));
+
+        let json = frame_json(Some(1), 1, 0x220);
+        assert!(matches!(
+            check_frame(path, &json.as_bytes()[..json.len() / 2]),
+            Err(CaptureIssue::Truncated { .. })
+        ));
+        assert!(matches!(
+            check_frame(path, b"{\"frame_nr\": \"one\"}"),
+            Err(CaptureIssue::Invalid { .. })
+        ));
+    }
+
+    #[test]
+    fn test_create_report() {
+        let results = vec![
+            (PathBuf::from("a"), summary(10, 0)),
+            (PathBuf::from("b"), summary(11, 0)),
+            (PathBuf::from("c"), summary(14, 1)),
+            (PathBuf::from("d"), summary(11, 0)),
+            (PathBuf::from("e"), summary(16, 0)),
+        ];
+        let report = create_report(results);
+        assert_eq!(4, report.frame_count);
+        assert_eq!(Some((10, 16)), report.frame_range);
+        assert_eq!(
+            vec![
+                CaptureIssue::DuplicateFrame {
+                    frame_nr: 11,
+                    paths: (PathBuf::from("b"), PathBuf::from("d"))
+                },
+                CaptureIssue::MissingFrames {
+                    first: 12,
+                    last: 13
+                },
+                CaptureIssue::MissingFrames {
+                    first: 15,
+                    last: 15
+                },
+                CaptureIssue::InconsistentObjSizeSelect {
+                    modes: vec![(0, 3, 10), (1, 1, 14)]
+                },
+            ],
+            report.issues
+        );
+        assert_eq!("Frame 15 is missing.", report.issues[2].to_string());
+    }
+
+    #[test]
+    fn test_real_capture() {
+        let mut input_frames_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        input_frames_dir.push("resources/test/mesen-s_frames");
+        let files: Vec<_> = (0..10)
+            .map(|frame| input_frames_dir.join(format!("frame_{}.json", 199250 + frame)))
+            .collect();
+
+        let mut processed = 0;
+        let report = diagnose_capture(files.iter(), |_, _| processed += 1);
+        assert_eq!(10, processed);
+        assert_eq!(10, report.frame_count);
+        assert_eq!(Some((199250, 199259)), report.frame_range);
+        assert!(report.issues.is_empty());
+    }
+}
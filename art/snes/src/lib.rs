@@ -1,30 +1,123 @@
-use crate::mesen::Frame;
-use std::path::Path;
+use crate::mesen::{Frame, FORMAT_VERSION};
+use anyhow::Context as _;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
 use ves_art_core::geom_art::Size;
-use ves_art_core::movie::{FrameRate, Movie};
+use ves_art_core::movie::{
+    CapacityWarning, FrameCapacity, FrameRate, Movie, MovieFrame, OverflowPolicy,
+};
+use ves_art_core::sprite::{Palette, PaletteRef, Sprite, Tile, TileRef};
 use ves_cache::VecCacheMut;
 
+pub mod doctor;
 mod mesen;
 mod obj;
 #[cfg(test)]
 pub(crate) mod test_util;
 
+/// The version of the extractor. Changes to the extraction logic can change the output for the same input files.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The number of entries in the OAM table of the SNES, which is the maximum number of sprites in a frame.
+pub const OAM_SIZE: usize = 128;
+
+/// Retrieves the [`FrameCapacity`] of the SNES: a frame can not contain more sprites than the OAM table, so the capacity is never
+/// exceeded.
+pub fn default_capacity() -> FrameCapacity {
+    // The unwrap is OK here, because the OAM size is not zero
+    FrameCapacity::new(OAM_SIZE, OverflowPolicy::Error).unwrap()
+}
+
 /// Creates a [`Movie`] from the provided Mesen-S export files.
+///
+/// The result is deterministic: the same set of files always results in the same movie, regardless of the order in which the files
+/// are provided. The palettes and tiles are numbered in the order of the frames in which they first occur.
 pub fn create_movie(
     files: impl ExactSizeIterator<Item = impl AsRef<Path>>,
 ) -> anyhow::Result<Movie> {
-    let mut palettes = VecCacheMut::new();
-    let mut tiles = VecCacheMut::new();
-
-    let mut movie_frames = Vec::with_capacity(files.len());
-    for file in files {
-        let file_handle = std::fs::File::open(file)?;
-        let mesen_frame: Frame = serde_json::from_reader(file_handle)?;
-        let movie_frame = obj::create_movie_frame(&mesen_frame, &mut palettes, &mut tiles)?;
-        movie_frames.push(movie_frame);
+    create_movie_with_progress(files, |_, _| {})
+}
+
+/// Creates a [`Movie`] from the provided Mesen-S export files and reports the progress.
+///
+/// # Arguments
+///
+/// * `files`: The Mesen-S export files.
+/// * `progress`: A function that is called right before a file is processed. The arguments are the index of the file and its path.
+///
+/// See [`create_movie()`] for the order of the palettes and tiles.
+pub fn create_movie_with_progress(
+    files: impl ExactSizeIterator<Item = impl AsRef<Path>>,
+    progress: impl FnMut(usize, &Path),
+) -> anyhow::Result<Movie> {
+    let (movie, _) = create_movie_with_capacity(files, &default_capacity(), progress)?;
+    Ok(movie)
+}
+
+/// Creates a [`Movie`] from the provided Mesen-S export files, with a limit on the number of sprites per frame, and reports the
+/// progress.
+///
+/// # Arguments
+///
+/// * `files`: The Mesen-S export files.
+/// * `capacity`: The [`FrameCapacity`] that is enforced on the frames, in the order of their frame numbers (see
+///   [`FrameCapacity::apply()`]).
+/// * `progress`: A function that is called right before a file is processed. The arguments are the index of the file and its path.
+///
+/// returns: The movie and a warning for every frame from which sprites have been dropped.
+///
+/// See [`create_movie()`] for the order of the palettes and tiles.
+pub fn create_movie_with_capacity(
+    files: impl ExactSizeIterator<Item = impl AsRef<Path>>,
+    capacity: &FrameCapacity,
+    mut progress: impl FnMut(usize, &Path),
+) -> anyhow::Result<(Movie, Vec<CapacityWarning>)> {
+    let mut extracted = Vec::with_capacity(files.len());
+    for (i, file) in files.enumerate() {
+        let file = file.as_ref();
+        progress(i, file);
+        let file_handle = std::fs::File::open(file)
+            .with_context(|| format!("Could not open {}.", file.display()))?;
+        let mesen_frame: Frame = serde_json::from_reader(file_handle)
+            .with_context(|| format!("Could not parse {}.", file.display()))?;
+        if mesen_frame.format_version != FORMAT_VERSION {
+            anyhow::bail!(
+                "{} has format version {} (expected {}).",
+                file.display(),
+                mesen_frame.format_version,
+                FORMAT_VERSION
+            );
+        }
+        // Every frame gets its own caches, such that the result of a file does not depend on the files before it
+        let mut frame_palettes = VecCacheMut::new();
+        let mut frame_tiles = VecCacheMut::new();
+        let movie_frame =
+            obj::create_movie_frame(&mesen_frame, &mut frame_palettes, &mut frame_tiles)
+                .with_context(|| format!("Could not process {}.", file.display()))?;
+        extracted.push(ExtractedFrame {
+            path: file.to_path_buf(),
+            frame: movie_frame,
+            palettes: frame_palettes.into_vec(),
+            tiles: frame_tiles.into_vec(),
+        });
     }
 
-    movie_frames.sort_unstable_by_key(|a| a.frame_number());
+    // Merge the frames in a fixed order. The path only matters for files that contain the same frame.
+    extracted.sort_by(|a, b| {
+        a.frame
+            .frame_number()
+            .cmp(&b.frame.frame_number())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    // The movie contains at least as many palettes and tiles as its largest frame
+    let max_len = |len: fn(&ExtractedFrame) -> usize| extracted.iter().map(len).max().unwrap_or(0);
+    let mut palettes = VecCacheMut::with_capacity(max_len(|frame| frame.palettes.len()));
+    let mut tiles = VecCacheMut::with_capacity(max_len(|frame| frame.tiles.len()));
+    let movie_frames = extracted
+        .into_iter()
+        .map(|extracted| extracted.merge_into(&mut palettes, &mut tiles))
+        .collect();
+    let (movie_frames, warnings) = capacity.apply(movie_frames).map_err(anyhow::Error::msg)?;
 
     let movie = Movie::new(
         Size::new(512, 256),
@@ -33,14 +126,64 @@ pub fn create_movie(
         movie_frames,
         FrameRate::Ntsc,
     );
-    Ok(movie)
+    Ok((movie, warnings))
+}
+
+/// A frame that has been extracted from a single file, together with its own palettes and tiles.
+struct ExtractedFrame {
+    path: PathBuf,
+    frame: MovieFrame,
+    palettes: Vec<Palette>,
+    tiles: Vec<Tile>,
+}
+
+impl ExtractedFrame {
+    /// Moves the palettes and tiles of the frame into the caches of the movie.
+    ///
+    /// # Arguments
+    ///
+    /// * `palettes`: The palette cache of the movie.
+    /// * `tiles`: The tile cache of the movie.
+    ///
+    /// returns: The frame, with its sprites referring to the caches of the movie.
+    fn merge_into(
+        self,
+        palettes: &mut VecCacheMut<Palette, PaletteRef>,
+        tiles: &mut VecCacheMut<Tile, TileRef>,
+    ) -> MovieFrame {
+        let palette_refs: Vec<PaletteRef> = self
+            .palettes
+            .into_iter()
+            .map(|palette| palettes.offer(Cow::Owned(palette)))
+            .collect();
+        let tile_refs: Vec<TileRef> = self
+            .tiles
+            .into_iter()
+            .map(|tile| tiles.offer(Cow::Owned(tile)))
+            .collect();
+        let sprites = self
+            .frame
+            .sprites()
+            .iter()
+            .map(|sprite| {
+                Sprite::new(
+                    tile_refs[sprite.tile().value()],
+                    palette_refs[sprite.palette().value()],
+                    sprite.position(),
+                    sprite.h_flip(),
+                    sprite.v_flip(),
+                )
+            })
+            .collect();
+        MovieFrame::new(self.frame.frame_number(), sprites)
+    }
 }
 
 #[cfg(test)]
 mod test_create_movie {
-    use super::create_movie;
+    use super::{create_movie, create_movie_with_capacity};
     use std::fs::File;
-    use ves_art_core::movie::Movie;
+    use ves_art_core::movie::{FrameCapacity, Movie, MovieFrame, OverflowPolicy};
     use ves_cache::SliceCache;
 
     #[test]
@@ -101,4 +244,84 @@ mod test_create_movie {
 
         assert_eq!(expected_movie, actual_movie);
     }
+
+    #[test]
+    fn test_deterministic() {
+        let mut input_frames_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        input_frames_dir.push("resources/test/mesen-s_frames");
+        let files: Vec<_> = (199250..199260)
+            .map(|frame| input_frames_dir.join(format!("frame_{}.json", frame)))
+            .collect();
+
+        // Like the order in which a directory is listed, the order of the files should not matter
+        let mut shuffled = files.clone();
+        shuffled.reverse();
+        shuffled.swap(2, 7);
+
+        let serialize = |files: &[std::path::PathBuf]| {
+            bincode::serialize(&create_movie(files.iter()).unwrap()).unwrap()
+        };
+        let expected = serialize(&files);
+        assert_eq!(expected, serialize(&files));
+        assert_eq!(expected, serialize(&shuffled));
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut input_frames_dir = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        input_frames_dir.push("resources/test/mesen-s_frames");
+        let files: Vec<_> = (199250..199260)
+            .map(|frame| input_frames_dir.join(format!("frame_{}.json", frame)))
+            .collect();
+        let movie = create_movie(files.iter()).unwrap();
+        let max_sprites = |movie: &Movie| {
+            movie
+                .frames()
+                .iter()
+                .map(|frame| frame.sprites().len())
+                .max()
+                .unwrap()
+        };
+        let sprite_count = |movie: &Movie| -> usize {
+            movie
+                .frames()
+                .iter()
+                .map(|frame| frame.sprites().len())
+                .sum()
+        };
+        let limit = max_sprites(&movie) - 1;
+        let capacity = |overflow| FrameCapacity::new(limit, overflow).unwrap();
+
+        assert!(create_movie_with_capacity(
+            files.iter(),
+            &capacity(OverflowPolicy::Error),
+            |_, _| {}
+        )
+        .is_err());
+
+        let (truncated, warnings) = create_movie_with_capacity(
+            files.iter(),
+            &capacity(OverflowPolicy::Truncate),
+            |_, _| {},
+        )
+        .unwrap();
+        assert_eq!(movie.frames().len(), truncated.frames().len());
+        assert_eq!(limit, max_sprites(&truncated));
+        assert!(!warnings.is_empty());
+        let dropped: usize = warnings.iter().map(|warning| warning.dropped).sum();
+        assert_eq!(sprite_count(&movie), sprite_count(&truncated) + dropped);
+
+        let (split, warnings) =
+            create_movie_with_capacity(files.iter(), &capacity(OverflowPolicy::Split), |_, _| {})
+                .unwrap();
+        assert!(warnings.is_empty());
+        assert!(split.frames().len() > movie.frames().len());
+        assert_eq!(sprite_count(&movie), sprite_count(&split));
+        let frame_numbers: Vec<u64> = split
+            .frames()
+            .iter()
+            .map(MovieFrame::frame_number)
+            .collect();
+        assert!(frame_numbers.windows(2).all(|pair| pair[0] < pair[1]));
+    }
 }
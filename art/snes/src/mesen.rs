@@ -11,6 +11,9 @@
 /// struct.
 #[derive(serde::Deserialize)]
 pub struct Frame {
+    /// The version of the capture format (see [`FORMAT_VERSION`]). Captures from before the introduction of this field have version 1.
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
     /// The frame number. This can be useful for autmatically determining animation timings, movement speeds etc.
     pub frame_nr: u64,
     /// The `OBJ SIZE SELECT` from PPU register 0x2100. See Chapter 27 in the SNES Developer Manual.
@@ -26,9 +29,23 @@ pub struct Frame {
     pub obj_name_select_table: Vec<u8>,
 }
 
+/// The version of the capture format that is written by `sprite_extractor.lua` and supported by this crate.
+pub const FORMAT_VERSION: u32 = 1;
+
+fn default_format_version() -> u32 {
+    1
+}
+
+/// The expected length of [`Frame::cgram`].
+pub const CGRAM_LEN: usize = 0x200;
+/// The expected length of [`Frame::oam`].
+pub const OAM_LEN: usize = 0x220;
+/// The expected length of [`Frame::obj_name_base_table`] and [`Frame::obj_name_select_table`].
+pub const OBJ_NAME_TABLE_LEN: usize = 0x2000;
+
 #[cfg(test)]
 mod test_frame {
-    use super::Frame;
+    use super::{Frame, CGRAM_LEN, OAM_LEN, OBJ_NAME_TABLE_LEN};
 
     /// Tests the JSON deserialization with synthetic input.
     #[test]
@@ -43,6 +60,7 @@ mod test_frame {
         }"###;
 
         let frame: Frame = serde_json::from_str(TEST_JSON).unwrap();
+        assert_eq!(frame.format_version, 1);
         assert_eq!(frame.frame_nr, 123);
         assert_eq!(frame.obj_size_select, 2);
         assert_eq!(
@@ -81,10 +99,10 @@ mod test_frame {
         assert_eq!(frame.frame_nr, 199250);
         assert_eq!(frame.obj_size_select, 0);
         // Not going to verify the content, just the lengths
-        assert_eq!(frame.cgram.len(), 0x200);
-        assert_eq!(frame.oam.len(), 0x220);
-        assert_eq!(frame.obj_name_base_table.len(), 0x2000);
-        assert_eq!(frame.obj_name_select_table.len(), 0x2000);
+        assert_eq!(frame.cgram.len(), CGRAM_LEN);
+        assert_eq!(frame.oam.len(), OAM_LEN);
+        assert_eq!(frame.obj_name_base_table.len(), OBJ_NAME_TABLE_LEN);
+        assert_eq!(frame.obj_name_select_table.len(), OBJ_NAME_TABLE_LEN);
         // A quick and dirty check that depends on internal implementations of slice and DefaultHasher, but it's better than just checking the length
         assert_eq!(
             hash_value(&frame.obj_name_base_table.as_slice()),
@@ -7,12 +7,13 @@
 use anyhow::{anyhow, bail, Result};
 use std::borrow::Cow;
 use std::usize;
+use ves_art_core::color::rgb_from_bgr555;
 use ves_art_core::geom_art::{ArtworkSpaceUnit, Point, Rect, Size};
 use ves_art_core::movie::MovieFrame;
 use ves_art_core::sprite::{
     Color, Palette, PaletteIndex, PaletteRef, Sprite, Tile, TileRef, TileSurface,
 };
-use ves_art_core::surface::Surface;
+use ves_art_core::surface::{ArraySurface, Surface};
 use ves_cache::VecCacheMut;
 
 /// A trait for constructing objects from (raw) SNES data.
@@ -32,16 +33,6 @@ where
     fn from_snes_data(data: T) -> Result<Self>;
 }
 
-/// Make a color component from a 5-bit color value.
-///
-/// # Parameters
-/// * A byte with the color data. Only the least-significant 5 bits are considered.
-#[inline(always)]
-fn make_color_component_5bit(bits: u8) -> u8 {
-    // NOTE: "repeat" the bit pattern across the 8 bits to get the most accurate color
-    bits << 3 | (bits >> 2) & 0b00000111
-}
-
 /// Implementation of [`FromSnesData`] for [`Color`].
 ///
 /// The input data is a tuple where the first byte is the lower byte and the second is the higher byte of the color data. Refer to section
@@ -49,11 +40,8 @@ fn make_color_component_5bit(bits: u8) -> u8 {
 impl FromSnesData<(u8, u8)> for Color {
     fn from_snes_data(data: (u8, u8)) -> Result<Self> {
         let (low, high) = data;
-        let r = make_color_component_5bit(low);
-        let g = make_color_component_5bit(high << 3 | low >> 5);
-        let b = make_color_component_5bit(high >> 2);
-
-        Ok(Self::new(r, g, b))
+        let value = u16::from_le_bytes([low, high]);
+        Ok(Self::Opaque(rgb_from_bgr555(value)))
     }
 }
 
@@ -160,14 +148,8 @@ impl FromSnesData<&[u8]> for Vec<Palette> {
     }
 }
 
-ves_art_core::sized_surface!(
-    ObjNameTableSurface,
-    PaletteIndex,
-    ArtworkSpaceUnit,
-    128,
-    256,
-    PaletteIndex::new(0)
-);
+/// The surface of an [`ObjNameTable`].
+type ObjNameTableSurface = ArraySurface<PaletteIndex, 128, 256>;
 
 /// An `OBJ NAME` table. This table contains all the graphics data for objects. In VRAM the data is stored in two separate tables:
 /// `OBJ NAME BASE` and `OBJ NAME SELECT`. The SNES treats the concatenation of the two as one table for looking up sprite data. See
@@ -214,7 +196,7 @@ impl ObjNameTable {
             );
         }
 
-        let mut surface = ObjNameTableSurface::new();
+        let mut surface = ObjNameTableSurface::new(PaletteIndex::new(0));
 
         Self::read_name_table_into_surface(&mut surface, obj_name_base, 0u32.into());
         Self::read_name_table_into_surface(&mut surface, obj_name_select, Self::TILES_Y.into());
@@ -240,9 +222,8 @@ impl ObjNameTable {
                     for pixel_y in 0..Self::TILE_HEIGHT {
                         let plane1 = *data_iter.next().unwrap();
                         let plane2 = *data_iter.next().unwrap();
-                        let x: ArtworkSpaceUnit = (Self::TILE_WIDTH * tile_x).into();
-                        let y: ArtworkSpaceUnit =
-                            (y_offset + tile_y.into()) * Self::TILE_HEIGHT.into() + pixel_y.into();
+                        let x = ArtworkSpaceUnit::from(tile_x) * Self::TILE_WIDTH;
+                        let y = (y_offset + tile_y.into()) * Self::TILE_HEIGHT + pixel_y.into();
 
                         let offset: usize = surface.offset((x, y)).unwrap();
                         let plus: usize = ArtworkSpaceUnit::from(Self::TILE_WIDTH).into();
@@ -763,6 +744,10 @@ pub fn create_movie_frame(
     let src_size = name_table.surface().size();
     let src_data = name_table.surface().data();
 
+    // Every object adds at most one tile and one palette
+    tile_cache.reserve(oam.objects().len());
+    palette_cache.reserve(palettes.len());
+
     let mut sprites = Vec::with_capacity(oam.objects().len());
     for obj in oam.objects() {
         let obj_size = if obj.size_large {
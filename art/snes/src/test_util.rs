@@ -1,19 +1,11 @@
 use bmp::Pixel;
 use std::ops::Index;
-use ves_art_core::geom_art::{ArtworkSpaceUnit, Point, Rect, Size};
+use ves_art_core::geom_art::{Point, Size};
 use ves_art_core::movie::MovieFrame;
+use ves_art_core::render::{render_sprites, RenderOptions};
 use ves_art_core::sprite::{Color, Palette, PaletteRef, Tile, TileRef};
 use ves_art_core::surface::{surface_iterate, Surface};
 
-ves_art_core::sized_surface!(
-    ScreenSurface,
-    Color,
-    ArtworkSpaceUnit,
-    512,
-    256,
-    Color::Transparent
-);
-
 pub fn create_bitmap(
     size: Size,
     mut func: impl FnMut(usize, Point, &mut bmp::Image),
@@ -37,39 +29,17 @@ pub fn bmp_from_movie_frame(
     movie_frame: &MovieFrame,
     palettes: &impl Index<PaletteRef, Output = Palette>,
     tiles: &impl Index<TileRef, Output = Tile>,
-) -> bmp::Image where {
-    // Render everything to our special screen surface.
-    let mut screen_surface = ScreenSurface::new();
+) -> bmp::Image {
+    let screen_surface = render_sprites(
+        movie_frame.sprites(),
+        palettes,
+        tiles,
+        Size::new(512, 256),
+        &RenderOptions::default(),
+    )
+    .unwrap();
     let screen_size = screen_surface.size();
-    let screen_data = screen_surface.data_mut();
-
-    // Reverse-iterate because the first objects should be rendered on top
-    for sprite in movie_frame.sprites().iter().rev() {
-        let tile = &tiles[sprite.tile()];
-        let sprite_surface = tile.surface();
-        let src_data = sprite_surface.data();
-        let src_size = sprite_surface.size();
-        let src_rect = Rect::new_from_size((0, 0), src_size);
-
-        let palette = &palettes[sprite.palette()];
-        ves_art_core::surface::surface_iterate_2(
-            src_size,
-            src_rect,
-            screen_size,
-            sprite.position(),
-            sprite.h_flip(),
-            sprite.v_flip(),
-            |_src_pos, src_idx, _dest_pos, dest_idx| {
-                let index = src_data[src_idx];
-                if index.value() == 0 {
-                    return;
-                }
-                let color = palette[index];
-                screen_data[dest_idx] = color;
-            },
-        )
-        .unwrap();
-    }
+    let screen_data = screen_surface.data();
 
     // Write BMP
     let transparent = Pixel::new(255, 0, 255);
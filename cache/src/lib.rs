@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::Index;
@@ -66,6 +66,123 @@ where
     }
 }
 
+/// An immutable slice-based cache with reverse lookups.
+///
+/// Like [`SliceCache`], this cache only borrows the values. Additionally, it indexes the hashes of the values on creation, such that
+/// the key of a value can be looked up without cloning the values into a [`VecCacheMut`]. Unlike [`VecCacheMut`], the slice may
+/// contain duplicate values.
+///
+/// # Generic types
+/// * `T`: The element type. This type should implement [`PartialEq`] and [`Hash`].
+/// * `K`: The key type. This type should implement [`Copy`], [`AsIndex`] and [`FromIndex`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BorrowedIndexedCache<'a, T, K = usize> {
+    /// A slice of cached values.
+    values: &'a [T],
+    /// A hash map of value hash values to indices into `values`, in ascending order.
+    hashes: HashMap<u64, Vec<K>>,
+}
+
+impl<'a, T, K> BorrowedIndexedCache<'a, T, K>
+where
+    T: PartialEq + Hash,
+    K: Copy + AsIndex + FromIndex,
+{
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `values`: The values.
+    pub fn new(values: &'a [T]) -> Self {
+        let mut hashes: HashMap<u64, Vec<K>> = HashMap::with_capacity(values.len());
+        for (index, value) in values.iter().enumerate() {
+            hashes
+                .entry(hash_of(value))
+                .or_default()
+                .push(K::from_index(index));
+        }
+        Self { values, hashes }
+    }
+
+    /// Return the values.
+    pub fn values(&self) -> &'a [T] {
+        self.values
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Determines whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Retrieves a value.
+    ///
+    /// # Parameters
+    /// * `key`: The key.
+    ///
+    /// # Return
+    /// The value or `None` if the key is out of range.
+    pub fn get(&self, key: K) -> Option<&'a T> {
+        self.values.get(key.as_index())
+    }
+
+    /// Retrieves the keys of all values that are equal to a value.
+    ///
+    /// # Parameters
+    /// * `value`: The value.
+    ///
+    /// # Return
+    /// The keys, in ascending order.
+    pub fn keys_of<'b>(&'b self, value: &'b T) -> impl Iterator<Item = K> + 'b {
+        self.hashes
+            .get(&hash_of(value))
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(move |key| &self.values[key.as_index()] == value)
+    }
+
+    /// Looks up the key of a value. This is the key that [`VecCacheMut::offer()`] would have returned for the same values.
+    ///
+    /// # Parameters
+    /// * `value`: The value.
+    ///
+    /// # Return
+    /// The key of the first equal value or `None` if the cache does not contain the value.
+    pub fn find(&self, value: &T) -> Option<K> {
+        self.keys_of(value).next()
+    }
+
+    /// Counts the values that are equal to a value.
+    ///
+    /// # Parameters
+    /// * `value`: The value.
+    pub fn count(&self, value: &T) -> usize {
+        self.keys_of(value).count()
+    }
+}
+
+impl<T, K> Index<K> for BorrowedIndexedCache<'_, T, K>
+where
+    K: AsIndex,
+{
+    type Output = T;
+
+    fn index(&self, index: K) -> &Self::Output {
+        &self.values[index.as_index()]
+    }
+}
+
+/// Calculates the hash value of a value.
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// A mutable [`Vec`]-based cache.
 ///
 /// Due to implementation details this cache does not support removal of values.
@@ -90,6 +207,26 @@ impl<T, K> VecCacheMut<T, K> {
         }
     }
 
+    /// Creates a new instance that can hold a number of distinct values without reallocating.
+    ///
+    /// # Parameters
+    /// * `capacity`: The number of distinct values.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Vec::with_capacity(capacity),
+            hashes: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Reserves room for a number of additional distinct values, such that offering these does not reallocate.
+    ///
+    /// # Parameters
+    /// * `additional`: The number of additional distinct values.
+    pub fn reserve(&mut self, additional: usize) {
+        self.values.reserve(additional);
+        self.hashes.reserve(additional);
+    }
+
     /// Returns the number of values.
     pub fn len(&self) -> usize {
         self.values.len()
@@ -125,9 +262,7 @@ where
     /// # Return
     /// The key.
     pub fn offer(&mut self, value: Cow<T>) -> K {
-        let mut hasher = DefaultHasher::new();
-        value.hash(&mut hasher);
-        let hash = hasher.finish();
+        let hash = hash_of(&*value);
 
         if let Some(indices) = self.hashes.get_mut(&hash) {
             // We've seen this hash before, so we need to compare with the existing values of this hash
@@ -170,6 +305,203 @@ where
     }
 }
 
+/// The result of [`LruCacheMut::offer()`].
+///
+/// Keys of an [`LruCacheMut`] are only stable until the value that they refer to is evicted, after which the key is reused for another
+/// value. The variants tell the caller whether this has happened, such that it can invalidate anything that still refers to the old
+/// value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Offered<K, T> {
+    /// The value was already in the cache. The key is the same one that was returned before.
+    Hit(K),
+    /// The value has been added with a key that has not been used before.
+    Inserted(K),
+    /// The value has been added by evicting the least recently used value. The key used to refer to the evicted value and now refers
+    /// to the offered value.
+    Replaced {
+        /// The key.
+        key: K,
+        /// The evicted value.
+        evicted: T,
+    },
+}
+
+impl<K, T> Offered<K, T>
+where
+    K: Copy,
+{
+    /// Retrieves the key of the offered value.
+    pub fn key(&self) -> K {
+        match self {
+            Offered::Hit(key) | Offered::Inserted(key) | Offered::Replaced { key, .. } => *key,
+        }
+    }
+}
+
+/// A mutable [`Vec`]-based cache with a limited capacity.
+///
+/// Unlike [`VecCacheMut`], this cache does not grow without limit. Once it is full, offering a new value evicts the least recently
+/// used value and reuses its key (see [`Offered`]). Keys are therefore always smaller than the capacity.
+///
+/// # Generic types
+/// * `T`: The element type. This type should implement [`PartialEq`], [`Hash`] and [`Clone`].
+/// * `K`: The key type. This type should implement [`Copy`], [`AsIndex`] and [`FromIndex`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LruCacheMut<T, K = usize> {
+    /// The maximum number of values.
+    capacity: usize,
+    /// A vector of cached values.
+    values: Vec<T>,
+    /// The time of the last use of every value, in the same order as `values`.
+    last_used: Vec<u64>,
+    /// A hash map of value hash values to indices into `values`.
+    hashes: HashMap<u64, Vec<K>>,
+    /// A map of the times of the last use to indices into `values`, from least to most recently used.
+    recency: BTreeMap<u64, K>,
+    /// The current time, which is incremented on every use.
+    time: u64,
+}
+
+impl<T, K> LruCacheMut<T, K> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `capacity`: The maximum number of values.
+    ///
+    /// # Panics
+    /// This function panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "The capacity must be greater than zero.");
+        Self {
+            capacity,
+            values: Vec::new(),
+            last_used: Vec::new(),
+            hashes: HashMap::new(),
+            recency: BTreeMap::new(),
+            time: 0,
+        }
+    }
+
+    /// Returns the maximum number of values.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Determines whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the values, in the order of their keys.
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    /// Consumes this instance and returns the [`Vec`] of values, in the order of their keys.
+    pub fn into_vec(self) -> Vec<T> {
+        self.values
+    }
+}
+
+impl<T, K> LruCacheMut<T, K>
+where
+    K: Copy + AsIndex,
+{
+    /// Retrieves a value without marking it as used.
+    ///
+    /// # Parameters
+    /// * `key`: The key.
+    ///
+    /// # Return
+    /// The value or `None` if the key is out of range.
+    pub fn get(&self, key: K) -> Option<&T> {
+        self.values.get(key.as_index())
+    }
+
+    /// Marks a value as used, such that it is evicted last.
+    ///
+    /// # Parameters
+    /// * `key`: The key.
+    ///
+    /// # Panics
+    /// This function panics if the key is out of range.
+    pub fn touch(&mut self, key: K) {
+        let index = key.as_index();
+        self.recency.remove(&self.last_used[index]);
+        self.time += 1;
+        self.last_used[index] = self.time;
+        self.recency.insert(self.time, key);
+    }
+}
+
+impl<T, K> LruCacheMut<T, K>
+where
+    T: PartialEq + Hash + Clone,
+    K: Copy + PartialEq + AsIndex + FromIndex,
+{
+    /// Offers a value. The value is marked as used.
+    ///
+    /// # Parameters
+    /// * `value`: A [`Cow`] of the value to add. [`Cow::into_owned`] will be called if the value is not found in the cache.
+    ///
+    /// # Return
+    /// The key and whether it has been reused (see [`Offered`]).
+    pub fn offer(&mut self, value: Cow<T>) -> Offered<K, T> {
+        let hash = hash_of(&*value);
+
+        let found = self.hashes.get(&hash).and_then(|keys| {
+            keys.iter()
+                .copied()
+                .find(|key| self.values[key.as_index()] == *value)
+        });
+        if let Some(key) = found {
+            self.touch(key);
+            return Offered::Hit(key);
+        }
+
+        let offered = if self.values.len() < self.capacity {
+            let key = K::from_index(self.values.len());
+            self.values.push(value.into_owned());
+            // The time is set by touch() below
+            self.last_used.push(0);
+            Offered::Inserted(key)
+        } else {
+            // The unwrap is OK here, because the cache is full and the capacity is greater than zero
+            let (_, key) = self.recency.pop_first().unwrap();
+            let evicted = std::mem::replace(&mut self.values[key.as_index()], value.into_owned());
+            let evicted_hash = hash_of(&evicted);
+            if let Some(keys) = self.hashes.get_mut(&evicted_hash) {
+                keys.retain(|other| *other != key);
+                if keys.is_empty() {
+                    self.hashes.remove(&evicted_hash);
+                }
+            }
+            Offered::Replaced { key, evicted }
+        };
+
+        let key = offered.key();
+        self.hashes.entry(hash).or_default().push(key);
+        self.touch(key);
+        offered
+    }
+}
+
+impl<T, K> Index<K> for LruCacheMut<T, K>
+where
+    K: AsIndex,
+{
+    type Output = T;
+
+    fn index(&self, index: K) -> &Self::Output {
+        &self.values[index.as_index()]
+    }
+}
+
 #[cfg(test)]
 mod test_vec_cache_mut {
     use crate::VecCacheMut;
@@ -246,4 +578,128 @@ mod test_vec_cache_mut {
         assert_eq!(Val::new(0x8877665544332211, 240), cache[3usize]);
         assert_eq!(4, cache.len());
     }
+
+    #[test]
+    fn test_capacity() {
+        let mut cache = VecCacheMut::<u32>::with_capacity(10);
+        assert!(cache.is_empty());
+        assert!(cache.values.capacity() >= 10);
+        assert!(cache.hashes.capacity() >= 10);
+
+        for value in 0..10 {
+            cache.offer(Cow::Owned(value));
+        }
+        cache.reserve(20);
+        assert!(cache.values.capacity() >= 30);
+        assert!(cache.hashes.capacity() >= 30);
+
+        // Reserving does not affect the keys
+        assert_eq!(3usize, cache.offer(Cow::Owned(3)));
+        assert_eq!(10usize, cache.offer(Cow::Owned(42)));
+    }
+}
+
+#[cfg(test)]
+mod test_borrowed_indexed_cache {
+    use crate::{BorrowedIndexedCache, VecCacheMut};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_lookup() {
+        let values = ["a", "b", "a", "c", "a"];
+        let cache = BorrowedIndexedCache::<&str>::new(&values);
+
+        assert_eq!(5, cache.len());
+        assert_eq!("c", cache[3usize]);
+        assert_eq!(Some(&"b"), cache.get(1));
+        assert_eq!(None, cache.get(5));
+
+        assert_eq!(Some(0), cache.find(&"a"));
+        assert_eq!(Some(3), cache.find(&"c"));
+        assert_eq!(None, cache.find(&"d"));
+        assert_eq!(vec![0, 2, 4], cache.keys_of(&"a").collect::<Vec<_>>());
+        assert_eq!(3, cache.count(&"a"));
+        assert_eq!(0, cache.count(&"d"));
+    }
+
+    #[test]
+    fn test_same_keys_as_vec_cache_mut() {
+        let mut vec_cache = VecCacheMut::<u32>::new();
+        for value in [7, 3, 7, 9] {
+            vec_cache.offer(Cow::Owned(value));
+        }
+        let values = vec_cache.clone().into_vec();
+        let cache = BorrowedIndexedCache::<u32>::new(&values);
+        for value in [7, 3, 9] {
+            assert_eq!(Some(vec_cache.offer(Cow::Owned(value))), cache.find(&value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_lru_cache_mut {
+    use crate::{LruCacheMut, Offered};
+    use std::borrow::Cow;
+
+    #[test]
+    fn test_offer() {
+        let mut cache = LruCacheMut::<&str>::new(2);
+        assert_eq!(Offered::Inserted(0), cache.offer(Cow::Owned("a")));
+        assert_eq!(Offered::Inserted(1), cache.offer(Cow::Owned("b")));
+        assert_eq!(Offered::Hit(0), cache.offer(Cow::Owned("a")));
+
+        // "b" is the least recently used value, so its key is reused
+        assert_eq!(
+            Offered::Replaced {
+                key: 1,
+                evicted: "b"
+            },
+            cache.offer(Cow::Owned("c"))
+        );
+        assert_eq!(&["a", "c"], cache.values());
+        assert_eq!(2, cache.len());
+
+        // The evicted value is not found anymore
+        assert_eq!(
+            Offered::Replaced {
+                key: 0,
+                evicted: "a"
+            },
+            cache.offer(Cow::Owned("b"))
+        );
+        assert_eq!(Offered::Hit(1), cache.offer(Cow::Owned("c")));
+        assert_eq!(Offered::Hit(0), cache.offer(Cow::Owned("b")));
+        assert_eq!("b", cache[0usize]);
+        assert_eq!(
+            1,
+            cache
+                .hashes
+                .values()
+                .filter(|keys| keys.contains(&0))
+                .count()
+        );
+        assert_eq!(2, cache.hashes.values().map(Vec::len).sum::<usize>());
+    }
+
+    #[test]
+    fn test_touch() {
+        let mut cache = LruCacheMut::<u32>::new(2);
+        cache.offer(Cow::Owned(1));
+        cache.offer(Cow::Owned(2));
+        cache.touch(0);
+
+        assert_eq!(Some(&1), cache.get(0));
+        assert_eq!(None, cache.get(2));
+        assert_eq!(
+            Offered::Replaced { key: 1, evicted: 2 },
+            cache.offer(Cow::Owned(3))
+        );
+        assert_eq!(vec![1, 3], cache.into_vec());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_capacity() {
+        LruCacheMut::<u32>::new(0);
+    }
 }
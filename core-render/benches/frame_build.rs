@@ -0,0 +1,79 @@
+//! Benchmarks for building the frames of a movie during playback.
+//!
+//! All benchmarks use the 10-frame movie that is extracted from the bundled Mesen-S capture (see `art/snes/resources/test`). Every
+//! iteration processes all frames of the movie, such that frames with few and with many sprites are weighted equally.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use ves_art_core::movie::Movie;
+use ves_art_core::render::{render_frame, RenderOptions};
+use ves_art_core::sprite::Color;
+use ves_art_core::surface::{surface_iterate, Surface as _};
+use ves_core_render::movie::MovieScene;
+
+fn load_movie() -> Movie {
+    bincode::deserialize(include_bytes!(
+        "../../art/snes/resources/test/expected/movie_10_frames.bincode"
+    ))
+    .unwrap()
+}
+
+/// Converts the sprites of a frame into RGBA images, like the art GUI does before it creates the textures.
+fn extract_sprites(movie: &Movie, frame_idx: usize) -> Vec<Vec<u8>> {
+    let palettes = movie.palettes_at(frame_idx).unwrap();
+    movie.frames()[frame_idx]
+        .sprites()
+        .iter()
+        .map(|sprite| {
+            let palette = &palettes[sprite.palette().value()];
+            let surface = movie.tiles()[sprite.tile().value()].surface();
+            let data = surface.data();
+            let mut image = Vec::with_capacity(data.len() * 4);
+            surface_iterate(
+                surface.size(),
+                surface.size().as_rect(),
+                false,
+                false,
+                |_, idx| match palette[data[idx]] {
+                    Color::Opaque(rgb) => image.extend_from_slice(&[rgb.r, rgb.g, rgb.b, 0xff]),
+                    Color::Transparent => image.extend_from_slice(&[0; 4]),
+                },
+            )
+            .unwrap();
+            image
+        })
+        .collect()
+}
+
+fn bench_frame_build(c: &mut Criterion) {
+    let movie = load_movie();
+    let frame_count = movie.frames().len();
+    let options = RenderOptions::default();
+
+    c.bench_function("render_frame", |b| {
+        b.iter(|| {
+            for frame_idx in 0..frame_count {
+                black_box(render_frame(&movie, frame_idx, &options).unwrap());
+            }
+        })
+    });
+
+    c.bench_function("extract_sprites", |b| {
+        b.iter(|| {
+            for frame_idx in 0..frame_count {
+                black_box(extract_sprites(&movie, frame_idx));
+            }
+        })
+    });
+
+    c.bench_function("movie_scene", |b| {
+        b.iter(|| {
+            for frame_idx in 0..frame_count {
+                let scene = MovieScene::from_movie_frame(&movie, frame_idx).unwrap();
+                black_box(scene.render().unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_frame_build);
+criterion_main!(benches);
@@ -0,0 +1,411 @@
+//! The reference renderer for cores that implement the prototype architecture.
+//!
+//! This crate contains the compositing logic that turns the core state (OAM table, palette table, screen control register and the
+//! tiles from VROM) into pixels. It does not depend on any platform layer, such that the SDL core, other cores and tests all produce
+//! the exact same output.
+
+pub mod movie;
+
+use ves_art_core::sprite::Tile;
+use ves_art_core::surface::Surface as _;
+use ves_proto_common::gpu::{OamTableEntry, PaletteColor, ScreenControl};
+
+/// The width of the screen buffer in pixels.
+pub const SCREEN_BUFFER_WIDTH: u32 = 512;
+/// The height of the screen buffer in pixels.
+pub const SCREEN_BUFFER_HEIGHT: u32 = 256;
+/// The size of the screen buffer in bytes (RGBA, 4 bytes per pixel).
+pub const SCREEN_BUFFER_LEN: usize = (SCREEN_BUFFER_WIDTH * SCREEN_BUFFER_HEIGHT * 4) as usize;
+
+/// A palette in the palette table.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct Palette {
+    /// The colors. The first entry is transparent.
+    pub colors: [PaletteColor; 16],
+}
+
+/// The core state that is required for rendering a frame.
+#[derive(Copy, Clone, Debug)]
+pub struct Scene<'a> {
+    /// The tiles (from VROM).
+    pub tiles: &'a [Tile],
+    /// The OAM table. The first entry has the highest priority.
+    pub oam: &'a [OamTableEntry],
+    /// The palette table.
+    pub palettes: &'a [Palette],
+    /// The screen control register.
+    pub screen_control: ScreenControl,
+}
+
+/// Renders the objects in the OAM table onto a screen buffer.
+///
+/// # Arguments
+///
+/// * `scene`: The scene to render.
+/// * `screen_buffer`: The screen buffer. This buffer contains RGBA pixels (4 bytes per pixel) and is expected to be
+///   [`SCREEN_BUFFER_LEN`] bytes in size. Pixels that are not covered by any object are left untouched.
+/// * `sprites_per_scanline`: An optional limit on the number of sprites per scanline. Sprites that exceed the limit are dropped on
+///   that scanline, starting with the sprite with the highest OAM index.
+pub fn render(
+    scene: &Scene,
+    screen_buffer: &mut [u8],
+    sprites_per_scanline: Option<usize>,
+) -> Result<(), String> {
+    if screen_buffer.len() != SCREEN_BUFFER_LEN {
+        return Err(format!(
+            "Expected a screen buffer of {} bytes, but got {} bytes.",
+            SCREEN_BUFFER_LEN,
+            screen_buffer.len()
+        ));
+    }
+
+    let objects = shown_objects(scene)?;
+
+    let visible_rows = sprites_per_scanline.map(|limit| scanline_visibility(&objects, limit));
+
+    // Render in reverse order, such that the objects with the highest priority end up on top
+    for (i, (obj, tile)) in objects.iter().enumerate().rev() {
+        let palette = scene
+            .palettes
+            .get(usize::from(obj.palette_table_index()))
+            .ok_or_else(|| {
+                format!(
+                    "Invalid palette table index: {:?}.",
+                    obj.palette_table_index()
+                )
+            })?;
+        render_tile(
+            screen_buffer,
+            tile,
+            palette,
+            scene.screen_control,
+            obj.position(),
+            (obj.h_flip(), obj.v_flip()),
+            |row| match &visible_rows {
+                Some(visible_rows) => visible_rows[i][row],
+                None => true,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Statistics about the objects in a [`Scene`] (see [`scene_stats()`]).
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct SceneStats {
+    /// The number of objects that lie at least partially within the visible area.
+    pub active_objects: usize,
+    /// The number of pixels within the visible area that are covered by objects. Pixels that are covered by multiple objects are
+    /// counted multiple times and transparent pixels are counted as well.
+    pub covered_pixels: usize,
+}
+
+impl SceneStats {
+    /// Estimates the overdraw, which is the number of covered pixels relative to the visible area. A value of 1 means that the
+    /// objects could cover the visible area exactly once.
+    ///
+    /// # Arguments
+    ///
+    /// * `visible_size`: The size of the visible area in the form `(width, height)`.
+    pub fn overdraw(&self, visible_size: (u32, u32)) -> f32 {
+        let area = visible_size.0 * visible_size.1;
+        if area == 0 {
+            return 0.0;
+        }
+        self.covered_pixels as f32 / area as f32
+    }
+}
+
+/// Collects statistics about the objects in a scene.
+///
+/// # Arguments
+///
+/// * `scene`: The scene.
+/// * `visible_size`: The size of the visible area of the screen buffer in the form `(width, height)`. The visible area starts at the
+///   top-left corner of the screen buffer.
+pub fn scene_stats(scene: &Scene, visible_size: (u32, u32)) -> Result<SceneStats, String> {
+    let mut stats = SceneStats::default();
+    for (obj, tile) in shown_objects(scene)? {
+        let size = tile.surface().size();
+        let (x, y) = obj.position();
+        let width = visible_len(
+            u32::from(x),
+            size.width.raw(),
+            SCREEN_BUFFER_WIDTH,
+            visible_size.0,
+        );
+        let height = visible_len(
+            u32::from(y),
+            size.height.raw(),
+            SCREEN_BUFFER_HEIGHT,
+            visible_size.1,
+        );
+        if width > 0 && height > 0 {
+            stats.active_objects += 1;
+            // The cast is OK here, because the area lies within the screen buffer
+            stats.covered_pixels += (width * height) as usize;
+        }
+    }
+    Ok(stats)
+}
+
+/// Determines the length of the part of an object that lies within the visible area, along a single axis.
+///
+/// # Arguments
+///
+/// * `pos`: The position of the object.
+/// * `len`: The length of the object.
+/// * `buffer_len`: The length of the screen buffer. Objects wrap around at the edge of the screen buffer.
+/// * `visible_len`: The length of the visible area.
+fn visible_len(pos: u32, len: u32, buffer_len: u32, visible_len: u32) -> u32 {
+    (0..len)
+        .filter(|offset| (pos + offset) % buffer_len < visible_len)
+        .count() as u32
+}
+
+/// Looks up the tiles for the objects in the OAM table of a scene. Hidden objects are skipped.
+///
+/// returns: The objects with their tiles, in the order of the OAM table.
+fn shown_objects<'a>(scene: &Scene<'a>) -> Result<Vec<(&'a OamTableEntry, &'a Tile)>, String> {
+    scene
+        .oam
+        .iter()
+        .filter(|obj| !obj.hidden())
+        .map(|obj| {
+            let char_table_index = usize::try_from(obj.char_table_index())
+                .map_err(|_| "Could not convert char_table_index to usize.".to_string())?;
+            scene
+                .tiles
+                .get(char_table_index)
+                .map(|tile| (obj, tile))
+                .ok_or_else(|| format!("Invalid char_table_index: {char_table_index}."))
+        })
+        .collect()
+}
+
+/// Determines on which scanlines the objects are visible, given a limit on the number of sprites per scanline.
+///
+/// # Arguments
+///
+/// * `objects`: The objects with their tiles (see [`shown_objects()`]). The first object has the highest priority.
+/// * `limit`: The maximum number of sprites per scanline.
+///
+/// # Returns
+/// For every object, a flag per scanline of the screen buffer.
+fn scanline_visibility(
+    objects: &[(&OamTableEntry, &Tile)],
+    limit: usize,
+) -> Vec<[bool; SCREEN_BUFFER_HEIGHT as usize]> {
+    let mut counts = [0usize; SCREEN_BUFFER_HEIGHT as usize];
+    objects
+        .iter()
+        .map(|(obj, tile)| {
+            let mut visible = [false; SCREEN_BUFFER_HEIGHT as usize];
+            let top = u32::from(obj.position().1);
+            for offset in 0..tile.surface().size().height.raw() {
+                let row = ((top + offset) % SCREEN_BUFFER_HEIGHT) as usize;
+                if counts[row] < limit {
+                    counts[row] += 1;
+                    visible[row] = true;
+                }
+            }
+            visible
+        })
+        .collect()
+}
+
+/// Renders a single tile onto the screen buffer.
+///
+/// This takes the attributes rather than an [`OamTableEntry`], such that the tiles of background layers (see
+/// [`TilemapEntry`](ves_proto_common::gpu::TilemapEntry)) can be rendered in the same way.
+///
+/// # Arguments
+///
+/// * `screen_buffer`: The screen buffer.
+/// * `tile`: The tile.
+/// * `palette`: The palette.
+/// * `screen_control`: The screen control register.
+/// * `position`: The position of the top-left pixel.
+/// * `flip`: The flip flags in the form `(horizontal, vertical)`.
+/// * `row_visible`: Determines whether a scanline of the screen buffer should be rendered.
+fn render_tile(
+    screen_buffer: &mut [u8],
+    tile: &Tile,
+    palette: &Palette,
+    screen_control: ScreenControl,
+    position: (u16, u16),
+    flip: (bool, bool),
+    row_visible: impl Fn(usize) -> bool,
+) -> Result<(), String> {
+    let surf = tile.surface();
+    let src_size = surf.size();
+    let src_data = surf.data();
+
+    ves_art_core::surface::surface_iterate_2(
+        src_size,
+        src_size.as_rect(),
+        ves_art_core::geom_art::Size::new(SCREEN_BUFFER_WIDTH, SCREEN_BUFFER_HEIGHT),
+        ves_art_core::geom_art::Point::new(u32::from(position.0), u32::from(position.1)),
+        flip.0,
+        flip.1,
+        |_, src_idx, dest_pos, dest_idx| {
+            // Skip the scanlines on which the object has been dropped
+            if !row_visible(dest_pos.y.raw() as usize) {
+                return;
+            }
+
+            // Get the index in the palette
+            let pal_idx: usize = src_data[src_idx].value().into();
+            // The first entry in the palette is reserved for transparency (aka: write nothing)
+            if pal_idx == 0 {
+                return;
+            }
+            // Get the color value
+            let (r, g, b) = screen_control.apply(palette.colors[pal_idx].to_real());
+
+            // Write the color to the target buffer
+            let i = 4 * dest_idx; // because RGBA is 4 bytes per pixel
+            screen_buffer[i] = r;
+            screen_buffer[i + 1] = g;
+            screen_buffer[i + 2] = b;
+            screen_buffer[i + 3] = 255;
+        },
+    )
+    .map_err(|err| format!("Could not render object onto screen buffer: {err}"))
+}
+
+#[cfg(test)]
+mod test_render {
+    use super::*;
+    use ves_art_core::geom_art::Size;
+    use ves_art_core::sprite::{PaletteIndex, TileSurface};
+
+    fn scene_tiles() -> Vec<Tile> {
+        let mut surface = TileSurface::new(Size::new(8, 8));
+        surface.data_mut().fill(PaletteIndex::new(1));
+        vec![Tile::new(surface)]
+    }
+
+    fn scene_palettes() -> Vec<Palette> {
+        let mut palette = Palette::default();
+        palette.colors[1] = PaletteColor::new(31, 0, 0);
+        vec![palette]
+    }
+
+    fn pixel(buffer: &[u8], x: usize, y: usize) -> &[u8] {
+        let i = 4 * (y * SCREEN_BUFFER_WIDTH as usize + x);
+        &buffer[i..i + 4]
+    }
+
+    #[test]
+    fn test_render() {
+        let tiles = scene_tiles();
+        let palettes = scene_palettes();
+        let oam = [OamTableEntry::new(10, 20, 0, 0, 0, 0)];
+        let scene = Scene {
+            tiles: &tiles,
+            oam: &oam,
+            palettes: &palettes,
+            screen_control: ScreenControl::default(),
+        };
+
+        let mut buffer = vec![0; SCREEN_BUFFER_LEN];
+        render(&scene, &mut buffer, None).unwrap();
+
+        assert_eq!(&[0, 0, 0, 0], pixel(&buffer, 9, 20));
+        assert_eq!(&[255, 0, 0, 255], pixel(&buffer, 10, 20));
+        assert_eq!(&[255, 0, 0, 255], pixel(&buffer, 17, 27));
+        assert_eq!(&[0, 0, 0, 0], pixel(&buffer, 18, 27));
+    }
+
+    #[test]
+    fn test_render_sprites_per_scanline() {
+        let tiles = scene_tiles();
+        let palettes = scene_palettes();
+        let oam = [
+            OamTableEntry::new(0, 0, 0, 0, 0, 0),
+            OamTableEntry::new(8, 4, 0, 0, 0, 0),
+        ];
+        let scene = Scene {
+            tiles: &tiles,
+            oam: &oam,
+            palettes: &palettes,
+            screen_control: ScreenControl::default(),
+        };
+
+        let mut buffer = vec![0; SCREEN_BUFFER_LEN];
+        render(&scene, &mut buffer, Some(1)).unwrap();
+
+        // The second object is dropped on the scanlines that it shares with the first object
+        assert_eq!(&[0, 0, 0, 0], pixel(&buffer, 8, 7));
+        assert_eq!(&[255, 0, 0, 255], pixel(&buffer, 8, 8));
+    }
+
+    #[test]
+    fn test_render_hidden() {
+        let tiles = scene_tiles();
+        let palettes = scene_palettes();
+        let mut hidden = OamTableEntry::new(0, 0, 0, 0, 0, 0);
+        hidden.set_hidden(true);
+        // The tiles of hidden objects are not looked up
+        let mut invalid = OamTableEntry::new(0, 0, 0, 0, 0, 100);
+        invalid.set_hidden(true);
+        let oam = [hidden, invalid, OamTableEntry::new(8, 4, 0, 0, 0, 0)];
+        let scene = Scene {
+            tiles: &tiles,
+            oam: &oam,
+            palettes: &palettes,
+            screen_control: ScreenControl::default(),
+        };
+
+        let mut buffer = vec![0; SCREEN_BUFFER_LEN];
+        render(&scene, &mut buffer, Some(1)).unwrap();
+
+        // The hidden objects are not drawn and do not count towards the limit
+        assert_eq!(&[0, 0, 0, 0], pixel(&buffer, 0, 0));
+        assert_eq!(&[255, 0, 0, 255], pixel(&buffer, 8, 4));
+        assert_eq!(1, scene_stats(&scene, (256, 224)).unwrap().active_objects);
+    }
+
+    #[test]
+    fn test_scene_stats() {
+        let tiles = scene_tiles();
+        let oam = [
+            OamTableEntry::new(0, 0, 0, 0, 0, 0),
+            OamTableEntry::new(4, 4, 0, 0, 0, 0),
+            // Partially visible
+            OamTableEntry::new(252, 220, 0, 0, 0, 0),
+            // Wraps around to the top-left corner
+            OamTableEntry::new(508, 252, 0, 0, 0, 0),
+            // Outside of the visible area
+            OamTableEntry::new(300, 0, 0, 0, 0, 0),
+        ];
+        let scene = Scene {
+            tiles: &tiles,
+            oam: &oam,
+            palettes: &[],
+            screen_control: ScreenControl::default(),
+        };
+
+        let stats = scene_stats(&scene, (256, 224)).unwrap();
+        assert_eq!(4, stats.active_objects);
+        assert_eq!(64 + 64 + 16 + 16, stats.covered_pixels);
+        assert_eq!(160.0 / (256.0 * 224.0), stats.overdraw((256, 224)));
+        assert_eq!(0.0, stats.overdraw((0, 224)));
+
+        let oam = [OamTableEntry::new(0, 0, 0, 0, 0, 1)];
+        let scene = Scene { oam: &oam, ..scene };
+        assert!(scene_stats(&scene, (256, 224)).is_err());
+    }
+
+    #[test]
+    fn test_render_invalid_buffer() {
+        let scene = Scene {
+            tiles: &[],
+            oam: &[],
+            palettes: &[],
+            screen_control: ScreenControl::default(),
+        };
+        assert!(render(&scene, &mut [0; 16], None).is_err());
+    }
+}
@@ -0,0 +1,414 @@
+//! Conversion of [`Movie`] frames into core state.
+
+use crate::{Palette, Scene, SCREEN_BUFFER_LEN};
+use std::collections::HashMap;
+use ves_art_core::geom_art::{Point, Size};
+use ves_art_core::movie::{
+    CapacityWarning, FrameCapacity, FrameRate, Movie, MovieFrame, OverflowPolicy,
+};
+use ves_art_core::sprite::{Color, PaletteRef, Sprite, Tile, TileRef};
+use ves_proto_common::gpu::{OamTableEntry, PaletteColor, ScreenControl};
+
+/// The number of entries in the OAM table.
+const OAM_TABLE_SIZE: usize = 128;
+
+/// The core state for a single movie frame.
+///
+/// Only the tiles and palettes that are used in the frame are retained. Sprites are assigned to OAM entries in the same order as in
+/// the movie frame, such that the priorities are preserved.
+#[derive(Clone, Debug)]
+pub struct MovieScene {
+    tiles: Vec<Tile>,
+    oam: Vec<OamTableEntry>,
+    palettes: Vec<Palette>,
+}
+
+impl MovieScene {
+    /// Converts a movie frame into core state.
+    ///
+    /// # Arguments
+    ///
+    /// * `movie`: The movie.
+    /// * `frame_index`: The index of the frame in the movie.
+    pub fn from_movie_frame(movie: &Movie, frame_index: usize) -> Result<Self, String> {
+        let frame = movie.frames().get(frame_index).ok_or_else(|| {
+            format!(
+                "Frame index {} is out of range (the movie contains {} frames).",
+                frame_index,
+                movie.frames().len()
+            )
+        })?;
+
+        if frame.sprites().len() > OAM_TABLE_SIZE {
+            return Err(format!(
+                "The frame contains {} sprites, but the OAM table only has {} entries.",
+                frame.sprites().len(),
+                OAM_TABLE_SIZE
+            ));
+        }
+
+        let movie_palettes = movie.palettes_at(frame_index)?;
+        let mut tile_map: HashMap<TileRef, u32> = HashMap::new();
+        let mut palette_map: HashMap<PaletteRef, u8> = HashMap::new();
+        let mut tiles = Vec::new();
+        let mut palettes = Vec::new();
+        let mut oam = Vec::new();
+
+        for sprite in frame.sprites() {
+            let char_table_index = match tile_map.get(&sprite.tile()) {
+                Some(index) => *index,
+                None => {
+                    let index = u32::try_from(tiles.len())
+                        .map_err(|_| "Too many tiles in the frame.".to_string())?;
+                    tiles.push(movie.tiles()[sprite.tile().value()].clone());
+                    tile_map.insert(sprite.tile(), index);
+                    index
+                }
+            };
+
+            let palette_table_index = match palette_map.get(&sprite.palette()) {
+                Some(index) => *index,
+                None => {
+                    let index = u8::try_from(palettes.len()).map_err(|_| {
+                        "The frame uses more palettes than the palette table can hold.".to_string()
+                    })?;
+                    palettes.push(convert_palette(&movie_palettes[sprite.palette().value()])?);
+                    palette_map.insert(sprite.palette(), index);
+                    index
+                }
+            };
+
+            let position = sprite.position();
+            let to_u16 = |value: u32| {
+                u16::try_from(value).map_err(|_| format!("Invalid sprite position: {position:?}."))
+            };
+            oam.push(OamTableEntry::new(
+                to_u16(position.x.raw())?,
+                to_u16(position.y.raw())?,
+                palette_table_index,
+                u8::from(sprite.h_flip()),
+                u8::from(sprite.v_flip()),
+                char_table_index,
+            ));
+        }
+
+        Ok(Self {
+            tiles,
+            oam,
+            palettes,
+        })
+    }
+
+    /// Retrieves the tiles.
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// Retrieves the OAM table.
+    pub fn oam(&self) -> &[OamTableEntry] {
+        &self.oam
+    }
+
+    /// Retrieves the palette table.
+    pub fn palettes(&self) -> &[Palette] {
+        &self.palettes
+    }
+
+    /// Creates a [`Scene`] with the default screen control register.
+    pub fn scene(&self) -> Scene<'_> {
+        Scene {
+            tiles: &self.tiles,
+            oam: &self.oam,
+            palettes: &self.palettes,
+            screen_control: ScreenControl::default(),
+        }
+    }
+
+    /// Renders the scene onto a new screen buffer (see [`crate::render()`]).
+    pub fn render(&self) -> Result<Vec<u8>, String> {
+        let mut screen_buffer = vec![0; SCREEN_BUFFER_LEN];
+        crate::render(&self.scene(), &mut screen_buffer, None)?;
+        Ok(screen_buffer)
+    }
+}
+
+/// Builds a [`Movie`] from the core state of consecutive frames. This is the inverse of [`MovieScene`].
+///
+/// Every object in the OAM table becomes a sprite, in the same order, such that the movie renders the same way as the scene. The
+/// screen control register is applied to the palettes, since movies have no equivalent of it.
+///
+/// By default, frames may contain as many sprites as the OAM table has entries. A different [`FrameCapacity`] can be configured
+/// with [`MovieBuilder::with_capacity()`].
+#[derive(Clone, Debug)]
+pub struct MovieBuilder {
+    tiles: Vec<Tile>,
+    tile_map: HashMap<u32, TileRef>,
+    palettes: Vec<ves_art_core::sprite::Palette>,
+    palette_map: HashMap<ves_art_core::sprite::Palette, PaletteRef>,
+    frames: Vec<MovieFrame>,
+    capacity: FrameCapacity,
+    next_frame_number: u64,
+    warnings: Vec<CapacityWarning>,
+}
+
+impl Default for MovieBuilder {
+    fn default() -> Self {
+        // The unwrap is OK here, because the OAM table size is not zero
+        let capacity = FrameCapacity::new(OAM_TABLE_SIZE, OverflowPolicy::Error).unwrap();
+        Self::with_capacity(capacity)
+    }
+}
+
+impl MovieBuilder {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity`: The [`FrameCapacity`] that is enforced on every frame.
+    pub fn with_capacity(capacity: FrameCapacity) -> Self {
+        Self {
+            tiles: Vec::new(),
+            tile_map: HashMap::new(),
+            palettes: Vec::new(),
+            palette_map: HashMap::new(),
+            frames: Vec::new(),
+            capacity,
+            next_frame_number: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Adds a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `scene`: The core state at the end of the frame.
+    pub fn add_frame(&mut self, scene: &Scene) -> Result<(), String> {
+        let mut sprites = Vec::with_capacity(scene.oam.len());
+        for obj in scene.oam {
+            let char_table_index = obj.char_table_index();
+            let tile = match self.tile_map.get(&char_table_index) {
+                Some(tile) => *tile,
+                None => {
+                    let tile = usize::try_from(char_table_index)
+                        .ok()
+                        .and_then(|index| scene.tiles.get(index))
+                        .ok_or_else(|| format!("Invalid char_table_index: {char_table_index}."))?;
+                    let tile_ref = TileRef::new(self.tiles.len());
+                    self.tiles.push(tile.clone());
+                    self.tile_map.insert(char_table_index, tile_ref);
+                    tile_ref
+                }
+            };
+
+            let palette = scene
+                .palettes
+                .get(usize::from(obj.palette_table_index()))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid palette table index: {:?}.",
+                        obj.palette_table_index()
+                    )
+                })?;
+            let palette = convert_core_palette(palette, scene.screen_control);
+            let palette = match self.palette_map.get(&palette) {
+                Some(palette) => *palette,
+                None => {
+                    let palette_ref = PaletteRef::new(self.palettes.len());
+                    self.palettes.push(palette.clone());
+                    self.palette_map.insert(palette, palette_ref);
+                    palette_ref
+                }
+            };
+
+            let (x, y) = obj.position();
+            sprites.push(Sprite::new(
+                tile,
+                palette,
+                Point::new(u32::from(x), u32::from(y)),
+                obj.h_flip(),
+                obj.v_flip(),
+            ));
+        }
+
+        let frame = MovieFrame::new(self.next_frame_number, sprites);
+        let (frames, warning) = frame.apply_capacity(&self.capacity)?;
+        // The parts of a split frame have consecutive frame numbers
+        self.next_frame_number += frames.len() as u64;
+        self.frames.extend(frames);
+        self.warnings.extend(warning);
+        Ok(())
+    }
+
+    /// Retrieves the number of frames in the movie. This can exceed the number of added frames if frames have been split (see
+    /// [`OverflowPolicy::Split`]).
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Retrieves the number of sprites that have been dropped (see [`OverflowPolicy::Truncate`]).
+    pub fn dropped_sprites(&self) -> usize {
+        self.warnings.iter().map(|warning| warning.dropped).sum()
+    }
+
+    /// Retrieves a warning for every frame from which sprites have been dropped (see [`OverflowPolicy::Truncate`]).
+    pub fn warnings(&self) -> &[CapacityWarning] {
+        &self.warnings
+    }
+
+    /// Creates the movie.
+    ///
+    /// # Arguments
+    ///
+    /// * `screen_size`: The size of the visible screen area.
+    /// * `frame_rate`: The frame rate.
+    pub fn build(self, screen_size: Size, frame_rate: FrameRate) -> Movie {
+        Movie::new(
+            screen_size,
+            self.palettes,
+            self.tiles,
+            self.frames,
+            frame_rate,
+        )
+    }
+}
+
+/// Converts a core [`Palette`] into a [`ves_art_core::sprite::Palette`].
+///
+/// # Arguments
+///
+/// * `palette`: The source palette. The first color is transparent.
+/// * `screen_control`: The screen control register, which is applied to all colors.
+fn convert_core_palette(
+    palette: &Palette,
+    screen_control: ScreenControl,
+) -> ves_art_core::sprite::Palette {
+    let colors = palette
+        .colors
+        .iter()
+        .enumerate()
+        .map(|(index, color)| {
+            // The first entry in the palette is reserved for transparency
+            if index == 0 {
+                return Color::new_transparent();
+            }
+            let (r, g, b) = screen_control.apply(color.to_real());
+            Color::new(r, g, b)
+        })
+        .collect();
+    ves_art_core::sprite::Palette::new(colors)
+}
+
+/// Converts a [`ves_art_core::sprite::Palette`] into a core [`Palette`].
+///
+/// # Arguments
+///
+/// * `palette`: The source palette. This palette can contain at most 16 colors.
+pub fn convert_palette(palette: &ves_art_core::sprite::Palette) -> Result<Palette, String> {
+    let mut out = Palette::default();
+    if palette.len() > out.colors.len() {
+        return Err(format!(
+            "The palette contains {} colors, but at most {} are supported.",
+            palette.len(),
+            out.colors.len()
+        ));
+    }
+
+    for ((_, color), target) in palette.iter().zip(out.colors.iter_mut()) {
+        *target = PaletteColor::from(*color);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test_movie_builder {
+    use super::*;
+    use ves_art_core::sprite::{PaletteIndex, TileSurface};
+    use ves_art_core::surface::Surface as _;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut surface = TileSurface::new(Size::new(8, 8));
+        surface.data_mut().fill(PaletteIndex::new(1));
+        let tiles = vec![
+            Tile::new(TileSurface::new(Size::new(8, 8))),
+            Tile::new(surface),
+        ];
+
+        let mut palette = Palette::default();
+        palette.colors[1] = PaletteColor::new(31, 0, 0);
+        let palettes = vec![Palette::default(), palette];
+
+        let mut obj = OamTableEntry::new(10, 20, 1, 1, 0, 1);
+        let oam = vec![obj, obj];
+        obj.set_position(30, 40);
+
+        let mut builder = MovieBuilder::default();
+        builder
+            .add_frame(&Scene {
+                tiles: &tiles,
+                oam: &oam,
+                palettes: &palettes,
+                screen_control: ScreenControl::default(),
+            })
+            .unwrap();
+        builder
+            .add_frame(&Scene {
+                tiles: &tiles,
+                oam: &[obj],
+                palettes: &palettes,
+                screen_control: ScreenControl::default(),
+            })
+            .unwrap();
+        assert_eq!(2, builder.frame_count());
+
+        let movie = builder.build(Size::new(256, 224), FrameRate::Ntsc);
+        movie.validate_refs().unwrap();
+        // Only the used tiles and palettes are retained
+        assert_eq!(1, movie.tiles().len());
+        assert_eq!(1, movie.palettes().len());
+        assert_eq!(2, movie.frames()[0].sprites().len());
+        assert_eq!(1, movie.frames()[1].frame_number());
+
+        let scene = MovieScene::from_movie_frame(&movie, 1).unwrap();
+        assert_eq!(&[OamTableEntry::new(30, 40, 0, 1, 0, 0)], scene.oam());
+        assert_eq!(&tiles[1..], scene.tiles());
+        assert_eq!(&palettes[1..], scene.palettes());
+    }
+
+    #[test]
+    fn test_capacity() {
+        let tiles = vec![Tile::new(TileSurface::new(Size::new(8, 8)))];
+        let palettes = vec![Palette::default()];
+        let oam = vec![OamTableEntry::new(0, 0, 0, 0, 0, 0); 3];
+        let scene = Scene {
+            tiles: &tiles,
+            oam: &oam,
+            palettes: &palettes,
+            screen_control: ScreenControl::default(),
+        };
+
+        let capacity = |overflow| FrameCapacity::new(2, overflow).unwrap();
+        assert!(MovieBuilder::with_capacity(capacity(OverflowPolicy::Error))
+            .add_frame(&scene)
+            .is_err());
+
+        let mut builder = MovieBuilder::with_capacity(capacity(OverflowPolicy::Truncate));
+        builder.add_frame(&scene).unwrap();
+        assert_eq!(1, builder.frame_count());
+        assert_eq!(1, builder.dropped_sprites());
+        assert_eq!(0, builder.warnings()[0].frame_number);
+
+        let mut builder = MovieBuilder::with_capacity(capacity(OverflowPolicy::Split));
+        builder.add_frame(&scene).unwrap();
+        builder.add_frame(&scene).unwrap();
+        assert_eq!(4, builder.frame_count());
+        let movie = builder.build(Size::new(256, 224), FrameRate::Ntsc);
+        let frame_numbers: Vec<_> = movie
+            .frames()
+            .iter()
+            .map(MovieFrame::frame_number)
+            .collect();
+        assert_eq!(vec![0, 1, 2, 3], frame_numbers);
+    }
+}
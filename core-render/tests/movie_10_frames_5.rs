@@ -0,0 +1,200 @@
+//! Generated by `ves-art-snes-cli movie fixture`. Do not edit.
+
+use ves_core_render::{Palette, Scene, SCREEN_BUFFER_LEN};
+use ves_proto_common::gpu::{OamTableEntry, ScreenControl};
+
+#[test]
+fn test_movie_10_frames_5() {
+    let tiles: Vec<ves_art_core::sprite::Tile> =
+        bincode::deserialize(include_bytes!("fixtures/movie_10_frames_5.tiles.bincode")).unwrap();
+
+    let oam: Vec<OamTableEntry> = [
+        0x0000000000005028u64,
+        0x0000000100004020u64,
+        0x0000000104004030u64,
+        0x0000000108006020u64,
+        0x000000010C006030u64,
+        0x000000020000C2EEu64,
+        0x000000030000BCF6u64,
+        0x000000020800C6EEu64,
+        0x000000030800CCF6u64,
+        0x000000040005E050u64,
+        0x000000040005E060u64,
+        0x000000050005E070u64,
+        0x000000040005E080u64,
+        0x000000060005E090u64,
+        0x000000070005E0A0u64,
+        0x000000080005E0B0u64,
+        0x0000000900092891u64,
+        0x00000009000930A5u64,
+        0x0000000A080CCCA2u64,
+        0x0000000B080CCCAAu64,
+        0x0000000C080CBCA2u64,
+        0x0000000D000CF6A7u64,
+        0x0000000E000CF6AFu64,
+        0x0000000F000CE6A8u64,
+        0x000000100811507Fu64,
+        0x0000001100093C7Eu64,
+        0x000000120C01387Du64,
+        0x0000001200012C79u64,
+        0x0000001004115882u64,
+        0x000000130010648Au64,
+        0x0000001400095670u64,
+        0x0000001404095678u64,
+        0x0000001504114270u64,
+        0x00000016000056FEu64,
+        0x00000017000076FEu64,
+        0x000000170001E0FEu64,
+        0x000000170001E0FEu64,
+        0x000000160001E0F8u64,
+        0x000000170001E0F8u64,
+        0x000000170001E0F6u64,
+        0x000000170001E0F5u64,
+        0x000000170001E0C5u64,
+        0x000000180001E0D5u64,
+        0x000000170001E0BDu64,
+        0x000000180001E0CDu64,
+        0x000000180001E0CBu64,
+        0x000000190001E062u64,
+        0x000000170001E052u64,
+        0x000000180001E062u64,
+        0x000000180001E062u64,
+        0x000000190001E062u64,
+        0x000000170001E052u64,
+        0x000000180001E062u64,
+        0x0000001A0415E080u64,
+        0x0000001B0415E080u64,
+        0x0000001B0415E080u64,
+        0x0000001C0415E080u64,
+        0x0000001A0C15E080u64,
+        0x0000001D0C15E080u64,
+        0x0000001E0C15E080u64,
+        0x0000000C0415E080u64,
+        0x000000120015E080u64,
+        0x0000001F0011E080u64,
+        0x000000200011E080u64,
+        0x000000210011E080u64,
+        0x000000220015E080u64,
+        0x000000230019E080u64,
+        0x000000240011E080u64,
+        0x00000022000DE080u64,
+        0x000000250011E080u64,
+        0x000000260011E080u64,
+        0x00000022000DE080u64,
+        0x000000250019E080u64,
+        0x000000270011E080u64,
+        0x00000022000DE080u64,
+        0x000000280011E080u64,
+        0x000000260011E080u64,
+        0x00000022000DE080u64,
+        0x000000280019E080u64,
+        0x000000270011E080u64,
+        0x00000022000DE080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x000000290009E080u64,
+        0x0000002A0005E080u64,
+        0x0000002B0005E080u64,
+        0x0000002C0005E080u64,
+        0x0000002C0005E080u64,
+    ]
+    .into_iter()
+    .map(OamTableEntry::from)
+    .collect();
+
+    let palettes: Vec<Palette> = [
+        [
+            0x0000, 0x0000, 0x7FFF, 0x000B, 0x0014, 0x001F, 0x01FF, 0x031F, 0x03FF, 0x44EE, 0x6270,
+            0x7FF2, 0x2A26, 0x3B13, 0x57D9, 0x6BFF,
+        ],
+        [
+            0x0000, 0x0000, 0x7FFF, 0x013F, 0x02BF, 0x03FF, 0x76EE, 0x7756, 0x7FDC, 0x38AA, 0x614F,
+            0x7E55, 0x31A8, 0x466F, 0x6336, 0x77DB,
+        ],
+        [
+            0x0000, 0x0000, 0x7FFF, 0x0180, 0x02A0, 0x03E0, 0x36B5, 0x4B7C, 0x63BE, 0x40C0, 0x65E9,
+            0x7F72, 0x1D5B, 0x3A3F, 0x475F, 0x5BFF,
+        ],
+        [
+            0x0000, 0x0000, 0x0180, 0x0012, 0x10D2, 0x02A0, 0x001F, 0x467F, 0x111F, 0x03E0, 0x023F,
+            0x025F, 0x271F, 0x571F, 0x6B9F, 0x7FFF,
+        ],
+        [
+            0x0000, 0x0000, 0x7FFF, 0x140D, 0x3416, 0x5C1F, 0x5951, 0x7A7B, 0x7AFF, 0x1D58, 0x467F,
+            0x633F, 0x0CEC, 0x25F3, 0x3A9A, 0x573F,
+        ],
+        [
+            0x0000, 0x0000, 0x7FFF, 0x39C4, 0x56A8, 0x6F52, 0x7FFA, 0x7C18, 0x0000, 0x1042, 0x1884,
+            0x2928, 0x7FB2, 0x7FF9, 0x7FFC, 0x7FFF,
+        ],
+        [
+            0x0000, 0x0000, 0x0842, 0x1084, 0x18C6, 0x2108, 0x2D6B, 0x35AD, 0x3DEF, 0x4631, 0x4E73,
+            0x5AD6, 0x6318, 0x6B5A, 0x739C, 0x7FFF,
+        ],
+    ]
+    .into_iter()
+    .map(|colors: [u16; 16]| Palette {
+        colors: colors.map(Into::into),
+    })
+    .collect();
+
+    let scene = Scene {
+        tiles: &tiles,
+        oam: &oam,
+        palettes: &palettes,
+        screen_control: ScreenControl::default(),
+    };
+    let mut actual = vec![0; SCREEN_BUFFER_LEN];
+    ves_core_render::render(&scene, &mut actual, None).unwrap();
+
+    let png_data = include_bytes!("fixtures/movie_10_frames_5.png");
+    let decoder = png::Decoder::new(std::io::Cursor::new(&png_data[..]));
+    let mut reader = decoder.read_info().unwrap();
+    let mut expected = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut expected).unwrap();
+
+    assert!(
+        expected == actual,
+        "Rendered output differs from movie_10_frames_5.png."
+    );
+}
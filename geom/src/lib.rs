@@ -15,8 +15,10 @@
 //! accidental bugs. Additionally, from a code-view perspective the more advanced types are more explicit, making code easier to
 //! understand and reason about.
 
+use std::cmp::Ordering;
 use std::fmt::{Debug, Formatter};
-use std::ops::{Add, RangeInclusive, Sub};
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, RangeInclusive, Rem, Sub};
 
 /// Returns the value zero (0) for a type.
 pub trait Zero {
@@ -26,9 +28,12 @@ pub trait Zero {
 
 macro_rules! impl_zero {
     ($ty:ty) => {
+        impl_zero!($ty, 0);
+    };
+    ($ty:ty, $value:literal) => {
         impl Zero for $ty {
             fn zero() -> Self {
-                0
+                $value
             }
         }
     };
@@ -44,6 +49,8 @@ impl_zero!(i16);
 impl_zero!(i32);
 impl_zero!(i64);
 impl_zero!(isize);
+impl_zero!(f32, 0.0);
+impl_zero!(f64, 0.0);
 
 /// Returns the value one (1) for a type.
 pub trait One {
@@ -53,9 +60,12 @@ pub trait One {
 
 macro_rules! impl_one {
     ($ty:ty) => {
+        impl_one!($ty, 1);
+    };
+    ($ty:ty, $value:literal) => {
         impl One for $ty {
             fn one() -> Self {
-                1
+                $value
             }
         }
     };
@@ -71,11 +81,64 @@ impl_one!(i16);
 impl_one!(i32);
 impl_one!(i64);
 impl_one!(isize);
+impl_one!(f32, 1.0);
+impl_one!(f64, 1.0);
+
+/// Converts a value into an `f32`, for instance for rendering. Integers that do not fit in the mantissa of an `f32` lose
+/// precision.
+pub trait IntoF32 {
+    /// Converts the value.
+    fn into_f32(self) -> f32;
+}
+
+macro_rules! impl_into_f32 {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoF32 for $ty {
+                #[inline(always)]
+                fn into_f32(self) -> f32 {
+                    // The cast is OK here, because the loss of precision is documented
+                    self as f32
+                }
+            }
+        )*
+    };
+}
+
+impl_into_f32!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// Converts a value into a `usize`, for instance for counting or indexing.
+///
+/// # Panics
+/// The conversion panics if the value does not fit in a `usize` (like a negative value).
+pub trait IntoUsize {
+    /// Converts the value.
+    fn into_usize(self) -> usize;
+}
+
+macro_rules! impl_into_usize {
+    ($($ty:ty),*) => {
+        $(
+            impl IntoUsize for $ty {
+                #[inline(always)]
+                fn into_usize(self) -> usize {
+                    usize::try_from(self).expect("Value does not fit in a usize.")
+                }
+            }
+        )*
+    };
+}
+
+impl_into_usize!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
 
 /// A finite range.
 ///
 /// This serves as an alterative to the [`core::ops::Range`] family of types that can not be used for iteration when the containing type
 /// does not implement [`core::iter::Step`] (which is a nightly-only experimental trait).
+///
+/// The range steps by one. For types with fractional values (like `f32`) the span does not have to be a whole number: iterating
+/// forwards yields `start`, `start + 1`, ... up to `end` and iterating backwards yields `end`, `end - 1`, ... down to `start`. See
+/// [`FiniteRange::aligned()`] for making both directions yield the same values.
 pub struct FiniteRange<T> {
     /// The start value (inclusive).
     start: T,
@@ -96,15 +159,85 @@ where
     /// * `end`: The end value (inclusive).
     ///
     /// # Panics
-    /// This function panics if `start` is greater than `end`.
+    /// This function panics if `start` is greater than `end`. See [`FiniteRange::try_new()`] for a non-panicking alternative.
     pub fn new(start: T, end: T) -> Self {
+        match Self::try_new(start, end) {
+            Ok(range) => range,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `start`: The start value (inclusive).
+    /// * `end`: The end value (inclusive).
+    ///
+    /// # Returns
+    /// The range or an error if `start` is greater than `end`.
+    pub fn try_new(start: T, end: T) -> Result<Self, String> {
         if start > end {
-            panic!("Invalid range.");
+            return Err("Invalid range.".to_string());
         }
-        Self {
+        Ok(Self {
             start,
             end,
             exhausted: false,
+        })
+    }
+}
+
+impl<T> FiniteRange<T>
+where
+    T: Zero,
+{
+    /// Creates an empty range, which yields no values. Unlike [`FiniteRange::new()`], this covers the case where there is nothing to
+    /// iterate over, since both bounds of a regular range are inclusive.
+    pub fn empty() -> Self {
+        Self {
+            start: T::zero(),
+            end: T::zero(),
+            exhausted: true,
+        }
+    }
+}
+
+impl<T> FiniteRange<T>
+where
+    T: Copy + PartialOrd,
+{
+    /// Checks whether a value lies within the range. Only the values that have not been yielded yet are considered.
+    ///
+    /// # Parameters
+    /// * `value`: The value.
+    pub fn contains(&self, value: T) -> bool {
+        !self.exhausted && self.start <= value && value <= self.end
+    }
+
+    /// Determines whether all values have been yielded.
+    pub fn is_empty(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Retrieves the number of values that have not been yielded yet.
+    ///
+    /// The difference between the bounds is converted before the inclusive end is accounted for, such that a range over all values of
+    /// a type that is narrower than `usize` (like `u8`) does not overflow.
+    ///
+    /// # Panics
+    /// This function panics if the number of values does not fit in a `usize`, which is the case for a range over all values of a type
+    /// that is at least as wide as `usize` (like `usize` itself).
+    pub fn len(&self) -> usize
+    where
+        T: Sub<Output = T> + IntoUsize,
+    {
+        if self.exhausted {
+            0
+        } else {
+            (self.end - self.start)
+                .into_usize()
+                .checked_add(1)
+                .expect("The length of the range does not fit in a usize.")
         }
     }
 }
@@ -118,6 +251,16 @@ where
     }
 }
 
+impl<T> From<RangeInclusive<T>> for FiniteRange<T>
+where
+    T: PartialOrd,
+{
+    fn from(value: RangeInclusive<T>) -> Self {
+        let (start, end) = value.into_inner();
+        FiniteRange::new(start, end)
+    }
+}
+
 impl<T> Iterator for FiniteRange<T>
 where
     T: Copy + PartialOrd + PartialEq + One + Add<Output = T>,
@@ -125,18 +268,19 @@ where
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.start == self.end {
-            if self.exhausted {
-                None
-            } else {
-                self.exhausted = true;
-                Some(self.start)
-            }
-        } else {
-            let out = self.start;
+        if self.exhausted {
+            return None;
+        }
+
+        let out = self.start;
+        // Check for the end before stepping, such that a range that ends at the maximum value of T does not overflow. The second
+        // check is for types with fractional values, for which the step can go past the end.
+        if self.start < self.end && self.start + T::one() <= self.end {
             self.start = self.start + T::one();
-            Some(out)
+        } else {
+            self.exhausted = true;
         }
+        Some(out)
     }
 }
 
@@ -145,18 +289,90 @@ where
     T: Copy + PartialOrd + PartialEq + One + Add<Output = T> + Sub<Output = T>,
 {
     fn next_back(&mut self) -> Option<Self::Item> {
-        if self.end == self.start {
-            if self.exhausted {
-                None
-            } else {
-                self.exhausted = true;
-                Some(self.end)
-            }
-        } else {
-            let out = self.end;
+        if self.exhausted {
+            return None;
+        }
+
+        let out = self.end;
+        if self.start < self.end && self.start <= self.end - T::one() {
             self.end = self.end - T::one();
-            Some(out)
+        } else {
+            self.exhausted = true;
+        }
+        Some(out)
+    }
+}
+
+impl<T> FiniteRange<T>
+where
+    T: Copy + One + Sub<Output = T> + Rem<Output = T>,
+{
+    /// Moves the end back to the last value that is a whole number of steps away from the start, such that iterating backwards yields
+    /// the same values as iterating forwards. This is only needed for types with fractional values (like `f32`).
+    ///
+    /// ```text
+    /// FiniteRange::new(0.5, 3.0).rev() yields 3.0, 2.0, 1.0
+    /// FiniteRange::new(0.5, 3.0).aligned().rev() yields 2.5, 1.5, 0.5
+    /// ```
+    pub fn aligned(mut self) -> Self {
+        if !self.exhausted {
+            self.end = self.end - (self.end - self.start) % T::one();
+        }
+        self
+    }
+}
+
+impl<T> FiniteRange<T>
+where
+    T: Copy + PartialOrd + Zero,
+{
+    /// Creates an iterator that steps through the range by a fixed amount, starting at the start value. The end value is only yielded
+    /// if it is a whole number of steps away from the start value. Unlike [`Iterator::step_by()`], the step is an amount of `T` rather
+    /// than a number of values.
+    ///
+    /// ```text
+    /// FiniteRange::new(0, 20).stepped(8) yields 0, 8, 16
+    /// ```
+    ///
+    /// # Parameters
+    /// * `step`: The amount by which to step.
+    ///
+    /// # Panics
+    /// This function panics if `step` is not greater than zero.
+    pub fn stepped(self, step: T) -> Stepped<T> {
+        if step <= T::zero() {
+            panic!("Invalid step.");
+        }
+        Stepped { range: self, step }
+    }
+}
+
+/// An iterator that steps through a [`FiniteRange`] by a fixed amount. See [`FiniteRange::stepped()`].
+pub struct Stepped<T> {
+    range: FiniteRange<T>,
+    step: T,
+}
+
+impl<T> Iterator for Stepped<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let range = &mut self.range;
+        if range.exhausted {
+            return None;
+        }
+
+        let out = range.start;
+        // Compare the remainder of the range with the step, such that a range that ends near the maximum value of T does not overflow
+        if range.end - range.start >= self.step {
+            range.start = range.start + self.step;
+        } else {
+            range.exhausted = true;
         }
+        Some(out)
     }
 }
 
@@ -205,6 +421,307 @@ where
     }
 }
 
+/// A vector in 2D space: the signed difference between two [`Point`]s.
+///
+/// A [`Point`] is an absolute position, whereas a vector is relative (for instance a movement or a scroll offset). Space units are
+/// usually unsigned, so every component is stored as a direction and a magnitude.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Vector<T> {
+    x: SignedValue<T>,
+    y: SignedValue<T>,
+}
+
+impl<T> Debug for Vector<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = |value: &SignedValue<T>| if value.negative { "-" } else { "+" };
+        f.write_fmt(format_args!(
+            "[{}{:?}, {}{:?}]",
+            sign(&self.x),
+            self.x.magnitude,
+            sign(&self.y),
+            self.y.magnitude
+        ))
+    }
+}
+
+impl<T> Vector<T>
+where
+    T: PartialEq + Zero,
+{
+    /// Creates a new instance from the directions and magnitudes of the components.
+    ///
+    /// # Parameters
+    /// * `x`: Whether the X-component is negative and its magnitude.
+    /// * `y`: Whether the Y-component is negative and its magnitude.
+    pub fn from_components(x: (bool, impl Into<T>), y: (bool, impl Into<T>)) -> Self {
+        Self {
+            x: SignedValue::new(x.0, x.1.into()),
+            y: SignedValue::new(y.0, y.1.into()),
+        }
+    }
+
+    /// Creates a new instance with non-negative components.
+    ///
+    /// # Parameters
+    /// * `x`: The X-component.
+    /// * `y`: The Y-component.
+    pub fn new(x: impl Into<T>, y: impl Into<T>) -> Self {
+        Self::from_components((false, x), (false, y))
+    }
+}
+
+impl<T> Vector<T>
+where
+    T: Copy,
+{
+    /// Retrieves the X-component.
+    ///
+    /// # Returns
+    /// Whether the component is negative and its magnitude.
+    pub fn x(&self) -> (bool, T) {
+        (self.x.negative, self.x.magnitude)
+    }
+
+    /// Retrieves the Y-component.
+    ///
+    /// # Returns
+    /// Whether the component is negative and its magnitude.
+    pub fn y(&self) -> (bool, T) {
+        (self.y.negative, self.y.magnitude)
+    }
+}
+
+impl<T> Vector<T>
+where
+    T: Copy + PartialOrd + Sub<Output = T>,
+{
+    /// Creates the vector that leads from one point to another.
+    ///
+    /// # Parameters
+    /// * `from`: The start point.
+    /// * `to`: The end point.
+    pub fn between(from: Point<T>, to: Point<T>) -> Self {
+        Self {
+            x: SignedValue::difference(to.x, from.x),
+            y: SignedValue::difference(to.y, from.y),
+        }
+    }
+}
+
+impl<T> From<Point<T>> for Vector<T> {
+    /// Creates the vector that leads from the origin to a point.
+    fn from(point: Point<T>) -> Self {
+        Self {
+            x: SignedValue::positive(point.x),
+            y: SignedValue::positive(point.y),
+        }
+    }
+}
+
+impl<T> TryFrom<Vector<T>> for Point<T>
+where
+    T: Debug,
+{
+    type Error = String;
+
+    /// Creates the point that the vector leads to from the origin. This fails if one of the components is negative.
+    fn try_from(vector: Vector<T>) -> Result<Self, Self::Error> {
+        if vector.x.negative || vector.y.negative {
+            return Err(format!(
+                "The vector {:?} leads to a point with negative coordinates.",
+                vector
+            ));
+        }
+        Ok(Point {
+            x: vector.x.magnitude,
+            y: vector.y.magnitude,
+        })
+    }
+}
+
+impl<T> Neg for Vector<T>
+where
+    T: PartialEq + Zero,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            x: self.x.neg(),
+            y: self.y.neg(),
+        }
+    }
+}
+
+impl<T> Add for Vector<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Zero,
+{
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x.add(rhs.x),
+            y: self.y.add(rhs.y),
+        }
+    }
+}
+
+impl<T> Sub for Vector<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Zero,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T> Add<Vector<T>> for Point<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T>,
+{
+    type Output = Self;
+
+    /// Moves the point by a vector. Like the underlying arithmetic on `T`, this panics (in debug builds) if the result can not be
+    /// represented.
+    fn add(self, rhs: Vector<T>) -> Self::Output {
+        Point {
+            x: rhs.x.apply(self.x),
+            y: rhs.y.apply(self.y),
+        }
+    }
+}
+
+impl<T> Sub<Vector<T>> for Point<T>
+where
+    T: Copy + PartialEq + Add<Output = T> + Sub<Output = T> + Zero,
+{
+    type Output = Self;
+
+    fn sub(self, rhs: Vector<T>) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl<T> Sub for Point<T>
+where
+    T: Copy + PartialOrd + Sub<Output = T>,
+{
+    type Output = Vector<T>;
+
+    /// Creates the vector that leads from `rhs` to `self` (see [`Vector::between()`]).
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vector::between(rhs, self)
+    }
+}
+
+/// A direction in 2D space. The Y-axis points down, like on the screen, so [`Direction::Up`] decreases the Y-coordinate.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// The orthogonal directions, clockwise from [`Direction::Up`].
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// All directions, clockwise from [`Direction::Up`].
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::UpRight,
+        Direction::Right,
+        Direction::DownRight,
+        Direction::Down,
+        Direction::DownLeft,
+        Direction::Left,
+        Direction::UpLeft,
+    ];
+
+    /// Retrieves the opposite direction.
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+
+    /// Determines whether this is a diagonal direction.
+    pub fn is_diagonal(self) -> bool {
+        !Self::ORTHOGONAL.contains(&self)
+    }
+
+    /// Splits the direction into its components.
+    ///
+    /// # Returns
+    /// The horizontal and vertical component, each in the form `-1`, `0` or `1`.
+    fn components(self) -> (i8, i8) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+
+    /// Creates the vector of a single step in this direction.
+    pub fn vector<T>(self) -> Vector<T>
+    where
+        T: PartialEq + Zero + One,
+    {
+        let component = |value: i8| match value {
+            0 => (false, T::zero()),
+            _ => (value < 0, T::one()),
+        };
+        let (x, y) = self.components();
+        Vector::from_components(component(x), component(y))
+    }
+}
+
+impl<T> Point<T>
+where
+    T: Copy + PartialEq + Add<Output = T> + Sub<Output = T> + Zero + One,
+{
+    /// Retrieves the adjacent point in a direction.
+    ///
+    /// # Parameters
+    /// * `direction`: The direction.
+    ///
+    /// # Panics
+    /// Like the arithmetic on `T`, this function panics (in debug builds) if the neighbor can not be represented, for instance when
+    /// moving up from a Y-coordinate of zero with an unsigned `T`.
+    pub fn neighbor(&self, direction: Direction) -> Self {
+        *self + direction.vector()
+    }
+}
+
 /// A size (or dimension) in 2D space.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
@@ -271,7 +788,17 @@ where
 }
 
 /// A rectangle in 2D space.
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///
+/// Deserialization fails for rectangles of which the start position lies beyond the end position (see [`Rect::try_new()`]), such
+/// that a corrupt file is rejected when it is read instead of causing a panic later on.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "RectData<T>",
+        bound(deserialize = "T: serde::Deserialize<'de> + Copy + PartialOrd + Debug")
+    )
+)]
 #[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Rect<T> {
     /// The start position (inclusive).
@@ -280,6 +807,26 @@ pub struct Rect<T> {
     pub max: Point<T>,
 }
 
+/// The unvalidated serialized form of a [`Rect`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RectData<T> {
+    min: Point<T>,
+    max: Point<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T> TryFrom<RectData<T>> for Rect<T>
+where
+    T: Copy + PartialOrd + Debug,
+{
+    type Error = String;
+
+    fn try_from(data: RectData<T>) -> Result<Self, Self::Error> {
+        Rect::try_new(data.min, data.max)
+    }
+}
+
 impl<T> Debug for Rect<T>
 where
     T: Debug,
@@ -310,20 +857,241 @@ where
         );
         Self { min, max }
     }
-}
 
-impl<T> Rect<T>
-where
-    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug + One,
-{
-    /// Creates a new instance.
+    /// Creates a new instance if the start position does not lie beyond the end position on either axis.
     ///
     /// # Parameters
-    /// * `origin`: The point of origin.
-    /// * `size`: The size.
-    #[inline(always)]
-    pub fn new_from_size(origin: impl Into<Point<T>>, size: Size<T>) -> Self {
-        let origin: Point<T> = origin.into();
+    /// * `min`: The start position (inclusive).
+    /// * `max`: The end position (inclusive).
+    ///
+    /// # Returns
+    /// The rectangle or an error if it would be inverted.
+    pub fn try_new(min: impl Into<Point<T>>, max: impl Into<Point<T>>) -> Result<Self, String> {
+        let min: Point<T> = min.into();
+        let max: Point<T> = max.into();
+        if min.x <= max.x && min.y <= max.y {
+            Ok(Self { min, max })
+        } else {
+            Err(format!(
+                "Invalid rectangle: the start position {:?} lies beyond the end position {:?}.",
+                min, max
+            ))
+        }
+    }
+
+    /// Creates the intersection of this rectangle with another rectangle.
+    ///
+    /// # Parameters
+    /// - `other`: The other rectangle.
+    ///
+    /// # Returns
+    /// The area that is covered by both rectangles or `None` if the rectangles do not overlap.
+    ///
+    /// # Example
+    ///
+    /// ```example
+    ///    3     6     9              6     9
+    /// 12 +-----------+
+    ///    |           |
+    ///    |     +-----+-----+        +-----+
+    ///    |     |     |     |  ===>  |     |
+    /// 16 |     |     |     |        |     |
+    ///    |     |     |     |        |     |
+    /// 19 +-----+-----+     |        +-----+
+    ///          +-----------+
+    /// ```
+    pub fn intersect_rect(&self, other: &Rect<T>) -> Option<Rect<T>> {
+        let max = |a: T, b: T| if a < b { b } else { a };
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let min_x = max(self.min.x, other.min.x);
+        let min_y = max(self.min.y, other.min.y);
+        let max_x = min(self.max.x, other.max.x);
+        let max_y = min(self.max.y, other.max.y);
+        (min_x <= max_x && min_y <= max_y).then(|| Rect::new((min_x, min_y), (max_x, max_y)))
+    }
+
+    /// Creates the union of this rectangle with another rectangle.
+    ///
+    /// # Parameters
+    /// - `other`: The other rectangle.
+    ///
+    /// # Returns
+    /// The smallest rectangle that contains both rectangles. This also covers the area between the rectangles if they do not overlap.
+    pub fn union(&self, other: &Rect<T>) -> Rect<T> {
+        let max = |a: T, b: T| if a < b { b } else { a };
+        let min = |a: T, b: T| if b < a { b } else { a };
+        Rect::new(
+            (min(self.min.x, other.min.x), min(self.min.y, other.min.y)),
+            (max(self.max.x, other.max.x), max(self.max.y, other.max.y)),
+        )
+    }
+}
+
+/// An accumulator for the bounding box of points and rectangles.
+///
+/// The accumulator can be fed one item at a time or be collected from an iterator of [`Point`]s or [`Rect`]s.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct BoundingBox<T> {
+    rect: Option<Rect<T>>,
+}
+
+impl<T> Default for BoundingBox<T> {
+    fn default() -> Self {
+        Self { rect: None }
+    }
+}
+
+impl<T> BoundingBox<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    /// Creates a new, empty instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Extends the bounding box, such that it contains a point.
+    ///
+    /// # Parameters
+    /// * `point`: The point.
+    pub fn add_point(&mut self, point: impl Into<Point<T>>) {
+        let point = point.into();
+        self.add_rect(&Rect::new(point, point));
+    }
+
+    /// Extends the bounding box, such that it contains a rectangle.
+    ///
+    /// # Parameters
+    /// * `rect`: The rectangle.
+    pub fn add_rect(&mut self, rect: &Rect<T>) {
+        self.rect = Some(match &self.rect {
+            Some(current) => current.union(rect),
+            None => *rect,
+        });
+    }
+
+    /// Retrieves the bounding box.
+    ///
+    /// # Returns
+    /// The smallest rectangle that contains everything that has been added or `None` if nothing has been added.
+    pub fn rect(&self) -> Option<Rect<T>> {
+        self.rect
+    }
+}
+
+impl<T> Extend<Point<T>> for BoundingBox<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    fn extend<I: IntoIterator<Item = Point<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|point| self.add_point(point));
+    }
+}
+
+impl<T> Extend<Rect<T>> for BoundingBox<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    fn extend<I: IntoIterator<Item = Rect<T>>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|rect| self.add_rect(&rect));
+    }
+}
+
+impl<T> FromIterator<Point<T>> for BoundingBox<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    fn from_iter<I: IntoIterator<Item = Point<T>>>(iter: I) -> Self {
+        let mut bounds = Self::new();
+        bounds.extend(iter);
+        bounds
+    }
+}
+
+impl<T> FromIterator<Rect<T>> for BoundingBox<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    fn from_iter<I: IntoIterator<Item = Rect<T>>>(iter: I) -> Self {
+        let mut bounds = Self::new();
+        bounds.extend(iter);
+        bounds
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug,
+{
+    /// Moves the rectangle by a vector. The size remains the same.
+    ///
+    /// # Parameters
+    /// * `vector`: The vector.
+    ///
+    /// # Panics
+    /// Like the arithmetic on `T`, this function panics (in debug builds) if the moved rectangle can not be represented.
+    pub fn translate(&self, vector: Vector<T>) -> Rect<T> {
+        Self {
+            min: self.min + vector,
+            max: self.max + vector,
+        }
+    }
+
+    /// Grows the rectangle by the same amount on all sides.
+    ///
+    /// # Parameters
+    /// * `amount`: The amount.
+    ///
+    /// # Panics
+    /// Like the arithmetic on `T`, this function panics (in debug builds) if the grown rectangle can not be represented (for instance
+    /// when it would extend past zero for an unsigned `T`).
+    ///
+    /// # Example
+    ///
+    /// ```example
+    ///    2           8               1             9
+    ///                             3  +-------------+
+    /// 4  +-----------+               |             |
+    ///    |           |    ===>       |             |
+    /// 7  +-----------+               |             |
+    ///                             8  +-------------+
+    /// ```
+    pub fn inflate(&self, amount: T) -> Rect<T> {
+        Self {
+            min: Point::new(self.min.x - amount, self.min.y - amount),
+            max: Point::new(self.max.x + amount, self.max.y + amount),
+        }
+    }
+
+    /// Shrinks the rectangle by the same amount on all sides. This is the inverse of [`Rect::inflate()`].
+    ///
+    /// # Parameters
+    /// * `amount`: The amount.
+    ///
+    /// # Returns
+    /// The shrunk rectangle or `None` if nothing remains of the rectangle.
+    pub fn deflate(&self, amount: T) -> Option<Rect<T>> {
+        // Compare the distances, such that no intermediate value can underflow
+        let fits = |min: T, max: T| amount + amount <= max - min;
+        (fits(self.min.x, self.max.x) && fits(self.min.y, self.max.y)).then(|| Self {
+            min: Point::new(self.min.x + amount, self.min.y + amount),
+            max: Point::new(self.max.x - amount, self.max.y - amount),
+        })
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug + One,
+{
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `origin`: The point of origin.
+    /// * `size`: The size.
+    #[inline(always)]
+    pub fn new_from_size(origin: impl Into<Point<T>>, size: Size<T>) -> Self {
+        let origin: Point<T> = origin.into();
         Self::new(
             origin,
             (
@@ -369,6 +1137,31 @@ where
     }
 }
 
+impl<T> Rect<T>
+where
+    T: Copy,
+{
+    /// Retrieves the outermost points of this rectangle in a direction: the top or bottom row, the left or right column or, for a
+    /// diagonal direction, the corner point.
+    ///
+    /// # Parameters
+    /// * `direction`: The direction.
+    pub fn edge(&self, direction: Direction) -> Rect<T> {
+        let (x, y) = direction.components();
+        let select = |component: i8, min: T, max: T| match component {
+            0 => (min, max),
+            c if c < 0 => (min, min),
+            _ => (max, max),
+        };
+        let (min_x, max_x) = select(x, self.min.x, self.max.x);
+        let (min_y, max_y) = select(y, self.min.y, self.max.y);
+        Rect {
+            min: Point { x: min_x, y: min_y },
+            max: Point { x: max_x, y: max_y },
+        }
+    }
+}
+
 impl<T> Rect<T>
 where
     T: Copy + Add<Output = T> + Sub<Output = T> + One,
@@ -389,6 +1182,272 @@ where
     }
 }
 
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug + Zero + One,
+{
+    /// Converts this rectangle into exclusive (half-open) semantics, as used by APIs like egui and SDL. The far edge of the
+    /// rectangle lies at `min + size`, which is one past the inclusive maximum.
+    ///
+    /// # Returns
+    /// The minimum point and the size.
+    #[inline(always)]
+    pub fn to_exclusive(&self) -> (Point<T>, Size<T>) {
+        (self.min, self.size())
+    }
+
+    /// Creates a new instance from exclusive (half-open) semantics, as used by APIs like egui and SDL. This is the inverse of
+    /// [`Rect::to_exclusive()`].
+    ///
+    /// # Parameters
+    /// * `min`: The minimum point.
+    /// * `size`: The size.
+    ///
+    /// # Returns
+    /// The rectangle or `None` if the size is empty, since an inclusive rectangle always contains at least one point.
+    pub fn from_exclusive(min: impl Into<Point<T>>, size: Size<T>) -> Option<Self> {
+        if size.width == T::zero() || size.height == T::zero() {
+            return None;
+        }
+        Some(Self::new_from_size(min, size))
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + One + IntoF32,
+{
+    /// Determines the position of this rectangle relative to a rectangle that contains it, where the containing rectangle spans from
+    /// 0.0 to 1.0 on both axes. This is what is needed for the UV coordinates of a section of a texture.
+    ///
+    /// Since the rectangles are inclusive, the end of this rectangle is the far edge of its last pixel. For instance, the right half
+    /// of a rectangle from 0 to 7 (8 pixels wide) lies between 0.5 and 1.0.
+    ///
+    /// # Parameters
+    /// * `outer`: The containing rectangle.
+    ///
+    /// # Returns
+    /// The normalized minimum X, minimum Y, maximum X and maximum Y, in that order.
+    ///
+    /// # Panics
+    /// Like the arithmetic on `T`, this function panics (in debug builds) for an unsigned `T` if this rectangle starts before the
+    /// containing rectangle.
+    pub fn normalized_within(&self, outer: &Rect<T>) -> [f32; 4] {
+        let (width, height) = (outer.width().into_f32(), outer.height().into_f32());
+        let normalize = |value: T, origin: T, length: f32| (value - origin).into_f32() / length;
+        [
+            normalize(self.min.x, outer.min.x, width),
+            normalize(self.min.y, outer.min.y, height),
+            normalize(self.max.x + T::one(), outer.min.x, width),
+            normalize(self.max.y + T::one(), outer.min.y, height),
+        ]
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + Zero + One,
+{
+    /// Subdivides this rectangle into tiles of a fixed size, starting at the top-left corner. The tiles at the right and bottom edges
+    /// are cut off if the size of this rectangle is not a multiple of the tile size.
+    ///
+    /// ```text
+    /// Rect::new((0, 0), (19, 9)).tiles(Size::new(8, 8)) yields
+    ///     (0, 0)-(7, 7), (8, 0)-(15, 7), (16, 0)-(19, 7),
+    ///     (0, 8)-(7, 9), (8, 8)-(15, 9), (16, 8)-(19, 9)
+    /// ```
+    ///
+    /// # Parameters
+    /// * `size`: The size of the tiles.
+    ///
+    /// # Returns
+    /// An iterator over the tiles, row by row.
+    ///
+    /// # Panics
+    /// This function panics if the width or the height of `size` is not greater than zero.
+    pub fn tiles(&self, size: Size<T>) -> RectTiles<T> {
+        if size.width <= T::zero() || size.height <= T::zero() {
+            panic!("Invalid tile size.");
+        }
+        RectTiles {
+            rect: *self,
+            size,
+            next: Some(self.min),
+        }
+    }
+}
+
+/// An iterator over the tiles of a [`Rect`]. See [`Rect::tiles()`].
+pub struct RectTiles<T> {
+    rect: Rect<T>,
+    size: Size<T>,
+    /// The top-left corner of the next tile.
+    next: Option<Point<T>>,
+}
+
+impl<T> Iterator for RectTiles<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + One,
+{
+    type Item = Rect<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let min = self.next?;
+        let Rect { max: end, .. } = self.rect;
+        // Compare the remainder of the rectangle with the tile size, such that a rectangle that ends near the maximum value of T does
+        // not overflow
+        let more_x = end.x - min.x >= self.size.width;
+        let more_y = end.y - min.y >= self.size.height;
+        let max = Point {
+            x: if more_x {
+                min.x + self.size.width - T::one()
+            } else {
+                end.x
+            },
+            y: if more_y {
+                min.y + self.size.height - T::one()
+            } else {
+                end.y
+            },
+        };
+
+        self.next = if more_x {
+            Some(Point {
+                x: min.x + self.size.width,
+                y: min.y,
+            })
+        } else if more_y {
+            Some(Point {
+                x: self.rect.min.x,
+                y: min.y + self.size.height,
+            })
+        } else {
+            None
+        };
+        Some(Rect { min, max })
+    }
+}
+
+impl<T> Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + Zero + One,
+{
+    /// Creates an iterator over all points of this rectangle in Z-order (also known as Morton order). Points that lie close to each
+    /// other in 2D space are mostly close to each other in this order as well, which improves the cache locality when traversing
+    /// large surfaces.
+    ///
+    /// ```text
+    /// Rect::new((0, 0), (3, 1)).iter_morton() yields
+    ///     (0, 0), (1, 0), (0, 1), (1, 1), (2, 0), (3, 0), (2, 1), (3, 1)
+    /// ```
+    ///
+    /// The order is that of a square with a power-of-two side that covers the rectangle, with the points outside of the rectangle
+    /// left out.
+    pub fn iter_morton(&self) -> RectMorton<T> {
+        let extent = Point {
+            x: self.max.x - self.min.x,
+            y: self.max.y - self.min.y,
+        };
+        let extent_max = if extent.x > extent.y {
+            extent.x
+        } else {
+            extent.y
+        };
+
+        // The halves of the sides of the quadrants on every level, up to the level that covers the whole rectangle
+        let mut halves = Vec::new();
+        let mut half = T::one();
+        let root = if extent_max < T::one() {
+            None
+        } else {
+            halves.push(half);
+            // Compare with the remainder, such that the side of a quadrant is never calculated if it exceeds the extent
+            while half <= extent_max - half {
+                half = half + half;
+                halves.push(half);
+            }
+            Some(halves.len() - 1)
+        };
+
+        RectMorton {
+            min: self.min,
+            extent,
+            halves,
+            stack: vec![(Point::new(T::zero(), T::zero()), root)],
+        }
+    }
+}
+
+/// An iterator over the points of a [`Rect`] in Z-order. See [`Rect::iter_morton()`].
+pub struct RectMorton<T> {
+    min: Point<T>,
+    /// The offset of the last point from `min`.
+    extent: Point<T>,
+    /// See [`Rect::iter_morton()`].
+    halves: Vec<T>,
+    /// The quadrants that still have to be traversed, the next one at the end. Every quadrant is stored as the offset of its
+    /// top-left corner from `min` and its level in `halves`, or `None` for a single point.
+    stack: Vec<(Point<T>, Option<usize>)>,
+}
+
+impl<T> Iterator for RectMorton<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd,
+{
+    type Item = Point<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (offset, level) = self.stack.pop()?;
+            let level = match level {
+                None => {
+                    return Some(Point {
+                        x: self.min.x + offset.x,
+                        y: self.min.y + offset.y,
+                    })
+                }
+                Some(level) => level,
+            };
+
+            let half = self.halves[level];
+            let child_level = level.checked_sub(1);
+            // Compare with the remainder, such that the offset of a quadrant outside of the rectangle is never calculated
+            let right = self.extent.x - offset.x >= half;
+            let bottom = self.extent.y - offset.y >= half;
+            let quadrants = [
+                (offset, true),
+                (
+                    Point {
+                        x: if right { offset.x + half } else { offset.x },
+                        y: offset.y,
+                    },
+                    right,
+                ),
+                (
+                    Point {
+                        x: offset.x,
+                        y: if bottom { offset.y + half } else { offset.y },
+                    },
+                    bottom,
+                ),
+                (
+                    Point {
+                        x: if right { offset.x + half } else { offset.x },
+                        y: if bottom { offset.y + half } else { offset.y },
+                    },
+                    right && bottom,
+                ),
+            ];
+            // Push in reverse order, such that the top-left quadrant is traversed first
+            for (offset, inside) in quadrants.into_iter().rev() {
+                if inside {
+                    self.stack.push((offset, child_level));
+                }
+            }
+        }
+    }
+}
+
 impl<T> Rect<T>
 where
     T: Copy + Add<Output = T> + PartialOrd + PartialEq + Debug + One,
@@ -478,216 +1537,2650 @@ pub enum RectIntersection<T> {
 }
 
 impl<T> RectIntersection<T> {
-    pub fn for_each(&self, mut func: impl FnMut(&Rect<T>)) {
+    /// Calls a function for every rectangle of the intersection.
+    ///
+    /// # Parameters
+    /// * `func`: The function.
+    pub fn for_each(&self, func: impl FnMut(&Rect<T>)) {
+        self.iter().for_each(func);
+    }
+
+    /// Creates an iterator over the rectangles of the intersection, in the order top-left, top-right, bottom-left, bottom-right.
+    pub fn iter(&self) -> RectIntersectionIter<&Rect<T>> {
         match self {
-            RectIntersection::None => {}
-            RectIntersection::Vertical { left, right } => {
-                func(left);
-                func(right);
-            }
-            RectIntersection::Horizontal { top, bottom } => {
-                func(top);
-                func(bottom);
-            }
+            RectIntersection::None => [None, None, None, None],
+            RectIntersection::Vertical { left, right } => [Some(left), Some(right), None, None],
+            RectIntersection::Horizontal { top, bottom } => [Some(top), Some(bottom), None, None],
             RectIntersection::Both {
                 top_left,
                 top_right,
                 bottom_left,
                 bottom_right,
-            } => {
-                func(top_left);
-                func(top_right);
-                func(bottom_left);
-                func(bottom_right);
-            }
+            } => [
+                Some(top_left),
+                Some(top_right),
+                Some(bottom_left),
+                Some(bottom_right),
+            ],
         }
+        .into_iter()
+        .flatten()
     }
-}
-
-/// Macro for generating simple "space unit" implementations.
-///
-/// # Parameters
-/// * `name`: Output type name.
-/// * `raw_type`: The raw (inner) value type.
-#[macro_export]
-macro_rules! space_unit {
-    ($(#[doc = $doc:expr])* $name:ident, $raw_type:ty) => {
-        $(#[doc = $doc])*
-        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-        pub struct $name($raw_type);
 
-        impl std::ops::Add for $name {
-            type Output = Self;
+    /// Retrieves the number of rectangles of the intersection.
+    pub fn count(&self) -> usize {
+        match self {
+            RectIntersection::None => 0,
+            RectIntersection::Vertical { .. } | RectIntersection::Horizontal { .. } => 2,
+            RectIntersection::Both { .. } => 4,
+        }
+    }
 
-            #[inline(always)]
-            fn add(self, rhs: Self) -> Self::Output {
-                Self(self.0 + rhs.0)
-            }
+    /// Converts the rectangles of the intersection, for instance into another space. The kind of intersection remains the same.
+    ///
+    /// # Parameters
+    /// * `func`: The function that converts a rectangle.
+    pub fn map<U>(self, mut func: impl FnMut(Rect<T>) -> Rect<U>) -> RectIntersection<U> {
+        match self {
+            RectIntersection::None => RectIntersection::None,
+            RectIntersection::Vertical { left, right } => RectIntersection::Vertical {
+                left: func(left),
+                right: func(right),
+            },
+            RectIntersection::Horizontal { top, bottom } => RectIntersection::Horizontal {
+                top: func(top),
+                bottom: func(bottom),
+            },
+            RectIntersection::Both {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            } => RectIntersection::Both {
+                top_left: func(top_left),
+                top_right: func(top_right),
+                bottom_left: func(bottom_left),
+                bottom_right: func(bottom_right),
+            },
         }
+    }
+}
 
-        impl std::ops::Sub for $name {
+/// An iterator over the rectangles of a [`RectIntersection`] (see [`RectIntersection::iter()`]).
+pub type RectIntersectionIter<R> = std::iter::Flatten<std::array::IntoIter<Option<R>, 4>>;
+
+impl<T> IntoIterator for RectIntersection<T> {
+    type Item = Rect<T>;
+    type IntoIter = RectIntersectionIter<Rect<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        match self {
+            RectIntersection::None => [None, None, None, None],
+            RectIntersection::Vertical { left, right } => [Some(left), Some(right), None, None],
+            RectIntersection::Horizontal { top, bottom } => [Some(top), Some(bottom), None, None],
+            RectIntersection::Both {
+                top_left,
+                top_right,
+                bottom_left,
+                bottom_right,
+            } => [
+                Some(top_left),
+                Some(top_right),
+                Some(bottom_left),
+                Some(bottom_right),
+            ],
+        }
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RectIntersection<T> {
+    type Item = &'a Rect<T>;
+    type IntoIter = RectIntersectionIter<&'a Rect<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A value with a sign, for calculations on (possibly) unsigned types.
+///
+/// A magnitude of zero is never negative, such that the derived equality is correct.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+struct SignedValue<T> {
+    negative: bool,
+    magnitude: T,
+}
+
+impl<T> SignedValue<T> {
+    fn positive(magnitude: T) -> Self {
+        Self {
+            negative: false,
+            magnitude,
+        }
+    }
+}
+
+impl<T> SignedValue<T>
+where
+    T: PartialEq + Zero,
+{
+    fn new(negative: bool, magnitude: T) -> Self {
+        Self {
+            negative: negative && magnitude != T::zero(),
+            magnitude,
+        }
+    }
+
+    fn neg(self) -> Self {
+        Self::new(!self.negative, self.magnitude)
+    }
+}
+
+impl<T> SignedValue<T>
+where
+    T: Copy + PartialOrd + Sub<Output = T>,
+{
+    /// Creates the difference `a - b`.
+    fn difference(a: T, b: T) -> Self {
+        if a >= b {
+            Self {
+                negative: false,
+                magnitude: a - b,
+            }
+        } else {
+            Self {
+                negative: true,
+                magnitude: b - a,
+            }
+        }
+    }
+
+    fn add(self, rhs: Self) -> Self
+    where
+        T: Add<Output = T> + Zero,
+    {
+        if self.negative == rhs.negative {
+            Self::new(self.negative, self.magnitude + rhs.magnitude)
+        } else {
+            // The sign of the value with the larger magnitude wins
+            let difference = Self::difference(self.magnitude, rhs.magnitude);
+            Self::new(self.negative != difference.negative, difference.magnitude)
+        }
+    }
+}
+
+impl<T> SignedValue<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T>,
+{
+    /// Adds this value to an unsigned value.
+    fn apply(self, value: T) -> T {
+        if self.negative {
+            value - self.magnitude
+        } else {
+            value + self.magnitude
+        }
+    }
+}
+
+impl<T> SignedValue<T>
+where
+    T: Copy + Sub<Output = T> + Mul<Output = T> + Ord + Zero,
+{
+    fn mul(self, rhs: Self) -> Self {
+        let magnitude = self.magnitude * rhs.magnitude;
+        Self {
+            negative: magnitude != T::zero() && self.negative != rhs.negative,
+            magnitude,
+        }
+    }
+
+    fn compare(self, rhs: Self) -> Ordering {
+        match (self.negative, rhs.negative) {
+            (false, false) => self.magnitude.cmp(&rhs.magnitude),
+            (true, true) => rhs.magnitude.cmp(&self.magnitude),
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+        }
+    }
+}
+
+/// Determines the orientation of the triangle `a`, `b`, `c`.
+///
+/// returns: The sign of the cross product of `b - a` and `c - a`. [`Ordering::Equal`] means that the points are collinear.
+fn orientation<T>(a: Point<T>, b: Point<T>, c: Point<T>) -> Ordering
+where
+    T: Copy + Sub<Output = T> + Mul<Output = T> + Ord + Zero,
+{
+    let lhs = SignedValue::difference(b.x, a.x).mul(SignedValue::difference(c.y, a.y));
+    let rhs = SignedValue::difference(b.y, a.y).mul(SignedValue::difference(c.x, a.x));
+    lhs.compare(rhs)
+}
+
+/// A line segment in 2D space.
+///
+/// Like [`Rect`], a segment is inclusive: both end points are part of the segment.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Segment<T> {
+    start: Point<T>,
+    end: Point<T>,
+}
+
+impl<T> Debug for Segment<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:?} - {:?}", self.start, self.end))
+    }
+}
+
+impl<T> Segment<T> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `start`: The start point.
+    /// * `end`: The end point.
+    pub fn new(start: impl Into<Point<T>>, end: impl Into<Point<T>>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+        }
+    }
+}
+
+impl<T> Segment<T>
+where
+    T: Copy,
+{
+    /// Retrieves the start point.
+    pub fn start(&self) -> Point<T> {
+        self.start
+    }
+
+    /// Retrieves the end point.
+    pub fn end(&self) -> Point<T> {
+        self.end
+    }
+}
+
+/// The outcode of a point for [`Segment::clip()`]: one bit for every side of the rectangle that the point lies beyond.
+#[derive(Copy, Clone, Eq, PartialEq)]
+struct Outcode(u8);
+
+impl Outcode {
+    const LEFT: u8 = 0b0001;
+    const RIGHT: u8 = 0b0010;
+    const TOP: u8 = 0b0100;
+    const BOTTOM: u8 = 0b1000;
+
+    fn of<T>(point: Point<T>, rect: &Rect<T>) -> Self
+    where
+        T: PartialOrd,
+    {
+        let mut code = 0;
+        if point.x < rect.min.x {
+            code |= Self::LEFT;
+        } else if point.x > rect.max.x {
+            code |= Self::RIGHT;
+        }
+        if point.y < rect.min.y {
+            code |= Self::TOP;
+        } else if point.y > rect.max.y {
+            code |= Self::BOTTOM;
+        }
+        Self(code)
+    }
+
+    fn is_inside(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl<T> Segment<T>
+where
+    T: Copy
+        + PartialOrd
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<Output = T>
+        + Div<Output = T>
+        + Zero,
+{
+    /// Clips the segment against a rectangle, using the Cohen-Sutherland algorithm.
+    ///
+    /// The intersections with the edges of the rectangle are rounded towards the outside end point, so the result does not contain
+    /// points beyond the original segment. The products of the coordinate differences must fit in `T`.
+    ///
+    /// # Parameters
+    /// * `rect`: The rectangle.
+    ///
+    /// # Returns
+    /// The part of the segment that lies within the rectangle or `None` if the segment does not cross the rectangle.
+    ///
+    /// # Example
+    ///
+    /// ```example
+    ///    0   2       8  10              2       8
+    ///  0 *
+    ///      \
+    ///  2     +-------+                  *-------+
+    ///        |   \   |          ===>    |   \   |
+    ///  6     +-------+                  +-------*
+    ///                  \
+    ///  8                 *
+    /// ```
+    pub fn clip(&self, rect: &Rect<T>) -> Option<Segment<T>> {
+        let mut start = self.start;
+        let mut end = self.end;
+        let mut start_code = Outcode::of(start, rect);
+        let mut end_code = Outcode::of(end, rect);
+
+        loop {
+            if (start_code.0 | end_code.0) == 0 {
+                return Some(Segment { start, end });
+            }
+            if (start_code.0 & end_code.0) != 0 {
+                // Both end points lie beyond the same side
+                return None;
+            }
+
+            // Move an end point that lies outside onto the edge, towards the other end point. The other end point does not lie
+            // beyond the same edge, so the difference along the clipped axis is never zero.
+            let (outside, code, other) = if start_code.is_inside() {
+                (&mut end, &mut end_code, start)
+            } else {
+                (&mut start, &mut start_code, end)
+            };
+            let dx = SignedValue::difference(other.x, outside.x);
+            let dy = SignedValue::difference(other.y, outside.y);
+            let along = |delta: SignedValue<T>, distance: T, total: T| {
+                SignedValue::new(delta.negative, delta.magnitude * distance / total)
+            };
+
+            if code.0 & (Outcode::TOP | Outcode::BOTTOM) != 0 {
+                let edge = if code.0 & Outcode::TOP != 0 {
+                    rect.min.y
+                } else {
+                    rect.max.y
+                };
+                let distance = SignedValue::difference(edge, outside.y).magnitude;
+                outside.x = along(dx, distance, dy.magnitude).apply(outside.x);
+                outside.y = edge;
+            } else {
+                let edge = if code.0 & Outcode::LEFT != 0 {
+                    rect.min.x
+                } else {
+                    rect.max.x
+                };
+                let distance = SignedValue::difference(edge, outside.x).magnitude;
+                outside.y = along(dy, distance, dx.magnitude).apply(outside.y);
+                outside.x = edge;
+            }
+            *code = Outcode::of(*outside, rect);
+        }
+    }
+}
+
+/// A polygon in 2D space.
+///
+/// Like [`Rect`], a polygon is inclusive: points that lie on the edges are considered to be part of the polygon. All calculations
+/// are performed in `T`, so the products of the coordinates must fit in `T`.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Polygon<T> {
+    /// The vertices. The last vertex is implicitly connected to the first.
+    points: Vec<Point<T>>,
+}
+
+impl<T> Debug for Polygon<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(&self.points).finish()
+    }
+}
+
+impl<T> Polygon<T> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `points`: The vertices. The last vertex is implicitly connected to the first.
+    pub fn new(points: Vec<Point<T>>) -> Self {
+        Self { points }
+    }
+
+    /// Retrieves the vertices.
+    pub fn points(&self) -> &[Point<T>] {
+        &self.points
+    }
+
+    /// Determines whether the polygon has no vertices.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Creates an iterator over the edges of the polygon.
+    fn edges(&self) -> impl Iterator<Item = (&Point<T>, &Point<T>)> {
+        self.points.iter().zip(self.points.iter().cycle().skip(1))
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: Copy + PartialOrd + PartialEq + Debug,
+{
+    /// Calculates the bounding rectangle.
+    ///
+    /// returns: The smallest [`Rect`] that contains all vertices or `None` if the polygon is empty.
+    pub fn bounding_rect(&self) -> Option<Rect<T>> {
+        self.points
+            .iter()
+            .copied()
+            .collect::<BoundingBox<T>>()
+            .rect()
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: Copy + Sub<Output = T> + Mul<Output = T> + Ord + Zero,
+{
+    /// Determines whether a point lies inside the polygon (including its edges).
+    ///
+    /// This uses the winding number, so the polygon does not need to be convex.
+    ///
+    /// # Parameters
+    /// * `point`: The point.
+    pub fn contains(&self, point: impl Into<Point<T>>) -> bool {
+        let p = point.into();
+        let mut winding_number = 0isize;
+        for (a, b) in self.edges() {
+            let side = orientation(*a, *b, p);
+            if side == Ordering::Equal
+                && a.x.min(b.x) <= p.x
+                && p.x <= a.x.max(b.x)
+                && a.y.min(b.y) <= p.y
+                && p.y <= a.y.max(b.y)
+            {
+                // On the edge
+                return true;
+            }
+
+            if a.y <= p.y {
+                if b.y > p.y && side == Ordering::Greater {
+                    winding_number += 1;
+                }
+            } else if b.y <= p.y && side == Ordering::Less {
+                winding_number -= 1;
+            }
+        }
+        winding_number != 0
+    }
+
+    /// Calculates the convex hull of a set of points.
+    ///
+    /// Collinear points on the hull are omitted.
+    ///
+    /// # Parameters
+    /// * `points`: The points.
+    ///
+    /// returns: The convex hull. This is empty if `points` is empty.
+    pub fn convex_hull(points: impl IntoIterator<Item = Point<T>>) -> Self {
+        let mut points: Vec<Point<T>> = points.into_iter().collect();
+        points.sort_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)));
+        points.dedup();
+        if points.len() <= 2 {
+            return Self::new(points);
+        }
+
+        // Andrew's monotone chain algorithm
+        let mut hull: Vec<Point<T>> = Vec::with_capacity(2 * points.len());
+        let build_chain = |hull: &mut Vec<Point<T>>, point: Point<T>, min_len: usize| {
+            while hull.len() >= min_len + 2
+                && orientation(hull[hull.len() - 2], hull[hull.len() - 1], point)
+                    != Ordering::Greater
+            {
+                hull.pop();
+            }
+            hull.push(point);
+        };
+
+        for point in points.iter() {
+            build_chain(&mut hull, *point, 0);
+        }
+        // The last point of the lower chain is the first point of the upper chain
+        let lower_len = hull.len() - 1;
+        for point in points.iter().rev().skip(1) {
+            build_chain(&mut hull, *point, lower_len);
+        }
+        // The last point of the upper chain is the first point of the lower chain
+        hull.pop();
+
+        Self::new(hull)
+    }
+}
+
+impl<T> Polygon<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Ord + Zero + One,
+{
+    /// Calculates the convex hull of a mask, like the opaque pixels of a sprite.
+    ///
+    /// # Parameters
+    /// * `size`: The size of the mask.
+    /// * `is_set`: A function that determines whether the mask is set at a given point.
+    ///
+    /// returns: The convex hull of all points for which `is_set` returns `true`. This is empty if no such point exists.
+    pub fn convex_hull_of_mask(size: Size<T>, is_set: impl Fn(Point<T>) -> bool) -> Self {
+        if size.width == T::zero() || size.height == T::zero() {
+            return Self::new(Vec::new());
+        }
+
+        // Only the outermost points of every row can be part of the hull
+        let mut points = Vec::new();
+        for y in FiniteRange::new(T::zero(), size.height - T::one()) {
+            let mut row = FiniteRange::new(T::zero(), size.width - T::one())
+                .map(|x| Point { x, y })
+                .filter(|p| is_set(*p));
+            if let Some(first) = row.next() {
+                points.push(first);
+                if let Some(last) = row.last() {
+                    points.push(last);
+                }
+            }
+        }
+
+        Self::convex_hull(points)
+    }
+}
+
+/// A circle in 2D space.
+///
+/// Like [`Rect`], a circle is inclusive: points that lie exactly on the circumference are considered to be part of the circle. All
+/// calculations are performed in `T`, so the square of the radius must fit in `T` (the squared distance to a point is never computed
+/// as a whole).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Circle<T> {
+    center: Point<T>,
+    radius: T,
+}
+
+impl<T> Debug for Circle<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{:?} r={:?}", self.center, self.radius))
+    }
+}
+
+impl<T> Circle<T> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `center`: The center.
+    /// * `radius`: The radius. A circle with a radius of zero only contains its center.
+    pub fn new(center: impl Into<Point<T>>, radius: impl Into<T>) -> Self {
+        Self {
+            center: center.into(),
+            radius: radius.into(),
+        }
+    }
+}
+
+impl<T> Circle<T>
+where
+    T: Copy,
+{
+    /// Retrieves the center.
+    pub fn center(&self) -> Point<T> {
+        self.center
+    }
+
+    /// Retrieves the radius.
+    pub fn radius(&self) -> T {
+        self.radius
+    }
+}
+
+impl<T> Circle<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + PartialOrd,
+{
+    /// Determines whether a point lies inside the circle (including its circumference).
+    ///
+    /// # Parameters
+    /// * `point`: The point.
+    pub fn contains_point(&self, point: impl Into<Point<T>>) -> bool {
+        let p = point.into();
+        // Subtract the smaller value from the larger one, such that this also works for unsigned units
+        let distance = |a: T, b: T| if a > b { a - b } else { b - a };
+        let dx = distance(p.x, self.center.x);
+        let dy = distance(p.y, self.center.y);
+        // Check the axes first, such that the squares below do not overflow for points that are far away
+        if dx > self.radius || dy > self.radius {
+            return false;
+        }
+        // Since both distances are at most the radius, none of the terms below exceed the square of the radius (unlike the sum of
+        // the squares of the distances)
+        let squared_radius = self.radius * self.radius;
+        dx * dx <= squared_radius - dy * dy
+    }
+}
+
+impl<T> Circle<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug,
+{
+    /// Calculates the bounding rectangle.
+    ///
+    /// # Returns
+    /// The smallest [`Rect`] that contains the circle.
+    ///
+    /// # Panics
+    /// Like the arithmetic on `T`, this function panics (in debug builds) for an unsigned `T` if the circle extends beyond the origin
+    /// of the space.
+    pub fn bounding_rect(&self) -> Rect<T> {
+        let Point { x, y } = self.center;
+        Rect::new(
+            (x - self.radius, y - self.radius),
+            (x + self.radius, y + self.radius),
+        )
+    }
+}
+
+impl<T> From<Circle<T>> for Rect<T>
+where
+    T: Copy + Add<Output = T> + Sub<Output = T> + PartialOrd + PartialEq + Debug,
+{
+    fn from(circle: Circle<T>) -> Self {
+        circle.bounding_rect()
+    }
+}
+
+/// A 2-dimensional grid of cells, like a tile map. Every cell is addressed by a [`Point`] and the cells are stored row by row.
+///
+/// The unit `U` is converted into a `usize` to index the cells (see [`IntoUsize`]), so the grid works for the primitive integer types
+/// and for the integer space units.
+///
+/// Deserialization fails for grids of which the number of cells does not match the size (see [`Grid::from_cells()`]).
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        try_from = "GridData<T, U>",
+        bound(
+            serialize = "T: serde::Serialize, U: serde::Serialize",
+            deserialize = "T: serde::Deserialize<'de>, U: serde::Deserialize<'de> + Copy + IntoUsize"
+        )
+    )
+)]
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Grid<T, U> {
+    size: Size<U>,
+    cells: Vec<T>,
+}
+
+/// The unvalidated serialized form of a [`Grid`].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct GridData<T, U> {
+    size: Size<U>,
+    cells: Vec<T>,
+}
+
+#[cfg(feature = "serde")]
+impl<T, U> TryFrom<GridData<T, U>> for Grid<T, U>
+where
+    U: Copy + IntoUsize,
+{
+    type Error = String;
+
+    fn try_from(data: GridData<T, U>) -> Result<Self, Self::Error> {
+        Grid::from_cells(data.size, data.cells)
+    }
+}
+
+impl<T, U> Debug for Grid<T, U>
+where
+    T: Debug,
+    U: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Grid")
+            .field("size", &self.size)
+            .field("cells", &self.cells)
+            .finish()
+    }
+}
+
+impl<T, U> Grid<T, U>
+where
+    U: Copy + IntoUsize,
+{
+    /// Creates a new instance in which all cells have the same value.
+    ///
+    /// # Parameters
+    /// * `size`: The number of columns and rows.
+    /// * `value`: The value of the cells.
+    pub fn new(size: Size<U>, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let (width, height) = (size.width.into_usize(), size.height.into_usize());
+        Self {
+            size,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a new instance from the values of the cells.
+    ///
+    /// # Parameters
+    /// * `size`: The number of columns and rows.
+    /// * `cells`: The values of the cells, row by row.
+    ///
+    /// # Returns
+    /// The grid or an error if the number of values does not match the size.
+    pub fn from_cells(size: Size<U>, cells: Vec<T>) -> Result<Self, String> {
+        let expected = size.width.into_usize() * size.height.into_usize();
+        if cells.len() != expected {
+            return Err(format!(
+                "Invalid number of cells: {} (expected {}).",
+                cells.len(),
+                expected
+            ));
+        }
+        Ok(Self { size, cells })
+    }
+
+    /// Retrieves the number of columns and rows.
+    pub fn size(&self) -> Size<U> {
+        self.size
+    }
+
+    /// Retrieves the values of all cells, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Determines the position of a cell in [`Grid::cells()`].
+    ///
+    /// # Parameters
+    /// * `point`: The address of the cell.
+    ///
+    /// # Returns
+    /// The index or `None` if the point lies outside of the grid.
+    pub fn index_of(&self, point: impl Into<Point<U>>) -> Option<usize> {
+        let Point { x, y } = point.into();
+        let (x, y, width) = (x.into_usize(), y.into_usize(), self.size.width.into_usize());
+        (x < width && y < self.size.height.into_usize()).then(|| y * width + x)
+    }
+
+    /// Retrieves the value of a cell.
+    ///
+    /// # Parameters
+    /// * `point`: The address of the cell.
+    ///
+    /// # Returns
+    /// The value or `None` if the point lies outside of the grid.
+    pub fn get(&self, point: impl Into<Point<U>>) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    /// Retrieves the value of a cell for modification.
+    ///
+    /// # Parameters
+    /// * `point`: The address of the cell.
+    ///
+    /// # Returns
+    /// The value or `None` if the point lies outside of the grid.
+    pub fn get_mut(&mut self, point: impl Into<Point<U>>) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    /// Replaces the value of a cell.
+    ///
+    /// # Parameters
+    /// * `point`: The address of the cell.
+    /// * `value`: The new value.
+    ///
+    /// # Returns
+    /// The previous value or `None` if the point lies outside of the grid (in which case the grid remains unchanged).
+    pub fn set(&mut self, point: impl Into<Point<U>>, value: T) -> Option<T> {
+        self.get_mut(point)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Sets all cells to the same value.
+    ///
+    /// # Parameters
+    /// * `value`: The value.
+    pub fn fill(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.cells.fill(value);
+    }
+}
+
+impl<T, U> Grid<T, U>
+where
+    U: Copy + Add<Output = U> + Sub<Output = U> + PartialOrd + Zero + One + IntoUsize,
+{
+    /// Creates an iterator over the addresses of all cells, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point<U>> {
+        let Size { width, height } = self.size;
+        // An empty grid has no last column or row to create the ranges with
+        let is_empty = self.cells.is_empty();
+        (!is_empty)
+            .then(|| FiniteRange::new(U::zero(), height - U::one()))
+            .into_iter()
+            .flatten()
+            .flat_map(move |y| {
+                FiniteRange::new(U::zero(), width - U::one()).map(move |x| Point { x, y })
+            })
+    }
+
+    /// Creates an iterator over all cells and their addresses, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point<U>, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// Creates an iterator over all cells and their addresses for modification, row by row.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point<U>, &mut T)> {
+        self.points().zip(self.cells.iter_mut())
+    }
+}
+
+/// An explicit conversion from one space (`F`) to another (`T`).
+///
+/// Spaces should never mix (see the crate documentation), so every conversion between them is performed by a mapper. This makes
+/// conversions like artwork space to screen space first-class objects that can be passed around and tested on their own.
+///
+/// A [`Rect`] is mapped by mapping its corners, so a mapper should preserve the order of the coordinates.
+pub trait SpaceMapper<F, T> {
+    /// Maps a point.
+    ///
+    /// # Parameters
+    /// * `point`: The point in the source space.
+    fn map_point(&self, point: Point<F>) -> Point<T>;
+
+    /// Maps a size.
+    ///
+    /// # Parameters
+    /// * `size`: The size in the source space.
+    fn map_size(&self, size: Size<F>) -> Size<T>;
+
+    /// Maps a rectangle.
+    ///
+    /// # Parameters
+    /// * `rect`: The rectangle in the source space.
+    fn map_rect(&self, rect: Rect<F>) -> Rect<T>
+    where
+        T: Copy + PartialOrd + PartialEq + Debug,
+    {
+        Rect::new(self.map_point(rect.min), self.map_point(rect.max))
+    }
+
+    /// Creates a mapper that applies this mapper first and another mapper after that.
+    ///
+    /// # Parameters
+    /// * `next`: The mapper that is applied to the output of this mapper.
+    fn then<M, U>(self, next: M) -> ChainedMapper<Self, M, T>
+    where
+        Self: Sized,
+        M: SpaceMapper<T, U>,
+    {
+        ChainedMapper {
+            first: self,
+            second: next,
+            _space: PhantomData,
+        }
+    }
+}
+
+/// A [`SpaceMapper`] that scales every coordinate by a factor.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScaleMapper<F, T> {
+    factor_x: T,
+    factor_y: T,
+    _from: PhantomData<fn(F) -> T>,
+}
+
+impl<F, T> ScaleMapper<F, T> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `factor_x`: The factor for the X-axis.
+    /// * `factor_y`: The factor for the Y-axis.
+    pub fn new(factor_x: impl Into<T>, factor_y: impl Into<T>) -> Self {
+        Self {
+            factor_x: factor_x.into(),
+            factor_y: factor_y.into(),
+            _from: PhantomData,
+        }
+    }
+}
+
+impl<F, T> ScaleMapper<F, T>
+where
+    T: Copy,
+{
+    /// Creates a new instance that scales both axes by the same factor.
+    ///
+    /// # Parameters
+    /// * `factor`: The factor.
+    pub fn new_uniform(factor: impl Into<T>) -> Self {
+        let factor = factor.into();
+        Self::new(factor, factor)
+    }
+}
+
+impl<F, T> SpaceMapper<F, T> for ScaleMapper<F, T>
+where
+    F: Copy + Add<Output = F> + Sub<Output = F> + One,
+    T: Copy + From<F> + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + One,
+{
+    fn map_point(&self, point: Point<F>) -> Point<T> {
+        Point {
+            x: T::from(point.x) * self.factor_x,
+            y: T::from(point.y) * self.factor_y,
+        }
+    }
+
+    fn map_size(&self, size: Size<F>) -> Size<T> {
+        Size {
+            width: T::from(size.width) * self.factor_x,
+            height: T::from(size.height) * self.factor_y,
+        }
+    }
+
+    /// Maps a rectangle. Unlike the default implementation, this scales the size of the rectangle rather than its corners, such
+    /// that the result covers the same area as [`map_size()`](Self::map_size).
+    fn map_rect(&self, rect: Rect<F>) -> Rect<T>
+    where
+        T: Copy + PartialOrd + PartialEq + Debug,
+    {
+        Rect::new_from_size(self.map_point(rect.min), self.map_size(rect.size()))
+    }
+}
+
+/// A [`SpaceMapper`] that moves every point by an offset. Sizes are not affected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OffsetMapper<F, T> {
+    offset: Vector<T>,
+    _from: PhantomData<fn(F) -> T>,
+}
+
+impl<F, T> OffsetMapper<F, T> {
+    /// Creates a new instance.
+    ///
+    /// # Parameters
+    /// * `offset`: The offset in the target space.
+    pub fn new(offset: Vector<T>) -> Self {
+        Self {
+            offset,
+            _from: PhantomData,
+        }
+    }
+}
+
+impl<F, T> SpaceMapper<F, T> for OffsetMapper<F, T>
+where
+    T: Copy + From<F> + Add<Output = T> + Sub<Output = T>,
+{
+    /// Maps a point. Like the underlying arithmetic on `T`, this panics (in debug builds) if the result can not be represented.
+    fn map_point(&self, point: Point<F>) -> Point<T> {
+        Point {
+            x: T::from(point.x),
+            y: T::from(point.y),
+        } + self.offset
+    }
+
+    fn map_size(&self, size: Size<F>) -> Size<T> {
+        Size {
+            width: T::from(size.width),
+            height: T::from(size.height),
+        }
+    }
+}
+
+/// A [`SpaceMapper`] that applies two mappers in succession (see [`SpaceMapper::then()`]). `M` is the intermediate space.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ChainedMapper<A, B, M> {
+    first: A,
+    second: B,
+    _space: PhantomData<fn() -> M>,
+}
+
+impl<F, M, T, A, B> SpaceMapper<F, T> for ChainedMapper<A, B, M>
+where
+    A: SpaceMapper<F, M>,
+    B: SpaceMapper<M, T>,
+    M: Copy + PartialOrd + PartialEq + Debug,
+{
+    fn map_point(&self, point: Point<F>) -> Point<T> {
+        self.second.map_point(self.first.map_point(point))
+    }
+
+    fn map_size(&self, size: Size<F>) -> Size<T> {
+        self.second.map_size(self.first.map_size(size))
+    }
+
+    fn map_rect(&self, rect: Rect<F>) -> Rect<T>
+    where
+        T: Copy + PartialOrd + PartialEq + Debug,
+    {
+        self.second.map_rect(self.first.map_rect(rect))
+    }
+}
+
+/// An affine transformation that scales by a whole factor and then translates.
+///
+/// Points are scaled with respect to the origin. Since a [`Rect`] is inclusive, every unit that it covers becomes a block of
+/// `scale` units, such that a scaled rectangle covers exactly the scaled area. For instance, a zoom of 3 turns the rectangle
+/// `(1, 1)` to `(2, 1)` into `(3, 3)` to `(8, 5)`.
+///
+/// Transformations can be combined with [`Transform2D::followed_by()`].
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Transform2D<T> {
+    scale_x: T,
+    scale_y: T,
+    translation: Vector<T>,
+}
+
+impl<T> Debug for Transform2D<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "scale({:?}, {:?}) translate{:?}",
+            self.scale_x, self.scale_y, self.translation
+        ))
+    }
+}
+
+impl<T> Transform2D<T>
+where
+    T: Copy + PartialEq + Zero + One,
+{
+    /// Creates a transformation that does not change anything.
+    pub fn identity() -> Self {
+        Self::new_scale(T::one(), T::one())
+    }
+
+    /// Creates a transformation that only scales.
+    ///
+    /// # Parameters
+    /// * `scale_x`: The factor for the X-axis.
+    /// * `scale_y`: The factor for the Y-axis.
+    pub fn new_scale(scale_x: impl Into<T>, scale_y: impl Into<T>) -> Self {
+        Self {
+            scale_x: scale_x.into(),
+            scale_y: scale_y.into(),
+            translation: Vector::new(T::zero(), T::zero()),
+        }
+    }
+
+    /// Creates a transformation that only translates.
+    ///
+    /// # Parameters
+    /// * `translation`: The translation.
+    pub fn new_translation(translation: Vector<T>) -> Self {
+        Self {
+            scale_x: T::one(),
+            scale_y: T::one(),
+            translation,
+        }
+    }
+}
+
+impl<T> Transform2D<T>
+where
+    T: Copy,
+{
+    /// Retrieves the factors for the X-axis and the Y-axis.
+    pub fn scale(&self) -> (T, T) {
+        (self.scale_x, self.scale_y)
+    }
+
+    /// Retrieves the translation (which is applied after the scaling).
+    pub fn translation(&self) -> Vector<T> {
+        self.translation
+    }
+}
+
+impl<T> Transform2D<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Zero + One,
+{
+    /// Creates a transformation that applies this transformation first and another transformation after that.
+    ///
+    /// # Parameters
+    /// * `next`: The transformation that is applied to the output of this transformation.
+    ///
+    /// # Example
+    ///
+    /// ```example
+    /// scale(2) translate(1, 0)  followed by  scale(3) translate(0, 5)  ===>  scale(6) translate(3, 5)
+    /// ```
+    pub fn followed_by(self, next: Transform2D<T>) -> Transform2D<T> {
+        let scale = |value: SignedValue<T>, factor: T| {
+            SignedValue::new(value.negative, value.magnitude * factor)
+        };
+        Transform2D {
+            scale_x: self.scale_x * next.scale_x,
+            scale_y: self.scale_y * next.scale_y,
+            translation: Vector {
+                x: scale(self.translation.x, next.scale_x).add(next.translation.x),
+                y: scale(self.translation.y, next.scale_y).add(next.translation.y),
+            },
+        }
+    }
+
+    /// Transforms a point. Like the underlying arithmetic on `T`, this panics (in debug builds) if the result can not be
+    /// represented.
+    ///
+    /// # Parameters
+    /// * `point`: The point.
+    pub fn apply_point(&self, point: Point<T>) -> Point<T> {
+        Point {
+            x: point.x * self.scale_x,
+            y: point.y * self.scale_y,
+        } + self.translation
+    }
+
+    /// Transforms a size. The translation does not affect sizes.
+    ///
+    /// # Parameters
+    /// * `size`: The size.
+    pub fn apply_size(&self, size: Size<T>) -> Size<T> {
+        Size {
+            width: size.width * self.scale_x,
+            height: size.height * self.scale_y,
+        }
+    }
+
+    /// Transforms a rectangle.
+    ///
+    /// # Parameters
+    /// * `rect`: The rectangle.
+    ///
+    /// # Returns
+    /// The rectangle that covers the scaled area (see [`Transform2D`]).
+    pub fn apply_rect(&self, rect: &Rect<T>) -> Rect<T>
+    where
+        T: Debug,
+    {
+        Rect::new_from_size(self.apply_point(rect.min), self.apply_size(rect.size()))
+    }
+}
+
+impl<T> SpaceMapper<T, T> for Transform2D<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + Zero + One,
+{
+    fn map_point(&self, point: Point<T>) -> Point<T> {
+        self.apply_point(point)
+    }
+
+    fn map_size(&self, size: Size<T>) -> Size<T> {
+        self.apply_size(size)
+    }
+
+    fn map_rect(&self, rect: Rect<T>) -> Rect<T>
+    where
+        T: Debug,
+    {
+        self.apply_rect(&rect)
+    }
+}
+
+/// Macro for generating simple "space unit" implementations.
+///
+/// For a floating-point raw type, the name must be preceded by `float`. Such a unit does not implement [`Eq`], [`Ord`] and [`Hash`],
+/// because the raw type does not either. It does not implement [`IntoUsize`] either, since it can hold fractional values.
+///
+/// # Parameters
+/// * `name`: Output type name.
+/// * `raw_type`: The raw (inner) value type.
+#[macro_export]
+macro_rules! space_unit {
+    ($(#[doc = $doc:expr])* float $name:ident, $raw_type:ty) => {
+        $(#[doc = $doc])*
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Copy, Clone, PartialEq, PartialOrd)]
+        pub struct $name($raw_type);
+
+        $crate::space_unit!(@impl $name, $raw_type, 0.0, 1.0);
+    };
+    ($(#[doc = $doc:expr])* $name:ident, $raw_type:ty) => {
+        $(#[doc = $doc])*
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        pub struct $name($raw_type);
+
+        $crate::space_unit!(@impl $name, $raw_type, 0, 1);
+
+        impl $crate::IntoUsize for $name {
+            #[inline(always)]
+            fn into_usize(self) -> usize {
+                $crate::IntoUsize::into_usize(self.0)
+            }
+        }
+    };
+    (@impl $name:ident, $raw_type:ty, $zero:literal, $one:literal) => {
+        impl std::ops::Add for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self::Output {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl std::ops::Sub for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn sub(self, rhs: Self) -> Self::Output {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl std::ops::Mul for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: Self) -> Self::Output {
+                Self(self.0 * rhs.0)
+            }
+        }
+
+        impl std::ops::Div for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: Self) -> Self::Output {
+                Self(self.0 / rhs.0)
+            }
+        }
+
+        impl std::ops::Rem for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn rem(self, rhs: Self) -> Self::Output {
+                Self(self.0 % rhs.0)
+            }
+        }
+
+        impl std::ops::Mul<$raw_type> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn mul(self, rhs: $raw_type) -> Self::Output {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl std::ops::Div<$raw_type> for $name {
+            type Output = Self;
+
+            #[inline(always)]
+            fn div(self, rhs: $raw_type) -> Self::Output {
+                Self(self.0 / rhs)
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl $crate::Zero for $name {
+            #[inline(always)]
+            fn zero() -> Self {
+                Self($zero)
+            }
+        }
+
+        impl $crate::One for $name {
+            #[inline(always)]
+            fn one() -> Self {
+                Self($one)
+            }
+        }
+
+        impl From<$raw_type> for $name {
+            #[inline(always)]
+            fn from(value: $raw_type) -> Self {
+                Self(value)
+            }
+        }
+
+        impl $crate::IntoF32 for $name {
+            #[inline(always)]
+            fn into_f32(self) -> f32 {
+                $crate::IntoF32::into_f32(self.0)
+            }
+        }
+
+        impl From<$name> for $raw_type {
+            #[inline(always)]
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl  $name {
+            #[inline(always)]
+            pub fn raw(&self) -> $raw_type {
+                self.0
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+space_unit!(
+    /// A space unit for tests.
+    TestSpaceUnit,
+    u16
+);
+
+#[cfg(test)]
+space_unit!(
+    /// A floating-point space unit for tests.
+    float TestFloatSpaceUnit,
+    f32
+);
+
+#[cfg(test)]
+mod test_finite_range {
+    use super::TestSpaceUnit;
+
+    type FiniteRange = super::FiniteRange<TestSpaceUnit>;
+
+    fn range(start: u16, end: u16) -> FiniteRange {
+        FiniteRange::new(start.into(), end.into())
+    }
+
+    fn raw(values: impl Iterator<Item = TestSpaceUnit>) -> Vec<u16> {
+        values.map(|value| value.raw()).collect()
+    }
+
+    #[test]
+    fn test_iterate() {
+        assert_eq!(vec![3, 4, 5], raw(range(3, 5)));
+        assert_eq!(vec![5, 4, 3], raw(range(3, 5).rev()));
+        assert_eq!(vec![7], raw(range(7, 7)));
+        assert_eq!(vec![7], raw(range(7, 7).rev()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_invalid() {
+        range(5, 4);
+    }
+
+    #[test]
+    fn test_try_new() {
+        let range = FiniteRange::try_new(4.into(), 4.into()).unwrap();
+        assert_eq!(vec![4], raw(range));
+        assert!(FiniteRange::try_new(5.into(), 4.into()).is_err());
+    }
+
+    #[test]
+    fn test_empty() {
+        let mut range = FiniteRange::empty();
+        assert!(range.is_empty());
+        assert_eq!(0, super::FiniteRange::<u16>::empty().len());
+        assert!(!range.contains(0.into()));
+        assert_eq!(None, range.next());
+        assert_eq!(None, range.next_back());
+    }
+
+    #[test]
+    fn test_double_ended() {
+        // Odd number of values: the crossing value is yielded exactly once
+        let mut iter = range(0, 4);
+        assert_eq!(Some(0), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(4), iter.next_back().map(|value| value.raw()));
+        assert_eq!(Some(1), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(3), iter.next_back().map(|value| value.raw()));
+        assert_eq!(Some(2), iter.next_back().map(|value| value.raw()));
+        assert_eq!(None, iter.next());
+        assert_eq!(None, iter.next_back());
+
+        // Even number of values
+        let mut iter = range(0, 3);
+        assert_eq!(Some(3), iter.next_back().map(|value| value.raw()));
+        assert_eq!(Some(0), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(1), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(2), iter.next_back().map(|value| value.raw()));
+        assert_eq!(None, iter.next_back());
+        assert_eq!(None, iter.next());
+    }
+
+    #[test]
+    fn test_double_ended_without_rem() {
+        // A unit that only supports the operations that are needed for iterating in both directions
+        #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+        struct Unit(u8);
+
+        impl super::One for Unit {
+            fn one() -> Self {
+                Unit(1)
+            }
+        }
+
+        impl std::ops::Add for Unit {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self::Output {
+                Unit(self.0 + rhs.0)
+            }
+        }
+
+        impl std::ops::Sub for Unit {
             type Output = Self;
 
-            #[inline(always)]
             fn sub(self, rhs: Self) -> Self::Output {
-                Self(self.0 - rhs.0)
+                Unit(self.0 - rhs.0)
             }
         }
 
-        impl std::ops::Mul for $name {
-            type Output = Self;
+        let values: Vec<_> = super::FiniteRange::new(Unit(1), Unit(3)).rev().collect();
+        assert_eq!(vec![Unit(3), Unit(2), Unit(1)], values);
+    }
+
+    #[test]
+    fn test_len() {
+        let mut iter = super::FiniteRange::new(3u16, 5u16);
+        assert_eq!(3, iter.len());
+        iter.next();
+        assert_eq!(2, iter.len());
+        iter.next_back();
+        assert_eq!(1, iter.len());
+        iter.next();
+        assert_eq!(0, iter.len());
+        assert!(iter.is_empty());
+
+        // A range over all values of a type that is narrower than usize does not overflow
+        assert_eq!(256, super::FiniteRange::new(0u8, u8::MAX).len());
+        assert_eq!(256, super::FiniteRange::new(0u8, u8::MAX).count());
+        assert_eq!(65536, range(0, u16::MAX).len());
+        assert_eq!(
+            usize::MAX,
+            super::FiniteRange::new(1usize, usize::MAX).len()
+        );
+        assert_eq!(1, super::FiniteRange::new(u64::MAX, u64::MAX).len());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_len_overflow() {
+        let _ = super::FiniteRange::new(0usize, usize::MAX).len();
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut iter = range(3, 5);
+        assert!(!iter.contains(2.into()));
+        assert!(iter.contains(3.into()));
+        assert!(iter.contains(5.into()));
+        assert!(!iter.contains(6.into()));
+
+        // Values that have been yielded are no longer contained
+        iter.next();
+        assert!(!iter.contains(3.into()));
+        iter.next_back();
+        assert!(!iter.contains(5.into()));
+        assert!(iter.contains(4.into()));
+        iter.next();
+        assert!(!iter.contains(4.into()));
+    }
+
+    #[test]
+    fn test_float() {
+        type FloatRange = super::FiniteRange<super::TestFloatSpaceUnit>;
+        let raw = |values: &mut dyn Iterator<Item = super::TestFloatSpaceUnit>| {
+            values.map(|value| value.raw()).collect::<Vec<_>>()
+        };
+
+        let range = || FloatRange::new(0.5.into(), 3.0.into());
+        assert_eq!(vec![0.5, 1.5, 2.5], raw(&mut range()));
+        assert_eq!(vec![3.0, 2.0, 1.0], raw(&mut range().rev()));
+        assert_eq!(vec![0.5, 1.5, 2.5], raw(&mut range().aligned()));
+        assert_eq!(vec![2.5, 1.5, 0.5], raw(&mut range().aligned().rev()));
+        assert_eq!(
+            vec![1.0, 2.0],
+            raw(&mut FloatRange::new(1.0.into(), 2.0.into()))
+        );
+        assert_eq!(
+            vec![0.25],
+            raw(&mut FloatRange::new(0.25.into(), 1.0.into()))
+        );
+
+        // Mixing directions never yields a value twice
+        let mut iter = FloatRange::new(0.0.into(), 1.5.into());
+        assert_eq!(Some(0.0), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(1.5), iter.next_back().map(|value| value.raw()));
+        assert_eq!(None, iter.next());
+
+        let mut iter = FloatRange::new(0.0.into(), 2.5.into()).aligned();
+        assert_eq!(Some(0.0), iter.next().map(|value| value.raw()));
+        assert_eq!(Some(2.0), iter.next_back().map(|value| value.raw()));
+        assert_eq!(Some(1.0), iter.next().map(|value| value.raw()));
+        assert_eq!(None, iter.next_back());
+    }
+
+    #[test]
+    fn test_float_rev() {
+        type FloatRange = super::FiniteRange<super::TestFloatSpaceUnit>;
+
+        for (start, end) in [
+            (0.0, 2.5),
+            (0.5, 3.0),
+            (0.25, 0.5),
+            (1.0, 4.0),
+            (-1.5, 1.75),
+        ] {
+            let range = || {
+                FloatRange::new(start.into(), end.into())
+                    .aligned()
+                    .map(|value| value.raw())
+            };
+            let mut forward: Vec<f32> = range().collect();
+            forward.reverse();
+            assert_eq!(forward, range().rev().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_stepped() {
+        assert_eq!(vec![0, 8, 16], raw(range(0, 20).stepped(8.into())));
+        assert_eq!(vec![0, 8, 16], raw(range(0, 16).stepped(8.into())));
+        assert_eq!(vec![3], raw(range(3, 5).stepped(16.into())));
+        assert_eq!(vec![3, 4, 5], raw(range(3, 5).stepped(1.into())));
+
+        // Values that have been yielded are skipped
+        let mut iter = range(0, 4);
+        iter.next();
+        assert_eq!(vec![1, 3], raw(iter.stepped(2.into())));
+
+        // A range that ends at the maximum value of the type does not overflow
+        assert_eq!(
+            vec![0, 128],
+            super::FiniteRange::new(0u8, u8::MAX)
+                .stepped(128)
+                .collect::<Vec<_>>()
+        );
+
+        let floats = super::FiniteRange::new(0.0f32, 1.0).stepped(0.25);
+        assert_eq!(vec![0.0, 0.25, 0.5, 0.75, 1.0], floats.collect::<Vec<_>>());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stepped_zero() {
+        let _ = range(0, 4).stepped(0.into());
+    }
+
+    #[test]
+    fn test_from_range_inclusive() {
+        assert_eq!(vec![1, 2, 3], raw(FiniteRange::from(1.into()..=3.into())));
+        assert_eq!(
+            vec![0, 1],
+            super::FiniteRange::from((0u8, 1u8))
+                .map(u16::from)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_space_unit {
+    use super::{TestFloatSpaceUnit, TestSpaceUnit};
+
+    #[test]
+    fn test_raw_arithmetic() {
+        let value = TestSpaceUnit::from(6);
+        assert_eq!(TestSpaceUnit::from(48), value * 8);
+        assert_eq!(TestSpaceUnit::from(3), value / 2);
+        assert_eq!(6, u16::from(value));
+
+        let value = TestFloatSpaceUnit::from(1.5);
+        assert_eq!(TestFloatSpaceUnit::from(3.0), value * 2.0);
+        assert_eq!(TestFloatSpaceUnit::from(0.75), value / 2.0);
+        assert_eq!(1.5, f32::from(value));
+    }
+}
+
+#[cfg(test)]
+mod test_vector {
+    use super::TestSpaceUnit;
+
+    type Point = super::Point<TestSpaceUnit>;
+    type Vector = super::Vector<TestSpaceUnit>;
+
+    #[test]
+    fn test_between() {
+        let vector = Vector::between((10, 4).into(), (7, 9).into());
+        assert_eq!((true, 3.into()), vector.x());
+        assert_eq!((false, 5.into()), vector.y());
+        assert_eq!(vector, Point::new(7, 9) - Point::new(10, 4));
+        assert_eq!("[-3, +5]", format!("{:?}", vector));
+    }
+
+    #[test]
+    fn test_zero_is_not_negative() {
+        assert_eq!(
+            Vector::new(0, 0),
+            Vector::from_components((true, 0), (true, 0))
+        );
+        assert_eq!(Vector::new(0, 0), -Vector::new(0, 0));
+        assert_eq!(
+            Vector::new(0, 0),
+            Vector::new(3, 4) + Vector::from_components((true, 3), (true, 4))
+        );
+    }
+
+    #[test]
+    fn test_point_arithmetic() {
+        let from = Point::new(10, 4);
+        let to = Point::new(7, 9);
+        assert_eq!(to, from + (to - from));
+        assert_eq!(from, to - (to - from));
+        assert_eq!(from, to + (from - to));
+    }
+
+    #[test]
+    fn test_vector_arithmetic() {
+        let a = Vector::from_components((true, 3), (false, 5));
+        let b = Vector::from_components((false, 8), (true, 2));
+        assert_eq!(Vector::from_components((false, 5), (false, 3)), a + b);
+        assert_eq!(Vector::from_components((true, 11), (false, 7)), a - b);
+        assert_eq!(Vector::from_components((false, 3), (true, 5)), -a);
+    }
+
+    #[test]
+    fn test_point_conversions() {
+        let point = Point::new(3, 4);
+        let vector = Vector::from(point);
+        assert_eq!(Vector::new(3, 4), vector);
+        assert_eq!(Ok(point), Point::try_from(vector));
+        assert!(Point::try_from(-vector).is_err());
+    }
+}
+
+#[cfg(test)]
+mod test_direction {
+    use super::{Direction, TestSpaceUnit};
+
+    type Point = super::Point<TestSpaceUnit>;
+    type Vector = super::Vector<TestSpaceUnit>;
+
+    #[test]
+    fn test_vector() {
+        assert_eq!(
+            Vector::from_components((false, 0), (true, 1)),
+            Direction::Up.vector()
+        );
+        assert_eq!(Vector::new(1, 1), Direction::DownRight.vector());
+        assert_eq!(
+            Vector::from_components((true, 1), (false, 1)),
+            Direction::DownLeft.vector()
+        );
+    }
+
+    #[test]
+    fn test_opposite() {
+        for direction in Direction::ALL {
+            assert_ne!(direction, direction.opposite());
+            assert_eq!(direction, direction.opposite().opposite());
+            assert_eq!(
+                -direction.vector::<TestSpaceUnit>(),
+                direction.opposite().vector()
+            );
+            assert_eq!(direction.is_diagonal(), direction.opposite().is_diagonal());
+        }
+        assert_eq!(4, Direction::ALL.iter().filter(|d| d.is_diagonal()).count());
+    }
+
+    #[test]
+    fn test_neighbor() {
+        let point = Point::new(5, 5);
+        let neighbors: Vec<_> = Direction::ALL.iter().map(|d| point.neighbor(*d)).collect();
+        assert_eq!(
+            vec![
+                Point::new(5, 4),
+                Point::new(6, 4),
+                Point::new(6, 5),
+                Point::new(6, 6),
+                Point::new(5, 6),
+                Point::new(4, 6),
+                Point::new(4, 5),
+                Point::new(4, 4),
+            ],
+            neighbors
+        );
+        assert_eq!(
+            point,
+            point
+                .neighbor(Direction::UpLeft)
+                .neighbor(Direction::DownRight)
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_rect {
+    use super::TestSpaceUnit;
+
+    type Rect = super::Rect<TestSpaceUnit>;
+    type RectIntersection = super::RectIntersection<TestSpaceUnit>;
+    type BoundingBox = super::BoundingBox<TestSpaceUnit>;
+    type Vector = super::Vector<TestSpaceUnit>;
+
+    #[test]
+    fn test_intersect_point_inside() {
+        let expected_intersection = RectIntersection::Both {
+            top_left: ((3, 14), (5, 24)).into(),
+            top_right: ((6, 14), (12, 24)).into(),
+            bottom_left: ((3, 25), (5, 30)).into(),
+            bottom_right: ((6, 25), (12, 30)).into(),
+        };
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((5, 24));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersect_point_outside_before() {
+        let expected_intersection = RectIntersection::None;
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((2, 12));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersect_point_outside_after() {
+        let expected_intersection = RectIntersection::None;
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((14, 31));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersect_point_vertical() {
+        let expected_intersection = RectIntersection::Vertical {
+            left: ((3, 14), (5, 30)).into(),
+            right: ((6, 14), (12, 30)).into(),
+        };
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((5, 31));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersect_point_horizontal() {
+        let expected_intersection = RectIntersection::Horizontal {
+            top: ((3, 14), (12, 24)).into(),
+            bottom: ((3, 25), (12, 30)).into(),
+        };
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((2, 24));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersect_point_top_left() {
+        let expected_intersection = RectIntersection::Both {
+            top_left: ((3, 14), (3, 14)).into(),
+            top_right: ((4, 14), (12, 14)).into(),
+            bottom_left: ((3, 15), (3, 30)).into(),
+            bottom_right: ((4, 15), (12, 30)).into(),
+        };
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((3, 14));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_translate() {
+        let rect = Rect::from(((3, 12), (9, 19)));
+        assert_eq!(
+            Rect::from(((5, 8), (11, 15))),
+            rect.translate(Vector::from_components((false, 2), (true, 4)))
+        );
+        assert_eq!(rect, rect.translate(Vector::new(0, 0)));
+        assert_eq!(rect.size(), rect.translate(Vector::new(7, 1)).size());
+    }
+
+    #[test]
+    fn test_inflate_deflate() {
+        let rect = Rect::from(((2, 4), (8, 6)));
+        let inflated = Rect::from(((1, 3), (9, 7)));
+        assert_eq!(inflated, rect.inflate(1.into()));
+        assert_eq!(Some(rect), inflated.deflate(1.into()));
+        assert_eq!(rect, rect.inflate(0.into()));
+
+        // A single row remains
+        assert_eq!(Some(Rect::from(((3, 5), (7, 5)))), rect.deflate(1.into()));
+        // Nothing remains
+        assert_eq!(None, rect.deflate(2.into()));
+        assert_eq!(None, Rect::from(((0, 0), (0, 0))).deflate(1.into()));
+    }
+
+    #[test]
+    fn test_intersect_rect_overlap() {
+        let rect = Rect::from(((3, 12), (9, 19)));
+        let other = Rect::from(((6, 14), (12, 21)));
+        let expected = Some(Rect::from(((6, 14), (9, 19))));
+        assert_eq!(expected, rect.intersect_rect(&other));
+        assert_eq!(expected, other.intersect_rect(&rect));
+    }
+
+    #[test]
+    fn test_intersect_rect_contained() {
+        let rect = Rect::from(((3, 12), (9, 19)));
+        let inner = Rect::from(((4, 13), (5, 14)));
+        assert_eq!(Some(inner), rect.intersect_rect(&inner));
+        assert_eq!(Some(rect), rect.intersect_rect(&rect));
+    }
+
+    #[test]
+    fn test_intersect_rect_edge() {
+        // The bounds are inclusive, so rectangles that share an edge overlap in a single row
+        let rect = Rect::from(((3, 12), (9, 19)));
+        let below = Rect::from(((0, 19), (20, 25)));
+        assert_eq!(
+            Some(Rect::from(((3, 19), (9, 19)))),
+            rect.intersect_rect(&below)
+        );
+    }
+
+    #[test]
+    fn test_intersect_rect_disjoint() {
+        let rect = Rect::from(((3, 12), (9, 19)));
+        assert_eq!(None, rect.intersect_rect(&Rect::from(((10, 12), (14, 19)))));
+        assert_eq!(None, rect.intersect_rect(&Rect::from(((3, 20), (9, 24)))));
+        // Overlapping on one axis is not enough
+        assert_eq!(None, rect.intersect_rect(&Rect::from(((0, 0), (20, 11)))));
+    }
+
+    #[test]
+    fn test_union() {
+        let rect: Rect = ((3, 12), (9, 19)).into();
+        let other: Rect = ((6, 14), (12, 21)).into();
+        let expected: Rect = ((3, 12), (12, 21)).into();
+        assert_eq!(expected, rect.union(&other));
+        assert_eq!(expected, other.union(&rect));
+        assert_eq!(rect, rect.union(&rect));
+
+        // The area between disjoint rectangles is covered as well
+        let far: Rect = ((20, 0), (22, 1)).into();
+        assert_eq!(Rect::from(((3, 0), (22, 19))), rect.union(&far));
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let mut bounds = BoundingBox::default();
+        assert_eq!(None, bounds.rect());
+
+        bounds.add_point((5, 24));
+        assert_eq!(Some(Rect::from(((5, 24), (5, 24)))), bounds.rect());
+        bounds.add_rect(&((3, 14), (4, 20)).into());
+        assert_eq!(Some(Rect::from(((3, 14), (5, 24)))), bounds.rect());
+
+        let rects = [
+            Rect::from(((3, 14), (12, 30))),
+            Rect::from(((0, 20), (2, 22))),
+        ];
+        let bounds: BoundingBox = rects.into_iter().collect();
+        assert_eq!(Some(Rect::from(((0, 14), (12, 30)))), bounds.rect());
+    }
+
+    #[test]
+    fn test_intersect_point_bottom_right() {
+        let expected_intersection = RectIntersection::None;
+
+        let rect: Rect = ((3, 14), (12, 30)).into();
+        let intersection = rect.intersect_point((12, 30));
+        assert_eq!(expected_intersection, intersection);
+    }
+
+    #[test]
+    fn test_intersection_iter() {
+        let rect: Rect = ((3, 12), (9, 19)).into();
+        let intersection = rect.intersect_point((6, 16));
+        assert_eq!(4, intersection.count());
+        let expected: Vec<Rect> = vec![
+            ((3, 12), (6, 16)).into(),
+            ((7, 12), (9, 16)).into(),
+            ((3, 17), (6, 19)).into(),
+            ((7, 17), (9, 19)).into(),
+        ];
+        assert_eq!(expected, intersection.iter().copied().collect::<Vec<_>>());
+        assert_eq!(expected, intersection.into_iter().collect::<Vec<_>>());
+
+        let intersection = rect.intersect_point((6, 19));
+        assert_eq!(2, intersection.count());
+        assert_eq!(2, (&intersection).into_iter().count());
+
+        let intersection = rect.intersect_point((9, 19));
+        assert_eq!(0, intersection.count());
+        assert_eq!(None, intersection.iter().next());
+    }
+
+    #[test]
+    fn test_intersection_map() {
+        let rect: Rect = ((3, 12), (9, 19)).into();
+        let moved = rect
+            .intersect_point((6, 19))
+            .map(|rect| rect.translate(Vector::new(1, 2)));
+        assert_eq!(
+            RectIntersection::Vertical {
+                left: ((4, 14), (7, 21)).into(),
+                right: ((8, 14), (10, 21)).into(),
+            },
+            moved
+        );
+        assert_eq!(
+            RectIntersection::None,
+            rect.intersect_point((9, 19))
+                .map(|rect| rect.translate(Vector::new(1, 2)))
+        );
+    }
+
+    #[test]
+    fn test_normalized_within() {
+        let outer = Rect::new((8, 16), (15, 31));
+        assert_eq!([0.0, 0.0, 1.0, 1.0], outer.normalized_within(&outer));
+        assert_eq!(
+            [0.5, 0.0, 1.0, 0.25],
+            Rect::new((12, 16), (15, 19)).normalized_within(&outer)
+        );
+        assert_eq!(
+            [0.125, 0.5, 0.25, 0.5625],
+            Rect::new((9, 24), (9, 24)).normalized_within(&outer)
+        );
+    }
+
+    fn size(width: u16, height: u16) -> super::Size<TestSpaceUnit> {
+        super::Size::new(width, height)
+    }
+
+    #[test]
+    fn test_tiles() {
+        let tiles: Vec<Rect> = Rect::new((0, 0), (19, 9)).tiles(size(8, 8)).collect();
+        assert_eq!(
+            vec![
+                Rect::new((0, 0), (7, 7)),
+                Rect::new((8, 0), (15, 7)),
+                Rect::new((16, 0), (19, 7)),
+                Rect::new((0, 8), (7, 9)),
+                Rect::new((8, 8), (15, 9)),
+                Rect::new((16, 8), (19, 9)),
+            ],
+            tiles
+        );
+
+        // A rectangle that is a multiple of the tile size has no partial tiles
+        let tiles: Vec<Rect> = Rect::new((4, 2), (19, 9)).tiles(size(8, 8)).collect();
+        assert_eq!(
+            vec![Rect::new((4, 2), (11, 9)), Rect::new((12, 2), (19, 9))],
+            tiles
+        );
+
+        // A tile that is larger than the rectangle is cut off on both axes
+        let rect = Rect::new((3, 5), (6, 7));
+        assert_eq!(vec![rect], rect.tiles(size(16, 16)).collect::<Vec<_>>());
+
+        // The tiles at the edge of the space do not overflow
+        let tiles: Vec<super::Rect<u8>> = super::Rect::new((250u8, 0u8), (255u8, 0u8))
+            .tiles(super::Size::new(4u8, 1u8))
+            .collect();
+        assert_eq!(
+            vec![
+                super::Rect::new((250u8, 0u8), (253u8, 0u8)),
+                super::Rect::new((254u8, 0u8), (255u8, 0u8))
+            ],
+            tiles
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_tiles_invalid_size() {
+        Rect::new((0, 0), (19, 9)).tiles(size(8, 0));
+    }
+
+    #[test]
+    fn test_edge() {
+        use super::Direction;
+
+        let rect = Rect::new((3, 14), (12, 30));
+        assert_eq!(Rect::new((3, 14), (12, 14)), rect.edge(Direction::Up));
+        assert_eq!(Rect::new((3, 30), (12, 30)), rect.edge(Direction::Down));
+        assert_eq!(Rect::new((3, 14), (3, 30)), rect.edge(Direction::Left));
+        assert_eq!(Rect::new((12, 14), (12, 30)), rect.edge(Direction::Right));
+        assert_eq!(Rect::new((3, 14), (3, 14)), rect.edge(Direction::UpLeft));
+        assert_eq!(
+            Rect::new((12, 30), (12, 30)),
+            rect.edge(Direction::DownRight)
+        );
+    }
 
-            #[inline(always)]
-            fn mul(self, rhs: Self) -> Self::Output {
-                Self(self.0 * rhs.0)
-            }
+    #[test]
+    fn test_iter_morton() {
+        fn points(rect: Rect) -> Vec<(u16, u16)> {
+            rect.iter_morton()
+                .map(|point| (point.x.raw(), point.y.raw()))
+                .collect()
         }
 
-        impl std::ops::Div for $name {
-            type Output = Self;
+        assert_eq!(
+            vec![
+                (0, 0),
+                (1, 0),
+                (0, 1),
+                (1, 1),
+                (2, 0),
+                (3, 0),
+                (2, 1),
+                (3, 1),
+                (0, 2),
+                (1, 2),
+                (0, 3),
+                (1, 3),
+                (2, 2),
+                (3, 2),
+                (2, 3),
+                (3, 3),
+            ],
+            points(Rect::new((0, 0), (3, 3)))
+        );
 
-            #[inline(always)]
-            fn div(self, rhs: Self) -> Self::Output {
-                Self(self.0 / rhs.0)
-            }
-        }
+        // Points outside of the rectangle are left out
+        assert_eq!(
+            vec![
+                (5, 7),
+                (6, 7),
+                (5, 8),
+                (6, 8),
+                (7, 7),
+                (7, 8),
+                (5, 9),
+                (6, 9),
+                (7, 9)
+            ],
+            points(Rect::new((5, 7), (7, 9)))
+        );
+        assert_eq!(vec![(5, 7)], points(Rect::new((5, 7), (5, 7))));
+        assert_eq!(
+            vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0)],
+            points(Rect::new((0, 0), (4, 0)))
+        );
 
-        impl std::ops::Rem for $name {
-            type Output = Self;
+        // Every point is visited exactly once
+        let rect = Rect::new((3, 14), (12, 30));
+        let mut actual = points(rect);
+        actual.sort();
+        let expected: Vec<_> = (3..=12)
+            .flat_map(|x| (14..=30).map(move |y| (x, y)))
+            .collect();
+        assert_eq!(expected, actual);
+    }
 
-            #[inline(always)]
-            fn rem(self, rhs: Self) -> Self::Output {
-                Self(self.0 % rhs.0)
-            }
-        }
+    #[test]
+    fn test_iter_morton_max() {
+        // The quadrants that cover this rectangle would exceed the maximum value of the unit
+        let rect = super::Rect::<u8>::new((250, 0), (255, 200));
+        assert_eq!(6 * 201, rect.iter_morton().count());
+        assert_eq!(
+            Some(super::Point::new(255, 200)),
+            rect.iter_morton().max_by_key(|p| (p.y, p.x))
+        );
+    }
 
-        impl core::fmt::Debug for $name {
-            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-                core::fmt::Debug::fmt(&self.0, f)
-            }
-        }
+    #[test]
+    fn test_exclusive() {
+        let rect = Rect::new((3, 14), (12, 30));
+        let (min, rect_size) = rect.to_exclusive();
+        assert_eq!(super::Point::new(3, 14), min);
+        assert_eq!(size(10, 17), rect_size);
+        assert_eq!(Some(rect), Rect::from_exclusive(min, rect_size));
 
-        impl $crate::Zero for $name {
-            #[inline(always)]
-            fn zero() -> Self {
-                Self(0)
-            }
-        }
+        // A single point
+        let rect = Rect::new((5, 5), (5, 5));
+        assert_eq!((super::Point::new(5, 5), size(1, 1)), rect.to_exclusive());
+        assert_eq!(Some(rect), Rect::from_exclusive((5, 5), size(1, 1)));
 
-        impl $crate::One for $name {
-            #[inline(always)]
-            fn one() -> Self {
-                Self(1)
-            }
-        }
+        // Empty ranges have no inclusive counterpart
+        assert_eq!(None, Rect::from_exclusive((5, 5), size(0, 1)));
+        assert_eq!(None, Rect::from_exclusive((5, 5), size(1, 0)));
+    }
 
-        impl From<$raw_type> for $name {
-            #[inline(always)]
-            fn from(value: $raw_type) -> Self {
-                Self(value)
-            }
-        }
+    #[test]
+    fn test_try_new() {
+        assert_eq!(
+            Ok(Rect::new((3, 14), (12, 30))),
+            Rect::try_new((3, 14), (12, 30))
+        );
+        assert_eq!(
+            Ok(Rect::new((3, 14), (3, 14))),
+            Rect::try_new((3, 14), (3, 14))
+        );
+        assert!(Rect::try_new((13, 14), (12, 30)).is_err());
+        assert!(Rect::try_new((3, 31), (12, 30)).is_err());
+    }
 
-        impl  $name {
-            #[inline(always)]
-            pub fn raw(&self) -> $raw_type {
-                self.0
-            }
-        }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize() {
+        let rect: Rect =
+            serde_json::from_str(r#"{ "min": { "x": 3, "y": 14 }, "max": { "x": 12, "y": 30 } }"#)
+                .unwrap();
+        assert_eq!(Rect::new((3, 14), (12, 30)), rect);
+        assert_eq!(
+            rect,
+            serde_json::from_str(&serde_json::to_string(&rect).unwrap()).unwrap()
+        );
+
+        let err = serde_json::from_str::<Rect>(
+            r#"{ "min": { "x": 3, "y": 31 }, "max": { "x": 12, "y": 30 } }"#,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Invalid rectangle: the start position (3, 31) lies beyond the end position (12, 30).",
+            err.to_string()
+        );
     }
 }
 
 #[cfg(test)]
-space_unit!(
-    /// A space unit for tests.
-    TestSpaceUnit,
-    u16
-);
+mod test_polygon {
+    use super::TestSpaceUnit;
+
+    type Point = super::Point<TestSpaceUnit>;
+    type Polygon = super::Polygon<TestSpaceUnit>;
+    type Size = super::Size<TestSpaceUnit>;
+
+    fn points(coords: &[(u16, u16)]) -> Vec<Point> {
+        coords.iter().map(|coords| (*coords).into()).collect()
+    }
+
+    #[test]
+    fn test_contains_concave() {
+        // An L-shape
+        let polygon = Polygon::new(points(&[(0, 0), (4, 0), (4, 2), (2, 2), (2, 6), (0, 6)]));
+
+        assert!(polygon.contains((1, 1)));
+        assert!(polygon.contains((3, 1)));
+        assert!(polygon.contains((1, 5)));
+        assert!(!polygon.contains((3, 3)));
+        assert!(!polygon.contains((5, 1)));
+        assert!(!polygon.contains((1, 7)));
+    }
+
+    #[test]
+    fn test_contains_edges() {
+        let polygon = Polygon::new(points(&[(2, 2), (6, 2), (4, 6)]));
+
+        assert!(polygon.contains((2, 2)));
+        assert!(polygon.contains((4, 2)));
+        assert!(polygon.contains((5, 4)));
+        assert!(polygon.contains((4, 6)));
+        assert!(!polygon.contains((1, 2)));
+        assert!(!polygon.contains((6, 4)));
+    }
+
+    #[test]
+    fn test_contains_degenerate() {
+        assert!(!Polygon::new(Vec::new()).contains((0, 0)));
+
+        let point = Polygon::new(points(&[(3, 3)]));
+        assert!(point.contains((3, 3)));
+        assert!(!point.contains((3, 4)));
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let hull = Polygon::convex_hull(points(&[
+            (2, 2),
+            (0, 0),
+            (4, 0),
+            (2, 0),
+            (1, 3),
+            (4, 4),
+            (0, 4),
+            (4, 0),
+        ]));
+        assert_eq!(points(&[(0, 0), (4, 0), (4, 4), (0, 4)]), hull.points());
+    }
+
+    #[test]
+    fn test_convex_hull_collinear() {
+        let hull = Polygon::convex_hull(points(&[(0, 0), (2, 2), (1, 1), (3, 3)]));
+        assert_eq!(points(&[(0, 0), (3, 3)]), hull.points());
+    }
+
+    #[test]
+    fn test_convex_hull_of_mask() {
+        // . . # . .
+        // . # # # .
+        // # # # # #
+        // . . . . .
+        let rows = ["..#..", ".###.", "#####", "....."];
+        let hull = Polygon::convex_hull_of_mask(Size::new(5, 4), |p| {
+            rows[usize::from(p.y.raw())].as_bytes()[usize::from(p.x.raw())] == b'#'
+        });
+
+        assert_eq!(points(&[(0, 2), (2, 0), (4, 2)]), hull.points());
+        assert_eq!(Some(((0, 0), (4, 2)).into()), hull.bounding_rect());
+        assert!(hull.contains((1, 1)));
+        assert!(!hull.contains((0, 1)));
+        assert!(!hull.contains((2, 3)));
+    }
+
+    #[test]
+    fn test_convex_hull_of_empty_mask() {
+        assert!(Polygon::convex_hull_of_mask(Size::new(4, 4), |_| false).is_empty());
+        assert!(Polygon::convex_hull_of_mask(Size::new(0, 4), |_| true).is_empty());
+    }
+}
 
 #[cfg(test)]
-mod test_rect {
+mod test_circle {
     use super::TestSpaceUnit;
 
+    type Circle = super::Circle<TestSpaceUnit>;
     type Rect = super::Rect<TestSpaceUnit>;
-    type RectIntersection = super::RectIntersection<TestSpaceUnit>;
 
     #[test]
-    fn test_intersect_point_inside() {
-        let expected_intersection = RectIntersection::Both {
-            top_left: ((3, 14), (5, 24)).into(),
-            top_right: ((6, 14), (12, 24)).into(),
-            bottom_left: ((3, 25), (5, 30)).into(),
-            bottom_right: ((6, 25), (12, 30)).into(),
-        };
+    fn test_contains_point() {
+        let circle = Circle::new((10, 10), 5u16);
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((5, 24));
-        assert_eq!(expected_intersection, intersection);
+        assert!(circle.contains_point((10, 10)));
+        assert!(circle.contains_point((13, 14)));
+        assert!(circle.contains_point((6, 7)));
+        // On the circumference
+        assert!(circle.contains_point((15, 10)));
+        assert!(circle.contains_point((10, 5)));
+        // Inside the bounding rectangle, but outside of the circle
+        assert!(!circle.contains_point((14, 14)));
+        assert!(!circle.contains_point((6, 6)));
+        assert!(!circle.contains_point((16, 10)));
+        // Far away, where the squared distance would overflow
+        assert!(!circle.contains_point((10, 60000)));
     }
 
     #[test]
-    fn test_intersect_point_outside_before() {
-        let expected_intersection = RectIntersection::None;
+    fn test_contains_point_large_radius() {
+        // The square of the radius fits in the unit, but the sum of the squares of the distances does not
+        let circle = Circle::new((200, 200), 200u16);
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((2, 12));
-        assert_eq!(expected_intersection, intersection);
+        assert!(!circle.contains_point((400, 400)));
+        assert!(!circle.contains_point((0, 0)));
+        assert!(circle.contains_point((400, 200)));
+        assert!(circle.contains_point((341, 341)));
+        assert!(!circle.contains_point((342, 342)));
     }
 
     #[test]
-    fn test_intersect_point_outside_after() {
-        let expected_intersection = RectIntersection::None;
+    fn test_contains_point_zero_radius() {
+        let circle = Circle::new((3, 4), 0u16);
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((14, 31));
-        assert_eq!(expected_intersection, intersection);
+        assert!(circle.contains_point((3, 4)));
+        assert!(!circle.contains_point((3, 5)));
+        assert!(!circle.contains_point((2, 4)));
     }
 
     #[test]
-    fn test_intersect_point_vertical() {
-        let expected_intersection = RectIntersection::Vertical {
-            left: ((3, 14), (5, 30)).into(),
-            right: ((6, 14), (12, 30)).into(),
-        };
+    fn test_bounding_rect() {
+        let circle = Circle::new((10, 20), 5u16);
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((5, 31));
-        assert_eq!(expected_intersection, intersection);
+        assert_eq!(Rect::new((5, 15), (15, 25)), circle.bounding_rect());
+        assert_eq!(Rect::new((5, 15), (15, 25)), Rect::from(circle));
+        assert_eq!(
+            Rect::new((3, 4), (3, 4)),
+            Circle::new((3, 4), 0u16).bounding_rect()
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_grid {
+    use super::{Point, Size};
+
+    type Grid = super::Grid<char, u16>;
+
+    fn p(x: u16, y: u16) -> Point<u16> {
+        Point { x, y }
+    }
+
+    fn size(width: u16, height: u16) -> Size<u16> {
+        Size { width, height }
     }
 
     #[test]
-    fn test_intersect_point_horizontal() {
-        let expected_intersection = RectIntersection::Horizontal {
-            top: ((3, 14), (12, 24)).into(),
-            bottom: ((3, 25), (12, 30)).into(),
-        };
+    fn test_get_set() {
+        let mut grid = Grid::new(size(3, 2), '.');
+        assert_eq!(size(3, 2), grid.size());
+        assert_eq!(Some(&'.'), grid.get(p(2, 1)));
+        assert_eq!(None, grid.get(p(3, 0)));
+        assert_eq!(None, grid.get(p(0, 2)));
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((2, 24));
-        assert_eq!(expected_intersection, intersection);
+        assert_eq!(Some('.'), grid.set(p(2, 0), 'a'));
+        assert_eq!(Some('a'), grid.set(p(2, 0), 'b'));
+        assert_eq!(None, grid.set(p(3, 0), 'c'));
+        *grid.get_mut(p(0, 1)).unwrap() = 'd';
+        assert_eq!(&['.', '.', 'b', 'd', '.', '.'], grid.cells());
+
+        grid.fill('e');
+        assert_eq!(&['e'; 6], grid.cells());
     }
 
     #[test]
-    fn test_intersect_point_top_left() {
-        let expected_intersection = RectIntersection::Both {
-            top_left: ((3, 14), (3, 14)).into(),
-            top_right: ((4, 14), (12, 14)).into(),
-            bottom_left: ((3, 15), (3, 30)).into(),
-            bottom_right: ((4, 15), (12, 30)).into(),
-        };
+    fn test_from_cells() {
+        let grid = Grid::from_cells(size(2, 2), vec!['a', 'b', 'c', 'd']).unwrap();
+        assert_eq!(Some(&'b'), grid.get(p(1, 0)));
+        assert_eq!(Some(&'c'), grid.get(p(0, 1)));
+        assert_eq!(Some(3), grid.index_of(p(1, 1)));
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((3, 14));
-        assert_eq!(expected_intersection, intersection);
+        assert_eq!(
+            Err("Invalid number of cells: 3 (expected 4).".to_string()),
+            Grid::from_cells(size(2, 2), vec!['a', 'b', 'c'])
+        );
     }
 
     #[test]
-    fn test_intersect_point_bottom_right() {
-        let expected_intersection = RectIntersection::None;
+    fn test_iter() {
+        let mut grid = Grid::from_cells(size(2, 2), vec!['a', 'b', 'c', 'd']).unwrap();
+        let cells: Vec<(Point<u16>, char)> =
+            grid.iter().map(|(point, cell)| (point, *cell)).collect();
+        assert_eq!(
+            vec![
+                (p(0, 0), 'a'),
+                (p(1, 0), 'b'),
+                (p(0, 1), 'c'),
+                (p(1, 1), 'd'),
+            ],
+            cells
+        );
 
-        let rect: Rect = ((3, 14), (12, 30)).into();
-        let intersection = rect.intersect_point((12, 30));
-        assert_eq!(expected_intersection, intersection);
+        for (point, cell) in grid.iter_mut() {
+            if point.x == point.y {
+                *cell = '*';
+            }
+        }
+        assert_eq!(&['*', 'b', 'c', '*'], grid.cells());
+
+        let empty = Grid::new(size(0, 3), '.');
+        assert_eq!(0, empty.iter().count());
+    }
+
+    #[test]
+    fn test_space_unit() {
+        use super::TestSpaceUnit;
+
+        let mut grid = super::Grid::<char, TestSpaceUnit>::new(Size::new(3, 2), '.');
+        assert_eq!(Some('.'), grid.set((2, 1), 'a'));
+        assert_eq!(Some(5), grid.index_of((2, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(
+            vec![Point::new(0, 0), Point::new(1, 0), Point::new(2, 0)],
+            grid.points().take(3).collect::<Vec<Point<TestSpaceUnit>>>()
+        );
+
+        let grid =
+            super::Grid::<char, u32>::from_cells(Size::new(1u32, 2u32), vec!['a', 'b']).unwrap();
+        assert_eq!(Some(&'b'), grid.get((0u32, 1u32)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let grid = Grid::from_cells(size(2, 1), vec!['a', 'b']).unwrap();
+        let json = serde_json::to_string(&grid).unwrap();
+        assert_eq!(grid, serde_json::from_str::<Grid>(&json).unwrap());
+
+        // The number of cells must match the size
+        let err = serde_json::from_str::<Grid>(
+            r#"{ "size": { "width": 2, "height": 2 }, "cells": ["a", "b"] }"#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Invalid number of cells: 2 (expected 4)."));
+    }
+}
+
+#[cfg(test)]
+mod test_space_mapper {
+    use super::{OffsetMapper, ScaleMapper, SpaceMapper, TestFloatSpaceUnit, TestSpaceUnit};
+
+    type Point = super::Point<TestSpaceUnit>;
+    type Size = super::Size<TestSpaceUnit>;
+    type Rect = super::Rect<TestSpaceUnit>;
+    type FloatPoint = super::Point<TestFloatSpaceUnit>;
+    type FloatSize = super::Size<TestFloatSpaceUnit>;
+    type FloatRect = super::Rect<TestFloatSpaceUnit>;
+    type Vector = super::Vector<TestFloatSpaceUnit>;
+
+    impl From<TestSpaceUnit> for TestFloatSpaceUnit {
+        fn from(value: TestSpaceUnit) -> Self {
+            Self(value.raw().into())
+        }
+    }
+
+    #[test]
+    fn test_scale() {
+        let mapper = ScaleMapper::<TestSpaceUnit, TestFloatSpaceUnit>::new(2.0, 0.5);
+        assert_eq!(
+            FloatPoint::new(6.0, 2.5),
+            mapper.map_point(Point::new(3, 5))
+        );
+        assert_eq!(FloatSize::new(16.0, 4.0), mapper.map_size(Size::new(8, 8)));
+        assert_eq!(
+            FloatRect::new((2.0, 1.0), (9.0, 3.0)),
+            mapper.map_rect(Rect::new((1, 2), (4, 7)))
+        );
+
+        let uniform = ScaleMapper::<TestSpaceUnit, TestFloatSpaceUnit>::new_uniform(3.0);
+        assert_eq!(
+            FloatPoint::new(3.0, 6.0),
+            uniform.map_point(Point::new(1, 2))
+        );
+    }
+
+    #[test]
+    fn test_offset() {
+        let mapper = OffsetMapper::<TestSpaceUnit, TestFloatSpaceUnit>::new(
+            Vector::from_components((false, 10.0), (true, 2.0)),
+        );
+        assert_eq!(
+            FloatPoint::new(13.0, 3.0),
+            mapper.map_point(Point::new(3, 5))
+        );
+        assert_eq!(FloatSize::new(8.0, 4.0), mapper.map_size(Size::new(8, 4)));
+        assert_eq!(
+            FloatRect::new((11.0, 0.0), (14.0, 5.0)),
+            mapper.map_rect(Rect::new((1, 2), (4, 7)))
+        );
+    }
+
+    #[test]
+    fn test_then() {
+        // Scale first, such that the offset is not scaled
+        let mapper = ScaleMapper::<TestSpaceUnit, TestFloatSpaceUnit>::new_uniform(2.0)
+            .then(OffsetMapper::new(Vector::new(100.0, 50.0)));
+        assert_eq!(
+            FloatPoint::new(106.0, 60.0),
+            mapper.map_point(Point::new(3, 5))
+        );
+        assert_eq!(FloatSize::new(16.0, 8.0), mapper.map_size(Size::new(8, 4)));
+        assert_eq!(
+            FloatRect::new((102.0, 54.0), (109.0, 65.0)),
+            mapper.map_rect(Rect::new((1, 2), (4, 7)))
+        );
+    }
+}
+
+#[cfg(test)]
+mod test_transform {
+    use super::{SpaceMapper, TestSpaceUnit};
+
+    type Point = super::Point<TestSpaceUnit>;
+    type Size = super::Size<TestSpaceUnit>;
+    type Rect = super::Rect<TestSpaceUnit>;
+    type Vector = super::Vector<TestSpaceUnit>;
+    type Transform2D = super::Transform2D<TestSpaceUnit>;
+
+    #[test]
+    fn test_apply() {
+        let transform = Transform2D::new_scale(3, 2).followed_by(Transform2D::new_translation(
+            Vector::from_components((false, 10), (true, 1)),
+        ));
+        assert_eq!(Point::new(16, 9), transform.apply_point(Point::new(2, 5)));
+        assert_eq!(Size::new(12, 8), transform.apply_size(Size::new(4, 4)));
+        // Every covered unit becomes a block of 3 by 2 units
+        assert_eq!(
+            Rect::new((13, 1), (18, 4)),
+            transform.apply_rect(&Rect::new((1, 1), (2, 2)))
+        );
+        assert_eq!(
+            transform.apply_rect(&Rect::new((1, 1), (2, 2))),
+            transform.map_rect(Rect::new((1, 1), (2, 2)))
+        );
+
+        let identity = Transform2D::identity();
+        assert_eq!(Point::new(2, 5), identity.apply_point(Point::new(2, 5)));
+        assert_eq!(
+            Rect::new((1, 1), (2, 2)),
+            identity.apply_rect(&Rect::new((1, 1), (2, 2)))
+        );
+    }
+
+    #[test]
+    fn test_followed_by() {
+        let first = Transform2D::new_scale(2, 2)
+            .followed_by(Transform2D::new_translation(Vector::new(1, 0)));
+        let second = Transform2D::new_scale(3, 3)
+            .followed_by(Transform2D::new_translation(Vector::new(0, 5)));
+        let combined = first.followed_by(second);
+        assert_eq!((6.into(), 6.into()), combined.scale());
+        assert_eq!(Vector::new(3, 5), combined.translation());
+
+        for point in [Point::new(0, 0), Point::new(4, 7)] {
+            assert_eq!(
+                second.apply_point(first.apply_point(point)),
+                combined.apply_point(point)
+            );
+        }
+
+        // A negative translation can be cancelled out by a later one
+        let back = Transform2D::new_translation(Vector::from_components((true, 4), (true, 4)))
+            .followed_by(Transform2D::new_scale(2, 2))
+            .followed_by(Transform2D::new_translation(Vector::new(8, 10)));
+        assert_eq!(Vector::new(0, 2), back.translation());
+        assert_eq!(Point::new(10, 12), back.apply_point(Point::new(5, 5)));
+    }
+}
+
+#[cfg(test)]
+mod test_segment {
+    use super::TestSpaceUnit;
+
+    type Rect = super::Rect<TestSpaceUnit>;
+    type Segment = super::Segment<TestSpaceUnit>;
+
+    fn rect() -> Rect {
+        Rect::new((2, 2), (8, 6))
+    }
+
+    #[test]
+    fn test_clip_inside() {
+        let segment = Segment::new((3, 3), (7, 5));
+        assert_eq!(Some(segment), segment.clip(&rect()));
+        // The edges are part of the rectangle
+        let segment = Segment::new((2, 2), (8, 2));
+        assert_eq!(Some(segment), segment.clip(&rect()));
+    }
+
+    #[test]
+    fn test_clip_outside() {
+        // Beyond the same side
+        assert_eq!(None, Segment::new((0, 0), (10, 1)).clip(&rect()));
+        assert_eq!(None, Segment::new((9, 0), (12, 8)).clip(&rect()));
+        // Beyond different sides, but passing a corner
+        assert_eq!(None, Segment::new((0, 3), (3, 0)).clip(&rect()));
+        // Touching a corner
+        assert_eq!(
+            Some(Segment::new((2, 2), (2, 2))),
+            Segment::new((0, 4), (4, 0)).clip(&rect())
+        );
+    }
+
+    #[test]
+    fn test_clip_crossing() {
+        assert_eq!(
+            Some(Segment::new((2, 2), (8, 6))),
+            Segment::new((0, 0), (10, 8)).clip(&rect())
+        );
+        // Horizontal and vertical
+        assert_eq!(
+            Some(Segment::new((2, 4), (8, 4))),
+            Segment::new((0, 4), (20, 4)).clip(&rect())
+        );
+        assert_eq!(
+            Some(Segment::new((5, 6), (5, 2))),
+            Segment::new((5, 9), (5, 0)).clip(&rect())
+        );
+        // One end point inside, the direction is retained
+        assert_eq!(
+            Some(Segment::new((4, 4), (8, 5))),
+            Segment::new((4, 4), (12, 6)).clip(&rect())
+        );
+        assert_eq!(
+            Some(Segment::new((8, 5), (4, 4))),
+            Segment::new((12, 6), (4, 4)).clip(&rect())
+        );
+    }
+
+    #[test]
+    fn test_clip_result_inside() {
+        let rect = rect();
+        for (start, end) in [
+            ((0, 0), (12, 3)),
+            ((1, 9), (9, 0)),
+            ((0, 7), (11, 1)),
+            ((9, 9), (1, 1)),
+        ] {
+            if let Some(clipped) = Segment::new(start, end).clip(&rect) {
+                for point in [clipped.start(), clipped.end()] {
+                    assert!(rect.range_x().contains(&point.x), "{:?}", clipped);
+                    assert!(rect.range_y().contains(&point.y), "{:?}", clipped);
+                }
+            }
+        }
     }
 }
@@ -23,4 +23,9 @@ cargo build ${CARGO_ARGS}
 cd ../game
 # shellcheck disable=SC2086
 cargo build ${CARGO_ARGS}
+for example in "${SCRIPT_DIR}"/examples/*/ ; do
+  cd "${example}"
+  # shellcheck disable=SC2086
+  cargo build ${CARGO_ARGS}
+done
 "${SCRIPT_DIR}/../generate_wat.sh"
@@ -1,6 +1,16 @@
-use crate::gpu::{OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex, PaletteTableIndex};
+use crate::gpu::{
+    DmaTable, ExtendedOamEntry, OamAttributes, OamTableEntry, OamTableIndex, PaletteColor,
+    PaletteIndex, PaletteTableIndex, ScreenControl,
+};
+use crate::input::{Buttons, PlayerIndex};
+use crate::metasprite::Metasprite;
+use crate::result::{from_code, ProtoError, ProtoResult};
+use crate::system::BootKind;
 
 /// The prototype core API.
+///
+/// All calls return a [`ProtoResult`], which contains the error that the core reported if the call was rejected (see
+/// [`crate::result`]).
 pub trait Core {
     /// Sets an OAM entry.
     ///
@@ -8,7 +18,58 @@ pub trait Core {
     ///
     /// * `index`: The index into the OAM table.
     /// * `entry`: The entry.
-    fn oam_set(&self, index: &OamTableIndex, entry: &OamTableEntry);
+    fn oam_set(&self, index: &OamTableIndex, entry: &OamTableEntry) -> ProtoResult<()>;
+
+    /// Sets consecutive OAM entries with a single block copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index into the OAM table of the first entry.
+    /// * `entries`: The entries. These must fit in the OAM table.
+    fn oam_copy(&self, index: &OamTableIndex, entries: &[OamTableEntry]) -> ProtoResult<()>;
+
+    /// Hides an OAM entry, such that it is no longer drawn (see [`OamTableEntry::hidden()`]). The other fields of the entry are left
+    /// untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index into the OAM table.
+    fn oam_hide(&self, index: &OamTableIndex) -> ProtoResult<()>;
+
+    /// Resets all OAM entries to hidden entries (see [`OamTableEntry::new_hidden()`]). This is the cheapest way to remove all
+    /// objects from the screen, for instance between scenes. The OAM attributes are left untouched.
+    fn oam_clear(&self) -> ProtoResult<()>;
+
+    /// Sets consecutive OAM attributes entries with a single block copy (see [`DmaTable::OamAttributes`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index into the OAM table of the first entry.
+    /// * `attributes`: The attributes. These must fit in the OAM attributes table.
+    fn oam_attributes_copy(
+        &self,
+        index: &OamTableIndex,
+        attributes: &[OamAttributes],
+    ) -> ProtoResult<()>;
+
+    /// Sets consecutive OAM entries and their attributes. This performs a block copy for either table.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`: The index into the OAM table of the first entry.
+    /// * `entries`: The entries. These must fit in the OAM table.
+    fn oam_copy_extended(
+        &self,
+        index: &OamTableIndex,
+        entries: &[ExtendedOamEntry],
+    ) -> ProtoResult<()> {
+        let (oam, attributes): (Vec<OamTableEntry>, Vec<OamAttributes>) = entries
+            .iter()
+            .map(|extended| (extended.entry, extended.attributes))
+            .unzip();
+        self.oam_copy(index, &oam)?;
+        self.oam_attributes_copy(index, &attributes)
+    }
 
     /// Sets a palette entry.
     ///
@@ -17,7 +78,48 @@ pub trait Core {
     /// * `palette`: The index of the palette in the palette table.
     /// * `index`: The index inside the palette.
     /// * `color`: The color to set.
-    fn palette_set(&self, palette: &PaletteTableIndex, index: &PaletteIndex, color: &PaletteColor);
+    fn palette_set(
+        &self,
+        palette: &PaletteTableIndex,
+        index: &PaletteIndex,
+        color: &PaletteColor,
+    ) -> ProtoResult<()>;
+
+    /// Sets consecutive palette entries with a single block copy.
+    ///
+    /// # Arguments
+    ///
+    /// * `palette`: The index of the palette in the palette table.
+    /// * `colors`: The colors, starting at the first entry of the palette. Colors beyond the size of the palette continue in the
+    ///   next palettes (see [`DmaTable::Palettes`]). These must fit in the palette table.
+    fn palette_copy(&self, palette: &PaletteTableIndex, colors: &[PaletteColor])
+        -> ProtoResult<()>;
+
+    /// Sets the screen control register.
+    ///
+    /// # Arguments
+    ///
+    /// * `control`: The screen control register value.
+    fn screen_control_set(&self, control: &ScreenControl) -> ProtoResult<()>;
+
+    /// Retrieves the state of the buttons on the controller of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player. The buttons of a disconnected controller are never pressed.
+    fn input_buttons(&self, player: &PlayerIndex) -> ProtoResult<Buttons>;
+
+    /// Determines whether the controller of a player is connected.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    fn input_connected(&self, player: &PlayerIndex) -> ProtoResult<bool>;
+
+    /// Determines how the game has been started.
+    ///
+    /// This allows a game to distinguish a cold boot from a reset.
+    fn boot_kind(&self) -> ProtoResult<BootKind>;
 }
 
 /// The prototype game API.
@@ -35,8 +137,16 @@ pub trait Game {
 }
 
 pub struct CoreBootstrap {
-    core_gpu_oam_set: unsafe extern "C" fn(index: u8, entry: u64),
-    core_gpu_palette_set: unsafe extern "C" fn(palette: u8, index: u8, color: u16),
+    core_gpu_oam_set: unsafe extern "C" fn(index: u8, entry: u64) -> i32,
+    core_gpu_oam_hide: unsafe extern "C" fn(index: u8) -> i32,
+    core_gpu_oam_clear: unsafe extern "C" fn() -> i32,
+    core_gpu_palette_set: unsafe extern "C" fn(palette: u8, index: u8, color: u16) -> i32,
+    core_gpu_screen_control_set: unsafe extern "C" fn(control: u8) -> i32,
+    core_gpu_dma_copy:
+        unsafe extern "C" fn(src_ptr: *const u8, table: u8, offset: u32, len: u32) -> i32,
+    core_input_buttons_get: unsafe extern "C" fn(player: u8) -> i32,
+    core_input_connected_get: unsafe extern "C" fn(player: u8) -> i32,
+    core_system_boot_kind_get: unsafe extern "C" fn() -> i32,
 }
 
 /// A helper for bootstrapping the core to the game code.
@@ -52,36 +162,186 @@ impl CoreBootstrap {
     ///
     /// * `core_log_log`: The pointer to the `log::log()` function.
     /// * `core_gpu_oam_set`: The pointer to the `gpu::oam_set()` function.
+    /// * `core_gpu_oam_hide`: The pointer to the `gpu::oam_hide()` function.
+    /// * `core_gpu_oam_clear`: The pointer to the `gpu::oam_clear()` function.
     /// * `core_gpu_palette_set`: The pointer to the `gpu::palette_set()` function.
+    /// * `core_gpu_screen_control_set`: The pointer to the `gpu::screen_control_set()` function.
+    /// * `core_gpu_dma_copy`: The pointer to the `gpu::dma_copy()` function.
+    /// * `core_input_buttons_get`: The pointer to the `input::buttons_get()` function.
+    /// * `core_input_connected_get`: The pointer to the `input::connected_get()` function.
+    /// * `core_system_boot_kind_get`: The pointer to the `system::boot_kind_get()` function.
     /// * `log_init`: A callback for initializing the logger.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
-        core_log_log: unsafe extern "C" fn(level: u32, ptr: *const u8, len: usize),
-        core_gpu_oam_set: unsafe extern "C" fn(index: u8, entry: u64),
-        core_gpu_palette_set: unsafe extern "C" fn(palette: u8, index: u8, color: u16),
+        core_log_log: unsafe extern "C" fn(level: u32, ptr: *const u8, len: usize) -> i32,
+        core_gpu_oam_set: unsafe extern "C" fn(index: u8, entry: u64) -> i32,
+        core_gpu_oam_hide: unsafe extern "C" fn(index: u8) -> i32,
+        core_gpu_oam_clear: unsafe extern "C" fn() -> i32,
+        core_gpu_palette_set: unsafe extern "C" fn(palette: u8, index: u8, color: u16) -> i32,
+        core_gpu_screen_control_set: unsafe extern "C" fn(control: u8) -> i32,
+        core_gpu_dma_copy: unsafe extern "C" fn(
+            src_ptr: *const u8,
+            table: u8,
+            offset: u32,
+            len: u32,
+        ) -> i32,
+        core_input_buttons_get: unsafe extern "C" fn(player: u8) -> i32,
+        core_input_connected_get: unsafe extern "C" fn(player: u8) -> i32,
+        core_system_boot_kind_get: unsafe extern "C" fn() -> i32,
         log_init: impl FnOnce(
-            unsafe extern "C" fn(level: u32, ptr: *const u8, len: usize),
+            unsafe extern "C" fn(level: u32, ptr: *const u8, len: usize) -> i32,
         ) -> Result<(), String>,
     ) -> Self {
         log_init(core_log_log).unwrap();
 
         Self {
             core_gpu_oam_set,
+            core_gpu_oam_hide,
+            core_gpu_oam_clear,
             core_gpu_palette_set,
+            core_gpu_screen_control_set,
+            core_gpu_dma_copy,
+            core_input_buttons_get,
+            core_input_connected_get,
+            core_system_boot_kind_get,
         }
     }
 }
 
-impl Core for CoreBootstrap {
-    fn oam_set(&self, index: &OamTableIndex, entry: &OamTableEntry) {
-        unsafe {
-            (self.core_gpu_oam_set)(index.into(), entry.into());
-        }
+impl CoreBootstrap {
+    /// Copies a block of entries into a table of the core.
+    ///
+    /// # Arguments
+    ///
+    /// * `table`: The table.
+    /// * `offset`: The index of the first entry in the table.
+    /// * `entries`: The entries. The type must be `#[repr(transparent)]` over the internal value of the table entries (see
+    ///   [`DmaTable`]).
+    fn dma_copy<T>(&self, table: DmaTable, offset: usize, entries: &[T]) -> ProtoResult<()> {
+        debug_assert_eq!(std::mem::size_of::<T>(), table.entry_size());
+        let offset = u32::try_from(offset).map_err(|_| ProtoError::OutOfRange)?;
+        let len = u32::try_from(entries.len()).map_err(|_| ProtoError::OutOfRange)?;
+        let code = unsafe {
+            (self.core_gpu_dma_copy)(entries.as_ptr() as *const u8, table.into(), offset, len)
+        };
+        from_code(code).map(|_| ())
     }
 
-    fn palette_set(&self, palette: &PaletteTableIndex, index: &PaletteIndex, color: &PaletteColor) {
-        unsafe {
-            (self.core_gpu_palette_set)(palette.into(), index.into(), color.into());
+    /// Places a metasprite by writing its objects into consecutive OAM entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `metasprite`: The metasprite.
+    /// * `origin`: The position of the origin on the screen (see [`Metasprite::oam_entries()`]).
+    /// * `base_oam_index`: The index into the OAM table of the first object.
+    ///
+    /// returns: The number of OAM entries that have been written.
+    pub fn place_metasprite(
+        &self,
+        metasprite: &Metasprite,
+        origin: (i32, i32),
+        base_oam_index: &OamTableIndex,
+    ) -> ProtoResult<usize> {
+        self.place_metasprites(&[(metasprite, origin)], base_oam_index)
+    }
+
+    /// Places multiple metasprites with a single block copy. The objects are written into consecutive OAM entries, in the order of
+    /// the metasprites, such that the first metasprite ends up on top.
+    ///
+    /// # Arguments
+    ///
+    /// * `placements`: The metasprites and the positions of their origins on the screen.
+    /// * `base_oam_index`: The index into the OAM table of the first object.
+    ///
+    /// returns: The number of OAM entries that have been written. If the objects do not fit in the OAM table, no entries are
+    /// written and [`ProtoError::OutOfRange`] is returned.
+    pub fn place_metasprites(
+        &self,
+        placements: &[(&Metasprite, (i32, i32))],
+        base_oam_index: &OamTableIndex,
+    ) -> ProtoResult<usize> {
+        let entries: Vec<OamTableEntry> = placements
+            .iter()
+            .flat_map(|(metasprite, origin)| metasprite.oam_entries(*origin))
+            .collect();
+        if usize::from(*base_oam_index) + entries.len() > DmaTable::Oam.entry_count() {
+            return Err(ProtoError::OutOfRange);
         }
+        self.oam_copy(base_oam_index, &entries)?;
+        Ok(entries.len())
+    }
+}
+
+impl Core for CoreBootstrap {
+    fn oam_set(&self, index: &OamTableIndex, entry: &OamTableEntry) -> ProtoResult<()> {
+        let code = unsafe { (self.core_gpu_oam_set)(index.into(), entry.into()) };
+        from_code(code).map(|_| ())
+    }
+
+    fn oam_copy(&self, index: &OamTableIndex, entries: &[OamTableEntry]) -> ProtoResult<()> {
+        self.dma_copy(DmaTable::Oam, usize::from(*index), entries)
+    }
+
+    fn oam_attributes_copy(
+        &self,
+        index: &OamTableIndex,
+        attributes: &[OamAttributes],
+    ) -> ProtoResult<()> {
+        self.dma_copy(DmaTable::OamAttributes, usize::from(*index), attributes)
+    }
+
+    fn oam_hide(&self, index: &OamTableIndex) -> ProtoResult<()> {
+        let code = unsafe { (self.core_gpu_oam_hide)(index.into()) };
+        from_code(code).map(|_| ())
+    }
+
+    fn oam_clear(&self) -> ProtoResult<()> {
+        let code = unsafe { (self.core_gpu_oam_clear)() };
+        from_code(code).map(|_| ())
+    }
+
+    fn palette_set(
+        &self,
+        palette: &PaletteTableIndex,
+        index: &PaletteIndex,
+        color: &PaletteColor,
+    ) -> ProtoResult<()> {
+        let code =
+            unsafe { (self.core_gpu_palette_set)(palette.into(), index.into(), color.into()) };
+        from_code(code).map(|_| ())
+    }
+
+    fn palette_copy(
+        &self,
+        palette: &PaletteTableIndex,
+        colors: &[PaletteColor],
+    ) -> ProtoResult<()> {
+        self.dma_copy(DmaTable::Palettes, usize::from(*palette) * 16, colors)
+    }
+
+    fn screen_control_set(&self, control: &ScreenControl) -> ProtoResult<()> {
+        let code = unsafe { (self.core_gpu_screen_control_set)(control.into()) };
+        from_code(code).map(|_| ())
+    }
+
+    fn input_buttons(&self, player: &PlayerIndex) -> ProtoResult<Buttons> {
+        let code = unsafe { (self.core_input_buttons_get)(player.into()) };
+        from_code(code).and_then(|value| {
+            u16::try_from(value)
+                .map(Buttons::from)
+                .map_err(|_| ProtoError::OutOfRange)
+        })
+    }
+
+    fn input_connected(&self, player: &PlayerIndex) -> ProtoResult<bool> {
+        let code = unsafe { (self.core_input_connected_get)(player.into()) };
+        from_code(code).map(|value| value != 0)
+    }
+
+    fn boot_kind(&self) -> ProtoResult<BootKind> {
+        let code = unsafe { (self.core_system_boot_kind_get)() };
+        from_code(code)
+            .and_then(|value| BootKind::try_from(value).map_err(|_| ProtoError::InvalidArgument))
     }
 }
 
@@ -130,7 +390,7 @@ macro_rules! create_game {
             /// * `ptr`: A pointer to the start of the message.
             /// * `len`: The length of the message in bytes.
             #[link_name = "log"]
-            fn core_log_log(level: u32, ptr: *const u8, len: usize);
+            fn core_log_log(level: u32, ptr: *const u8, len: usize) -> i32;
         }
 
         #[link(wasm_import_module = "gpu")]
@@ -142,7 +402,19 @@ macro_rules! create_game {
             /// * `index`: The [`OamTableIndex`](ves_proto_common::gpu::OamTableIndex).
             /// * `entry`: The [`OamTableEntry`](ves_proto_common::gpu::OamTableEntry).
             #[link_name = "oam_set"]
-            fn core_gpu_oam_set(index: u8, entry: u64);
+            fn core_gpu_oam_set(index: u8, entry: u64) -> i32;
+
+            /// Core function for hiding an entry in the OAM table.
+            ///
+            /// # Arguments
+            ///
+            /// * `index`: The [`OamTableIndex`](ves_proto_common::gpu::OamTableIndex).
+            #[link_name = "oam_hide"]
+            fn core_gpu_oam_hide(index: u8) -> i32;
+
+            /// Core function for hiding all entries in the OAM table.
+            #[link_name = "oam_clear"]
+            fn core_gpu_oam_clear() -> i32;
 
             /// Core function for setting an entry in the palette table.
             ///
@@ -152,7 +424,64 @@ macro_rules! create_game {
             /// * `index`: The [`PaletteIndex`](ves_proto_common::gpu::PaletteIndex).
             /// * `color`: The [`PaletteColor`](ves_proto_common::gpu::PaletteColor).
             #[link_name = "palette_set"]
-            fn core_gpu_palette_set(palette: u8, index: u8, color: u16);
+            fn core_gpu_palette_set(palette: u8, index: u8, color: u16) -> i32;
+
+            /// Core function for setting the screen control register.
+            ///
+            /// # Arguments
+            ///
+            /// * `control`: The [`ScreenControl`](ves_proto_common::gpu::ScreenControl).
+            #[link_name = "screen_control_set"]
+            fn core_gpu_screen_control_set(control: u8) -> i32;
+
+            /// Core function for copying a block of entries into a table.
+            ///
+            /// # Arguments
+            ///
+            /// * `src_ptr`: A pointer to the first entry.
+            /// * `table`: The [`DmaTable`](ves_proto_common::gpu::DmaTable).
+            /// * `offset`: The index of the first entry in the table.
+            /// * `len`: The number of entries.
+            #[link_name = "dma_copy"]
+            fn core_gpu_dma_copy(src_ptr: *const u8, table: u8, offset: u32, len: u32) -> i32;
+        }
+
+        #[link(wasm_import_module = "input")]
+        extern "C" {
+            /// Core function for retrieving the state of the buttons on the controller of a player.
+            ///
+            /// # Arguments
+            ///
+            /// * `player`: The [`PlayerIndex`](ves_proto_common::input::PlayerIndex).
+            ///
+            /// # Returns
+            /// The [`Buttons`](ves_proto_common::input::Buttons) or a negative error code (see
+            /// [`ProtoError`](ves_proto_common::result::ProtoError)).
+            #[link_name = "buttons_get"]
+            fn core_input_buttons_get(player: u8) -> i32;
+
+            /// Core function for determining whether the controller of a player is connected.
+            ///
+            /// # Arguments
+            ///
+            /// * `player`: The [`PlayerIndex`](ves_proto_common::input::PlayerIndex).
+            ///
+            /// # Returns
+            /// `1` if the controller is connected, `0` if it is not or a negative error code (see
+            /// [`ProtoError`](ves_proto_common::result::ProtoError)).
+            #[link_name = "connected_get"]
+            fn core_input_connected_get(player: u8) -> i32;
+        }
+
+        #[link(wasm_import_module = "system")]
+        extern "C" {
+            /// Core function for determining how the game has been started.
+            ///
+            /// # Returns
+            /// The [`BootKind`](ves_proto_common::system::BootKind) or a negative error code (see
+            /// [`ProtoError`](ves_proto_common::result::ProtoError)).
+            #[link_name = "boot_kind_get"]
+            fn core_system_boot_kind_get() -> i32;
         }
 
         #[no_mangle]
@@ -160,7 +489,14 @@ macro_rules! create_game {
             let core = CoreBootstrap::new(
                 core_log_log,
                 core_gpu_oam_set,
+                core_gpu_oam_hide,
+                core_gpu_oam_clear,
                 core_gpu_palette_set,
+                core_gpu_screen_control_set,
+                core_gpu_dma_copy,
+                core_input_buttons_get,
+                core_input_connected_get,
+                core_system_boot_kind_get,
                 |cll| {
                     ves_proto_logger::Logger::new(core_log_log)
                         .init(Some(ves_proto_common::log::LogLevel::Trace))
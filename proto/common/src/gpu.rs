@@ -41,9 +41,11 @@ bit_struct!(
     /// * Bits 18-25: Palette table index.
     /// * Bit 26: Horizontal flip flag.
     /// * Bit 27: Vertical flip flag.
-    /// * Bits 28-31: Unused.
+    /// * Bit 28: Hidden flag (see [`OamTableEntry::hidden()`]).
+    /// * Bits 29-31: Unused.
     /// * Bits 32-63: Character table index.
     #[derive(Copy, Clone, Eq, PartialEq, Default)]
+    #[repr(transparent)]
     pub struct OamTableEntry {
         value: u64
     }
@@ -69,12 +71,40 @@ bit_struct!(
     }
 
     padding {
-        #[bit_struct_field(shift = 28, mask = 0xF)]
+        #[bit_struct_field(shift = 28, mask = 0b1)]
+        fn hidden_flag(&self) -> u8;
+
+        #[bit_struct_field(shift = 29, mask = 0b111)]
         fn unused(&self) -> u8;
     }
 );
 
 impl OamTableEntry {
+    /// The bit of the hidden flag. The flag is declared as padding rather than as a field, such that it is not part of the
+    /// constructor: new entries are always shown.
+    const HIDDEN_MASK: u64 = 1 << 28;
+
+    /// Creates an entry that is not drawn (see [`OamTableEntry::hidden()`]).
+    pub fn new_hidden() -> Self {
+        Self {
+            value: Self::HIDDEN_MASK,
+        }
+    }
+
+    /// Retrieves the hidden flag. Hidden entries are not drawn and do not count towards any per-scanline sprite limit.
+    pub fn hidden(&self) -> bool {
+        self.value & Self::HIDDEN_MASK != 0
+    }
+
+    /// Sets the hidden flag.
+    pub fn set_hidden(&mut self, hidden: bool) {
+        if hidden {
+            self.value |= Self::HIDDEN_MASK;
+        } else {
+            self.value &= !Self::HIDDEN_MASK;
+        }
+    }
+
     /// Retrieves the position of the top-left pixel.
     ///
     /// Note that only the 9 least-significant bits of the coordinates are used.
@@ -144,6 +174,7 @@ mod tests_oam_entry {
         assert!(!subject.v_flip());
         assert_eq!(subject.char_table_index(), 0u32);
         assert_eq!(u8::from(subject.palette_table_index()), 0);
+        assert!(!subject.hidden());
     }
 
     #[test]
@@ -186,6 +217,26 @@ mod tests_oam_entry {
         assert_eq!(subject.palette_table_index(), palette_table_index);
     }
 
+    #[test]
+    fn hidden() {
+        let mut subject: OamTableEntry = TEST_VAL.into();
+        assert!(!subject.hidden());
+
+        subject.set_hidden(true);
+        assert!(subject.hidden());
+        assert_eq!(u64::from(subject), TEST_VAL | 1 << 28);
+        // The other fields are not affected
+        assert_eq!(subject.position(), (0x1AC, 0x13));
+        assert_eq!(subject.char_table_index(), 5u32);
+
+        subject.set_hidden(false);
+        assert_eq!(u64::from(subject), TEST_VAL);
+
+        let subject = OamTableEntry::new_hidden();
+        assert!(subject.hidden());
+        assert_eq!(subject.position(), (0, 0));
+    }
+
     #[test]
     fn debug() {
         let subject: OamTableEntry = TEST_VAL.into();
@@ -196,6 +247,377 @@ mod tests_oam_entry {
     }
 }
 
+bit_struct!(
+    /// The secondary attributes of an OAM entry.
+    ///
+    /// An [`OamTableEntry`] has no room left for attributes like the size or the priority of an object, so these are kept in a
+    /// separate table with an entry for every OAM entry (see [`DmaTable::OamAttributes`]). The default value (zero) describes an
+    /// object as it is drawn without attributes, such that games that do not use the attributes table are not affected.
+    ///
+    /// The entry can be converted to an [u16] and sent from the game to the core.
+    ///
+    /// The internal format is as follows:
+    /// * Bits 0-1: Size (see [`OamAttributes::size()`]).
+    /// * Bits 2-3: Priority (see [`OamAttributes::priority()`]).
+    /// * Bit 4: Affine flag (see [`OamAttributes::affine()`]).
+    /// * Bits 5-9: Affine matrix index.
+    /// * Bits 10-15: Unused.
+    #[derive(Copy, Clone, Eq, PartialEq, Default)]
+    #[repr(transparent)]
+    pub struct OamAttributes {
+        value: u16
+    }
+
+    impl {
+        #[bit_struct_field(shift = 0, mask = 0b11)]
+        fn size_u8(&self) -> u8;
+
+        #[bit_struct_field(shift = 2, mask = 0b11)]
+        fn priority_u8(&self) -> u8;
+
+        #[bit_struct_field(shift = 4, mask = 0b1)]
+        fn affine_u8(&self) -> u8;
+
+        #[bit_struct_field(shift = 5, mask = 0b11111)]
+        fn affine_index_u8(&self) -> u8;
+    }
+
+    padding {
+        #[bit_struct_field(shift = 10, mask = 0b111111)]
+        fn unused(&self) -> u8;
+    }
+);
+
+impl OamAttributes {
+    /// The maximum value for the size.
+    pub const MAX_SIZE: u8 = 0b11;
+    /// The maximum value for the priority.
+    pub const MAX_PRIORITY: u8 = 0b11;
+    /// The maximum value for the affine matrix index.
+    pub const MAX_AFFINE_INDEX: u8 = 0b11111;
+
+    /// Retrieves the size.
+    ///
+    /// The size is the base-2 logarithm of the factor by which the character is scaled up: `0` draws the character as it is, `1`
+    /// doubles its width and height, and so on.
+    pub fn size(&self) -> u8 {
+        self.size_u8()
+    }
+
+    /// Sets the size (see [`OamAttributes::size()`]).
+    ///
+    /// Note that only the 2 least-significant bits are used.
+    pub fn set_size(&mut self, size: u8) {
+        self.set_size_u8(size & Self::MAX_SIZE);
+    }
+
+    /// Retrieves the priority.
+    ///
+    /// The priority determines the order of the objects and the background layers during compositing, like the priority of a
+    /// [`TilemapEntry`].
+    pub fn priority(&self) -> u8 {
+        self.priority_u8()
+    }
+
+    /// Sets the priority (see [`OamAttributes::priority()`]).
+    ///
+    /// Note that only the 2 least-significant bits are used.
+    pub fn set_priority(&mut self, priority: u8) {
+        self.set_priority_u8(priority & Self::MAX_PRIORITY);
+    }
+
+    /// Retrieves the index of the affine matrix that transforms the object, if any.
+    pub fn affine(&self) -> Option<u8> {
+        (self.affine_u8() != 0).then(|| self.affine_index_u8())
+    }
+
+    /// Sets the index of the affine matrix that transforms the object (see [`OamAttributes::affine()`]).
+    ///
+    /// Note that only the 5 least-significant bits of the index are used.
+    pub fn set_affine(&mut self, index: Option<u8>) {
+        self.set_affine_u8(index.is_some() as u8);
+        self.set_affine_index_u8(index.unwrap_or(0) & Self::MAX_AFFINE_INDEX);
+    }
+}
+
+/// An [`OamTableEntry`] together with its [`OamAttributes`].
+///
+/// This is a convenience for code that deals with both tables at once. An [`OamTableEntry`] converts into an extended entry with
+/// the default attributes, which is how the core draws entries for which no attributes have been set.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ExtendedOamEntry {
+    /// The entry.
+    pub entry: OamTableEntry,
+    /// The secondary attributes.
+    pub attributes: OamAttributes,
+}
+
+impl ExtendedOamEntry {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `entry`: The entry.
+    /// * `attributes`: The secondary attributes.
+    pub fn new(entry: OamTableEntry, attributes: OamAttributes) -> Self {
+        Self { entry, attributes }
+    }
+}
+
+impl From<OamTableEntry> for ExtendedOamEntry {
+    fn from(entry: OamTableEntry) -> Self {
+        Self::new(entry, Default::default())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
+mod tests_oam_attributes {
+    use super::{ExtendedOamEntry, OamAttributes, OamTableEntry};
+
+    // size: 2
+    // priority: 1
+    // affine: 1
+    // affine_index: 0x13
+    //                      pad    aff_idx a prio size
+    const TEST_VAL: u16 = 0b000000_10011_1_01_10;
+
+    #[test]
+    fn zero() {
+        let subject: OamAttributes = 0.into();
+        assert_eq!(subject, OamAttributes::default());
+        assert_eq!(subject.size(), 0);
+        assert_eq!(subject.priority(), 0);
+        assert_eq!(subject.affine(), None);
+    }
+
+    #[test]
+    fn getters() {
+        let subject: OamAttributes = TEST_VAL.into();
+        assert_eq!(subject.value, TEST_VAL);
+        assert_eq!(subject.size(), 2);
+        assert_eq!(subject.priority(), 1);
+        assert_eq!(subject.affine(), Some(0x13));
+    }
+
+    #[test]
+    fn constructor() {
+        let subject = OamAttributes::new(2, 1, 1, 0x13);
+        assert_eq!(subject.value, TEST_VAL);
+    }
+
+    #[test]
+    fn setters() {
+        let mut subject: OamAttributes = TEST_VAL.into();
+
+        subject.set_size(3);
+        subject.set_priority(2);
+        subject.set_affine(Some(4));
+        assert_eq!(subject.size(), 3);
+        assert_eq!(subject.priority(), 2);
+        assert_eq!(subject.affine(), Some(4));
+
+        subject.set_affine(None);
+        assert_eq!(subject.affine(), None);
+        assert_eq!(u16::from(subject), 0b10_11);
+
+        // Out-of-range values are truncated
+        subject.set_size(5);
+        subject.set_affine(Some(0x21));
+        assert_eq!(subject.size(), 1);
+        assert_eq!(subject.affine(), Some(1));
+    }
+
+    #[test]
+    fn extended_entry() {
+        let entry = OamTableEntry::new(1, 2, 3, 0, 1, 4);
+        let extended = ExtendedOamEntry::from(entry);
+        assert_eq!(entry, extended.entry);
+        assert_eq!(OamAttributes::default(), extended.attributes);
+    }
+
+    #[test]
+    fn debug() {
+        let subject: OamAttributes = TEST_VAL.into();
+        assert_eq!(
+            format!("{:?}", subject).as_str(),
+            "OamAttributes { size_u8: 2, priority_u8: 1, affine_u8: 1, affine_index_u8: 19 }"
+        );
+    }
+}
+
+bit_struct!(
+    /// An entry in a tilemap of a background layer.
+    ///
+    /// The entry can be converted to an [u32] and sent from the game to the core. Since a tilemap contains an entry for every tile
+    /// on the layer, the entry is kept to 32 bits and therefore only addresses the first 65536 entries of the character table.
+    ///
+    /// The internal format is as follows:
+    /// * Bits 0-15: Character table index.
+    /// * Bits 16-23: Palette table index.
+    /// * Bit 24: Horizontal flip flag.
+    /// * Bit 25: Vertical flip flag.
+    /// * Bits 26-27: Priority (see [`TilemapEntry::priority()`]).
+    /// * Bits 28-31: Unused.
+    #[derive(Copy, Clone, Eq, PartialEq, Default)]
+    pub struct TilemapEntry {
+        value: u32
+    }
+
+    impl {
+        #[bit_struct_field(shift = 0, mask = 0xFFFF)]
+        /// The character table index.
+        pub fn char_table_index(&self) -> u16;
+
+        #[bit_struct_field(shift = 16, mask = 0xFF)]
+        fn palette_table_index_u8(&self) -> u8;
+
+        #[bit_struct_field(shift = 24, mask = 0b1)]
+        fn flip_x(&self) -> u8;
+
+        #[bit_struct_field(shift = 25, mask = 0b1)]
+        fn flip_y(&self) -> u8;
+
+        #[bit_struct_field(shift = 26, mask = 0b11)]
+        fn priority_u8(&self) -> u8;
+    }
+
+    padding {
+        #[bit_struct_field(shift = 28, mask = 0xF)]
+        fn unused(&self) -> u8;
+    }
+);
+
+impl TilemapEntry {
+    /// The maximum value for the priority.
+    pub const MAX_PRIORITY: u8 = 0b11;
+
+    /// Retrieves the horizontal-flip flag.
+    pub fn h_flip(&self) -> bool {
+        self.flip_x() != 0
+    }
+
+    /// Sets the horizontal-flip flag.
+    pub fn set_h_flip(&mut self, flip: bool) {
+        self.set_flip_x(flip as u8);
+    }
+
+    /// Retrieves the vertical-flip flag.
+    pub fn v_flip(&self) -> bool {
+        self.flip_y() != 0
+    }
+
+    /// Sets the vertical-flip flag.
+    pub fn set_v_flip(&mut self, flip: bool) {
+        self.set_flip_y(flip as u8);
+    }
+
+    /// Retrieves the palette table index.
+    pub fn palette_table_index(&self) -> PaletteTableIndex {
+        self.palette_table_index_u8().into()
+    }
+
+    /// Sets the palette table index.
+    pub fn set_palette_table_index(&mut self, index: PaletteTableIndex) {
+        self.set_palette_table_index_u8(index.into())
+    }
+
+    /// Retrieves the priority.
+    ///
+    /// The priority determines the order of the tiles of all background layers during compositing: a tile with a higher priority is
+    /// drawn on top of a tile with a lower priority. This allows for parts of a layer to be drawn in front of another layer (like
+    /// the foliage in front of a character), without having to split the layer.
+    pub fn priority(&self) -> u8 {
+        self.priority_u8()
+    }
+
+    /// Sets the priority (see [`TilemapEntry::priority()`]).
+    ///
+    /// Note that only the 2 least-significant bits are used.
+    pub fn set_priority(&mut self, priority: u8) {
+        self.set_priority_u8(priority & Self::MAX_PRIORITY);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
+mod tests_tilemap_entry {
+    use super::TilemapEntry;
+
+    // char_table_index: 0x1234
+    // palette_table_index: 4
+    // flip_x: 0
+    // flip_y: 1
+    // priority: 2
+    //                      pad prio y x pal      chr_idx
+    const TEST_VAL: u32 = 0b0000_10_1_0_00000100_0001001000110100;
+
+    #[test]
+    fn zero() {
+        let subject: TilemapEntry = 0.into();
+        assert_eq!(subject.value, 0);
+        assert_eq!(subject.char_table_index(), 0);
+        assert_eq!(u8::from(subject.palette_table_index()), 0);
+        assert!(!subject.h_flip());
+        assert!(!subject.v_flip());
+        assert_eq!(subject.priority(), 0);
+    }
+
+    #[test]
+    fn getters() {
+        let subject: TilemapEntry = TEST_VAL.into();
+        assert_eq!(subject.value, TEST_VAL);
+        assert_eq!(subject.char_table_index(), 0x1234);
+        assert_eq!(u8::from(subject.palette_table_index()), 4);
+        assert!(!subject.h_flip());
+        assert!(subject.v_flip());
+        assert_eq!(subject.priority(), 2);
+    }
+
+    #[test]
+    fn constructor() {
+        let subject = TilemapEntry::new(0x1234, 4, 0, 1, 2);
+        assert_eq!(subject.value, TEST_VAL);
+    }
+
+    #[test]
+    fn setters() {
+        let mut subject: TilemapEntry = TEST_VAL.into();
+
+        let char_table_index = 0xFFFF;
+        let palette_table_index = 1.into();
+        let h_flip = true;
+        let v_flip = false;
+        let priority = 3;
+
+        subject.set_char_table_index(char_table_index);
+        subject.set_palette_table_index(palette_table_index);
+        subject.set_h_flip(h_flip);
+        subject.set_v_flip(v_flip);
+        subject.set_priority(priority);
+
+        assert_eq!(subject.char_table_index(), char_table_index);
+        assert_eq!(subject.palette_table_index(), palette_table_index);
+        assert_eq!(subject.h_flip(), h_flip);
+        assert_eq!(subject.v_flip(), v_flip);
+        assert_eq!(subject.priority(), priority);
+
+        // Only the least-significant bits of the priority are used
+        subject.set_priority(0b101);
+        assert_eq!(subject.priority(), 1);
+        assert_eq!(u32::from(subject) >> 28, 0);
+    }
+
+    #[test]
+    fn debug() {
+        let subject: TilemapEntry = TEST_VAL.into();
+        assert_eq!(
+            format!("{:?}", subject).as_str(),
+            "TilemapEntry { char_table_index: 4660, palette_table_index_u8: 4, flip_x: 0, flip_y: 1, priority_u8: 2 }"
+        );
+    }
+}
+
 bit_struct!(
     /// An index in a palette table. A palette table is always at most 255 entries in size.
     ///
@@ -353,6 +775,7 @@ bit_struct!(
     /// * Bits 10-14: Blue component.
     /// * Bit 15: Unused.
     #[derive(Copy, Clone, Eq, PartialEq, Default)]
+    #[repr(transparent)]
     pub struct PaletteColor {
         value: u16
     }
@@ -387,6 +810,9 @@ bit_struct!(
 );
 
 impl PaletteColor {
+    /// The mask for the bits that are used by the color components.
+    const BGR555_MASK: u16 = 0x7FFF;
+
     #[inline(always)]
     fn component_to_real(bits: u8) -> u8 {
         bits << 3 | (bits >> 2) & 0b00000111
@@ -397,6 +823,13 @@ impl PaletteColor {
         bits >> 3
     }
 
+    /// Creates a new instance from 8-bit color components. The 3 least-significant bits of every component are discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `r`: The red color component.
+    /// * `g`: The green color component.
+    /// * `b`: The blue color component.
     pub fn from_real(r: u8, g: u8, b: u8) -> Self {
         Self::new(
             Self::component_from_real(r),
@@ -405,6 +838,9 @@ impl PaletteColor {
         )
     }
 
+    /// Retrieves the color components, expanded to 8 bits. This is the inverse of [`from_real()`](Self::from_real).
+    ///
+    /// returns: The red, green and blue color components.
     pub fn to_real(&self) -> (u8, u8, u8) {
         (
             Self::component_to_real(self.r()),
@@ -412,6 +848,65 @@ impl PaletteColor {
             Self::component_to_real(self.b()),
         )
     }
+
+    /// Creates a new instance from a packed 15-bit color in the SNES format (see the type documentation). The unused bit is
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `value`: The packed color.
+    pub fn from_bgr555(value: u16) -> Self {
+        Self {
+            value: value & Self::BGR555_MASK,
+        }
+    }
+
+    /// Retrieves the packed 15-bit color in the SNES format (see the type documentation). The unused bit is always `0`.
+    pub fn to_bgr555(&self) -> u16 {
+        self.value & Self::BGR555_MASK
+    }
+
+    /// Linearly interpolates between this color and another color. This is useful for fading between colors.
+    ///
+    /// # Arguments
+    ///
+    /// * `other`: The color to interpolate to.
+    /// * `t`: The interpolation factor, where `0.0` results in this color and `1.0` results in `other`. The value is clamped to
+    ///   that range.
+    pub fn lerp(&self, other: &PaletteColor, t: f32) -> Self {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let lerp = |from: u8, to: u8| {
+            let value = f32::from(from) + (f32::from(to) - f32::from(from)) * t;
+            // The cast is OK here, because the value lies between two 5-bit values
+            value.round() as u8
+        };
+        Self::new(
+            lerp(self.r(), other.r()),
+            lerp(self.g(), other.g()),
+            lerp(self.b(), other.b()),
+        )
+    }
+}
+
+#[cfg(feature = "art")]
+impl From<PaletteColor> for ves_art_core::sprite::Color {
+    fn from(color: PaletteColor) -> Self {
+        Self::Opaque(ves_art_core::color::rgb_from_bgr555(color.to_bgr555()))
+    }
+}
+
+/// Converts a [`Color`](ves_art_core::sprite::Color). Transparent colors are converted to black, since transparency is determined
+/// by the palette index, rather than the color.
+#[cfg(feature = "art")]
+impl From<ves_art_core::sprite::Color> for PaletteColor {
+    fn from(color: ves_art_core::sprite::Color) -> Self {
+        match color {
+            ves_art_core::sprite::Color::Opaque(rgb) => {
+                Self::from_bgr555(ves_art_core::color::rgb_to_bgr555(rgb))
+            }
+            ves_art_core::sprite::Color::Transparent => Self::default(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -474,4 +969,296 @@ mod tests_palette_color {
             "PaletteColor { r: 12, g: 22, b: 7 }"
         );
     }
+
+    #[test]
+    fn bgr555() {
+        let subject = PaletteColor::from_bgr555(TEST_VAL);
+        assert_eq!(subject.to_bgr555(), TEST_VAL);
+        // The unused bit is ignored
+        assert_eq!(PaletteColor::from_bgr555(TEST_VAL | 0x8000), subject);
+        let subject: PaletteColor = (TEST_VAL | 0x8000).into();
+        assert_eq!(subject.to_bgr555(), TEST_VAL);
+    }
+
+    #[test]
+    fn real() {
+        let subject: PaletteColor = TEST_VAL.into();
+        let (r, g, b) = subject.to_real();
+        assert_eq!((r, g, b), (0b01100011, 0b10110101, 0b00111001));
+        assert_eq!(PaletteColor::from_real(r, g, b), subject);
+    }
+
+    #[test]
+    fn lerp() {
+        let from = PaletteColor::new(0, 31, 10);
+        let to = PaletteColor::new(31, 0, 10);
+        assert_eq!(from.lerp(&to, 0.0), from);
+        assert_eq!(from.lerp(&to, 1.0), to);
+        assert_eq!(from.lerp(&to, 0.5), PaletteColor::new(16, 16, 10));
+        // The factor is clamped
+        assert_eq!(from.lerp(&to, -1.0), from);
+        assert_eq!(from.lerp(&to, 2.0), to);
+        assert_eq!(from.lerp(&to, f32::NAN), from);
+    }
+
+    #[cfg(feature = "art")]
+    #[test]
+    fn art_color() {
+        use ves_art_core::sprite::Color;
+
+        let subject: PaletteColor = TEST_VAL.into();
+        let color = Color::from(subject);
+        let (r, g, b) = subject.to_real();
+        assert_eq!(color, Color::new(r, g, b));
+        assert_eq!(PaletteColor::from(color), subject);
+        assert_eq!(
+            PaletteColor::from(Color::Transparent),
+            PaletteColor::default()
+        );
+    }
+}
+
+bit_struct!(
+    /// The screen control register. This register is applied to the final colors during compositing.
+    ///
+    /// The internal format is as follows:
+    /// * Bits 0-3: Master brightness (0 is black, 15 is full brightness).
+    /// * Bit 4: Red emphasis flag.
+    /// * Bit 5: Green emphasis flag.
+    /// * Bit 6: Blue emphasis flag.
+    /// * Bit 7: Unused.
+    #[derive(Copy, Clone, Eq, PartialEq)]
+    pub struct ScreenControl {
+        value: u8
+    }
+
+    impl {
+        #[bit_struct_field(shift = 0, mask = 0xF)]
+        /// The master brightness.
+        pub fn brightness(&self) -> u8;
+
+        #[bit_struct_field(shift = 4, mask = 0b1)]
+        fn emphasis_r(&self) -> u8;
+
+        #[bit_struct_field(shift = 5, mask = 0b1)]
+        fn emphasis_g(&self) -> u8;
+
+        #[bit_struct_field(shift = 6, mask = 0b1)]
+        fn emphasis_b(&self) -> u8;
+    }
+
+    padding {
+        #[bit_struct_field(shift = 7, mask = 0b1)]
+        fn unused(&self) -> u8;
+    }
+);
+
+impl Default for ScreenControl {
+    /// Creates a [`ScreenControl`] with full brightness and no color emphasis.
+    fn default() -> Self {
+        Self::new(Self::MAX_BRIGHTNESS, 0, 0, 0)
+    }
+}
+
+impl ScreenControl {
+    /// The maximum value for the master brightness.
+    pub const MAX_BRIGHTNESS: u8 = 0xF;
+
+    /// Retrieves the color emphasis flags in the form `(red, green, blue)`.
+    pub fn emphasis(&self) -> (bool, bool, bool) {
+        (
+            self.emphasis_r() != 0,
+            self.emphasis_g() != 0,
+            self.emphasis_b() != 0,
+        )
+    }
+
+    /// Sets the color emphasis flags.
+    pub fn set_emphasis(&mut self, r: bool, g: bool, b: bool) {
+        self.set_emphasis_r(r as u8);
+        self.set_emphasis_g(g as u8);
+        self.set_emphasis_b(b as u8);
+    }
+
+    /// Applies the register to a "real" color (see [`PaletteColor::to_real()`]).
+    ///
+    /// The color is first scaled according to the master brightness. If any of the emphasis flags are set, the color components
+    /// that are *not* emphasized are attenuated to 3/4 of their value.
+    ///
+    /// # Arguments
+    ///
+    /// * `color`: The color in the form `(r, g, b)`.
+    pub fn apply(&self, color: (u8, u8, u8)) -> (u8, u8, u8) {
+        let brightness = u16::from(self.brightness());
+        let emphasis = self.emphasis();
+        let any_emphasis = emphasis.0 || emphasis.1 || emphasis.2;
+
+        let apply_component = |component: u8, emphasized: bool| {
+            let mut value = u16::from(component) * brightness / u16::from(Self::MAX_BRIGHTNESS);
+            if any_emphasis && !emphasized {
+                value = value * 3 / 4;
+            }
+            // The unwrap is OK here, because the value can only have decreased
+            u8::try_from(value).unwrap()
+        };
+
+        (
+            apply_component(color.0, emphasis.0),
+            apply_component(color.1, emphasis.1),
+            apply_component(color.2, emphasis.2),
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unusual_byte_groupings)]
+mod tests_screen_control {
+    use super::ScreenControl;
+
+    // brightness: 9
+    // emphasis_r: 1
+    // emphasis_g: 0
+    // emphasis_b: 1
+    //                     bgr brightness
+    const TEST_VAL: u8 = 0b0_101_1001;
+
+    #[test]
+    fn zero() {
+        let subject: ScreenControl = 0.into();
+        assert_eq!(subject.value, 0);
+        assert_eq!(subject.brightness(), 0);
+        assert_eq!(subject.emphasis(), (false, false, false));
+    }
+
+    #[test]
+    fn default() {
+        let subject = ScreenControl::default();
+        assert_eq!(subject.brightness(), 15);
+        assert_eq!(subject.emphasis(), (false, false, false));
+    }
+
+    #[test]
+    fn getters() {
+        let subject: ScreenControl = TEST_VAL.into();
+        assert_eq!(subject.value, TEST_VAL);
+        assert_eq!(subject.brightness(), 9);
+        assert_eq!(subject.emphasis(), (true, false, true));
+    }
+
+    #[test]
+    fn constructor() {
+        let subject = ScreenControl::new(9, 1, 0, 1);
+        assert_eq!(subject.value, TEST_VAL);
+    }
+
+    #[test]
+    fn setters() {
+        let mut subject: ScreenControl = TEST_VAL.into();
+
+        subject.set_brightness(3);
+        subject.set_emphasis(false, true, false);
+
+        assert_eq!(subject.brightness(), 3);
+        assert_eq!(subject.emphasis(), (false, true, false));
+    }
+
+    #[test]
+    fn apply() {
+        let color = (255, 128, 60);
+        assert_eq!(ScreenControl::default().apply(color), color);
+        assert_eq!(ScreenControl::new(0, 0, 0, 0).apply(color), (0, 0, 0));
+        assert_eq!(ScreenControl::new(5, 0, 0, 0).apply(color), (85, 42, 20));
+        assert_eq!(ScreenControl::new(15, 1, 0, 0).apply(color), (255, 96, 45));
+        assert_eq!(ScreenControl::new(15, 1, 1, 1).apply(color), color);
+    }
+
+    #[test]
+    fn debug() {
+        let subject: ScreenControl = TEST_VAL.into();
+        assert_eq!(
+            format!("{:?}", subject).as_str(),
+            "ScreenControl { brightness: 9, emphasis_r: 1, emphasis_g: 0, emphasis_b: 1 }"
+        );
+    }
+}
+
+/// A table in the core that can be the target of a DMA-style block copy.
+///
+/// A block copy updates a range of consecutive entries in a table with a single call into the core. The entries are addressed by
+/// their index in the table (rather than by byte offset) and are expected to be laid out in memory as their internal value in
+/// little-endian byte order, which is the native representation of the entry types on WebAssembly.
+///
+/// Note that the values of the existing tables are part of the protocol. New tables (e.g. for background tilemaps) get new values.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum DmaTable {
+    /// The OAM table. Every entry is an [`OamTableEntry`].
+    Oam = 1,
+    /// The palette table. Every entry is a [`PaletteColor`]. The palettes are laid out one after the other, such that the color at
+    /// [`PaletteIndex`] `i` of the palette at [`PaletteTableIndex`] `p` is entry `p * 16 + i`.
+    Palettes,
+    /// The OAM attributes table. Every entry is an [`OamAttributes`], which belongs to the [`OamTableEntry`] at the same index.
+    OamAttributes,
+}
+
+impl DmaTable {
+    /// Retrieves the size of a single entry in bytes.
+    pub fn entry_size(&self) -> usize {
+        match self {
+            DmaTable::Oam => std::mem::size_of::<OamTableEntry>(),
+            DmaTable::Palettes => std::mem::size_of::<PaletteColor>(),
+            DmaTable::OamAttributes => std::mem::size_of::<OamAttributes>(),
+        }
+    }
+
+    /// Retrieves the number of entries in the table.
+    pub fn entry_count(&self) -> usize {
+        match self {
+            DmaTable::Oam | DmaTable::OamAttributes => 128,
+            DmaTable::Palettes => 256 * 16,
+        }
+    }
+}
+
+impl TryFrom<u8> for DmaTable {
+    type Error = String;
+
+    fn try_from(val: u8) -> Result<Self, Self::Error> {
+        match val {
+            1 => Ok(DmaTable::Oam),
+            2 => Ok(DmaTable::Palettes),
+            3 => Ok(DmaTable::OamAttributes),
+            val => Err(format!("Invalid DmaTable value: {val}.")),
+        }
+    }
+}
+
+impl From<DmaTable> for u8 {
+    fn from(table: DmaTable) -> Self {
+        match table {
+            DmaTable::Oam => 1,
+            DmaTable::Palettes => 2,
+            DmaTable::OamAttributes => 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_dma_table {
+    use super::DmaTable;
+
+    #[test]
+    fn conversion() {
+        for table in [DmaTable::Oam, DmaTable::Palettes, DmaTable::OamAttributes] {
+            assert_eq!(Ok(table), DmaTable::try_from(u8::from(table)));
+        }
+        assert!(DmaTable::try_from(0).is_err());
+        assert!(DmaTable::try_from(4).is_err());
+    }
+
+    #[test]
+    fn entry_size() {
+        assert_eq!(DmaTable::Oam.entry_size(), 8);
+        assert_eq!(DmaTable::Palettes.entry_size(), 2);
+        assert_eq!(DmaTable::OamAttributes.entry_size(), 2);
+    }
 }
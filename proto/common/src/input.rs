@@ -0,0 +1,249 @@
+use crate::bit_struct;
+use std::fmt::{Debug, Formatter};
+
+/// The number of players (and thus controllers) that are supported.
+pub const PLAYER_COUNT: usize = 2;
+
+bit_struct!(
+    /// The index of a player. Every player has their own controller.
+    ///
+    /// The internal format is as follows:
+    /// * Bit 0: Index.
+    /// * Bits 1-7: Unused.
+    #[derive(Copy, Clone, Eq, PartialEq, Hash, Default)]
+    pub struct PlayerIndex {
+        value: u8
+    }
+
+    impl {
+        #[bit_struct_field(shift = 0, mask = 0b1)]
+        /// The index value.
+        pub fn index(&self) -> u8;
+    }
+
+    padding {
+        #[bit_struct_field(shift = 1, mask = 0b1111111)]
+        fn unused(&self) -> u8;
+    }
+);
+
+impl PlayerIndex {
+    /// Creates an iterator over all players.
+    pub fn all() -> impl Iterator<Item = PlayerIndex> {
+        (0..PLAYER_COUNT as u8).map(PlayerIndex::new)
+    }
+}
+
+impl From<PlayerIndex> for usize {
+    fn from(index: PlayerIndex) -> Self {
+        index.value.into()
+    }
+}
+
+impl TryFrom<usize> for PlayerIndex {
+    type Error = String;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        if value < PLAYER_COUNT {
+            Ok(PlayerIndex::new(value as u8))
+        } else {
+            Err(format!("Player index {value} is out of range."))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_player_index {
+    use super::{PlayerIndex, PLAYER_COUNT};
+
+    #[test]
+    fn all() {
+        let all: Vec<usize> = PlayerIndex::all().map(usize::from).collect();
+        assert_eq!((0..PLAYER_COUNT).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn try_from() {
+        assert_eq!(Ok(PlayerIndex::new(1)), PlayerIndex::try_from(1usize));
+        assert!(PlayerIndex::try_from(PLAYER_COUNT).is_err());
+    }
+}
+
+/// A button on the VES controller.
+///
+/// The discriminant is the bit that represents the button in [`Buttons`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Button {
+    Up = 0,
+    Down,
+    Left,
+    Right,
+    A,
+    B,
+    X,
+    Y,
+    L,
+    R,
+    Start,
+    Select,
+}
+
+impl Button {
+    /// All buttons.
+    pub const ALL: [Button; 12] = [
+        Button::Up,
+        Button::Down,
+        Button::Left,
+        Button::Right,
+        Button::A,
+        Button::B,
+        Button::X,
+        Button::Y,
+        Button::L,
+        Button::R,
+        Button::Start,
+        Button::Select,
+    ];
+
+    /// Retrieves the name of the button, as used in configuration files and on the debug console.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Button::Up => "up",
+            Button::Down => "down",
+            Button::Left => "left",
+            Button::Right => "right",
+            Button::A => "a",
+            Button::B => "b",
+            Button::X => "x",
+            Button::Y => "y",
+            Button::L => "l",
+            Button::R => "r",
+            Button::Start => "start",
+            Button::Select => "select",
+        }
+    }
+
+    /// Finds a button by its name (see [`Button::name()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `name`: The name of the button.
+    pub fn from_name(name: &str) -> Result<Self, String> {
+        Self::ALL
+            .into_iter()
+            .find(|button| button.name() == name)
+            .ok_or_else(|| format!("Unknown button '{name}'."))
+    }
+
+    #[inline(always)]
+    fn mask(&self) -> u16 {
+        1 << *self as u16
+    }
+}
+
+/// The state of all buttons on a VES controller.
+///
+/// The state can be converted to an [u16] and sent from the core to the game. Every [`Button`] is represented by a single bit,
+/// which is set if the button is pressed:
+/// * Bits 0-3: Up, down, left and right.
+/// * Bits 4-7: A, B, X and Y.
+/// * Bits 8-9: L and R.
+/// * Bits 10-11: Start and select.
+/// * Bits 12-15: Unused.
+#[derive(Copy, Clone, Default, Eq, PartialEq)]
+pub struct Buttons {
+    value: u16,
+}
+
+impl Buttons {
+    /// Determines whether a button is pressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `button`: The button.
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.value & button.mask() != 0
+    }
+
+    /// Sets whether a button is pressed.
+    ///
+    /// # Arguments
+    ///
+    /// * `button`: The button.
+    /// * `pressed`: Whether the button is pressed.
+    pub fn set_pressed(&mut self, button: Button, pressed: bool) {
+        if pressed {
+            self.value |= button.mask();
+        } else {
+            self.value &= !button.mask();
+        }
+    }
+}
+
+impl From<u16> for Buttons {
+    fn from(value: u16) -> Self {
+        Self { value }
+    }
+}
+
+impl From<Buttons> for u16 {
+    fn from(buttons: Buttons) -> Self {
+        buttons.value
+    }
+}
+
+impl From<&Buttons> for u16 {
+    fn from(buttons: &Buttons) -> Self {
+        buttons.value
+    }
+}
+
+impl Debug for Buttons {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_set()
+            .entries(
+                Button::ALL
+                    .iter()
+                    .filter(|button| self.is_pressed(**button)),
+            )
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests_buttons {
+    use super::{Button, Buttons};
+
+    #[test]
+    fn test_names() {
+        for button in Button::ALL {
+            assert_eq!(Ok(button), Button::from_name(button.name()));
+        }
+        assert!(Button::from_name("turbo").is_err());
+    }
+
+    #[test]
+    fn test_pressed() {
+        let mut buttons = Buttons::default();
+        assert!(!buttons.is_pressed(Button::A));
+
+        buttons.set_pressed(Button::A, true);
+        buttons.set_pressed(Button::Select, true);
+        assert!(buttons.is_pressed(Button::A));
+        assert!(buttons.is_pressed(Button::Select));
+        assert!(!buttons.is_pressed(Button::B));
+        assert_eq!(0b1000_0001_0000, u16::from(buttons));
+
+        buttons.set_pressed(Button::A, false);
+        assert!(!buttons.is_pressed(Button::A));
+        assert_eq!(Buttons::from(0b1000_0000_0000), buttons);
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut buttons = Buttons::default();
+        buttons.set_pressed(Button::Up, true);
+        buttons.set_pressed(Button::Start, true);
+        assert_eq!("{Up, Start}", format!("{:?}", buttons));
+    }
+}
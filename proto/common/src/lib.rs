@@ -1,4 +1,16 @@
 pub mod api;
 pub mod gpu;
+pub mod input;
 pub mod log;
+pub mod metasprite;
+pub mod replay;
+pub mod result;
+pub mod system;
 mod util;
+
+pub use util::FieldOverflow;
+
+/// The serde crate, for the expansion of [`bit_struct!()`].
+#[cfg(feature = "serde_support")]
+#[doc(hidden)]
+pub use serde as __serde;
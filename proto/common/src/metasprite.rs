@@ -0,0 +1,383 @@
+//! Metasprites: groups of objects that are placed on the screen together.
+//!
+//! Characters are usually larger than a single tile, so they are drawn with multiple objects. A [`Metasprite`] describes such a group
+//! as a table of objects with offsets relative to the origin of the group (the top-left corner of its bounding box). The tables are
+//! compiled from the cels of the artwork at build time (see [`write_metasprite_module()`]), such that they end up as constants in the
+//! game. A game places a metasprite with [`CoreBootstrap::place_metasprite()`](crate::api::CoreBootstrap::place_metasprite).
+
+use crate::gpu::OamTableEntry;
+
+/// The size of the coordinate space of the objects. Positions wrap around at this value (see [`OamTableEntry`]).
+const POSITION_RANGE: i32 = 0x200;
+
+/// An object in a [`Metasprite`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct MetaspriteObject {
+    /// The index in the character table (the tile).
+    pub char_table_index: u32,
+    /// The index in the palette table.
+    pub palette_table_index: u8,
+    /// The horizontal offset from the origin of the metasprite.
+    pub x: i16,
+    /// The vertical offset from the origin of the metasprite.
+    pub y: i16,
+    /// Whether the object is flipped horizontally.
+    pub h_flip: bool,
+    /// Whether the object is flipped vertically.
+    pub v_flip: bool,
+}
+
+impl MetaspriteObject {
+    /// Creates a new instance.
+    pub const fn new(
+        char_table_index: u32,
+        palette_table_index: u8,
+        x: i16,
+        y: i16,
+        h_flip: bool,
+        v_flip: bool,
+    ) -> Self {
+        Self {
+            char_table_index,
+            palette_table_index,
+            x,
+            y,
+            h_flip,
+            v_flip,
+        }
+    }
+}
+
+/// A group of objects that make up a larger image.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Metasprite {
+    objects: &'static [MetaspriteObject],
+}
+
+impl Metasprite {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `objects`: The objects, in the order of their priority (the first object ends up on top).
+    pub const fn new(objects: &'static [MetaspriteObject]) -> Self {
+        Self { objects }
+    }
+
+    /// Retrieves the objects.
+    pub const fn objects(&self) -> &'static [MetaspriteObject] {
+        self.objects
+    }
+
+    /// Retrieves the number of objects.
+    pub const fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Determines whether the metasprite contains no objects.
+    pub const fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Converts the objects into OAM entries.
+    ///
+    /// # Arguments
+    ///
+    /// * `origin`: The position of the origin on the screen in the form `(x, y)`. Objects wrap around at the edges of the coordinate
+    ///   space, so negative coordinates place the metasprite partially beyond the left or top edge of the screen.
+    ///
+    /// returns: The entries, in the order of the objects.
+    pub fn oam_entries(&self, origin: (i32, i32)) -> Vec<OamTableEntry> {
+        // The casts are OK here, because the coordinates have been reduced to the coordinate space
+        let wrap = |origin: i32, offset: i16| {
+            origin
+                .wrapping_add(i32::from(offset))
+                .rem_euclid(POSITION_RANGE) as u16
+        };
+        self.objects
+            .iter()
+            .map(|object| {
+                OamTableEntry::new(
+                    wrap(origin.0, object.x),
+                    wrap(origin.1, object.y),
+                    object.palette_table_index,
+                    u8::from(object.h_flip),
+                    u8::from(object.v_flip),
+                    object.char_table_index,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Compiles a cel into the objects of a metasprite.
+///
+/// The offsets are relative to the top-left corner of the bounding box of the sprites. Sprites wrap around at the edges of the
+/// screen, so a sprite that lies more than half a screen away from the first sprite is considered to lie on the other side of it.
+///
+/// # Arguments
+///
+/// * `cel`: The cel. The tile and palette references are used as the character table and palette table indices.
+/// * `screen_size`: The size of the screen on which the cel has been captured.
+#[cfg(feature = "art")]
+pub fn compile_cel(
+    cel: &ves_art_core::sprite::Cel,
+    screen_size: ves_art_core::geom_art::Size,
+) -> Result<Vec<MetaspriteObject>, String> {
+    let sprites = cel.sprites();
+    let first = match sprites.first() {
+        Some(first) => first.position(),
+        None => return Ok(Vec::new()),
+    };
+
+    let wrap = |value: u32, from: u32, len: u32| -> Result<i32, String> {
+        let len = i32::try_from(len).map_err(|_| format!("Invalid screen size: {len}."))?;
+        // The casts are OK here, because the values have been reduced to the screen size
+        let offset = (i64::from(value) - i64::from(from)).rem_euclid(i64::from(len)) as i32;
+        Ok(if offset > len / 2 {
+            offset - len
+        } else {
+            offset
+        })
+    };
+    let offsets = sprites
+        .iter()
+        .map(|sprite| {
+            let position = sprite.position();
+            Ok((
+                wrap(position.x.raw(), first.x.raw(), screen_size.width.raw())?,
+                wrap(position.y.raw(), first.y.raw(), screen_size.height.raw())?,
+            ))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    // The unwraps are OK here, because there is at least one sprite
+    let min_x = offsets.iter().map(|(x, _)| *x).min().unwrap();
+    let min_y = offsets.iter().map(|(_, y)| *y).min().unwrap();
+
+    sprites
+        .iter()
+        .zip(offsets)
+        .map(|(sprite, (x, y))| {
+            let offset = |value: i32| {
+                i16::try_from(value).map_err(|_| format!("Offset {value} is out of range."))
+            };
+            Ok(MetaspriteObject::new(
+                u32::try_from(sprite.tile().value())
+                    .map_err(|_| format!("{} is out of range.", sprite.tile()))?,
+                u8::try_from(sprite.palette().value())
+                    .map_err(|_| format!("{} is out of range.", sprite.palette()))?,
+                offset(x - min_x)?,
+                offset(y - min_y)?,
+                sprite.h_flip(),
+                sprite.v_flip(),
+            ))
+        })
+        .collect()
+}
+
+/// Writes a Rust module with a constant for every metasprite and a typed accessor.
+///
+/// For a metasprite named `WalkLeft0`, the module contains the constant `WALK_LEFT_0` and the variant `MetaspriteId::WalkLeft0`.
+/// `MetaspriteId::ALL` lists all variants and `MetaspriteId::metasprite()` looks up the constant for a variant.
+///
+/// # Arguments
+///
+/// * `out`: The output.
+/// * `metasprites`: The names of the metasprites (in UpperCamelCase) and their objects (see [`compile_cel()`]).
+pub fn write_metasprite_module(
+    out: &mut impl std::fmt::Write,
+    metasprites: &[(String, Vec<MetaspriteObject>)],
+) -> std::fmt::Result {
+    writeln!(out, "// Generated by the asset compiler. Do not edit.")?;
+    writeln!(out, "#![allow(dead_code)]")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "use ves_proto_common::metasprite::{{Metasprite, MetaspriteObject}};"
+    )?;
+
+    for (name, objects) in metasprites {
+        writeln!(out)?;
+        writeln!(
+            out,
+            "pub const {}: Metasprite = Metasprite::new(&[",
+            constant_name(name)
+        )?;
+        for object in objects {
+            writeln!(
+                out,
+                "    MetaspriteObject::new({}, {}, {}, {}, {}, {}),",
+                object.char_table_index,
+                object.palette_table_index,
+                object.x,
+                object.y,
+                object.h_flip,
+                object.v_flip
+            )?;
+        }
+        writeln!(out, "]);")?;
+    }
+
+    writeln!(out)?;
+    writeln!(out, "/// The identifiers of the compiled metasprites.")?;
+    writeln!(out, "#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]")?;
+    writeln!(out, "pub enum MetaspriteId {{")?;
+    for (name, _) in metasprites {
+        writeln!(out, "    {name},")?;
+    }
+    writeln!(out, "}}")?;
+    writeln!(out)?;
+    writeln!(out, "impl MetaspriteId {{")?;
+    writeln!(out, "    /// All identifiers, in the order of compilation.")?;
+    writeln!(
+        out,
+        "    pub const ALL: [MetaspriteId; {}] = [",
+        metasprites.len()
+    )?;
+    for (name, _) in metasprites {
+        writeln!(out, "        MetaspriteId::{name},")?;
+    }
+    writeln!(out, "    ];")?;
+    writeln!(out)?;
+    writeln!(out, "    /// Retrieves the metasprite.")?;
+    writeln!(out, "    pub fn metasprite(self) -> &'static Metasprite {{")?;
+    writeln!(out, "        match self {{")?;
+    for (name, _) in metasprites {
+        writeln!(
+            out,
+            "            MetaspriteId::{} => &{},",
+            name,
+            constant_name(name)
+        )?;
+    }
+    writeln!(out, "        }}")?;
+    writeln!(out, "    }}")?;
+    writeln!(out, "}}")
+}
+
+/// Converts an UpperCamelCase name to SCREAMING_SNAKE_CASE. A word boundary is inserted before every uppercase letter and before every
+/// number that follows a letter.
+fn constant_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 4);
+    let mut previous: Option<char> = None;
+    for c in name.chars() {
+        if let Some(previous) = previous {
+            let boundary = (c.is_uppercase() && !previous.is_uppercase())
+                || (c.is_ascii_digit() && !previous.is_ascii_digit());
+            if boundary && previous != '_' {
+                result.push('_');
+            }
+        }
+        result.extend(c.to_uppercase());
+        previous = Some(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests_metasprite {
+    use super::{constant_name, write_metasprite_module, Metasprite, MetaspriteObject};
+    use crate::gpu::OamTableEntry;
+
+    #[test]
+    fn constant_names() {
+        assert_eq!("WALK_LEFT_0", constant_name("WalkLeft0"));
+        assert_eq!("FRAME_12", constant_name("Frame12"));
+        assert_eq!("IDLE", constant_name("Idle"));
+    }
+
+    #[test]
+    fn metasprite() {
+        const OBJECTS: &[MetaspriteObject] = &[
+            MetaspriteObject::new(3, 1, 0, 0, false, false),
+            MetaspriteObject::new(4, 1, 8, 0, true, false),
+        ];
+        const SUBJECT: Metasprite = Metasprite::new(OBJECTS);
+        assert_eq!(2, SUBJECT.len());
+        assert!(!SUBJECT.is_empty());
+        assert_eq!(OBJECTS, SUBJECT.objects());
+    }
+
+    #[test]
+    fn oam_entries() {
+        const SUBJECT: Metasprite = Metasprite::new(&[
+            MetaspriteObject::new(3, 1, 0, 0, false, false),
+            MetaspriteObject::new(4, 2, 8, 16, true, false),
+        ]);
+        assert_eq!(
+            vec![
+                OamTableEntry::new(100, 50, 1, 0, 0, 3),
+                OamTableEntry::new(108, 66, 2, 1, 0, 4),
+            ],
+            SUBJECT.oam_entries((100, 50))
+        );
+        // Positions wrap around
+        assert_eq!(
+            vec![
+                OamTableEntry::new(508, 0, 1, 0, 0, 3),
+                OamTableEntry::new(4, 16, 2, 1, 0, 4),
+            ],
+            SUBJECT.oam_entries((-4, 512))
+        );
+    }
+
+    #[test]
+    fn write_module() {
+        let metasprites = vec![
+            (
+                "WalkLeft0".to_string(),
+                vec![MetaspriteObject::new(3, 1, 0, -4, false, true)],
+            ),
+            ("Empty".to_string(), Vec::new()),
+        ];
+        let mut out = String::new();
+        write_metasprite_module(&mut out, &metasprites).unwrap();
+
+        assert!(out.contains("pub const WALK_LEFT_0: Metasprite = Metasprite::new(&[\n    MetaspriteObject::new(3, 1, 0, -4, false, true),\n]);"));
+        assert!(out.contains("pub const EMPTY: Metasprite = Metasprite::new(&[\n]);"));
+        assert!(out.contains("pub const ALL: [MetaspriteId; 2] = ["));
+        assert!(out.contains("MetaspriteId::WalkLeft0 => &WALK_LEFT_0,"));
+    }
+
+    #[cfg(feature = "art")]
+    #[test]
+    fn compile_cel() {
+        use ves_art_core::geom_art::Size;
+        use ves_art_core::sprite::{Cel, PaletteRef, Sprite, TileRef};
+
+        let sprite = |tile: usize, x: u32, y: u32| {
+            Sprite::new(
+                TileRef::new(tile),
+                PaletteRef::new(2),
+                (x, y).into(),
+                tile == 1,
+                false,
+            )
+        };
+        // The second sprite wraps around the left edge of the screen
+        let cel = Cel::new(vec![sprite(0, 4, 20), sprite(1, 252, 28), sprite(2, 4, 12)]);
+        let objects = super::compile_cel(&cel, Size::new(256, 224)).unwrap();
+        assert_eq!(
+            vec![
+                MetaspriteObject::new(0, 2, 8, 8, false, false),
+                MetaspriteObject::new(1, 2, 0, 16, true, false),
+                MetaspriteObject::new(2, 2, 8, 0, false, false),
+            ],
+            objects
+        );
+
+        assert!(
+            super::compile_cel(&Cel::new(Vec::new()), Size::new(256, 224))
+                .unwrap()
+                .is_empty()
+        );
+        let cel = Cel::new(vec![Sprite::new(
+            TileRef::new(0),
+            PaletteRef::new(256),
+            (0, 0).into(),
+            false,
+            false,
+        )]);
+        assert!(super::compile_cel(&cel, Size::new(256, 224)).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! The `ves-replay` format for recording the input of a game session.
+//!
+//! A replay consists of a hash of the initial state, followed by the input of all players, the duration and the restarts (resets
+//! and cold boots) of every frame. Since games are deterministic, feeding the recorded input into a game that starts from the same
+//! state and restarting it at the same frames reproduces the session exactly.
+//! This allows for turning a session into a movie by rendering it without a window.
+//!
+//! The binary format is as follows (all integers are little-endian):
+//! * Magic bytes: `VESR`.
+//! * Version: `u8`.
+//! * Initial state hash: `u64` (see [`state_hash()`]).
+//! * Frame count: `u32`.
+//! * For every frame:
+//!   * Duration in microseconds: `u32`.
+//!   * Restart: `u8`. `0` if the game was not restarted, otherwise the [`BootKind`] plus one. Version 1 does not have this field.
+//!   * For every player (see [`PLAYER_COUNT`]): connected flag (`u8`) and buttons (`u16`).
+
+use crate::input::{Buttons, PlayerIndex, PLAYER_COUNT};
+use crate::system::BootKind;
+use std::time::Duration;
+
+/// The magic bytes at the start of every replay.
+const MAGIC: &[u8; 4] = b"VESR";
+/// The version of the format.
+const VERSION: u8 = 2;
+/// The size of the header in bytes.
+const HEADER_LEN: usize = MAGIC.len() + 1 + 8 + 4;
+/// The size of a frame in bytes.
+const FRAME_LEN: usize = 5 + PLAYER_COUNT * 3;
+/// The size of a frame in bytes in version 1, which does not record restarts.
+const FRAME_LEN_V1: usize = 4 + PLAYER_COUNT * 3;
+
+/// Calculates the hash of the data that makes up the initial state (for instance: the game module).
+///
+/// This uses 64-bit FNV-1a, such that the hash is the same on all platforms and for all builds.
+///
+/// # Arguments
+///
+/// * `data`: The data.
+pub fn state_hash(data: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    data.iter().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(PRIME)
+    })
+}
+
+/// The input of a single player in a single frame.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerInput {
+    /// Whether the player has an input device.
+    pub connected: bool,
+    /// The state of the buttons.
+    pub buttons: Buttons,
+}
+
+/// The input for a single frame.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReplayFrame {
+    /// The input of every player.
+    pub inputs: [PlayerInput; PLAYER_COUNT],
+    /// The time between the start of this frame and the start of the next frame. This is stored with microsecond precision.
+    pub duration: Duration,
+    /// The way in which the game was restarted right before this frame or `None` if it was not restarted.
+    pub restart: Option<BootKind>,
+}
+
+impl ReplayFrame {
+    /// Retrieves the input of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    pub fn input(&self, player: PlayerIndex) -> PlayerInput {
+        self.inputs[usize::from(player)]
+    }
+}
+
+/// A recording of the input of a game session.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Replay {
+    state_hash: u64,
+    frames: Vec<ReplayFrame>,
+}
+
+impl Replay {
+    /// Creates a new instance without any frames.
+    ///
+    /// # Arguments
+    ///
+    /// * `state_hash`: The hash of the initial state (see [`state_hash()`]).
+    pub fn new(state_hash: u64) -> Self {
+        Self {
+            state_hash,
+            frames: Vec::new(),
+        }
+    }
+
+    /// Retrieves the hash of the initial state.
+    pub fn state_hash(&self) -> u64 {
+        self.state_hash
+    }
+
+    /// Retrieves the frames.
+    pub fn frames(&self) -> &[ReplayFrame] {
+        &self.frames
+    }
+
+    /// Appends a frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame`: The frame.
+    pub fn push_frame(&mut self, frame: ReplayFrame) {
+        self.frames.push(frame);
+    }
+
+    /// Serializes the replay.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let frame_count = u32::try_from(self.frames.len())
+            .map_err(|_| format!("Too many frames in replay: {}.", self.frames.len()))?;
+
+        let mut out = Vec::with_capacity(HEADER_LEN + self.frames.len() * FRAME_LEN);
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.extend_from_slice(&self.state_hash.to_le_bytes());
+        out.extend_from_slice(&frame_count.to_le_bytes());
+        for frame in &self.frames {
+            let micros = u32::try_from(frame.duration.as_micros()).unwrap_or(u32::MAX);
+            out.extend_from_slice(&micros.to_le_bytes());
+            out.push(frame.restart.map_or(0, |kind| u32::from(kind) as u8 + 1));
+            for input in &frame.inputs {
+                out.push(u8::from(input.connected));
+                out.extend_from_slice(&u16::from(input.buttons).to_le_bytes());
+            }
+        }
+        Ok(out)
+    }
+
+    /// Deserializes a replay.
+    ///
+    /// # Arguments
+    ///
+    /// * `data`: The serialized replay.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, String> {
+        let (header, mut data) = split(data, HEADER_LEN)?;
+        if &header[0..4] != MAGIC {
+            return Err("Not a replay file.".to_string());
+        }
+        let frame_len = match header[4] {
+            1 => FRAME_LEN_V1,
+            VERSION => FRAME_LEN,
+            version => return Err(format!("Unsupported replay version: {version}.")),
+        };
+        let state_hash = u64::from_le_bytes(header[5..13].try_into().unwrap());
+        let frame_count = u32::from_le_bytes(header[13..17].try_into().unwrap()) as usize;
+
+        let mut frames = Vec::with_capacity(frame_count.min(data.len() / frame_len));
+        for _ in 0..frame_count {
+            let (frame, rest) = split(data, frame_len)?;
+            data = rest;
+
+            let micros = u32::from_le_bytes(frame[0..4].try_into().unwrap());
+            let (restart, input_data) = match frame_len {
+                FRAME_LEN_V1 => (None, &frame[4..]),
+                _ => {
+                    let restart = match frame[4] {
+                        0 => None,
+                        value => Some(BootKind::try_from(u32::from(value) - 1)?),
+                    };
+                    (restart, &frame[5..])
+                }
+            };
+            let mut inputs = [PlayerInput::default(); PLAYER_COUNT];
+            for (input, bytes) in inputs.iter_mut().zip(input_data.chunks_exact(3)) {
+                input.connected = match bytes[0] {
+                    0 => false,
+                    1 => true,
+                    value => return Err(format!("Invalid connected flag: {value}.")),
+                };
+                input.buttons = u16::from_le_bytes([bytes[1], bytes[2]]).into();
+            }
+            frames.push(ReplayFrame {
+                inputs,
+                duration: Duration::from_micros(micros.into()),
+                restart,
+            });
+        }
+
+        if !data.is_empty() {
+            return Err(format!(
+                "Unexpected data after the last frame ({} bytes).",
+                data.len()
+            ));
+        }
+
+        Ok(Self { state_hash, frames })
+    }
+}
+
+fn split(data: &[u8], len: usize) -> Result<(&[u8], &[u8]), String> {
+    if data.len() < len {
+        return Err("Unexpected end of replay data.".to_string());
+    }
+    Ok(data.split_at(len))
+}
+
+#[cfg(test)]
+mod tests_replay {
+    use super::{state_hash, PlayerInput, Replay, ReplayFrame};
+    use crate::input::{Button, Buttons, PlayerIndex};
+    use crate::system::BootKind;
+    use std::time::Duration;
+
+    fn create_replay() -> Replay {
+        let mut buttons = Buttons::default();
+        buttons.set_pressed(Button::A, true);
+        buttons.set_pressed(Button::Left, true);
+
+        let mut replay = Replay::new(0x0123_4567_89ab_cdef);
+        replay.push_frame(ReplayFrame::default());
+        replay.push_frame(ReplayFrame {
+            inputs: [
+                PlayerInput {
+                    connected: true,
+                    buttons,
+                },
+                PlayerInput::default(),
+            ],
+            duration: Duration::from_micros(16_667),
+            restart: None,
+        });
+        replay.push_frame(ReplayFrame {
+            restart: Some(BootKind::Reset),
+            ..ReplayFrame::default()
+        });
+        replay.push_frame(ReplayFrame {
+            restart: Some(BootKind::ColdBoot),
+            ..ReplayFrame::default()
+        });
+        replay
+    }
+
+    #[test]
+    fn test_state_hash() {
+        assert_eq!(0xcbf29ce484222325, state_hash(&[]));
+        assert_eq!(0xaf63dc4c8601ec8c, state_hash(b"a"));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let replay = create_replay();
+        let data = replay.to_bytes().unwrap();
+        assert_eq!(17 + 4 * 11, data.len());
+
+        let read = Replay::from_bytes(&data).unwrap();
+        assert_eq!(replay, read);
+        assert_eq!(
+            Buttons::from(0b1_0100),
+            read.frames()[1].input(PlayerIndex::new(0)).buttons
+        );
+        assert_eq!(Some(BootKind::Reset), read.frames()[2].restart);
+    }
+
+    #[test]
+    fn test_version_1() {
+        let mut data = b"VESR\x01".to_vec();
+        data.extend_from_slice(&0x0123_4567_89ab_cdef_u64.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&16_667u32.to_le_bytes());
+        data.extend_from_slice(&[1, 0b1_0100, 0, 0, 0, 0]);
+
+        let read = Replay::from_bytes(&data).unwrap();
+        assert_eq!(0x0123_4567_89ab_cdef, read.state_hash());
+        let frame = read.frames()[0];
+        assert_eq!(Duration::from_micros(16_667), frame.duration);
+        assert_eq!(None, frame.restart);
+        assert_eq!(
+            Buttons::from(0b1_0100),
+            frame.input(PlayerIndex::new(0)).buttons
+        );
+        assert!(!frame.input(PlayerIndex::new(1)).connected);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let data = create_replay().to_bytes().unwrap();
+        assert!(Replay::from_bytes(&data[..data.len() - 1]).is_err());
+        assert!(Replay::from_bytes(&[data.as_slice(), &[0]].concat()).is_err());
+        assert!(Replay::from_bytes(b"VESX").is_err());
+
+        let mut invalid_restart = data.clone();
+        invalid_restart[17 + 4] = 3;
+        assert!(Replay::from_bytes(&invalid_restart).is_err());
+
+        let mut wrong_version = data;
+        wrong_version[4] = 3;
+        assert!(Replay::from_bytes(&wrong_version).is_err());
+    }
+}
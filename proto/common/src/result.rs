@@ -0,0 +1,153 @@
+//! The error-code convention for the host calls.
+//!
+//! Every host call returns an [i32]. A non-negative value indicates success and carries the return value of the call (or `0` if
+//! the call has no return value). A negative value is the code of a [`ProtoError`].
+
+use std::fmt::{Display, Formatter};
+
+/// The result of a host call.
+pub type ProtoResult<T> = Result<T, ProtoError>;
+
+/// An error that is returned by a host call.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum ProtoError {
+    /// An argument has a value that is not valid for the call (e.g. an unknown log level or player).
+    InvalidArgument,
+    /// An index or a range exceeds the bounds of a table.
+    OutOfRange,
+    /// A pointer and length do not describe a valid block of memory of the game, or the block does not contain valid data (e.g.
+    /// a string that is not UTF-8).
+    InvalidMemory,
+    /// An error with a code that is not known to this version of the protocol.
+    Unknown(UnknownCode),
+}
+
+/// The code of a [`ProtoError::Unknown`]. This is always a negative value, since non-negative values indicate success.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct UnknownCode(i32);
+
+impl UnknownCode {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `code`: The error code.
+    ///
+    /// returns: The instance or `None` if the code is not negative.
+    pub fn new(code: i32) -> Option<Self> {
+        (code < 0).then_some(Self(code))
+    }
+
+    /// Retrieves the error code.
+    pub fn value(&self) -> i32 {
+        self.0
+    }
+}
+
+impl ProtoError {
+    /// Retrieves the error code. This is always a negative value.
+    pub fn code(&self) -> i32 {
+        match self {
+            ProtoError::InvalidArgument => -1,
+            ProtoError::OutOfRange => -2,
+            ProtoError::InvalidMemory => -3,
+            ProtoError::Unknown(code) => code.value(),
+        }
+    }
+}
+
+impl Display for ProtoError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtoError::InvalidArgument => f.write_str("Invalid argument."),
+            ProtoError::OutOfRange => f.write_str("Index out of range."),
+            ProtoError::InvalidMemory => f.write_str("Invalid memory."),
+            ProtoError::Unknown(code) => write!(f, "Unknown error code: {}.", code.value()),
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {}
+
+/// Converts the result of a host call into the return value that is passed to the game.
+///
+/// # Arguments
+///
+/// * `result`: The result. A success value must not exceed [`i32::MAX`].
+///
+/// # Panics
+///
+/// Panics if the success value exceeds [`i32::MAX`].
+pub fn to_code(result: ProtoResult<u32>) -> i32 {
+    match result {
+        Ok(value) => {
+            i32::try_from(value).expect("Host call return values must not exceed i32::MAX.")
+        }
+        Err(err) => err.code(),
+    }
+}
+
+/// Converts the return value of a host call into a [`ProtoResult`].
+///
+/// # Arguments
+///
+/// * `code`: The return value of the host call.
+pub fn from_code(code: i32) -> ProtoResult<u32> {
+    match code {
+        -1 => Err(ProtoError::InvalidArgument),
+        -2 => Err(ProtoError::OutOfRange),
+        -3 => Err(ProtoError::InvalidMemory),
+        code => match UnknownCode::new(code) {
+            Some(code) => Err(ProtoError::Unknown(code)),
+            // The cast is OK here, because the value is not negative
+            None => Ok(code as u32),
+        },
+    }
+}
+
+#[cfg(test)]
+mod test_proto_result {
+    use super::{from_code, to_code, ProtoError, UnknownCode};
+
+    #[test]
+    fn round_trip() {
+        for result in [
+            Ok(0),
+            Ok(0xFFFF),
+            Ok(i32::MAX as u32),
+            Err(ProtoError::InvalidArgument),
+            Err(ProtoError::OutOfRange),
+            Err(ProtoError::InvalidMemory),
+            Err(ProtoError::Unknown(UnknownCode::new(-100).unwrap())),
+        ] {
+            assert_eq!(result, from_code(to_code(result)));
+        }
+    }
+
+    #[test]
+    fn codes() {
+        assert_eq!(to_code(Ok(5)), 5);
+        assert_eq!(to_code(Err(ProtoError::InvalidArgument)), -1);
+        assert_eq!(
+            from_code(-42),
+            Err(ProtoError::Unknown(UnknownCode::new(-42).unwrap()))
+        );
+    }
+
+    #[test]
+    fn unknown_code() {
+        // Non-negative values indicate success, so they can not be the code of an error
+        assert_eq!(None, UnknownCode::new(0));
+        assert_eq!(None, UnknownCode::new(5));
+        assert_eq!(
+            Some(i32::MIN),
+            UnknownCode::new(i32::MIN).map(|code| code.value())
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn value_too_large() {
+        to_code(Ok(u32::MAX));
+    }
+}
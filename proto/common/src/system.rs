@@ -0,0 +1,49 @@
+//! Types for the system-level state of the console.
+
+/// The way in which the game has been started.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum BootKind {
+    /// The console has been switched on.
+    ///
+    /// All core state (OAM table, palettes etc.) is in its initial state.
+    ColdBoot,
+    /// The console has been reset while the game was running.
+    ///
+    /// The core state is cleared in the same way as for a cold boot, but the game may want to behave differently (e.g. skip the
+    /// intro sequence).
+    Reset,
+}
+
+impl TryFrom<u32> for BootKind {
+    type Error = String;
+
+    fn try_from(val: u32) -> Result<Self, Self::Error> {
+        match val {
+            0 => Ok(BootKind::ColdBoot),
+            1 => Ok(BootKind::Reset),
+            val => Err(format!("Invalid BootKind value: {val}.")),
+        }
+    }
+}
+
+impl From<BootKind> for u32 {
+    fn from(kind: BootKind) -> Self {
+        match kind {
+            BootKind::ColdBoot => 0,
+            BootKind::Reset => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_boot_kind {
+    use super::BootKind;
+
+    #[test]
+    fn conversion() {
+        for kind in [BootKind::ColdBoot, BootKind::Reset] {
+            assert_eq!(Ok(kind), BootKind::try_from(u32::from(kind)));
+        }
+        assert!(BootKind::try_from(2).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::{Display, Formatter};
+
 /// Creates a bit-based struct that has another (primitive) data type as the internal value.
 ///
 /// # Usage notes
@@ -7,6 +9,17 @@
 /// (e.g. a setter method overriding values of another field). It is highly recommended to add some
 /// unit tests for the resulting struct.
 ///
+/// The internal value can be any unsigned primitive integer type up to [u64]. The fields can be of any unsigned primitive integer
+/// type that is not wider than the internal value.
+///
+/// For every field, the macro generates a getter, a setter that panics if the value does not fit in the field and a fallible
+/// `try_set_` variant of the setter that returns a [`FieldOverflow`] instead. The constructor does not check the values, but masks
+/// them.
+///
+/// With the `serde_support` feature of this crate, the struct implements `serde::Serialize` and `serde::Deserialize`. It is
+/// serialized as its internal value, such that the padding bits are retained. The crate that uses the macro does not need serde or
+/// the feature itself.
+///
 /// # Example
 ///
 /// ```rust
@@ -62,6 +75,8 @@ macro_rules! bit_struct {
             value: $value_type,
         }
 
+        $crate::__bit_struct_serde!($struct_name, $value_type);
+
         #[allow(dead_code)]
         #[allow(clippy::unnecessary_cast)]
         impl $struct_name {
@@ -91,14 +106,31 @@ macro_rules! bit_struct {
                     $(#[$field_meta])*
                     #[inline(always)]
                     $field_vis fn [<set_ $field_name>](&mut self, val: $field_type) {
+                        if let Err(err) = self.[<try_set_ $field_name>](val) {
+                            panic!("{}", err);
+                        }
+                    }
+
+                    $(#[$field_meta])*
+                    ///
+                    /// Returns an error if the provided value does not fit in the field. The value is left untouched in that case.
+                    #[inline(always)]
+                    $field_vis fn [<try_set_ $field_name>](&mut self, val: $field_type) -> Result<(), $crate::FieldOverflow> {
                         let masked_val = val & $field_mask;
                         // Make sure the provided value does not exceed the mask range.
-                        assert_eq!(val, masked_val, "Provided value for {} should not exceed {}, but is {}.", stringify!([<set_ $field_name>]), $field_mask as $field_type, val);
+                        if val != masked_val {
+                            return Err($crate::FieldOverflow {
+                                field: stringify!($field_name),
+                                value: val as u64,
+                                max: $field_mask as u64,
+                            });
+                        }
 
                         // Clear the backing bits.
                         let cleared = self.value ^ (self.value & Self::[<$field_name _mask>]());
                         // Apply the provided value.
                         self.value = cleared | ((masked_val as $value_type) << $field_shift);
+                        Ok(())
                     }
                 }
             )*
@@ -163,3 +195,138 @@ macro_rules! bit_struct {
         }
     }
 }
+
+/// Implements the serde traits for a [`bit_struct!()`] with the `serde_support` feature of this crate. The struct is serialized as its
+/// internal value, such that the padding bits are retained.
+///
+/// The feature is checked here rather than in the expansion of [`bit_struct!()`], such that the crates that use the macro do not need a
+/// feature or a dependency on serde of their own.
+#[cfg(feature = "serde_support")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bit_struct_serde {
+    ($struct_name:ident, $value_type:ty) => {
+        impl $crate::__serde::Serialize for $struct_name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::__serde::Serializer,
+            {
+                $crate::__serde::Serialize::serialize(&self.value, serializer)
+            }
+        }
+
+        impl<'de> $crate::__serde::Deserialize<'de> for $struct_name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::__serde::Deserializer<'de>,
+            {
+                <$value_type as $crate::__serde::Deserialize>::deserialize(deserializer)
+                    .map(|value| Self { value })
+            }
+        }
+    };
+}
+
+/// See the variant above. Without the `serde_support` feature, no serde traits are implemented.
+#[cfg(not(feature = "serde_support"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __bit_struct_serde {
+    ($struct_name:ident, $value_type:ty) => {};
+}
+
+/// The error of a fallible setter of a [`bit_struct!()`]: the provided value does not fit in the field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct FieldOverflow {
+    /// The name of the field.
+    pub field: &'static str,
+    /// The provided value.
+    pub value: u64,
+    /// The maximum value of the field.
+    pub max: u64,
+}
+
+impl Display for FieldOverflow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Provided value for field {} should not exceed {}, but is {}.",
+            self.field, self.max, self.value
+        )
+    }
+}
+
+impl std::error::Error for FieldOverflow {}
+
+#[cfg(test)]
+mod test_bit_struct {
+    use super::FieldOverflow;
+
+    bit_struct!(
+        #[derive(Copy, Clone, Eq, PartialEq, Default)]
+        struct Wide {
+            value: u64
+        }
+
+        impl {
+            #[bit_struct_field(shift = 0, mask = 0xFFFF_FFFF_FFFF)]
+            fn low(&self) -> u64;
+
+            #[bit_struct_field(shift = 48, mask = 0xFFF)]
+            fn high(&self) -> u16;
+        }
+
+        padding {
+            #[bit_struct_field(shift = 60, mask = 0xF)]
+            fn unused(&self) -> u8;
+        }
+    );
+
+    #[test]
+    fn test_wide() {
+        let mut subject = Wide::new(0xFFFF_FFFF_FFFF, 0xABC);
+        assert_eq!(u64::from(subject), 0x0ABC_FFFF_FFFF_FFFF);
+
+        subject.set_low(5);
+        assert_eq!(subject.low(), 5);
+        assert_eq!(subject.high(), 0xABC);
+        assert_eq!(u64::from(subject), 0x0ABC_0000_0000_0005);
+    }
+
+    #[test]
+    fn test_try_set() {
+        let mut subject = Wide::new(1, 2);
+        assert_eq!(Ok(()), subject.try_set_high(0xFFF));
+        let err = subject.try_set_high(0x1000).unwrap_err();
+        assert_eq!(
+            FieldOverflow {
+                field: "high",
+                value: 0x1000,
+                max: 0xFFF,
+            },
+            err
+        );
+        assert_eq!(
+            "Provided value for field high should not exceed 4095, but is 4096.",
+            err.to_string()
+        );
+        assert_eq!(subject.high(), 0xFFF);
+        assert_eq!(subject.low(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Provided value for field low should not exceed 281474976710655")]
+    fn test_set_out_of_range() {
+        Wide::default().set_low(1 << 48);
+    }
+
+    #[test]
+    #[cfg(feature = "serde_support")]
+    fn test_serde() {
+        // The padding bits are retained
+        let subject = Wide::from(0xF123_0000_0000_0004);
+        let json = serde_json::to_string(&subject).unwrap();
+        assert_eq!(u64::from(subject).to_string(), json);
+        assert_eq!(subject, serde_json::from_str(&json).unwrap());
+    }
+}
@@ -0,0 +1,459 @@
+//! The configuration of the core.
+//!
+//! The configuration is read from a TOML file, which is `ves-core.toml` in the working directory or in the configuration directory
+//! (see [`CorePaths`](crate::paths::CorePaths)) unless specified otherwise on the command line. All settings are optional and
+//! command line flags take precedence over the configuration file. Example:
+//!
+//! ```toml
+//! [window]
+//! scale = 3
+//! vsync = true
+//!
+//! [video]
+//! sprites_per_scanline = 32
+//! fast_forward = 4
+//! max_frame_skip = 2
+//!
+//! [keys]
+//! console = "`"
+//! quit = "Escape"
+//! hud = "F3"
+//! fast_forward = "Tab"
+//!
+//! [debug]
+//! console = true
+//! hud = true
+//! stats = true
+//!
+//! [paths]
+//! data_dir = "data"
+//! log_dir = "/var/log/ves"
+//!
+//! [input.keyboard]
+//! up = "Up"
+//! a = "X"
+//! start = "Return"
+//!
+//! [input.keyboard2]
+//! up = "I"
+//! a = "L"
+//!
+//! [input.controller]
+//! a = "b"
+//! select = "back"
+//! ```
+//!
+//! Keys are specified by their SDL name (see `SDL_GetKeyFromName()`) and controller buttons by their SDL game controller name (see
+//! `SDL_GameControllerGetButtonFromString()`). The `input` tables map the buttons of the VES controller (`up`, `down`, `left`,
+//! `right`, `a`, `b`, `x`, `y`, `l`, `r`, `start` and `select`) to a key or controller button. A table replaces all default bindings
+//! for that device. The `keyboard` table is for the first player and the `keyboard2` table for the second player, which has no
+//! keyboard bindings by default. The `controller` table applies to all controllers, which are assigned to the players in the order
+//! in which they are connected.
+//!
+//! The `paths` table overrides the directories of [`CorePaths`](crate::paths::CorePaths). Relative paths are relative to the
+//! directory of the configuration file. The `--data-dir` and `--log-dir` command line flags and the `VES_DATA_DIR` and
+//! `VES_LOG_DIR` environment variables take precedence over these settings.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use sdl2::controller::Button as SdlButton;
+use sdl2::keyboard::Keycode;
+use serde::Deserialize;
+use ves_proto_common::input::{Button, PlayerIndex};
+
+/// The name of the configuration file that is used when none is specified on the command line.
+pub const DEFAULT_CONFIG_FILE: &str = "ves-core.toml";
+
+/// The configuration of the core.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CoreConfig {
+    pub window: WindowConfig,
+    pub video: VideoConfig,
+    pub keys: KeyBindings,
+    pub debug: DebugConfig,
+    pub input: InputConfig,
+    pub paths: PathsConfig,
+}
+
+impl CoreConfig {
+    /// Loads the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The configuration file. If `None`, the defaults are used.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path,
+            None => return Ok(Self::default()),
+        };
+
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Could not read config file {}.", path.display()))?;
+        let mut config = Self::parse(&content)
+            .with_context(|| format!("Could not parse config file {}.", path.display()))?;
+        if let Some(dir) = path.parent() {
+            config.paths.resolve(dir);
+        }
+        Ok(config)
+    }
+
+    /// Parses the configuration from TOML.
+    ///
+    /// # Arguments
+    ///
+    /// * `content`: The TOML content.
+    fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Checks that all settings are within range.
+    pub fn validate(&self) -> Result<()> {
+        anyhow::ensure!(
+            self.window.scale > 0,
+            "The window scale must be at least 1."
+        );
+        anyhow::ensure!(
+            self.video.fast_forward > 0,
+            "The fast-forward factor must be at least 1."
+        );
+        Ok(())
+    }
+}
+
+/// The settings of the main window.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WindowConfig {
+    /// The factor by which the visible screen area is scaled up.
+    pub scale: u32,
+    /// Whether to synchronize the presentation of frames with the refresh rate of the display.
+    pub vsync: bool,
+}
+
+impl Default for WindowConfig {
+    fn default() -> Self {
+        Self {
+            scale: 2,
+            vsync: false,
+        }
+    }
+}
+
+/// The settings of the renderer.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VideoConfig {
+    /// The maximum number of sprites per scanline (see `ves_core_render::render()`).
+    pub sprites_per_scanline: Option<usize>,
+    /// The number of game steps per rendered frame while fast-forwarding.
+    pub fast_forward: u32,
+    /// The maximum number of consecutive frames that are not rendered when the core cannot keep up with the frame rate. `0`
+    /// disables automatic frame skip.
+    pub max_frame_skip: u32,
+}
+
+impl Default for VideoConfig {
+    fn default() -> Self {
+        Self {
+            sprites_per_scanline: None,
+            fast_forward: 4,
+            max_frame_skip: 0,
+        }
+    }
+}
+
+/// The keys for controlling the core.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    /// The key for toggling the debug console.
+    pub console: Key,
+    /// The key for closing the debug console or quitting the core.
+    pub quit: Key,
+    /// The key for resetting the game.
+    pub reset: Key,
+    /// The key for toggling the statistics HUD.
+    pub hud: Key,
+    /// The key that fast-forwards the game while it is held down.
+    pub fast_forward: Key,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            console: Key(Keycode::Backquote),
+            quit: Key(Keycode::Escape),
+            reset: Key(Keycode::F5),
+            hud: Key(Keycode::F3),
+            fast_forward: Key(Keycode::Tab),
+        }
+    }
+}
+
+/// A key, which is deserialized from its SDL name.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Key(pub Keycode);
+
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Keycode::from_name(&value)
+            .map(Key)
+            .ok_or_else(|| format!("Unknown key '{value}'."))
+    }
+}
+
+/// A controller button, which is deserialized from its SDL name.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ControllerButton(pub SdlButton);
+
+impl TryFrom<String> for ControllerButton {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        SdlButton::from_string(&value)
+            .map(ControllerButton)
+            .ok_or_else(|| format!("Unknown controller button '{value}'."))
+    }
+}
+
+/// A button on the VES controller, which is deserialized from its name (see [`Button::name()`]).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Deserialize)]
+#[serde(try_from = "String")]
+pub struct VesButton(pub Button);
+
+impl TryFrom<String> for VesButton {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Button::from_name(&value).map(VesButton)
+    }
+}
+
+/// The mapping of physical input devices to the buttons of the VES controller.
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InputConfig {
+    /// The key for every VES button of the first player.
+    pub keyboard: BTreeMap<VesButton, Key>,
+    /// The key for every VES button of the second player.
+    pub keyboard2: BTreeMap<VesButton, Key>,
+    /// The controller button for every VES button. This applies to the controllers of all players.
+    pub controller: BTreeMap<VesButton, ControllerButton>,
+}
+
+impl InputConfig {
+    /// Retrieves the keyboard bindings of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    pub fn keyboard(&self, player: PlayerIndex) -> &BTreeMap<VesButton, Key> {
+        match player.index() {
+            0 => &self.keyboard,
+            _ => &self.keyboard2,
+        }
+    }
+}
+
+impl Default for InputConfig {
+    fn default() -> Self {
+        let keyboard = [
+            (Button::Up, Keycode::Up),
+            (Button::Down, Keycode::Down),
+            (Button::Left, Keycode::Left),
+            (Button::Right, Keycode::Right),
+            (Button::A, Keycode::X),
+            (Button::B, Keycode::Z),
+            (Button::X, Keycode::S),
+            (Button::Y, Keycode::A),
+            (Button::L, Keycode::Q),
+            (Button::R, Keycode::W),
+            (Button::Start, Keycode::Return),
+            (Button::Select, Keycode::RShift),
+        ];
+        // The VES controller has the same layout as the SNES controller, which has A and B (and X and Y) swapped compared to the
+        // layout that SDL uses
+        let controller = [
+            (Button::Up, SdlButton::DPadUp),
+            (Button::Down, SdlButton::DPadDown),
+            (Button::Left, SdlButton::DPadLeft),
+            (Button::Right, SdlButton::DPadRight),
+            (Button::A, SdlButton::B),
+            (Button::B, SdlButton::A),
+            (Button::X, SdlButton::Y),
+            (Button::Y, SdlButton::X),
+            (Button::L, SdlButton::LeftShoulder),
+            (Button::R, SdlButton::RightShoulder),
+            (Button::Start, SdlButton::Start),
+            (Button::Select, SdlButton::Back),
+        ];
+
+        Self {
+            keyboard: keyboard
+                .into_iter()
+                .map(|(button, key)| (VesButton(button), Key(key)))
+                .collect(),
+            keyboard2: BTreeMap::new(),
+            controller: controller
+                .into_iter()
+                .map(|(button, sdl_button)| (VesButton(button), ControllerButton(sdl_button)))
+                .collect(),
+        }
+    }
+}
+
+/// The defaults for the debugging facilities.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebugConfig {
+    /// Whether the debug console is active on startup.
+    pub console: bool,
+    /// Whether the statistics HUD is shown on startup.
+    pub hud: bool,
+    /// Whether to log the statistics of every frame (see `stats::STATS_TARGET`).
+    pub stats: bool,
+}
+
+/// The directories of the core (see [`CorePaths`](crate::paths::CorePaths)).
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PathsConfig {
+    /// The directory for data, like replays.
+    pub data_dir: Option<PathBuf>,
+    /// The directory for log files.
+    pub log_dir: Option<PathBuf>,
+}
+
+impl PathsConfig {
+    /// Makes the relative paths relative to a directory.
+    ///
+    /// # Arguments
+    ///
+    /// * `dir`: The directory, which is usually the directory of the configuration file.
+    pub fn resolve(&mut self, dir: &Path) {
+        for path in [&mut self.data_dir, &mut self.log_dir]
+            .into_iter()
+            .flatten()
+        {
+            if path.is_relative() {
+                *path = dir.join(&*path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_core_config {
+    use super::*;
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(CoreConfig::default(), CoreConfig::parse("").unwrap());
+    }
+
+    #[test]
+    fn test_parse() {
+        let config = CoreConfig::parse(
+            r#"
+            [window]
+            scale = 3
+
+            [video]
+            sprites_per_scanline = 32
+            max_frame_skip = 2
+
+            [keys]
+            quit = "Q"
+
+            [debug]
+            console = true
+            stats = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(3, config.window.scale);
+        assert!(!config.window.vsync);
+        assert_eq!(Some(32), config.video.sprites_per_scanline);
+        assert_eq!(4, config.video.fast_forward);
+        assert_eq!(2, config.video.max_frame_skip);
+        assert_eq!(Key(Keycode::Backquote), config.keys.console);
+        assert_eq!(Key(Keycode::Q), config.keys.quit);
+        assert_eq!(Key(Keycode::F5), config.keys.reset);
+        assert!(config.debug.console);
+        assert!(!config.debug.hud);
+        assert!(config.debug.stats);
+        assert_eq!(Key(Keycode::F3), config.keys.hud);
+        assert_eq!(Key(Keycode::Tab), config.keys.fast_forward);
+        assert_eq!(InputConfig::default(), config.input);
+        assert_eq!(PathsConfig::default(), config.paths);
+    }
+
+    #[test]
+    fn test_parse_paths() {
+        let mut config = CoreConfig::parse(
+            r#"
+            [paths]
+            data_dir = "data"
+            log_dir = "/var/log/ves"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(Some(PathBuf::from("data")), config.paths.data_dir);
+        assert_eq!(Some(PathBuf::from("/var/log/ves")), config.paths.log_dir);
+
+        config.paths.resolve(Path::new("/etc/ves"));
+        assert_eq!(Some(PathBuf::from("/etc/ves/data")), config.paths.data_dir);
+        assert_eq!(Some(PathBuf::from("/var/log/ves")), config.paths.log_dir);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        let config = CoreConfig::parse(
+            r#"
+            [input.keyboard]
+            a = "Space"
+            start = "P"
+
+            [input.keyboard2]
+            a = "L"
+            "#,
+        )
+        .unwrap();
+
+        let keyboard = |player| -> Vec<_> {
+            config
+                .input
+                .keyboard(PlayerIndex::new(player))
+                .iter()
+                .map(|(button, key)| (button.0, key.0))
+                .collect()
+        };
+        assert_eq!(
+            vec![(Button::A, Keycode::Space), (Button::Start, Keycode::P)],
+            keyboard(0)
+        );
+        assert_eq!(vec![(Button::A, Keycode::L)], keyboard(1));
+        assert_eq!(InputConfig::default().controller, config.input.controller);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(CoreConfig::parse("[window]\nscale = 0").is_err());
+        assert!(CoreConfig::parse("[video]\nfast_forward = 0").is_err());
+        assert!(CoreConfig::parse("[keys]\nquit = \"NoSuchKey\"").is_err());
+        assert!(CoreConfig::parse("[window]\nsize = 3").is_err());
+        assert!(CoreConfig::parse("[input.keyboard]\nturbo = \"T\"").is_err());
+        assert!(CoreConfig::parse("[input.controller]\na = \"NoSuchButton\"").is_err());
+        assert!(CoreConfig::parse("[paths]\nconfig_dir = \"config\"").is_err());
+    }
+}
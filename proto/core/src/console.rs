@@ -0,0 +1,320 @@
+//! A debug console for poking the core state at runtime.
+//!
+//! The console is toggled with the configured console key (see [`KeyBindings`](crate::config::KeyBindings)). While it is active, the keyboard input is collected into a command line, which is
+//! executed when pressing return. The following commands are supported:
+//!
+//! * `oam <index>`: Prints an OAM table entry.
+//! * `oam <index> <entry>`: Sets an OAM table entry (equivalent to calling `gpu.oam_set`).
+//! * `palette <palette>`: Prints all colors in a palette.
+//! * `palette <palette> <index> <color>`: Sets a color in a palette (equivalent to calling `gpu.palette_set`).
+//! * `screen`: Prints the screen control register.
+//! * `screen <value>`: Sets the screen control register (equivalent to calling `gpu.screen_control_set`).
+//! * `log <level> <message>`: Logs a message through the game logger (equivalent to calling `log.log`).
+//! * `input`: Prints the state of the controllers of all players.
+//! * `bind`: Prints the input bindings of all VES controller buttons.
+//! * `bind [<player>] <button>`: Binds the next key or controller button that is pressed to a VES controller button (e.g.
+//!   `bind 1 start`). The player defaults to the first player.
+//! * `restart`: Restarts the game with a fresh instance, as if the console was switched on. The core state (like the OAM table and
+//!   the palettes) is cleared.
+//! * `reset`: Resets the game (equivalent to pressing the reset key).
+//!
+//! Numeric arguments can be provided either in decimal or in hexadecimal (with a `0x` prefix).
+
+use sdl2::keyboard::Keycode;
+use ves_proto_common::gpu::{
+    OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex, PaletteTableIndex, ScreenControl,
+};
+use ves_proto_common::input::{Button, PlayerIndex};
+use ves_proto_common::log::LogLevel;
+
+/// The number of entries in the OAM table.
+const OAM_TABLE_SIZE: u8 = 128;
+/// The number of colors in a palette.
+const PALETTE_SIZE: u8 = 16;
+
+/// A command that can be executed from the debug console.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ConsoleCommand {
+    OamGet(OamTableIndex),
+    OamSet(OamTableIndex, OamTableEntry),
+    PaletteGet(PaletteTableIndex),
+    PaletteSet(PaletteTableIndex, PaletteIndex, PaletteColor),
+    ScreenControlGet,
+    ScreenControlSet(ScreenControl),
+    Log(LogLevel, String),
+    InputGet,
+    BindGet,
+    BindSet(PlayerIndex, Button),
+    Restart,
+    Reset,
+}
+
+impl ConsoleCommand {
+    /// Parses a command line.
+    ///
+    /// # Arguments
+    ///
+    /// * `line`: The command line.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut args = line.split_whitespace();
+        let command = args
+            .next()
+            .ok_or_else(|| "No command provided.".to_string())?;
+        let args: Vec<&str> = args.collect();
+
+        match (command, args.as_slice()) {
+            ("oam", [index]) => Ok(Self::OamGet(parse_oam_index(index)?)),
+            ("oam", [index, entry]) => Ok(Self::OamSet(
+                parse_oam_index(index)?,
+                parse_number::<u64>(entry)?.into(),
+            )),
+            ("palette", [palette]) => Ok(Self::PaletteGet(parse_number::<u8>(palette)?.into())),
+            ("palette", [palette, index, color]) => {
+                let index = parse_number::<u8>(index)?;
+                if index >= PALETTE_SIZE {
+                    return Err(format!("Palette index {index} is out of range."));
+                }
+                Ok(Self::PaletteSet(
+                    parse_number::<u8>(palette)?.into(),
+                    index.into(),
+                    parse_number::<u16>(color)?.into(),
+                ))
+            }
+            ("screen", []) => Ok(Self::ScreenControlGet),
+            ("screen", [control]) => {
+                Ok(Self::ScreenControlSet(parse_number::<u8>(control)?.into()))
+            }
+            ("log", [level, ..]) => {
+                let level = LogLevel::try_from(parse_number::<u32>(level)?)?;
+                // Take the remainder of the line verbatim, so that the whitespace in the message is retained
+                let message =
+                    line.trim_start()[command.len()..].trim_start()[args[0].len()..].trim();
+                Ok(Self::Log(level, message.to_string()))
+            }
+            ("input", []) => Ok(Self::InputGet),
+            ("bind", []) => Ok(Self::BindGet),
+            ("bind", [button]) => Ok(Self::BindSet(
+                PlayerIndex::default(),
+                Button::from_name(button)?,
+            )),
+            ("bind", [player, button]) => Ok(Self::BindSet(
+                PlayerIndex::try_from(parse_number::<usize>(player)?)?,
+                Button::from_name(button)?,
+            )),
+            ("restart", []) => Ok(Self::Restart),
+            ("reset", []) => Ok(Self::Reset),
+            ("oam" | "palette" | "screen" | "log" | "input" | "bind" | "restart" | "reset", _) => {
+                Err(format!("Invalid number of arguments for '{command}'."))
+            }
+            _ => Err(format!("Unknown command '{command}'.")),
+        }
+    }
+}
+
+fn parse_oam_index(value: &str) -> Result<OamTableIndex, String> {
+    let index = parse_number::<u8>(value)?;
+    if index >= OAM_TABLE_SIZE {
+        return Err(format!("OAM index {index} is out of range."));
+    }
+    Ok(index.into())
+}
+
+fn parse_number<T: TryFrom<u64>>(value: &str) -> Result<T, String> {
+    let parsed = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse::<u64>(),
+    }
+    .map_err(|_| format!("Invalid number '{value}'."))?;
+
+    T::try_from(parsed).map_err(|_| format!("Number '{value}' is out of range."))
+}
+
+/// The state of the debug console.
+#[derive(Debug)]
+pub struct Console {
+    active: bool,
+    line: String,
+    /// The character that the console key types, if any.
+    toggle_char: Option<char>,
+}
+
+impl Console {
+    /// Creates a new, inactive instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `toggle_key`: The key that toggles the console.
+    pub fn new(toggle_key: Keycode) -> Self {
+        // The names of the keys that type a character are the (upper case) characters themselves
+        let name = toggle_key.name();
+        let mut chars = name.chars();
+        let toggle_char = match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(c.to_ascii_lowercase()),
+            _ => None,
+        };
+        Self {
+            active: false,
+            line: String::new(),
+            toggle_char,
+        }
+    }
+
+    /// Determines whether the console is currently active.
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Toggles the console on or off. The current command line is discarded.
+    pub fn toggle(&mut self) {
+        self.active = !self.active;
+        self.line.clear();
+    }
+
+    /// Retrieves the current command line.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Appends text to the current command line. The character of the configured console key is ignored, since that key toggles
+    /// the console.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The text to append.
+    pub fn push_str(&mut self, text: &str) {
+        let toggle_char = self.toggle_char;
+        self.line.extend(
+            text.chars()
+                .filter(|c| Some(c.to_ascii_lowercase()) != toggle_char),
+        );
+    }
+
+    /// Removes the last character from the current command line.
+    pub fn backspace(&mut self) {
+        self.line.pop();
+    }
+
+    /// Takes the current command line and parses it.
+    ///
+    /// # Returns
+    /// The command or `None` if the command line was empty.
+    pub fn submit(&mut self) -> Option<Result<ConsoleCommand, String>> {
+        let line = std::mem::take(&mut self.line);
+        if line.trim().is_empty() {
+            return None;
+        }
+        Some(ConsoleCommand::parse(&line))
+    }
+}
+
+#[cfg(test)]
+mod test_console_command {
+    use super::*;
+
+    #[test]
+    fn test_parse_oam() {
+        assert_eq!(
+            Ok(ConsoleCommand::OamGet(5.into())),
+            ConsoleCommand::parse("oam 5")
+        );
+        assert_eq!(
+            Ok(ConsoleCommand::OamSet(0x7F.into(), 0x1234_0000_0000.into())),
+            ConsoleCommand::parse("  oam 0x7F   0x123400000000 ")
+        );
+        assert!(ConsoleCommand::parse("oam 128").is_err());
+        assert!(ConsoleCommand::parse("oam").is_err());
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        assert_eq!(
+            Ok(ConsoleCommand::PaletteGet(255.into())),
+            ConsoleCommand::parse("palette 255")
+        );
+        assert_eq!(
+            Ok(ConsoleCommand::PaletteSet(
+                3.into(),
+                15.into(),
+                0x7FFF.into()
+            )),
+            ConsoleCommand::parse("palette 3 15 0x7FFF")
+        );
+        assert!(ConsoleCommand::parse("palette 3 16 0").is_err());
+        assert!(ConsoleCommand::parse("palette 256").is_err());
+    }
+
+    #[test]
+    fn test_parse_screen() {
+        assert_eq!(
+            Ok(ConsoleCommand::ScreenControlGet),
+            ConsoleCommand::parse("screen")
+        );
+        assert_eq!(
+            Ok(ConsoleCommand::ScreenControlSet(0x1F.into())),
+            ConsoleCommand::parse("screen 0x1F")
+        );
+        assert!(ConsoleCommand::parse("screen 256").is_err());
+    }
+
+    #[test]
+    fn test_parse_log() {
+        assert_eq!(
+            Ok(ConsoleCommand::Log(
+                LogLevel::Info,
+                "hello  world".to_string()
+            )),
+            ConsoleCommand::parse("log 3 hello  world")
+        );
+        assert!(ConsoleCommand::parse("bg 1 2").is_err());
+        assert!(ConsoleCommand::parse("").is_err());
+    }
+
+    #[test]
+    fn test_parse_bind() {
+        assert_eq!(Ok(ConsoleCommand::BindGet), ConsoleCommand::parse("bind"));
+        assert_eq!(
+            Ok(ConsoleCommand::BindSet(PlayerIndex::new(0), Button::Start)),
+            ConsoleCommand::parse("bind start")
+        );
+        assert_eq!(
+            Ok(ConsoleCommand::BindSet(PlayerIndex::new(1), Button::A)),
+            ConsoleCommand::parse("bind 1 a")
+        );
+        assert!(ConsoleCommand::parse("bind turbo").is_err());
+        assert!(ConsoleCommand::parse("bind 2 a").is_err());
+        assert!(ConsoleCommand::parse("bind a b").is_err());
+        assert_eq!(Ok(ConsoleCommand::InputGet), ConsoleCommand::parse("input"));
+    }
+
+    #[test]
+    fn test_parse_restart() {
+        assert_eq!(
+            Ok(ConsoleCommand::Restart),
+            ConsoleCommand::parse("restart")
+        );
+        assert!(ConsoleCommand::parse("restart now").is_err());
+        assert_eq!(Ok(ConsoleCommand::Reset), ConsoleCommand::parse("reset"));
+    }
+}
+
+#[cfg(test)]
+mod test_console {
+    use super::Console;
+    use sdl2::keyboard::Keycode;
+
+    #[test]
+    fn test_push_str() {
+        let mut console = Console::new(Keycode::Backquote);
+        console.push_str("`oam 5`");
+        assert_eq!("oam 5", console.line());
+
+        let mut console = Console::new(Keycode::C);
+        console.push_str("c`Cd");
+        assert_eq!("`d", console.line());
+
+        // Keys that do not type a character do not filter anything
+        let mut console = Console::new(Keycode::F1);
+        console.push_str("`oam");
+        assert_eq!("`oam", console.line());
+    }
+}
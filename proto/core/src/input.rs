@@ -0,0 +1,245 @@
+//! The mapping of physical input devices to the controllers of the players.
+//!
+//! The initial mapping comes from the configuration (see [`InputConfig`]). Every player can have their own keyboard bindings, while
+//! the controller bindings are shared by all controllers. Controllers are assigned to the players in the order in which they are
+//! connected. The mapping can be changed at runtime with the `bind` command on the debug console, after which the next key or
+//! controller button that is pressed is bound to the VES button.
+
+use std::collections::HashMap;
+
+use sdl2::controller::Button as SdlButton;
+use sdl2::keyboard::Keycode;
+use ves_proto_common::input::{Button, Buttons, PlayerIndex, PLAYER_COUNT};
+use ves_proto_common::replay::{PlayerInput, ReplayFrame};
+
+use crate::config::InputConfig;
+
+/// Tracks the state of the VES controller buttons of all players from keyboard and controller events.
+#[derive(Debug, Default)]
+pub struct InputMapper {
+    keyboard: HashMap<Keycode, (PlayerIndex, Button)>,
+    controller: HashMap<SdlButton, Button>,
+    /// The instance IDs of the controllers that are assigned to the players.
+    controller_ids: [Option<u32>; PLAYER_COUNT],
+    buttons: [Buttons; PLAYER_COUNT],
+    binding: Option<(PlayerIndex, Button)>,
+}
+
+impl InputMapper {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `config`: The initial mapping.
+    pub fn new(config: &InputConfig) -> Self {
+        Self {
+            keyboard: PlayerIndex::all()
+                .flat_map(|player| {
+                    config
+                        .keyboard(player)
+                        .iter()
+                        .map(move |(button, key)| (key.0, (player, button.0)))
+                })
+                .collect(),
+            controller: config
+                .controller
+                .iter()
+                .map(|(button, sdl_button)| (sdl_button.0, button.0))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Retrieves the current state of the VES controller buttons of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    pub fn buttons(&self, player: PlayerIndex) -> Buttons {
+        self.buttons[usize::from(player)]
+    }
+
+    /// Determines whether a player has an input device, which is either a controller or at least one key binding.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    pub fn is_connected(&self, player: PlayerIndex) -> bool {
+        self.controller_ids[usize::from(player)].is_some()
+            || self.keyboard.values().any(|(bound, _)| *bound == player)
+    }
+
+    /// Creates a replay frame from the current state of all players. The duration of the frame is left at zero.
+    pub fn replay_frame(&self) -> ReplayFrame {
+        let mut frame = ReplayFrame::default();
+        for player in PlayerIndex::all() {
+            frame.inputs[usize::from(player)] = PlayerInput {
+                connected: self.is_connected(player),
+                buttons: self.buttons(player),
+            };
+        }
+        frame
+    }
+
+    /// Handles a key being pressed or released.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The key.
+    /// * `pressed`: Whether the key was pressed (`true`) or released (`false`).
+    pub fn key_changed(&mut self, key: Keycode, pressed: bool) {
+        if let Some((player, button)) = self.keyboard.get(&key) {
+            self.buttons[usize::from(*player)].set_pressed(*button, pressed);
+        }
+    }
+
+    /// Handles a controller button being pressed or released.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller_id`: The instance ID of the controller.
+    /// * `sdl_button`: The controller button.
+    /// * `pressed`: Whether the button was pressed (`true`) or released (`false`).
+    pub fn controller_button_changed(
+        &mut self,
+        controller_id: u32,
+        sdl_button: SdlButton,
+        pressed: bool,
+    ) {
+        let player = self.controller_player(controller_id);
+        if let (Some(player), Some(button)) = (player, self.controller.get(&sdl_button)) {
+            self.buttons[usize::from(player)].set_pressed(*button, pressed);
+        }
+    }
+
+    /// Assigns a newly connected controller to the first player that has no controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller_id`: The instance ID of the controller.
+    ///
+    /// # Returns
+    /// The player or `None` if all players already have a controller.
+    pub fn controller_connected(&mut self, controller_id: u32) -> Option<PlayerIndex> {
+        if let Some(player) = self.controller_player(controller_id) {
+            return Some(player);
+        }
+        let player = PlayerIndex::all()
+            .find(|player| self.controller_ids[usize::from(*player)].is_none())?;
+        self.controller_ids[usize::from(player)] = Some(controller_id);
+        Some(player)
+    }
+
+    /// Removes a disconnected controller from its player. All buttons of the player are released.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller_id`: The instance ID of the controller.
+    ///
+    /// # Returns
+    /// The player or `None` if the controller was not assigned to a player.
+    pub fn controller_disconnected(&mut self, controller_id: u32) -> Option<PlayerIndex> {
+        let player = self.controller_player(controller_id)?;
+        self.controller_ids[usize::from(player)] = None;
+        self.buttons[usize::from(player)] = Buttons::default();
+        Some(player)
+    }
+
+    fn controller_player(&self, controller_id: u32) -> Option<PlayerIndex> {
+        PlayerIndex::all()
+            .find(|player| self.controller_ids[usize::from(*player)] == Some(controller_id))
+    }
+
+    /// Starts binding a VES button. The next key or controller button that is passed into [`InputMapper::bind_key()`] or
+    /// [`InputMapper::bind_controller_button()`] replaces the current binding for that device.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player. This is only relevant for key bindings, since controller bindings are shared by all players.
+    /// * `button`: The VES button.
+    pub fn start_binding(&mut self, player: PlayerIndex, button: Button) {
+        self.binding = Some((player, button));
+    }
+
+    /// Cancels binding a VES button.
+    pub fn cancel_binding(&mut self) {
+        self.binding = None;
+    }
+
+    /// Determines whether a VES button is being bound.
+    pub fn is_binding(&self) -> bool {
+        self.binding.is_some()
+    }
+
+    /// Binds the VES button that was passed into [`InputMapper::start_binding()`] to a key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key`: The key.
+    ///
+    /// # Returns
+    /// The player and VES button or `None` if no button was being bound.
+    pub fn bind_key(&mut self, key: Keycode) -> Option<(PlayerIndex, Button)> {
+        let binding = self.binding.take()?;
+        self.keyboard.retain(|_, bound| *bound != binding);
+        self.keyboard.insert(key, binding);
+        // The old bindings no longer release the buttons, so start over
+        self.buttons = Default::default();
+        Some(binding)
+    }
+
+    /// Binds the VES button that was passed into [`InputMapper::start_binding()`] to a controller button.
+    ///
+    /// # Arguments
+    ///
+    /// * `sdl_button`: The controller button.
+    ///
+    /// # Returns
+    /// The VES button or `None` if no button was being bound.
+    pub fn bind_controller_button(&mut self, sdl_button: SdlButton) -> Option<Button> {
+        let (_, button) = self.binding.take()?;
+        self.controller.retain(|_, bound| *bound != button);
+        self.controller.insert(sdl_button, button);
+        // The old bindings no longer release the buttons, so start over
+        self.buttons = Default::default();
+        Some(button)
+    }
+
+    /// Describes the keys and controller buttons that are bound to a VES button of a player.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    /// * `button`: The VES button.
+    pub fn describe_bindings(&self, player: PlayerIndex, button: Button) -> String {
+        let mut keys: Vec<String> = self
+            .keyboard
+            .iter()
+            .filter(|(_, bound)| **bound == (player, button))
+            .map(|(key, _)| key.name())
+            .collect();
+        keys.sort();
+        let mut sdl_buttons: Vec<String> = self
+            .controller
+            .iter()
+            .filter(|(_, bound)| **bound == button)
+            .map(|(sdl_button, _)| sdl_button.string())
+            .collect();
+        sdl_buttons.sort();
+
+        let or_none = |names: Vec<String>| {
+            if names.is_empty() {
+                "-".to_string()
+            } else {
+                names.join(", ")
+            }
+        };
+        format!(
+            "Player {} {}: keyboard {}, controller {}",
+            usize::from(player),
+            button.name(),
+            or_none(keys),
+            or_none(sdl_buttons)
+        )
+    }
+}
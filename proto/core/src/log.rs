@@ -1,4 +1,8 @@
-use log::log;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::sync::Mutex;
+
+use log::{log, Log, Metadata, Record};
 
 use ves_proto_common::log::LogLevel;
 
@@ -18,3 +22,60 @@ impl Logger {
         );
     }
 }
+
+/// A [`Log`] implementation that passes every record to another logger and also writes it to a file. The other logger determines
+/// which records are enabled.
+pub struct TeeLogger<L> {
+    inner: L,
+    file: Mutex<LineWriter<File>>,
+}
+
+impl<L> TeeLogger<L> {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner`: The other logger.
+    /// * `file`: The log file.
+    pub fn new(inner: L, file: File) -> Self {
+        Self {
+            inner,
+            file: Mutex::new(LineWriter::new(file)),
+        }
+    }
+}
+
+impl<L> Log for TeeLogger<L>
+where
+    L: Log,
+{
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.inner.log(record);
+        // Errors are ignored, since there is no way to report them
+        if let Ok(mut file) = self.file.lock() {
+            let _ = writeln!(
+                file,
+                "{} {:<5} [{}] {}",
+                chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+        if let Ok(mut file) = self.file.lock() {
+            let _ = file.flush();
+        }
+    }
+}
@@ -1,59 +1,171 @@
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-use ::log::{info, LevelFilter};
-use anyhow::{anyhow, Result};
+use ::log::{error, info, LevelFilter};
+use anyhow::{anyhow, Context, Result};
+use clap::Parser;
 use sdl2::event::Event;
 use sdl2::keyboard::Keycode;
-use sdl2::surface::Surface;
+use sdl2::render::{Canvas, TextureCreator};
+use sdl2::video::{Window, WindowContext};
 
 use ves_art_core::sprite::Tile;
+use ves_core_render::{Palette, Scene, SCREEN_BUFFER_HEIGHT, SCREEN_BUFFER_WIDTH};
 use ves_proto_common::gpu::{
-    OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex, PaletteTableIndex,
+    DmaTable, OamAttributes, OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex,
+    PaletteTableIndex, ScreenControl,
 };
-
-use crate::log::Logger;
+use ves_proto_common::input::{Button, PlayerIndex};
+use ves_proto_common::replay::{Replay, ReplayFrame};
+use ves_proto_common::result::{ProtoError, ProtoResult};
+use ves_proto_common::system::BootKind;
+
+use crate::config::CoreConfig;
+use crate::console::{Console, ConsoleCommand};
+use crate::input::InputMapper;
+use crate::log::{Logger, TeeLogger};
+use crate::pacing::FramePacer;
+use crate::paths::CorePaths;
 use crate::runtime::Runtime;
+use crate::stats::{FrameStats, STATS_TARGET};
 
+mod config;
+mod console;
+mod input;
 mod log;
+mod pacing;
+mod paths;
+mod replay;
 mod runtime;
+mod stats;
+
+/// The title of the main window (when the debug console is not active).
+const WINDOW_TITLE: &str = "SDL2";
 
 /// The width of the visible screen area in pixels.
 const SCREEN_VISIBLE_WIDTH: u32 = 256;
 /// The height of the visible screen area in pixels.
 const SCREEN_VISIBLE_HEIGHT: u32 = 224;
-
-/// The width of the screen buffer in pixels.
-const SCREEN_BUFFER_WIDTH: u32 = 512;
-/// The height of the screen buffer in pixels.
-const SCREEN_BUFFER_HEIGHT: u32 = 256;
+/// The number of game steps per second.
+const FRAME_RATE: u32 = 60;
+
+/// The prototype core for the VES Core-Game Architecture.
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct CoreArgs {
+    /// The game WASM file.
+    #[clap(name = "WASM_FILE")]
+    wasm_file: PathBuf,
+    /// Enables the hardware-accuracy mode that limits the number of sprites per scanline. Sprites that exceed the limit are dropped
+    /// on that scanline, starting with the sprite with the highest OAM index.
+    #[clap(long)]
+    sprites_per_scanline: Option<usize>,
+    /// The configuration file (defaults to `ves-core.toml` in the working directory or in the configuration directory of the
+    /// platform, if it exists).
+    #[clap(long, env = "VES_CONFIG")]
+    config: Option<PathBuf>,
+    /// The directory for data like replays (defaults to the `paths.data_dir` setting or the data directory of the platform).
+    #[clap(long, env = "VES_DATA_DIR")]
+    data_dir: Option<PathBuf>,
+    /// The directory for the log file (defaults to the `paths.log_dir` setting or a directory in the local data directory of the
+    /// platform).
+    #[clap(long, env = "VES_LOG_DIR")]
+    log_dir: Option<PathBuf>,
+    /// The factor by which the visible screen area is scaled up in the window.
+    #[clap(long)]
+    scale: Option<u32>,
+    /// Whether to synchronize the presentation of frames with the refresh rate of the display. `--vsync` without a value enables
+    /// it.
+    #[clap(
+        long,
+        value_name = "BOOL",
+        min_values = 0,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    vsync: Option<bool>,
+    /// Records the input of the session to a replay file. Without a file, a new file is created in the `replays` directory of the
+    /// data directory.
+    #[clap(long, value_name = "FILE", conflicts_with = "replay")]
+    record: Option<Option<PathBuf>>,
+    /// Plays back a replay file that was recorded with `--record` for the same WASM file. Live input takes over when the replay
+    /// ends.
+    #[clap(long)]
+    replay: Option<PathBuf>,
+    /// Renders the replay without a window and writes it to a movie file, which can be opened in the art tools.
+    #[clap(long, requires = "replay")]
+    export_movie: Option<PathBuf>,
+}
 
 struct ProtoCore {
     logger: Logger,
     vrom: Vrom,
     oam: [OamTableEntry; 128],
+    /// The secondary attributes of the OAM entries (see [`OamAttributes`]). These are stored, but not yet used for drawing.
+    oam_attributes: [OamAttributes; 128],
     palettes: [Palette; 256],
-}
-
-#[derive(Copy, Clone, Debug, Default)]
-struct Palette {
-    colors: [PaletteColor; 16], // 1st entry is transparent
+    screen_control: ScreenControl,
+    input: InputMapper,
+    /// The input that the game sees during the current frame. This is either taken from the [`InputMapper`] or from a replay.
+    frame_input: ReplayFrame,
+    /// The way in which the game has been started (see [`ProtoCore::reset()`]).
+    boot_kind: BootKind,
 }
 
 impl ProtoCore {
-    fn new(wasm_file: impl AsRef<Path>) -> Result<ProtoCore> {
+    fn new(wasm_file: impl AsRef<Path>, input: InputMapper) -> Result<ProtoCore> {
         let vrom = Vrom::from_file(&wasm_file)?;
-        let logger = Logger::new();
+        Ok(Self::from_vrom(vrom, input))
+    }
 
-        Ok(Self {
-            logger,
+    fn from_vrom(vrom: Vrom, input: InputMapper) -> ProtoCore {
+        Self {
+            logger: Logger::new(),
             vrom,
             oam: [Default::default(); 128],
+            oam_attributes: [Default::default(); 128],
             palettes: [Default::default(); 256],
-        })
+            screen_control: Default::default(),
+            input,
+            frame_input: Default::default(),
+            boot_kind: BootKind::ColdBoot,
+        }
+    }
+
+    /// Retrieves the scene that the game has set up.
+    fn scene(&self) -> Scene<'_> {
+        Scene {
+            tiles: &self.vrom.tiles,
+            oam: &self.oam,
+            palettes: &self.palettes,
+            screen_control: self.screen_control,
+        }
+    }
+
+    pub(crate) fn set_oam_entry(
+        &mut self,
+        index: OamTableIndex,
+        entry: OamTableEntry,
+    ) -> ProtoResult<()> {
+        let target = self
+            .oam
+            .get_mut(usize::from(index))
+            .ok_or(ProtoError::OutOfRange)?;
+        *target = entry;
+        Ok(())
     }
 
-    pub(crate) fn set_oam_entry(&mut self, index: OamTableIndex, entry: OamTableEntry) {
-        self.oam[usize::from(index)] = entry;
+    pub(crate) fn hide_oam_entry(&mut self, index: OamTableIndex) -> ProtoResult<()> {
+        let entry = self
+            .oam
+            .get_mut(usize::from(index))
+            .ok_or(ProtoError::OutOfRange)?;
+        entry.set_hidden(true);
+        Ok(())
+    }
+
+    pub(crate) fn clear_oam(&mut self) {
+        self.oam = [OamTableEntry::new_hidden(); 128];
     }
 
     pub(crate) fn set_palette_entry(
@@ -61,9 +173,141 @@ impl ProtoCore {
         palette: PaletteTableIndex,
         index: PaletteIndex,
         color: PaletteColor,
-    ) {
-        let palette = &mut self.palettes[usize::from(palette)];
-        palette.colors[usize::from(index)] = color;
+    ) -> ProtoResult<()> {
+        let target = self
+            .palettes
+            .get_mut(usize::from(palette))
+            .and_then(|palette| palette.colors.get_mut(usize::from(index)))
+            .ok_or(ProtoError::OutOfRange)?;
+        *target = color;
+        Ok(())
+    }
+
+    pub(crate) fn set_screen_control(&mut self, control: ScreenControl) {
+        self.screen_control = control;
+    }
+
+    /// Resets the state that is controlled by the game to the state of a cold boot. The VROM, the input and the logger are kept.
+    ///
+    /// The game instance should be recreated afterwards. The game can find out how it has been started with the
+    /// `system.boot_kind_get` call.
+    ///
+    /// # Arguments
+    ///
+    /// * `boot_kind`: The way in which the game is restarted.
+    fn reset(&mut self, boot_kind: BootKind) {
+        self.oam = [Default::default(); 128];
+        self.oam_attributes = [Default::default(); 128];
+        self.palettes = [Default::default(); 256];
+        self.screen_control = Default::default();
+        self.boot_kind = boot_kind;
+    }
+
+    /// Copies a block of entries into a table.
+    ///
+    /// # Arguments
+    ///
+    /// * `table`: The table.
+    /// * `offset`: The index of the first entry in the table.
+    /// * `data`: The entries in the memory layout that is described by [`DmaTable`].
+    pub(crate) fn dma_copy(
+        &mut self,
+        table: DmaTable,
+        offset: usize,
+        data: &[u8],
+    ) -> ProtoResult<()> {
+        let entry_size = table.entry_size();
+        let len = data.len() / entry_size;
+        if len * entry_size != data.len() {
+            return Err(ProtoError::InvalidMemory);
+        }
+        let end = offset.checked_add(len);
+        if end.filter(|end| *end <= table.entry_count()).is_none() {
+            return Err(ProtoError::OutOfRange);
+        }
+
+        let entries = data.chunks_exact(entry_size).zip(offset..);
+        match table {
+            DmaTable::Oam => {
+                for (chunk, index) in entries {
+                    // The unwrap is OK here, because the chunks have the size of the entries
+                    self.oam[index] = u64::from_le_bytes(chunk.try_into().unwrap()).into();
+                }
+            }
+            DmaTable::Palettes => {
+                for (chunk, index) in entries {
+                    let color = u16::from_le_bytes(chunk.try_into().unwrap()).into();
+                    self.palettes[index / 16].colors[index % 16] = color;
+                }
+            }
+            DmaTable::OamAttributes => {
+                for (chunk, index) in entries {
+                    self.oam_attributes[index] =
+                        u16::from_le_bytes(chunk.try_into().unwrap()).into();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn execute_console_command(&mut self, command: ConsoleCommand) -> ProtoResult<()> {
+        match command {
+            ConsoleCommand::OamGet(index) => {
+                let index = usize::from(index);
+                let entry = self.oam.get(index).ok_or(ProtoError::OutOfRange)?;
+                info!(
+                    "OAM[{}] = {:?}, {:?}",
+                    index, entry, self.oam_attributes[index]
+                );
+            }
+            ConsoleCommand::OamSet(index, entry) => self.set_oam_entry(index, entry)?,
+            ConsoleCommand::PaletteGet(palette) => {
+                let index = usize::from(palette);
+                let palette = self.palettes.get(index).ok_or(ProtoError::OutOfRange)?;
+                info!("Palette[{}] = {:?}", index, palette.colors);
+            }
+            ConsoleCommand::PaletteSet(palette, index, color) => {
+                self.set_palette_entry(palette, index, color)?
+            }
+            ConsoleCommand::ScreenControlGet => {
+                info!("ScreenControl = {:?}", self.screen_control);
+            }
+            ConsoleCommand::ScreenControlSet(control) => self.set_screen_control(control),
+            ConsoleCommand::Log(level, message) => self.logger.log(level, &message),
+            ConsoleCommand::InputGet => {
+                for player in PlayerIndex::all() {
+                    let state = if self.input.is_connected(player) {
+                        "connected"
+                    } else {
+                        "disconnected"
+                    };
+                    info!(
+                        "Player {}: {}, buttons = {:?}",
+                        usize::from(player),
+                        state,
+                        self.input.buttons(player)
+                    );
+                }
+            }
+            ConsoleCommand::BindGet => {
+                for player in PlayerIndex::all() {
+                    for button in Button::ALL {
+                        info!("{}", self.input.describe_bindings(player, button));
+                    }
+                }
+            }
+            // Restarting and resetting replace the runtime, so these are handled by the game loop
+            ConsoleCommand::Restart | ConsoleCommand::Reset => {}
+            ConsoleCommand::BindSet(player, button) => {
+                self.input.start_binding(player, button);
+                info!(
+                    "Press a key or controller button for '{}' of player {}.",
+                    button.name(),
+                    usize::from(player)
+                );
+            }
+        }
+        Ok(())
     }
 }
 
@@ -101,14 +345,125 @@ impl Vrom {
     }
 }
 
+/// Renders the scene of the core and presents it in the window.
+///
+/// # Arguments
+///
+/// * `canvas`: The canvas of the window.
+/// * `texture_creator`: The texture creator of the canvas.
+/// * `core`: The core.
+/// * `sprites_per_scanline`: The maximum number of sprites per scanline (see [`ves_core_render::render()`]).
+fn present_frame(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    core: &ProtoCore,
+    sprites_per_scanline: Option<usize>,
+) -> Result<()> {
+    // Create temporary surface to render our scene onto
+    // NOTE: Using RGBA32 and not RGBA8888, since that gives us a platform-indepenent lay-out in
+    //       memory.
+    let mut target = sdl2::surface::Surface::new(
+        SCREEN_BUFFER_WIDTH,
+        SCREEN_BUFFER_HEIGHT,
+        sdl2::pixels::PixelFormatEnum::RGBA32,
+    )
+    .map_err(|err| anyhow!("Could not create target surface: {err}"))?;
+
+    // Render the scene
+    // NOTE: RGBA32 at this width has no row padding, so the surface data can be used as a screen buffer directly.
+    debug_assert!(!target.must_lock());
+    let screen_buffer = target
+        .without_lock_mut()
+        .ok_or_else(|| anyhow!("Could not lock surface data."))?;
+    ves_core_render::render(&core.scene(), screen_buffer, sprites_per_scanline)
+        .map_err(|err| anyhow!("Could not render scene: {err}"))?;
+
+    // Create a texture for the scene surface
+    let texture = texture_creator.create_texture_from_surface(&target)?;
+
+    // Render onto the window canvas
+    let (r, g, b) = core.screen_control.apply((0, 0, 64));
+    canvas.set_draw_color(sdl2::pixels::Color::RGB(r, g, b));
+    canvas.clear();
+    canvas
+        .copy(
+            &texture,
+            sdl2::rect::Rect::new(0, 0, SCREEN_VISIBLE_WIDTH, SCREEN_VISIBLE_HEIGHT),
+            None,
+        )
+        .map_err(|err| anyhow!("Could not copy texture onto window canvas: {err}"))?;
+    canvas.present();
+    Ok(())
+}
+
+/// Resets the core (see [`ProtoCore::reset()`]) and creates a new game instance.
+///
+/// Restarts are recorded in replays (see [`ReplayFrame::restart`]), so this should only be called at the start of a frame.
+///
+/// # Arguments
+///
+/// * `runtime`: The runtime.
+/// * `boot_kind`: The way in which the game is restarted.
+///
+/// # Returns
+/// The new runtime and the new game instance.
+fn restart(mut runtime: Runtime, boot_kind: BootKind) -> Result<(Runtime, u32)> {
+    runtime.core_mut().reset(boot_kind);
+    let mut runtime = runtime.restart()?;
+    let instance_ptr = runtime.create_instance()?;
+    match boot_kind {
+        BootKind::ColdBoot => info!("Restarted game instance."),
+        BootKind::Reset => info!("Reset the game."),
+    }
+    Ok((runtime, instance_ptr))
+}
+
 fn main() -> Result<()> {
-    simple_logger::SimpleLogger::new()
+    let args = CoreArgs::parse();
+    let config_file = args.config.clone().or_else(CorePaths::default_config_file);
+    let mut config = CoreConfig::load(config_file.as_deref())?;
+    let paths = CorePaths::new(
+        args.data_dir
+            .clone()
+            .or_else(|| config.paths.data_dir.clone()),
+        args.log_dir
+            .clone()
+            .or_else(|| config.paths.log_dir.clone()),
+    );
+
+    let stats_level = if config.debug.stats {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    let console_logger = simple_logger::SimpleLogger::new()
         .with_level(LevelFilter::Off)
         .with_module_level(env!("CARGO_CRATE_NAME"), LevelFilter::Info)
-        .init()?;
+        .with_module_level(STATS_TARGET, stats_level);
+    let log_file = paths.log_file()?;
+    let log_file_handle = std::fs::File::create(&log_file)
+        .with_context(|| format!("Could not create log file {}.", log_file.display()))?;
+    ::log::set_boxed_logger(Box::new(TeeLogger::new(console_logger, log_file_handle)))?;
+    // The highest level of all modules, like SimpleLogger::init() does
+    ::log::set_max_level(LevelFilter::Info.max(stats_level));
+    info!("Writing log file: {}", log_file.display());
+    if let Some(config_file) = &config_file {
+        info!("Using config file: {}", config_file.display());
+    }
 
-    let args: Vec<String> = std::env::args().collect();
-    let wasm_file = PathBuf::from(&args[1]).canonicalize()?;
+    // Command line flags take precedence over the config file
+    if let Some(scale) = args.scale {
+        config.window.scale = scale;
+    }
+    if let Some(vsync) = args.vsync {
+        config.window.vsync = vsync;
+    }
+    if args.sprites_per_scanline.is_some() {
+        config.video.sprites_per_scanline = args.sprites_per_scanline;
+    }
+    config.validate()?;
+
+    let wasm_file = args.wasm_file.canonicalize()?;
     info!("Running core.");
     info!(
         "Loading WASM file: {}",
@@ -119,10 +474,31 @@ fn main() -> Result<()> {
     );
 
     let wasm_file = wasm_file.as_path();
-    let core = ProtoCore::new(wasm_file)?;
+    let state_hash = ves_proto_common::replay::state_hash(&std::fs::read(wasm_file)?);
+    let replay_input = args
+        .replay
+        .as_deref()
+        .map(|path| replay::load(path, state_hash))
+        .transpose()?;
+    let record_file = match &args.record {
+        Some(Some(path)) => Some(path.clone()),
+        Some(None) => Some(paths.new_replay_file(wasm_file)?),
+        None => None,
+    };
+    if let Some(path) = &record_file {
+        info!("Recording replay: {}", path.display());
+    }
+    let mut recording = record_file.as_ref().map(|_| Replay::new(state_hash));
+
+    let core = ProtoCore::new(wasm_file, InputMapper::new(&config.input))?;
     let mut runtime = Runtime::from_path(wasm_file, core)?;
     info!("Creating game instance.");
-    let instance_ptr = runtime.create_instance()?;
+    let mut instance_ptr = runtime.create_instance()?;
+
+    if let (Some(path), Some(replay_input)) = (&args.export_movie, &replay_input) {
+        info!("Exporting movie.");
+        return replay::export_movie(runtime, instance_ptr, replay_input, path);
+    }
 
     info!("Initializing SDL.");
     let sdl_context = sdl2::init().map_err(|e| anyhow!("Could not initialize SDL: {}", e))?;
@@ -131,12 +507,27 @@ fn main() -> Result<()> {
         .map_err(|e| anyhow!("Could not initialize SDL: {}", e))?;
     info!("Initializing video subsystem.");
     let window = video_subsystem
-        .window("SDL2", SCREEN_VISIBLE_WIDTH * 2, SCREEN_VISIBLE_HEIGHT * 2)
+        .window(
+            WINDOW_TITLE,
+            SCREEN_VISIBLE_WIDTH * config.window.scale,
+            SCREEN_VISIBLE_HEIGHT * config.window.scale,
+        )
         .position_centered()
         .build()?;
 
     info!("Creating canvas.");
-    let mut canvas = window.into_canvas().build()?;
+    let mut canvas_builder = window.into_canvas();
+    if config.window.vsync {
+        canvas_builder = canvas_builder.present_vsync();
+    }
+    let mut canvas = canvas_builder.build()?;
+
+    info!("Initializing game controller subsystem.");
+    let controller_subsystem = sdl_context
+        .game_controller()
+        .map_err(|e| anyhow!("Could not initialize SDL: {}", e))?;
+    // SDL sends an "added" event for every controller that is already connected at startup
+    let mut controllers = Vec::new();
 
     info!("Starting game loop.");
     let mut event_pump = sdl_context
@@ -151,136 +542,383 @@ fn main() -> Result<()> {
 
     let mut fps_manager = sdl2::gfx::framerate::FPSManager::new();
     fps_manager
-        .set_framerate(60)
+        .set_framerate(FRAME_RATE)
         .map_err(|err| anyhow!("Can not set framerate: {err}"))?;
 
+    let console_key = config.keys.console.0;
+    let mut console = Console::new(console_key);
+    if config.debug.console {
+        console.toggle();
+    }
+    let quit_key = config.keys.quit.0;
+    let reset_key = config.keys.reset.0;
+    let hud_key = config.keys.hud.0;
+    let fast_forward_key = config.keys.fast_forward.0;
+    let mut hud = config.debug.hud;
+    let mut pacer = FramePacer::new(
+        config.video.fast_forward,
+        config.video.max_frame_skip,
+        FRAME_RATE,
+    );
+    let mut frame_stats: Option<FrameStats> = None;
+    // A restart that is requested during a frame takes effect at the start of the next frame
+    let mut pending_restart: Option<BootKind> = None;
     let mut running = true;
+    let mut frame_index = 0;
     while running {
+        let frame_start = Instant::now();
+        let live_restart = pending_restart.take();
+        let replay_frame = replay_input
+            .as_ref()
+            .and_then(|replay_input| replay_input.frames().get(frame_index));
+        if matches!(&replay_input, Some(replay_input) if replay_input.frames().len() == frame_index)
+        {
+            info!("Replay finished after {frame_index} frames. Switching to live input.");
+        }
+        let frame_input = match replay_frame {
+            Some(replay_frame) => *replay_frame,
+            None => ReplayFrame {
+                restart: live_restart,
+                ..runtime.core().input.replay_frame()
+            },
+        };
+        if let Some(boot_kind) = frame_input.restart {
+            (runtime, instance_ptr) = restart(runtime, boot_kind)?;
+        }
+        runtime.core_mut().frame_input = frame_input;
+
         // Advance game state
-        let core = runtime.step(instance_ptr)?;
+        runtime.step(instance_ptr)?;
 
         // Event handling
         for event in event_pump.poll_iter() {
             match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
+                Event::Quit { .. } => {
+                    running = false;
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if runtime.core().input.is_binding() => {
+                    let input = &mut runtime.core_mut().input;
+                    if keycode == quit_key {
+                        input.cancel_binding();
+                        info!("Cancelled binding.");
+                    } else if let Some((player, button)) = input.bind_key(keycode) {
+                        info!(
+                            "Bound '{}' of player {} to key {}.",
+                            button.name(),
+                            usize::from(player),
+                            keycode.name()
+                        );
+                    }
+                }
+                Event::ControllerButtonDown { button, .. } if runtime.core().input.is_binding() => {
+                    if let Some(ves_button) =
+                        runtime.core_mut().input.bind_controller_button(button)
+                    {
+                        info!(
+                            "Bound '{}' to controller button {}.",
+                            ves_button.name(),
+                            button.string()
+                        );
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if keycode == console_key => {
+                    console.toggle();
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    ..
+                } if keycode == quit_key => {
+                    if console.is_active() {
+                        console.toggle();
+                    } else {
+                        running = false;
+                    }
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    repeat: false,
+                    ..
+                } if keycode == reset_key && !console.is_active() => {
+                    pending_restart = Some(BootKind::Reset);
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    repeat: false,
+                    ..
+                } if keycode == hud_key && !console.is_active() => {
+                    hud = !hud;
+                }
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    repeat: false,
+                    ..
+                } if keycode == fast_forward_key && !console.is_active() => {
+                    pacer.set_fast_forward(true);
+                }
+                Event::KeyUp {
+                    keycode: Some(keycode),
+                    ..
+                } if keycode == fast_forward_key => {
+                    pacer.set_fast_forward(false);
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } if console.is_active() => {
+                    console.backspace();
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    ..
+                } if console.is_active() => {
+                    match console.submit() {
+                        Some(Ok(ConsoleCommand::Restart)) => {
+                            // A restart is not a reset, so the game should not see the state of an earlier reset
+                            pending_restart = Some(BootKind::ColdBoot);
+                        }
+                        Some(Ok(ConsoleCommand::Reset)) => {
+                            pending_restart = Some(BootKind::Reset);
+                        }
+                        Some(Ok(command)) => {
+                            if let Err(err) = runtime.core_mut().execute_console_command(command) {
+                                error!("Console: {err}");
+                            }
+                        }
+                        Some(Err(err)) => error!("Console: {err}"),
+                        None => {}
+                    }
+                    // Close the console, such that the key for the binding does not end up on the command line
+                    if runtime.core().input.is_binding() {
+                        console.toggle();
+                    }
+                }
+                Event::TextInput { text, .. } if console.is_active() => {
+                    console.push_str(&text);
+                }
+                // The keyboard controls the console while it is active
+                Event::KeyDown {
+                    keycode: Some(keycode),
+                    repeat: false,
+                    ..
+                } if !console.is_active() => {
+                    runtime.core_mut().input.key_changed(keycode, true);
+                }
+                Event::KeyUp {
+                    keycode: Some(keycode),
                     ..
                 } => {
-                    running = false;
+                    runtime.core_mut().input.key_changed(keycode, false);
+                }
+                Event::ControllerButtonDown { which, button, .. } => {
+                    runtime
+                        .core_mut()
+                        .input
+                        .controller_button_changed(which, button, true);
+                }
+                Event::ControllerButtonUp { which, button, .. } => {
+                    runtime
+                        .core_mut()
+                        .input
+                        .controller_button_changed(which, button, false);
+                }
+                Event::ControllerDeviceAdded { which, .. } => {
+                    match controller_subsystem.open(which) {
+                        Ok(controller) => {
+                            let player = runtime
+                                .core_mut()
+                                .input
+                                .controller_connected(controller.instance_id());
+                            match player {
+                                Some(player) => info!(
+                                    "Connected controller {} for player {}.",
+                                    controller.name(),
+                                    usize::from(player)
+                                ),
+                                None => info!(
+                                    "Connected controller {}, but all players already have a controller.",
+                                    controller.name()
+                                ),
+                            }
+                            controllers.push(controller);
+                        }
+                        Err(err) => error!("Could not open controller {which}: {err}"),
+                    }
+                }
+                Event::ControllerDeviceRemoved { which, .. } => {
+                    if let Some(player) = runtime.core_mut().input.controller_disconnected(which) {
+                        info!("Disconnected controller of player {}.", usize::from(player));
+                    }
+                    controllers.retain(|controller| controller.instance_id() != which);
                 }
                 _ => {}
             }
         }
 
-        // Create temporary surface to render our scene onto
-        // NOTE: Using RGBA32 and not RGBA8888, since that gives us a platform-indepenent lay-out in
-        //       memory.
-        let mut target = sdl2::surface::Surface::new(
-            SCREEN_BUFFER_WIDTH,
-            SCREEN_BUFFER_HEIGHT,
-            sdl2::pixels::PixelFormatEnum::RGBA32,
-        )
-        .map_err(|err| anyhow!("Could not create target surface: {err}"))?;
-
-        // Render the scene
-        render_oam(&mut target, &core.oam, &core.palettes, &core.vrom)?;
-
-        // Create a texture for the scene surface
-        let texture = texture_creator.create_texture_from_surface(&target)?;
-
-        // Render onto the window canvas
-        canvas.set_draw_color(sdl2::pixels::Color::RGB(0, 0, 64));
-        canvas.clear();
-        canvas
-            .copy(
-                &texture,
-                sdl2::rect::Rect::new(0, 0, SCREEN_VISIBLE_WIDTH, SCREEN_VISIBLE_HEIGHT),
-                None,
-            )
-            .map_err(|err| anyhow!("Could not copy texture onto window canvas: {err}"))?;
-        canvas.present();
+        // Only the steps that are rendered wait for the next frame, so the steps in between run as fast as possible
+        let render = pacer.begin_step();
+        if render {
+            let mut title = match (console.is_active(), &frame_stats) {
+                (true, _) => format!("> {}", console.line()),
+                (false, Some(frame_stats)) if hud => format!("{WINDOW_TITLE} | {frame_stats}"),
+                (false, _) => WINDOW_TITLE.to_string(),
+            };
+            if pacer.is_fast_forward() {
+                title.push_str(" | fast-forward");
+            }
+            canvas
+                .window_mut()
+                .set_title(&title)
+                .map_err(|err| anyhow!("Could not set window title: {err}"))?;
+
+            let core = runtime.core();
+            let blit_start = Instant::now();
+            present_frame(
+                &mut canvas,
+                &texture_creator,
+                core,
+                config.video.sprites_per_scanline,
+            )?;
+            let blit_time = blit_start.elapsed();
+
+            // The statistics are shown in the title of the next frame
+            if hud || config.debug.stats {
+                let scene_stats = ves_core_render::scene_stats(
+                    &core.scene(),
+                    (SCREEN_VISIBLE_WIDTH, SCREEN_VISIBLE_HEIGHT),
+                )
+                .map_err(|err| anyhow!("Could not collect scene statistics: {err}"))?;
+                let stats = FrameStats::new(
+                    scene_stats,
+                    core.oam.len(),
+                    (SCREEN_VISIBLE_WIDTH, SCREEN_VISIBLE_HEIGHT),
+                    blit_time,
+                );
+                stats.log(frame_index);
+                frame_stats = Some(stats);
+            }
+        }
+        pacer.end_step(frame_start.elapsed());
+
+        // Keep the pace of the recording while replaying
+        match replay_frame {
+            Some(replay_frame) if render => {
+                std::thread::sleep(replay_frame.duration.saturating_sub(frame_start.elapsed()))
+            }
+            None if render => {
+                fps_manager.delay();
+            }
+            _ => {}
+        }
+
+        if let Some(recording) = &mut recording {
+            recording.push_frame(ReplayFrame {
+                duration: frame_start.elapsed(),
+                ..runtime.core().frame_input
+            });
+        }
+        frame_index += 1;
+    }
 
-        fps_manager.delay();
+    if let (Some(path), Some(recording)) = (&record_file, &recording) {
+        replay::save(path, recording)?;
     }
 
     Ok(())
 }
 
-fn render_oam(
-    screen_buffer: &mut Surface,
-    oam: &[OamTableEntry],
-    palettes: &[Palette],
-    vrom: &Vrom,
-) -> Result<()> {
-    for obj in oam.iter().rev() {
-        let char_table_index = usize::try_from(obj.char_table_index())
-            .map_err(|_| anyhow!("Could not convert char_table_index to usize."))?;
-        let tile = &vrom.tiles[char_table_index];
-
-        let palette = &palettes[usize::from(obj.palette_table_index())];
-        render_tile(
-            screen_buffer,
-            tile,
-            palette,
-            obj.position(),
-            obj.h_flip(),
-            obj.v_flip(),
-        )?;
+#[cfg(test)]
+mod test_proto_core {
+    use super::{InputMapper, ProtoCore, Vrom};
+    use crate::config::InputConfig;
+    use ves_proto_common::gpu::{OamTableIndex, PaletteColor, PaletteIndex, PaletteTableIndex};
+    use ves_proto_common::result::ProtoError;
+    use ves_proto_common::system::BootKind;
+
+    fn create_core() -> ProtoCore {
+        let vrom = Vrom { tiles: Vec::new() };
+        ProtoCore::from_vrom(vrom, InputMapper::new(&InputConfig::default()))
     }
-    Ok(())
-}
 
-fn render_tile(
-    screen_buffer: &mut Surface,
-    tile: &Tile,
-    palette: &Palette,
-    position: (u16, u16),
-    hflip: bool,
-    vflip: bool,
-) -> Result<()> {
-    // Checking some presumptions about the calling code
-    debug_assert!(!screen_buffer.must_lock());
-    debug_assert_eq!(
-        screen_buffer.pixel_format_enum(),
-        sdl2::pixels::PixelFormatEnum::RGBA32
-    );
+    #[test]
+    fn test_set_oam_entry() {
+        let mut core = create_core();
+        let entry = 0x1234.into();
+        assert_eq!(Ok(()), core.set_oam_entry(OamTableIndex::from(127), entry));
+        assert_eq!(entry, core.oam[127]);
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.set_oam_entry(OamTableIndex::from(128), entry)
+        );
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.set_oam_entry(OamTableIndex::from(255), entry)
+        );
+    }
 
-    use ves_art_core::surface::Surface as _;
-    let surf = tile.surface();
-    let src_size = surf.size();
-    let src_data = surf.data();
+    #[test]
+    fn test_set_palette_entry() {
+        let mut core = create_core();
+        let palette = PaletteTableIndex::from(255);
+        let color = PaletteColor::from(0x7FFF);
+        assert_eq!(
+            Ok(()),
+            core.set_palette_entry(palette, PaletteIndex::from(15), color)
+        );
+        assert_eq!(color, core.palettes[255].colors[15]);
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.set_palette_entry(palette, PaletteIndex::from(16), color)
+        );
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.set_palette_entry(palette, PaletteIndex::from(255), color)
+        );
+    }
 
-    let dest_data = screen_buffer
-        .without_lock_mut()
-        .ok_or_else(|| anyhow!("Could not lock surface data."))?;
+    #[test]
+    fn test_reset() {
+        let fresh = create_core();
+        for boot_kind in [BootKind::Reset, BootKind::ColdBoot] {
+            let mut core = create_core();
+            core.boot_kind = BootKind::Reset;
+            core.set_oam_entry(OamTableIndex::from(3), 0x1234.into())
+                .unwrap();
+            core.set_palette_entry(
+                PaletteTableIndex::from(2),
+                PaletteIndex::from(1),
+                PaletteColor::from(0x7FFF),
+            )
+            .unwrap();
 
-    ves_art_core::surface::surface_iterate_2(
-        src_size,
-        src_size.as_rect(),
-        ves_art_core::geom_art::Size::new(SCREEN_BUFFER_WIDTH, SCREEN_BUFFER_HEIGHT),
-        ves_art_core::geom_art::Point::new(u32::from(position.0), u32::from(position.1)),
-        hflip,
-        vflip,
-        |_, src_idx, _, dest_idx| {
-            // Get the index in the palette
-            let pal_idx: usize = src_data[src_idx].value().into();
-            // The first entry in the palette is reserved for transparency (aka: write nothing)
-            if pal_idx == 0 {
-                return;
-            }
-            // Get the color value
-            let (r, g, b) = palette.colors[pal_idx].to_real();
-
-            // Write the color to the target surface
-            let i = 4 * dest_idx; // because RGBA32 is 4 bytes per pixel
-            dest_data[i] = r;
-            dest_data[i + 1] = g;
-            dest_data[i + 2] = b;
-            dest_data[i + 3] = 255;
-        },
-    )
-    .map_err(|err| anyhow!("Could not render object onto screen buffer: {err}"))?;
+            core.reset(boot_kind);
+            assert_eq!(boot_kind, core.boot_kind);
+            assert_eq!(fresh.oam, core.oam);
+            assert_eq!(fresh.palettes, core.palettes);
+        }
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod test_core_args {
+    use super::CoreArgs;
+    use clap::Parser;
+
+    #[test]
+    fn test_vsync() {
+        let vsync = |args: &[&str]| {
+            CoreArgs::try_parse_from([&["ves-core"], args, &["game.wasm"]].concat())
+                .unwrap()
+                .vsync
+        };
+        assert_eq!(None, vsync(&[]));
+        assert_eq!(Some(true), vsync(&["--vsync"]));
+        assert_eq!(Some(true), vsync(&["--vsync=true"]));
+        assert_eq!(Some(false), vsync(&["--vsync=false"]));
+    }
 }
@@ -0,0 +1,140 @@
+//! The pacing of the game loop.
+//!
+//! The game logic advances by one step in every iteration of the game loop, but not every step has to be presented. The
+//! [`FramePacer`] decides which steps are rendered:
+//!
+//! * While fast-forwarding, only every Nth step is rendered, such that the game runs at N times its normal speed.
+//! * With automatic frame skip, rendering is skipped while the core lags behind the frame rate (for instance, because rendering
+//!   takes too long on a slow machine), such that the speed of the game logic is not affected.
+//!
+//! The loop only waits for the next frame after a step that has been rendered.
+
+use std::time::Duration;
+
+/// Decides which steps of the game loop are rendered.
+#[derive(Clone, Debug)]
+pub struct FramePacer {
+    /// The number of steps per rendered frame while fast-forwarding.
+    fast_forward: u32,
+    /// The maximum number of consecutive steps that are not rendered when the core lags behind.
+    max_frame_skip: u32,
+    /// The time that is available for a single step.
+    frame_budget: Duration,
+    fast_forward_active: bool,
+    /// The number of consecutive steps that have not been rendered.
+    skipped: u32,
+    /// The time by which the core lags behind the frame rate.
+    lag: Duration,
+}
+
+impl FramePacer {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `fast_forward`: The number of steps per rendered frame while fast-forwarding. This must be at least 1.
+    /// * `max_frame_skip`: The maximum number of consecutive steps that are not rendered when the core lags behind. `0` disables
+    ///   automatic frame skip.
+    /// * `frame_rate`: The number of steps per second.
+    pub fn new(fast_forward: u32, max_frame_skip: u32, frame_rate: u32) -> Self {
+        Self {
+            fast_forward: fast_forward.max(1),
+            max_frame_skip,
+            frame_budget: Duration::from_secs(1) / frame_rate,
+            fast_forward_active: false,
+            skipped: 0,
+            lag: Duration::ZERO,
+        }
+    }
+
+    /// Determines whether the core is fast-forwarding.
+    pub fn is_fast_forward(&self) -> bool {
+        self.fast_forward_active
+    }
+
+    /// Starts or stops fast-forwarding.
+    pub fn set_fast_forward(&mut self, active: bool) {
+        self.fast_forward_active = active;
+    }
+
+    /// Decides whether the current step should be rendered. This must be called once per step.
+    pub fn begin_step(&mut self) -> bool {
+        let render = if self.fast_forward_active {
+            self.skipped + 1 >= self.fast_forward
+        } else {
+            self.skipped >= self.max_frame_skip || self.lag < self.frame_budget
+        };
+
+        if render {
+            self.skipped = 0;
+        } else {
+            self.skipped += 1;
+        }
+        render
+    }
+
+    /// Records the time that the current step has taken, without the time that the loop has waited for the next frame.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed`: The time.
+    pub fn end_step(&mut self, elapsed: Duration) {
+        // Fast steps make up for the time that has been lost before, but the core never gets ahead of the frame rate
+        self.lag = (self.lag + elapsed).saturating_sub(self.frame_budget);
+    }
+}
+
+#[cfg(test)]
+mod test_frame_pacer {
+    use super::FramePacer;
+    use std::time::Duration;
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_fast_forward() {
+        let mut pacer = FramePacer::new(3, 0, 50);
+        assert!(pacer.begin_step());
+        assert!(!pacer.is_fast_forward());
+
+        pacer.set_fast_forward(true);
+        assert!(pacer.is_fast_forward());
+        let rendered: Vec<bool> = (0..6).map(|_| pacer.begin_step()).collect();
+        assert_eq!(vec![false, false, true, false, false, true], rendered);
+
+        pacer.set_fast_forward(false);
+        assert!(pacer.begin_step());
+    }
+
+    #[test]
+    fn test_frame_skip() {
+        // A frame rate of 50 leaves 20 ms per step
+        let mut pacer = FramePacer::new(4, 2, 50);
+        assert!(pacer.begin_step());
+        pacer.end_step(millis(15));
+
+        // A slow step puts the core behind by more than a frame, so the next steps are skipped until the limit is reached
+        assert!(pacer.begin_step());
+        pacer.end_step(millis(80));
+        assert!(!pacer.begin_step());
+        pacer.end_step(millis(5));
+        assert!(!pacer.begin_step());
+        pacer.end_step(millis(5));
+        assert!(pacer.begin_step());
+        pacer.end_step(millis(5));
+
+        // The fast steps have made up for the lag
+        assert!(pacer.begin_step());
+    }
+
+    #[test]
+    fn test_frame_skip_disabled() {
+        let mut pacer = FramePacer::new(4, 0, 50);
+        for _ in 0..4 {
+            assert!(pacer.begin_step());
+            pacer.end_step(millis(100));
+        }
+    }
+}
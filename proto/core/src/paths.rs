@@ -0,0 +1,94 @@
+//! The directories in which the core reads and writes its files.
+//!
+//! By default, the platform-specific directories are used (for instance `~/.config/ves` and `~/.local/share/ves` on Linux,
+//! `~/Library/Application Support/ves` on macOS and `%APPDATA%\ves` on Windows). The data and log directories can be overridden on the
+//! command line, with an environment variable or in the configuration file (see [`PathsConfig`](crate::config::PathsConfig)). If the home directory of the user can not be determined, the working directory is used.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+
+use crate::config::DEFAULT_CONFIG_FILE;
+
+/// The name of the log file.
+const LOG_FILE: &str = "ves-core.log";
+
+/// The extension of replay files.
+const REPLAY_EXTENSION: &str = "replay";
+
+/// The directories of the core.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CorePaths {
+    data_dir: PathBuf,
+    log_dir: PathBuf,
+}
+
+impl CorePaths {
+    /// Determines the directories.
+    ///
+    /// # Arguments
+    ///
+    /// * `data_dir`: Overrides the directory for data, like replays.
+    /// * `log_dir`: Overrides the directory for log files.
+    pub fn new(data_dir: Option<PathBuf>, log_dir: Option<PathBuf>) -> Self {
+        Self {
+            data_dir: data_dir.unwrap_or_else(|| platform_dir(ProjectDirs::data_dir)),
+            log_dir: log_dir
+                .unwrap_or_else(|| platform_dir(ProjectDirs::data_local_dir).join("logs")),
+        }
+    }
+
+    /// Determines the configuration file that is used when none is specified on the command line: [`DEFAULT_CONFIG_FILE`] in the
+    /// working directory takes precedence over the one in the configuration directory.
+    ///
+    /// returns: The path of the file or `None` if neither exists.
+    pub fn default_config_file() -> Option<PathBuf> {
+        [
+            PathBuf::from(DEFAULT_CONFIG_FILE),
+            platform_dir(ProjectDirs::config_dir).join(DEFAULT_CONFIG_FILE),
+        ]
+        .into_iter()
+        .find(|path| path.exists())
+    }
+
+    /// Creates the path for a new replay file in the replay directory. The name consists of the name of the game and the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `wasm_file`: The game WASM file.
+    pub fn new_replay_file(&self, wasm_file: &Path) -> Result<PathBuf> {
+        let dir = self.data_dir.join("replays");
+        create_dir(&dir)?;
+        let game = wasm_file
+            .file_stem()
+            .map_or_else(|| "game".into(), |stem| stem.to_string_lossy());
+        let time = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        Ok(dir.join(format!("{}-{}.{}", game, time, REPLAY_EXTENSION)))
+    }
+
+    /// Creates the log directory (if needed) and determines the path of the log file.
+    pub fn log_file(&self) -> Result<PathBuf> {
+        create_dir(&self.log_dir)?;
+        Ok(self.log_dir.join(LOG_FILE))
+    }
+}
+
+/// Determines a platform-specific directory.
+///
+/// # Arguments
+///
+/// * `dir`: The function that selects the directory.
+///
+/// returns: The directory or the working directory (an empty path) if the home directory of the user can not be determined.
+fn platform_dir(dir: fn(&ProjectDirs) -> &Path) -> PathBuf {
+    ProjectDirs::from("", "", "ves").map_or_else(PathBuf::new, |project_dirs| {
+        dir(&project_dirs).to_path_buf()
+    })
+}
+
+/// Creates a directory and all of its parents, if they do not exist yet.
+fn create_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Could not create directory {}.", dir.display()))
+}
@@ -0,0 +1,97 @@
+//! Recording and replaying game sessions (see [`ves_proto_common::replay`]).
+//!
+//! The initial state of a game is fully determined by its WASM file, so the hash of that file is stored in the replay. A replay
+//! can only be played back on the same WASM file.
+
+use std::path::Path;
+
+use ::log::info;
+use anyhow::{anyhow, ensure, Context, Result};
+use ves_art_core::geom_art::Size;
+use ves_art_core::movie::FrameRate;
+use ves_core_render::movie::MovieBuilder;
+use ves_proto_common::replay::Replay;
+
+use crate::runtime::Runtime;
+use crate::{SCREEN_VISIBLE_HEIGHT, SCREEN_VISIBLE_WIDTH};
+
+/// Loads a replay.
+///
+/// # Arguments
+///
+/// * `path`: The replay file.
+/// * `state_hash`: The hash of the initial state of the game that is being run.
+pub fn load(path: &Path, state_hash: u64) -> Result<Replay> {
+    let data = std::fs::read(path)
+        .with_context(|| format!("Could not read replay file {}.", path.display()))?;
+    let replay = Replay::from_bytes(&data)
+        .map_err(|err| anyhow!("Could not parse replay file {}: {err}", path.display()))?;
+    ensure!(
+        replay.state_hash() == state_hash,
+        "Replay file {} was recorded with a different game.",
+        path.display()
+    );
+    info!(
+        "Loaded replay {} ({} frames).",
+        path.display(),
+        replay.frames().len()
+    );
+    Ok(replay)
+}
+
+/// Saves a replay.
+///
+/// # Arguments
+///
+/// * `path`: The replay file.
+/// * `replay`: The replay.
+pub fn save(path: &Path, replay: &Replay) -> Result<()> {
+    let data = replay.to_bytes().map_err(anyhow::Error::msg)?;
+    std::fs::write(path, data)
+        .with_context(|| format!("Could not write replay file {}.", path.display()))?;
+    info!(
+        "Saved replay {} ({} frames).",
+        path.display(),
+        replay.frames().len()
+    );
+    Ok(())
+}
+
+/// Plays back a replay without a window and writes the resulting frames to a movie file. The game is restarted at the same frames
+/// as during the recording.
+///
+/// # Arguments
+///
+/// * `runtime`: The runtime.
+/// * `instance_ptr`: The game instance.
+/// * `replay`: The replay.
+/// * `path`: The movie file.
+pub fn export_movie(
+    mut runtime: Runtime,
+    mut instance_ptr: u32,
+    replay: &Replay,
+    path: &Path,
+) -> Result<()> {
+    let mut builder = MovieBuilder::default();
+    for frame in replay.frames() {
+        if let Some(boot_kind) = frame.restart {
+            (runtime, instance_ptr) = crate::restart(runtime, boot_kind)?;
+        }
+        runtime.core_mut().frame_input = *frame;
+        let core = runtime.step(instance_ptr)?;
+        builder
+            .add_frame(&core.scene())
+            .map_err(anyhow::Error::msg)?;
+    }
+
+    let frame_count = builder.frame_count();
+    let movie = builder.build(
+        Size::new(SCREEN_VISIBLE_WIDTH, SCREEN_VISIBLE_HEIGHT),
+        FrameRate::Ntsc,
+    );
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("Could not create movie file {}.", path.display()))?;
+    bincode::serialize_into(std::io::BufWriter::new(file), &movie)?;
+    info!("Exported {frame_count} frames to {}.", path.display());
+    Ok(())
+}
@@ -1,14 +1,23 @@
 use crate::ProtoCore;
+use ::log::debug;
 use anyhow::Result;
 use std::path::Path;
-use ves_proto_common::gpu::{PaletteColor, PaletteIndex, PaletteTableIndex};
+use ves_proto_common::gpu::{
+    DmaTable, PaletteColor, PaletteIndex, PaletteTableIndex, ScreenControl,
+};
+use ves_proto_common::input::PlayerIndex;
+use ves_proto_common::log::LogLevel;
+use ves_proto_common::result::{to_code, ProtoError, ProtoResult};
 use wasmtime::{
-    AsContext, Caller, Config, Engine, Extern, Linker, Memory, Module, Store, StoreContext, Trap,
-    TypedFunc,
+    AsContext, Caller, Config, Engine, Extern, InstancePre, Linker, Memory, Module, Store,
+    StoreContext, Trap, TypedFunc,
 };
 
 pub struct Runtime {
     store: Store<ProtoCore>,
+    /// The compiled module with all imports resolved. Game instances are created from this, such that the module does not have to be
+    /// compiled and linked again when the game is restarted.
+    instance_pre: InstancePre<ProtoCore>,
     create_instance_fn: TypedFunc<(), u32>,
     step_fn: TypedFunc<u32, ()>,
 }
@@ -26,12 +35,16 @@ impl Runtime {
             "log", // function
             move |mut caller: Caller<'_, ProtoCore>, level: u32, ptr: u32, len: u32| {
                 let mem = Self::get_memory(&mut caller)?;
-                let message = Self::get_str(Self::get_slice(caller.as_context(), &mem, ptr, len)?)?;
-
-                let log_level = level.try_into().map_err(Trap::new)?;
-                caller.data().logger.log(log_level, message);
+                let result = LogLevel::try_from(level)
+                    .map_err(|_| ProtoError::InvalidArgument)
+                    .and_then(|log_level| {
+                        let message =
+                            Self::get_str(Self::get_slice(caller.as_context(), &mem, ptr, len)?)?;
+                        caller.data().logger.log(log_level, message);
+                        Ok(0)
+                    });
 
-                Ok(())
+                Ok(Self::return_code("log::log", result))
             },
         )?;
 
@@ -39,12 +52,34 @@ impl Runtime {
             "gpu",     // module
             "oam_set", // function
             move |mut caller: Caller<'_, ProtoCore>, index: u32, entry: u64| {
-                let index = u8::try_from(index)
-                    .map_err(|_| Trap::new("Could not convert index value to u8."))?;
+                let result = u8::try_from(index)
+                    .map_err(|_| ProtoError::InvalidArgument)
+                    .and_then(|index| caller.data_mut().set_oam_entry(index.into(), entry.into()))
+                    .map(|_| 0);
+
+                Self::return_code("gpu::oam_set", result)
+            },
+        )?;
+
+        linker.func_wrap(
+            "gpu",      // module
+            "oam_hide", // function
+            move |mut caller: Caller<'_, ProtoCore>, index: u32| {
+                let result = u8::try_from(index)
+                    .map_err(|_| ProtoError::InvalidArgument)
+                    .and_then(|index| caller.data_mut().hide_oam_entry(index.into()))
+                    .map(|_| 0);
 
-                caller.data_mut().set_oam_entry(index.into(), entry.into());
+                Self::return_code("gpu::oam_hide", result)
+            },
+        )?;
 
-                Ok(())
+        linker.func_wrap(
+            "gpu",       // module
+            "oam_clear", // function
+            move |mut caller: Caller<'_, ProtoCore>| {
+                caller.data_mut().clear_oam();
+                Self::return_code("gpu::oam_clear", Ok(0))
             },
         )?;
 
@@ -52,23 +87,100 @@ impl Runtime {
             "gpu",         // module
             "palette_set", // function
             move |mut caller: Caller<'_, ProtoCore>, palette: u32, index: u32, color: u32| {
-                let palette = u8::try_from(palette)
-                    .map(PaletteTableIndex::from)
-                    .map_err(|_| Trap::new("Could not convert palette value to u8."))?;
-                let index = u8::try_from(index)
-                    .map(PaletteIndex::from)
-                    .map_err(|_| Trap::new("Could not convert index value to u8."))?;
-                let color = u16::try_from(color)
-                    .map(PaletteColor::from)
-                    .map_err(|_| Trap::new("Could not convert color value to u16."))?;
-
-                caller.data_mut().set_palette_entry(palette, index, color);
-
-                Ok(())
+                let palette = u8::try_from(palette).map(PaletteTableIndex::from);
+                let index = u8::try_from(index).map(PaletteIndex::from);
+                let color = u16::try_from(color).map(PaletteColor::from);
+                let result = match (palette, index, color) {
+                    (Ok(palette), Ok(index), Ok(color)) => caller
+                        .data_mut()
+                        .set_palette_entry(palette, index, color)
+                        .map(|_| 0),
+                    _ => Err(ProtoError::InvalidArgument),
+                };
+
+                Self::return_code("gpu::palette_set", result)
+            },
+        )?;
+
+        linker.func_wrap(
+            "gpu",                // module
+            "screen_control_set", // function
+            move |mut caller: Caller<'_, ProtoCore>, control: u32| {
+                let result = u8::try_from(control)
+                    .map(ScreenControl::from)
+                    .map_err(|_| ProtoError::InvalidArgument)
+                    .map(|control| {
+                        caller.data_mut().set_screen_control(control);
+                        0
+                    });
+
+                Self::return_code("gpu::screen_control_set", result)
+            },
+        )?;
+
+        linker.func_wrap(
+            "gpu",      // module
+            "dma_copy", // function
+            move |mut caller: Caller<'_, ProtoCore>,
+                  ptr: u32,
+                  table: u32,
+                  offset: u32,
+                  len: u32| {
+                let mem = Self::get_memory(&mut caller)?;
+                let result = Self::dma_copy(&mut caller, &mem, ptr, table, offset, len);
+
+                Ok(Self::return_code("gpu::dma_copy", result))
+            },
+        )?;
+
+        linker.func_wrap(
+            "input",       // module
+            "buttons_get", // function
+            move |caller: Caller<'_, ProtoCore>, player: u32| {
+                let result = Self::get_player(player).map(|player| {
+                    u32::from(u16::from(caller.data().frame_input.input(player).buttons))
+                });
+
+                Self::return_code("input::buttons_get", result)
+            },
+        )?;
+
+        linker.func_wrap(
+            "input",         // module
+            "connected_get", // function
+            move |caller: Caller<'_, ProtoCore>, player: u32| {
+                let result = Self::get_player(player)
+                    .map(|player| u32::from(caller.data().frame_input.input(player).connected));
+
+                Self::return_code("input::connected_get", result)
+            },
+        )?;
+
+        linker.func_wrap(
+            "system",        // module
+            "boot_kind_get", // function
+            move |caller: Caller<'_, ProtoCore>| {
+                let result = Ok(u32::from(caller.data().boot_kind));
+
+                Self::return_code("system::boot_kind_get", result)
             },
         )?;
 
-        let instance = linker.instantiate(&mut store, &module)?;
+        let instance_pre = linker.instantiate_pre(&mut store, &module)?;
+        Self::instantiate(store, instance_pre)
+    }
+
+    /// Creates a WASM instance of the game module and looks up the exported functions.
+    ///
+    /// # Arguments
+    ///
+    /// * `store`: The store for the instance.
+    /// * `instance_pre`: The linked module.
+    fn instantiate(
+        mut store: Store<ProtoCore>,
+        instance_pre: InstancePre<ProtoCore>,
+    ) -> Result<Self> {
+        let instance = instance_pre.instantiate(&mut store)?;
 
         let create_instance_fn =
             instance.get_typed_func::<(), u32, _>(&mut store, "create_instance")?;
@@ -77,11 +189,25 @@ impl Runtime {
 
         Ok(Self {
             store,
+            instance_pre,
             create_instance_fn,
             step_fn,
         })
     }
 
+    /// Restarts the game module.
+    ///
+    /// The module is instantiated in a new store, such that the game starts with freshly initialized memory. The core state is
+    /// moved into the new store as-is. Since the module has already been compiled and linked, this is a lot cheaper than creating a
+    /// new [`Runtime`].
+    ///
+    /// Note that the game instance has to be created again with [`Runtime::create_instance()`].
+    pub(crate) fn restart(self) -> Result<Self> {
+        let engine = self.store.engine().clone();
+        let store = Store::new(&engine, self.store.into_data());
+        Self::instantiate(store, self.instance_pre)
+    }
+
     pub(crate) fn create_instance(&mut self) -> Result<u32, Trap> {
         self.create_instance_fn.call(&mut self.store, ())
     }
@@ -91,6 +217,48 @@ impl Runtime {
         Ok(self.store.data())
     }
 
+    pub(crate) fn core(&self) -> &ProtoCore {
+        self.store.data()
+    }
+
+    pub(crate) fn core_mut(&mut self) -> &mut ProtoCore {
+        self.store.data_mut()
+    }
+
+    /// Converts the result of a host call into the return code for the game (see [`ves_proto_common::result`]).
+    ///
+    /// Errors are also logged, since the game might not handle them.
+    fn return_code(call: &str, result: ProtoResult<u32>) -> i32 {
+        if let Err(err) = &result {
+            debug!("Host call {call} failed: {err}");
+        }
+        to_code(result)
+    }
+
+    /// Implements the `gpu::dma_copy` host call (see [`ProtoCore::dma_copy()`]).
+    fn dma_copy(
+        caller: &mut Caller<'_, ProtoCore>,
+        mem: &Memory,
+        ptr: u32,
+        table: u32,
+        offset: u32,
+        len: u32,
+    ) -> ProtoResult<u32> {
+        let table = u8::try_from(table)
+            .ok()
+            .and_then(|table| DmaTable::try_from(table).ok())
+            .ok_or(ProtoError::InvalidArgument)?;
+        let offset = usize::try_from(offset).map_err(|_| ProtoError::OutOfRange)?;
+        let byte_len = u32::try_from(table.entry_size())
+            .ok()
+            .and_then(|entry_size| len.checked_mul(entry_size))
+            .ok_or(ProtoError::InvalidMemory)?;
+
+        let data = Self::get_slice(caller.as_context(), mem, ptr, byte_len)?.to_vec();
+        caller.data_mut().dma_copy(table, offset, &data)?;
+        Ok(0)
+    }
+
     fn get_memory<T>(caller: &mut Caller<'_, T>) -> std::result::Result<Memory, Trap> {
         match caller.get_export("memory") {
             Some(Extern::Memory(mem)) => Ok(mem),
@@ -103,29 +271,24 @@ impl Runtime {
         mem: &'b Memory,
         ptr: u32,
         len: u32,
-    ) -> std::result::Result<&'a [u8], Trap> {
-        let index_from: usize = ptr
-            .try_into()
-            .map_err(|_| Trap::new(format!("Could not convert ptr ({ptr}) to usize.")))?;
-        let index_to: usize = len
-            .try_into()
-            .map_err(|_| Trap::new(format!("Could not convert len ({len}) to usize.")))?;
+    ) -> ProtoResult<&'a [u8]> {
+        let index_from = usize::try_from(ptr).map_err(|_| ProtoError::InvalidMemory)?;
+        let index_to = usize::try_from(len).map_err(|_| ProtoError::InvalidMemory)?;
 
         mem.data(store)
             .get(index_from..)
             .and_then(|arr| arr.get(..index_to))
-            .ok_or_else(|| {
-                Trap::new(format!(
-                    "Could not get slice with pointer {} and length {}.",
-                    ptr, len
-                ))
-            })
+            .ok_or(ProtoError::InvalidMemory)
     }
 
-    fn get_str(data: &[u8]) -> std::result::Result<&str, Trap> {
-        match std::str::from_utf8(data) {
-            Ok(str) => Ok(str),
-            Err(_) => Err(Trap::new("Invalid UTF-8")),
-        }
+    fn get_player(player: u32) -> ProtoResult<PlayerIndex> {
+        usize::try_from(player)
+            .ok()
+            .and_then(|player| PlayerIndex::try_from(player).ok())
+            .ok_or(ProtoError::InvalidArgument)
+    }
+
+    fn get_str(data: &[u8]) -> ProtoResult<&str> {
+        std::str::from_utf8(data).map_err(|_| ProtoError::InvalidMemory)
     }
 }
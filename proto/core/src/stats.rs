@@ -0,0 +1,72 @@
+//! Per-frame statistics for game developers.
+//!
+//! The statistics show how close a game gets to the budgets of the hardware. They are shown in the window title while the HUD is
+//! active and logged to the [`STATS_TARGET`] log target at the debug level.
+
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use ::log::debug;
+use ves_core_render::SceneStats;
+
+/// The log target for the statistics.
+pub const STATS_TARGET: &str = concat!(env!("CARGO_CRATE_NAME"), "::stats");
+
+/// The statistics of a single frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct FrameStats {
+    /// The number of OAM entries that lie at least partially on the visible screen.
+    pub active_objects: usize,
+    /// The number of entries in the OAM table.
+    pub oam_len: usize,
+    /// The estimated overdraw (see [`SceneStats::overdraw()`]).
+    pub overdraw: f32,
+    /// The time it took to render the scene and present it in the window.
+    pub blit_time: Duration,
+}
+
+impl FrameStats {
+    /// Creates a new instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `scene`: The statistics of the rendered scene.
+    /// * `oam_len`: The number of entries in the OAM table.
+    /// * `visible_size`: The size of the visible screen area in the form `(width, height)`.
+    /// * `blit_time`: The time it took to render the scene and present it in the window.
+    pub fn new(
+        scene: SceneStats,
+        oam_len: usize,
+        visible_size: (u32, u32),
+        blit_time: Duration,
+    ) -> Self {
+        Self {
+            active_objects: scene.active_objects,
+            oam_len,
+            overdraw: scene.overdraw(visible_size),
+            blit_time,
+        }
+    }
+
+    /// Logs the statistics to the [`STATS_TARGET`] log target.
+    ///
+    /// # Arguments
+    ///
+    /// * `frame_index`: The index of the frame.
+    pub fn log(&self, frame_index: usize) {
+        debug!(target: STATS_TARGET, "Frame {frame_index}: {self}");
+    }
+}
+
+impl Display for FrameStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{} objects, overdraw {:.2}x, blit {:.2} ms",
+            self.active_objects,
+            self.oam_len,
+            self.overdraw,
+            self.blit_time.as_secs_f64() * 1000.0
+        )
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::fs::File;
+use std::path::PathBuf;
+use ves_art_core::geom_art::Size;
+use ves_art_core::sprite::{PaletteIndex, Tile, TileSurface};
+use ves_art_core::surface::Surface as _;
+
+/// The size of the sprite. This must match `SPRITE_SIZE` in `lib.rs`.
+const SPRITE_SIZE: u32 = 16;
+
+fn main() -> Result<()> {
+    // Unused OAM entries refer to the first tile, so that one is left transparent
+    let tiles = vec![
+        Tile::new(TileSurface::new(Size::new_square(8u32))),
+        sprite_tile(),
+    ];
+
+    let path = PathBuf::from(std::env::var("OUT_DIR")?).join("vrom.bincode");
+    bincode::serialize_into(File::create(path)?, &tiles)?;
+
+    println!("cargo:rerun-if-changed=build.rs");
+    Ok(())
+}
+
+/// Creates the tile of the sprite: a square with an "eye" on the right side, such that the direction in which it faces is visible.
+fn sprite_tile() -> Tile {
+    let mut surface = TileSurface::new(Size::new_square(SPRITE_SIZE));
+    for (idx, pixel) in surface.data_mut().iter_mut().enumerate() {
+        // The cast is OK here, because the index lies within the surface
+        let (x, y) = (idx as u32 % SPRITE_SIZE, idx as u32 / SPRITE_SIZE);
+        let is_eye = (10..14).contains(&x) && (4..8).contains(&y);
+        *pixel = PaletteIndex::new(if is_eye { 2 } else { 1 });
+    }
+    Tile::new(surface)
+}
@@ -0,0 +1,234 @@
+//! An example game that moves a sprite around with the controller of the first player.
+//!
+//! The D-pad moves the sprite by [`SPEED`] pixels per step (twice as fast while B is held) and the sprite faces the direction in which
+//! it has moved last. The sprite can not leave the visible part of the screen. The tiles are generated in `build.rs`.
+//!
+//! The game only uses the protocol of the core, so the tests run it against the [`TestCore`](ves_proto_testing::TestCore).
+
+use log::{error, info};
+use ves_proto_common::api::{Core, CoreBootstrap, Game};
+use ves_proto_common::gpu::{OamTableEntry, OamTableIndex, PaletteColor, PaletteTableIndex};
+use ves_proto_common::input::{Button, PlayerIndex};
+
+#[cfg(feature = "wee_alloc")]
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+const VROM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/vrom.bincode"));
+
+/// This will be used by the Core to grab graphics data like tiles.
+#[allow(dead_code)]
+#[link_section = "vrom"]
+pub static ROM_DATA: [u8; VROM.len()] = *include_bytes!(concat!(env!("OUT_DIR"), "/vrom.bincode"));
+
+/// The size of the visible part of the screen.
+const SCREEN_SIZE: (u16, u16) = (256, 224);
+/// The size of the sprite (see `build.rs`).
+const SPRITE_SIZE: u16 = 16;
+/// The index of the sprite tile in VROM (see `build.rs`).
+const SPRITE_TILE: u8 = 1;
+/// The number of pixels that the sprite moves per step.
+const SPEED: u16 = 2;
+
+/// The colors of the palette of the sprite: transparent, the body and the eye.
+fn palette() -> [PaletteColor; 3] {
+    [
+        PaletteColor::from_real(0, 0, 0),
+        PaletteColor::from_real(0xf8, 0xa0, 0x00),
+        PaletteColor::from_real(0x20, 0x20, 0x20),
+    ]
+}
+
+pub struct MoverGame {
+    core: CoreBootstrap,
+    /// The position of the top-left pixel of the sprite.
+    position: (u16, u16),
+    /// Whether the sprite faces left.
+    h_flip: bool,
+    palette_uploaded: bool,
+}
+
+impl MoverGame {
+    /// Moves the sprite according to the buttons of the first player. A disconnected controller leaves the sprite where it is.
+    fn handle_input(&mut self) {
+        let buttons = match self.core.input_buttons(&PlayerIndex::new(0)) {
+            Ok(buttons) => buttons,
+            Err(err) => {
+                error!("Could not read the buttons: {err}");
+                return;
+            }
+        };
+
+        let speed = if buttons.is_pressed(Button::B) {
+            2 * SPEED
+        } else {
+            SPEED
+        };
+        let (mut x, mut y) = self.position;
+        if buttons.is_pressed(Button::Left) {
+            x = x.saturating_sub(speed);
+            self.h_flip = true;
+        }
+        if buttons.is_pressed(Button::Right) {
+            x += speed;
+            self.h_flip = false;
+        }
+        if buttons.is_pressed(Button::Up) {
+            y = y.saturating_sub(speed);
+        }
+        if buttons.is_pressed(Button::Down) {
+            y += speed;
+        }
+        self.position = (
+            x.min(SCREEN_SIZE.0 - SPRITE_SIZE),
+            y.min(SCREEN_SIZE.1 - SPRITE_SIZE),
+        );
+    }
+}
+
+impl Game for MoverGame {
+    fn new(core: CoreBootstrap) -> Self {
+        Self {
+            core,
+            position: (
+                (SCREEN_SIZE.0 - SPRITE_SIZE) / 2,
+                (SCREEN_SIZE.1 - SPRITE_SIZE) / 2,
+            ),
+            h_flip: false,
+            palette_uploaded: false,
+        }
+    }
+
+    fn step(&mut self) {
+        if !self.palette_uploaded {
+            info!("Uploading the palette.");
+            if let Err(err) = self
+                .core
+                .palette_copy(&PaletteTableIndex::new(0), &palette())
+            {
+                error!("Could not upload the palette: {err}");
+            }
+            self.palette_uploaded = true;
+        }
+
+        self.handle_input();
+
+        let (x, y) = self.position;
+        let entry = OamTableEntry::new(x, y, 0, u8::from(self.h_flip), 0, SPRITE_TILE.into());
+        if let Err(err) = self.core.oam_set(&OamTableIndex::new(0), &entry) {
+            error!("Could not set the OAM entry: {err}");
+        }
+    }
+}
+
+// The host functions only exist when the game runs inside the core
+#[cfg(target_arch = "wasm32")]
+ves_proto_common::create_game!(MoverGame);
+
+#[cfg(test)]
+mod test_mover_game {
+    use super::*;
+    use ves_art_core::sprite::Tile;
+    use ves_proto_common::input::Buttons;
+    use ves_proto_testing::{CoreCall, TestCore};
+
+    const START: (u16, u16) = (120, 104);
+
+    fn buttons(pressed: &[Button]) -> Buttons {
+        let mut buttons = Buttons::default();
+        for button in pressed {
+            buttons.set_pressed(*button, true);
+        }
+        buttons
+    }
+
+    fn step_with(core: &TestCore, game: &mut MoverGame, pressed: &[Button], steps: usize) {
+        core.set_buttons(PlayerIndex::new(0), buttons(pressed));
+        for _ in 0..steps {
+            game.step();
+        }
+    }
+
+    #[test]
+    fn test_first_step() {
+        let core = TestCore::new();
+        let mut game = MoverGame::new(core.bootstrap());
+        game.step();
+
+        assert_eq!(
+            vec![
+                CoreCall::PaletteCopy(PaletteTableIndex::new(0), palette().to_vec()),
+                CoreCall::OamSet(
+                    OamTableIndex::new(0),
+                    OamTableEntry::new(START.0, START.1, 0, 0, 0, 1)
+                ),
+            ],
+            core.take_calls()
+        );
+
+        // The palette is only uploaded once and a disconnected controller does not move the sprite
+        game.step();
+        assert_eq!(
+            vec![CoreCall::OamSet(
+                OamTableIndex::new(0),
+                OamTableEntry::new(START.0, START.1, 0, 0, 0, 1)
+            )],
+            core.take_calls()
+        );
+    }
+
+    #[test]
+    fn test_move() {
+        let core = TestCore::new();
+        let mut game = MoverGame::new(core.bootstrap());
+
+        step_with(&core, &mut game, &[Button::Right, Button::Down], 3);
+        assert_eq!((START.0 + 6, START.1 + 6), core.oam()[0].position());
+        assert!(!core.oam()[0].h_flip());
+
+        step_with(&core, &mut game, &[Button::Left, Button::B], 1);
+        assert_eq!((START.0 + 2, START.1 + 6), core.oam()[0].position());
+        assert!(core.oam()[0].h_flip());
+
+        // The sprite keeps facing the same direction when it stops
+        step_with(&core, &mut game, &[], 1);
+        assert!(core.oam()[0].h_flip());
+    }
+
+    #[test]
+    fn test_bounds() {
+        let core = TestCore::new();
+        let mut game = MoverGame::new(core.bootstrap());
+
+        step_with(&core, &mut game, &[Button::Left, Button::Up], 100);
+        assert_eq!((0, 0), core.oam()[0].position());
+
+        step_with(&core, &mut game, &[Button::Right, Button::Down], 200);
+        assert_eq!((240, 208), core.oam()[0].position());
+    }
+
+    #[test]
+    fn test_render() {
+        let tiles: Vec<Tile> = bincode::deserialize(&ROM_DATA).unwrap();
+        let core = TestCore::new();
+        let mut game = MoverGame::new(core.bootstrap());
+        step_with(&core, &mut game, &[Button::Left], 1);
+
+        let buffer = core.render(&tiles).unwrap();
+        let pixel = |x: u16, y: u16| {
+            let idx = 4 * (usize::from(y) * 512 + usize::from(x));
+            buffer[idx..idx + 4].to_vec()
+        };
+        let color = |color: PaletteColor| {
+            let (r, g, b) = color.to_real();
+            vec![r, g, b, 0xff]
+        };
+
+        let (x, y) = (START.0 - 2, START.1);
+        assert_eq!(vec![0, 0, 0, 0], pixel(x - 1, y));
+        assert_eq!(color(palette()[1]), pixel(x, y));
+        // The eye is on the left, because the sprite faces left
+        assert_eq!(color(palette()[2]), pixel(x + 2, y + 4));
+        assert_eq!(color(palette()[1]), pixel(x + 10, y + 4));
+    }
+}
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use std::fs::File;
+use std::path::PathBuf;
+use ves_art_core::geom_art::Size;
+use ves_art_core::sprite::{PaletteIndex, Tile, TileSurface};
+use ves_art_core::surface::Surface as _;
+
+/// The size of a background block. This must match `BLOCK_SIZE` in `lib.rs`.
+const BLOCK_SIZE: u32 = 64;
+/// The width of the border of a block.
+const BORDER: u32 = 4;
+
+fn main() -> Result<()> {
+    // Unused OAM entries refer to the first tile, so that one is left transparent
+    let tiles = vec![
+        Tile::new(TileSurface::new(Size::new_square(8u32))),
+        block_tile(),
+    ];
+
+    let path = PathBuf::from(std::env::var("OUT_DIR")?).join("vrom.bincode");
+    bincode::serialize_into(File::create(path)?, &tiles)?;
+
+    println!("cargo:rerun-if-changed=build.rs");
+    Ok(())
+}
+
+/// Creates the tile of a background block: a filled square with a border.
+fn block_tile() -> Tile {
+    let mut surface = TileSurface::new(Size::new_square(BLOCK_SIZE));
+    let is_border = |value: u32| !(BORDER..BLOCK_SIZE - BORDER).contains(&value);
+    for (idx, pixel) in surface.data_mut().iter_mut().enumerate() {
+        // The cast is OK here, because the index lies within the surface
+        let (x, y) = (idx as u32 % BLOCK_SIZE, idx as u32 / BLOCK_SIZE);
+        *pixel = PaletteIndex::new(if is_border(x) || is_border(y) { 2 } else { 1 });
+    }
+    Tile::new(surface)
+}
@@ -0,0 +1,243 @@
+//! An example game with a scrolling background.
+//!
+//! The core does not have background layers yet, so the background is made out of large sprites ("blocks") that cover the entire
+//! screen buffer. Since objects wrap around at the edges of the screen buffer, scrolling only requires moving every block by the
+//! same offset. The blocks use two palettes in a checkerboard pattern. The background scrolls by [`SCROLL_SPEED`] pixels per step
+//! and the D-pad of the first player changes the direction. The tiles are generated in `build.rs`.
+//!
+//! The game only uses the protocol of the core, so the tests run it against the [`TestCore`](ves_proto_testing::TestCore).
+
+use log::{error, info};
+use ves_proto_common::api::{Core, CoreBootstrap, Game};
+use ves_proto_common::gpu::{OamTableEntry, OamTableIndex, PaletteColor, PaletteTableIndex};
+use ves_proto_common::input::{Button, PlayerIndex};
+
+#[cfg(feature = "wee_alloc")]
+#[global_allocator]
+static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
+
+const VROM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/vrom.bincode"));
+
+/// This will be used by the Core to grab graphics data like tiles.
+#[allow(dead_code)]
+#[link_section = "vrom"]
+pub static ROM_DATA: [u8; VROM.len()] = *include_bytes!(concat!(env!("OUT_DIR"), "/vrom.bincode"));
+
+/// The size of the screen buffer. Objects wrap around at its edges.
+const BUFFER_SIZE: (u16, u16) = (512, 256);
+/// The size of a block (see `build.rs`).
+const BLOCK_SIZE: u16 = 64;
+/// The index of the block tile in VROM (see `build.rs`).
+const BLOCK_TILE: u8 = 1;
+/// The number of blocks in a row and in a column.
+const BLOCKS: (u16, u16) = (BUFFER_SIZE.0 / BLOCK_SIZE, BUFFER_SIZE.1 / BLOCK_SIZE);
+/// The number of pixels that the background scrolls per step.
+const SCROLL_SPEED: u16 = 1;
+
+/// The palettes of the blocks. Every palette contains the colors for transparent, the fill and the border.
+fn palettes() -> [[PaletteColor; 3]; 2] {
+    [
+        [
+            PaletteColor::from_real(0, 0, 0),
+            PaletteColor::from_real(0x30, 0x60, 0xc0),
+            PaletteColor::from_real(0x10, 0x20, 0x60),
+        ],
+        [
+            PaletteColor::from_real(0, 0, 0),
+            PaletteColor::from_real(0x40, 0xa0, 0x40),
+            PaletteColor::from_real(0x10, 0x40, 0x10),
+        ],
+    ]
+}
+
+pub struct ScrollerGame {
+    core: CoreBootstrap,
+    /// The scroll offset, within the screen buffer.
+    offset: (u16, u16),
+    /// The scroll direction in X and Y. Every component is -1, 0 or 1.
+    direction: (i8, i8),
+    palettes_uploaded: bool,
+}
+
+impl ScrollerGame {
+    /// Changes the scroll direction according to the D-pad of the first player. Releasing the D-pad keeps the current direction.
+    fn handle_input(&mut self) {
+        let buttons = match self.core.input_buttons(&PlayerIndex::new(0)) {
+            Ok(buttons) => buttons,
+            Err(err) => {
+                error!("Could not read the buttons: {err}");
+                return;
+            }
+        };
+
+        let axis = |negative: Button, positive: Button| {
+            i8::from(buttons.is_pressed(positive)) - i8::from(buttons.is_pressed(negative))
+        };
+        let direction = (
+            axis(Button::Left, Button::Right),
+            axis(Button::Up, Button::Down),
+        );
+        if direction != (0, 0) {
+            self.direction = direction;
+        }
+    }
+
+    /// Creates the OAM entries of all blocks at the current scroll offset.
+    fn block_entries(&self) -> Vec<OamTableEntry> {
+        (0..BLOCKS.1)
+            .flat_map(|row| (0..BLOCKS.0).map(move |column| (column, row)))
+            .map(|(column, row)| {
+                let x = (column * BLOCK_SIZE + self.offset.0) % BUFFER_SIZE.0;
+                let y = (row * BLOCK_SIZE + self.offset.1) % BUFFER_SIZE.1;
+                let palette = u8::from((column + row) % 2 == 1);
+                OamTableEntry::new(x, y, palette, 0, 0, BLOCK_TILE.into())
+            })
+            .collect()
+    }
+}
+
+/// Moves a coordinate in a direction, wrapping around at the end of the screen buffer.
+fn scroll(value: u16, direction: i8, len: u16) -> u16 {
+    match direction {
+        1 => (value + SCROLL_SPEED) % len,
+        -1 => (value + len - SCROLL_SPEED) % len,
+        _ => value,
+    }
+}
+
+impl Game for ScrollerGame {
+    fn new(core: CoreBootstrap) -> Self {
+        Self {
+            core,
+            offset: (0, 0),
+            direction: (1, 0),
+            palettes_uploaded: false,
+        }
+    }
+
+    fn step(&mut self) {
+        if !self.palettes_uploaded {
+            info!("Uploading {} palettes.", palettes().len());
+            for (idx, palette) in (0u8..).zip(palettes()) {
+                if let Err(err) = self
+                    .core
+                    .palette_copy(&PaletteTableIndex::new(idx), &palette)
+                {
+                    error!("Could not upload palette {idx}: {err}");
+                }
+            }
+            self.palettes_uploaded = true;
+        }
+
+        let entries = self.block_entries();
+        if let Err(err) = self.core.oam_copy(&OamTableIndex::new(0), &entries) {
+            error!("Could not upload {} OAM entries: {err}", entries.len());
+        }
+
+        self.handle_input();
+        self.offset = (
+            scroll(self.offset.0, self.direction.0, BUFFER_SIZE.0),
+            scroll(self.offset.1, self.direction.1, BUFFER_SIZE.1),
+        );
+    }
+}
+
+// The host functions only exist when the game runs inside the core
+#[cfg(target_arch = "wasm32")]
+ves_proto_common::create_game!(ScrollerGame);
+
+#[cfg(test)]
+mod test_scroller_game {
+    use super::*;
+    use ves_art_core::sprite::Tile;
+    use ves_proto_common::input::Buttons;
+    use ves_proto_testing::{CoreCall, TestCore};
+
+    fn steps(game: &mut ScrollerGame, count: usize) {
+        for _ in 0..count {
+            game.step();
+        }
+    }
+
+    fn press(core: &TestCore, button: Button) {
+        let mut buttons = Buttons::default();
+        buttons.set_pressed(button, true);
+        core.set_buttons(PlayerIndex::new(0), buttons);
+    }
+
+    #[test]
+    fn test_first_step() {
+        let core = TestCore::new();
+        let mut game = ScrollerGame::new(core.bootstrap());
+        game.step();
+
+        let calls = core.take_calls();
+        assert_eq!(3, calls.len());
+        assert_eq!(
+            CoreCall::PaletteCopy(PaletteTableIndex::new(1), palettes()[1].to_vec()),
+            calls[1]
+        );
+        // The blocks cover the entire screen buffer
+        let oam = core.oam();
+        assert_eq!(
+            32,
+            oam.iter()
+                .filter(|entry| entry.char_table_index() == 1)
+                .count()
+        );
+        assert_eq!((0, 0), oam[0].position());
+        assert_eq!((448, 192), oam[31].position());
+        assert_eq!(
+            vec![0, 1, 0, 1, 0, 1, 0, 1, 1, 0],
+            oam[..10]
+                .iter()
+                .map(|entry| entry.palette_table_index().index())
+                .collect::<Vec<_>>()
+        );
+
+        // The palettes are only uploaded once
+        game.step();
+        assert_eq!(1, core.take_calls().len());
+        assert_eq!((1, 0), core.oam()[0].position());
+    }
+
+    #[test]
+    fn test_scroll() {
+        let core = TestCore::new();
+        let mut game = ScrollerGame::new(core.bootstrap());
+
+        // The last block wraps around to the left edge
+        steps(&mut game, 65);
+        assert_eq!((64, 0), core.oam()[0].position());
+        assert_eq!((0, 0), core.oam()[7].position());
+
+        // Releasing the D-pad keeps the direction
+        press(&core, Button::Up);
+        game.step();
+        press(&core, Button::Left);
+        game.step();
+        core.set_buttons(PlayerIndex::new(0), Buttons::default());
+        steps(&mut game, 4);
+        assert_eq!((61, 255), core.oam()[0].position());
+        assert_eq!((61, 191), core.oam()[24].position());
+    }
+
+    #[test]
+    fn test_render() {
+        let tiles: Vec<Tile> = bincode::deserialize(&ROM_DATA).unwrap();
+        let core = TestCore::new();
+        let mut game = ScrollerGame::new(core.bootstrap());
+
+        game.step();
+        let first = core.render(&tiles).unwrap();
+        // The checkerboard pattern repeats every two blocks
+        steps(&mut game, 2 * usize::from(BLOCK_SIZE));
+        assert_eq!(first, core.render(&tiles).unwrap());
+        steps(&mut game, usize::from(BLOCK_SIZE));
+        let shifted = core.render(&tiles).unwrap();
+        assert_ne!(first, shifted);
+
+        // The entire screen buffer is covered
+        assert!(shifted.chunks(4).all(|pixel| pixel[3] == 0xff));
+    }
+}
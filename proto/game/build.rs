@@ -2,12 +2,15 @@ use anyhow::{anyhow, Context, Result};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
-use ves_art_core::movie::Movie;
+use ves_art_core::movie::{Movie, MovieFrame};
+use ves_art_core::sprite::Cel;
+use ves_proto_common::metasprite::{compile_cel, write_metasprite_module};
 
 const INPUT_PATH: &str = "../../test_movie.bincode";
 fn main() -> Result<()> {
     let movie = load_movie_data()?;
     generate_static_code(&movie)?;
+    generate_metasprites(&movie)?;
     generate_vrom_data(&movie)?;
 
     println!("cargo:rerun-if-changed=build.rs");
@@ -49,17 +52,7 @@ fn generate_static_code(movie: &Movie) -> Result<()> {
         "pub const fn frames() -> &'static [MovieFrame] {{"
     )?;
 
-    let frames = if option_env!("FULL_FRAMES").is_some() {
-        movie.frames()
-    } else {
-        movie
-            .frames()
-            .chunks(10)
-            .next()
-            .ok_or_else(|| anyhow!("Got no frames."))?
-    };
-
-    frames.serialize(&mut serializer)?;
+    selected_frames(movie)?.serialize(&mut serializer)?;
 
     writeln!(serializer.out_mut(), "}}")?;
 
@@ -79,6 +72,40 @@ fn generate_static_code(movie: &Movie) -> Result<()> {
     Ok(())
 }
 
+/// Selects the frames that end up in the game: all frames if `FULL_FRAMES` is set and the first 10 frames otherwise.
+fn selected_frames(movie: &Movie) -> Result<&[MovieFrame]> {
+    if option_env!("FULL_FRAMES").is_some() {
+        Ok(movie.frames())
+    } else {
+        movie
+            .frames()
+            .chunks(10)
+            .next()
+            .ok_or_else(|| anyhow!("Got no frames."))
+    }
+}
+
+/// Compiles every selected frame into a metasprite (see [`ves_proto_common::metasprite`]).
+fn generate_metasprites(movie: &Movie) -> Result<()> {
+    let metasprites = selected_frames(movie)?
+        .iter()
+        .map(|frame| {
+            let cel = Cel::new(frame.sprites().to_vec());
+            let objects = compile_cel(&cel, movie.screen_size()).map_err(|err| {
+                anyhow!("Could not compile frame {}: {}", frame.frame_number(), err)
+            })?;
+            Ok((format!("Frame{}", frame.frame_number()), objects))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut code = String::new();
+    write_metasprite_module(&mut code, &metasprites)?;
+    let output_path = PathBuf::from("src/generated/metasprites.rs");
+    std::fs::write(&output_path, code)?;
+    rust_format::format_file(&output_path)?;
+    Ok(())
+}
+
 fn generate_vrom_data(movie: &Movie) -> Result<()> {
     let mut path = PathBuf::new();
     path.push(std::env::var("OUT_DIR")?);
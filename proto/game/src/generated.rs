@@ -1,2 +1,3 @@
+pub mod metasprites;
 pub mod methods;
 pub mod types;
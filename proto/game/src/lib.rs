@@ -1,10 +1,8 @@
 mod generated;
 
-use log::info;
+use log::{error, info};
 use ves_proto_common::api::{Core, CoreBootstrap, Game};
-use ves_proto_common::gpu::{
-    OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex, PaletteTableIndex,
-};
+use ves_proto_common::gpu::{OamTableEntry, OamTableIndex, PaletteColor, PaletteTableIndex};
 
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
@@ -42,36 +40,46 @@ impl Game for ProtoGame {
         if self.frame_nr == 0 {
             info!("Uploading {} palettes.", PALETTES.len());
             for (pal_idx, palette) in PALETTES.iter().enumerate() {
-                for (col_idx, color) in palette.colors.iter().enumerate() {
-                    use crate::generated::types::Color;
-                    let color = match color {
+                use crate::generated::types::Color;
+                let colors: Vec<PaletteColor> = palette
+                    .colors
+                    .iter()
+                    .map(|color| match color {
                         Color::Opaque(rgb) => PaletteColor::from_real(rgb.r, rgb.g, rgb.b),
                         Color::Transparent => PaletteColor::from_real(0, 0, 0),
-                    };
+                    })
+                    .collect();
 
-                    let palette = PaletteTableIndex::new(from_unchecked(pal_idx));
-                    let index = PaletteIndex::new(from_unchecked(col_idx));
-                    self.core.palette_set(&palette, &index, &color);
+                let palette = PaletteTableIndex::new(from_unchecked(pal_idx));
+                if let Err(err) = self.core.palette_copy(&palette, &colors) {
+                    error!("Could not upload palette {pal_idx}: {err}");
                 }
             }
         }
 
         let movie_frame = &FRAMES[self.frame_nr % FRAMES.len()];
-        for (i, sprite) in movie_frame.sprites.iter().enumerate() {
-            let entry = OamTableEntry::new(
-                from_unchecked(sprite.position.x.0),
-                from_unchecked(sprite.position.y.0),
-                from_unchecked(sprite.palette),
-                u8::from(sprite.h_flip),
-                u8::from(sprite.v_flip),
-                from_unchecked(sprite.tile),
-            );
-            self.core
-                .oam_set(&OamTableIndex::new(from_unchecked(i)), &entry);
+        let entries: Vec<OamTableEntry> = movie_frame
+            .sprites
+            .iter()
+            .map(|sprite| {
+                OamTableEntry::new(
+                    from_unchecked(sprite.position.x.0),
+                    from_unchecked(sprite.position.y.0),
+                    from_unchecked(sprite.palette),
+                    u8::from(sprite.h_flip),
+                    u8::from(sprite.v_flip),
+                    from_unchecked(sprite.tile),
+                )
+            })
+            .collect();
+        if let Err(err) = self.core.oam_copy(&OamTableIndex::new(0), &entries) {
+            error!("Could not upload {} OAM entries: {err}", entries.len());
         }
 
         self.frame_nr += 1;
     }
 }
 
+// The host functions only exist when the game runs inside the core
+#[cfg(target_arch = "wasm32")]
 ves_proto_common::create_game!(ProtoGame);
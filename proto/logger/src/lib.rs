@@ -1,7 +1,7 @@
 use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
 
 pub struct Logger {
-    log_fn: unsafe extern "C" fn(u32, *const u8, usize),
+    log_fn: unsafe extern "C" fn(u32, *const u8, usize) -> i32,
 }
 
 impl Logger {
@@ -13,7 +13,7 @@ impl Logger {
     ///
     /// * `log_fn`: The function pointer for logging to the Core.
     #[allow(unused)]
-    pub fn new(log_fn: unsafe extern "C" fn(u32, *const u8, usize)) -> Self {
+    pub fn new(log_fn: unsafe extern "C" fn(u32, *const u8, usize) -> i32) -> Self {
         Self { log_fn }
     }
 
@@ -33,7 +33,7 @@ impl Logger {
     /// #[link(wasm_import_module = "log")]
     /// extern "C" {
     ///     #[link_name = "log"]
-    ///     fn log_fn(level: u32, ptr: *const u8, len: usize);
+    ///     fn log_fn(level: u32, ptr: *const u8, len: usize) -> i32;
     /// }
     ///
     /// fn start_game() {
@@ -66,6 +66,7 @@ impl Logger {
     }
 
     fn send(&self, level: ves_proto_common::log::LogLevel, message: &str) {
+        // There is no way to report a failure from the logger, so the return code is ignored
         unsafe {
             (self.log_fn)(level.into(), message.as_ptr(), message.len());
         }
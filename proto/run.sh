@@ -9,5 +9,9 @@ if [ "$BUILD_TYPE" = "" ]; then
   BUILD_TYPE="debug"
 fi
 
+if [ "$GAME" = "" ]; then
+  GAME="ves_proto_game"
+fi
+
 ./build.sh
-"${SCRIPT_DIR}/../target/${BUILD_TYPE}/ves-proto-core" "${SCRIPT_DIR}/../target/wasm32-unknown-unknown/${BUILD_TYPE}/ves_proto_game.wasm"
\ No newline at end of file
+"${SCRIPT_DIR}/../target/${BUILD_TYPE}/ves-proto-core" "${SCRIPT_DIR}/../target/wasm32-unknown-unknown/${BUILD_TYPE}/${GAME}.wasm" "$@"
\ No newline at end of file
@@ -0,0 +1,595 @@
+//! A test harness for games that implement the prototype architecture.
+//!
+//! The [`TestCore`] is an in-memory implementation of the core. It keeps the same state as the real core (OAM table, palette table
+//! and screen control register), records the calls that the game makes and renders its state with the reference renderer (see
+//! [`ves_core_render`]). This allows game crates to unit-test their logic natively, without compiling to WebAssembly or opening a
+//! window.
+//!
+//! Code that is generic over [`Core`] can use a [`TestCore`] directly. A [`Game`](ves_proto_common::api::Game) is created with a
+//! [`CoreBootstrap`], which can be obtained with [`TestCore::bootstrap()`]:
+//!
+//! ```ignore
+//! use ves_proto_common::api::Game;
+//! use ves_proto_testing::TestCore;
+//!
+//! let core = TestCore::new();
+//! let mut game = MyGame::new(core.bootstrap());
+//! game.step();
+//! assert_eq!(expected_entry, core.oam()[0]);
+//! ```
+//!
+//! Note that the `create_game!()` macro imports the host functions from WebAssembly modules, so game crates should only invoke it
+//! when compiling for WebAssembly (e.g. with `#[cfg(target_arch = "wasm32")]`).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use ves_art_core::sprite::Tile;
+use ves_core_render::{Palette, Scene, SCREEN_BUFFER_LEN};
+use ves_proto_common::api::{Core, CoreBootstrap};
+use ves_proto_common::gpu::{
+    DmaTable, OamAttributes, OamTableEntry, OamTableIndex, PaletteColor, PaletteIndex,
+    PaletteTableIndex, ScreenControl,
+};
+use ves_proto_common::input::{Buttons, PlayerIndex, PLAYER_COUNT};
+use ves_proto_common::result::{to_code, ProtoError, ProtoResult};
+use ves_proto_common::system::BootKind;
+
+/// The number of entries in the OAM table.
+pub const OAM_TABLE_SIZE: usize = 128;
+/// The number of palettes in the palette table.
+pub const PALETTE_TABLE_SIZE: usize = 256;
+/// The number of colors in a palette.
+const PALETTE_SIZE: usize = 16;
+
+thread_local! {
+    /// The core that receives the calls of the [`CoreBootstrap`] that was created on this thread.
+    static CURRENT: RefCell<Option<TestCore>> = const { RefCell::new(None) };
+}
+
+/// A call from the game into the core that changes the core state.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CoreCall {
+    OamSet(OamTableIndex, OamTableEntry),
+    OamCopy(OamTableIndex, Vec<OamTableEntry>),
+    OamHide(OamTableIndex),
+    OamClear,
+    OamAttributesCopy(OamTableIndex, Vec<OamAttributes>),
+    PaletteSet(PaletteTableIndex, PaletteIndex, PaletteColor),
+    PaletteCopy(PaletteTableIndex, Vec<PaletteColor>),
+    ScreenControlSet(ScreenControl),
+}
+
+struct CoreState {
+    oam: [OamTableEntry; OAM_TABLE_SIZE],
+    oam_attributes: [OamAttributes; OAM_TABLE_SIZE],
+    palettes: [Palette; PALETTE_TABLE_SIZE],
+    screen_control: ScreenControl,
+    /// The buttons of every player. `None` means that the controller is disconnected.
+    input: [Option<Buttons>; PLAYER_COUNT],
+    boot_kind: BootKind,
+    calls: Vec<CoreCall>,
+}
+
+/// An in-memory core for testing games.
+///
+/// Cloning a [`TestCore`] creates another handle to the same core, such that the state can be inspected while the game holds on
+/// to its [`CoreBootstrap`].
+#[derive(Clone)]
+pub struct TestCore {
+    state: Rc<RefCell<CoreState>>,
+}
+
+impl Default for TestCore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestCore {
+    /// Creates a new instance in the state of a cold boot. All controllers are disconnected.
+    pub fn new() -> Self {
+        let state = CoreState {
+            oam: [Default::default(); OAM_TABLE_SIZE],
+            oam_attributes: [Default::default(); OAM_TABLE_SIZE],
+            palettes: [Default::default(); PALETTE_TABLE_SIZE],
+            screen_control: Default::default(),
+            input: [None; PLAYER_COUNT],
+            boot_kind: BootKind::ColdBoot,
+            calls: Vec::new(),
+        };
+        Self {
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+
+    /// Creates a [`CoreBootstrap`] that forwards all calls to this core.
+    ///
+    /// The host functions of a [`CoreBootstrap`] are plain function pointers, so the core is registered for the current thread.
+    /// Creating another bootstrap on the same thread replaces the registration. Since every test runs on its own thread, this is
+    /// not a limitation in practice.
+    ///
+    /// Note that the game cannot log through the core. Log messages should be captured with a regular logger instead.
+    pub fn bootstrap(&self) -> CoreBootstrap {
+        CURRENT.with(|current| *current.borrow_mut() = Some(self.clone()));
+        CoreBootstrap::new(
+            log_log,
+            gpu_oam_set,
+            gpu_oam_hide,
+            gpu_oam_clear,
+            gpu_palette_set,
+            gpu_screen_control_set,
+            gpu_dma_copy,
+            input_buttons_get,
+            input_connected_get,
+            system_boot_kind_get,
+            |_| Ok(()),
+        )
+    }
+
+    /// Retrieves the OAM table.
+    pub fn oam(&self) -> [OamTableEntry; OAM_TABLE_SIZE] {
+        self.state.borrow().oam
+    }
+
+    /// Retrieves the OAM attributes table.
+    pub fn oam_attributes(&self) -> [OamAttributes; OAM_TABLE_SIZE] {
+        self.state.borrow().oam_attributes
+    }
+
+    /// Retrieves a palette from the palette table.
+    pub fn palette(&self, palette: PaletteTableIndex) -> Palette {
+        self.state.borrow().palettes[usize::from(palette)]
+    }
+
+    /// Retrieves the screen control register.
+    pub fn screen_control(&self) -> ScreenControl {
+        self.state.borrow().screen_control
+    }
+
+    /// Retrieves all calls that have been recorded.
+    pub fn calls(&self) -> Vec<CoreCall> {
+        self.state.borrow().calls.clone()
+    }
+
+    /// Retrieves all calls that have been recorded and clears the record. This is useful for inspecting the calls of a single step.
+    pub fn take_calls(&self) -> Vec<CoreCall> {
+        std::mem::take(&mut self.state.borrow_mut().calls)
+    }
+
+    /// Sets the buttons on the controller of a player. This also connects the controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `player`: The player.
+    /// * `buttons`: The buttons.
+    pub fn set_buttons(&self, player: PlayerIndex, buttons: Buttons) {
+        self.state.borrow_mut().input[usize::from(player)] = Some(buttons);
+    }
+
+    /// Disconnects the controller of a player.
+    pub fn disconnect(&self, player: PlayerIndex) {
+        self.state.borrow_mut().input[usize::from(player)] = None;
+    }
+
+    /// Sets the way in which the game has been started.
+    pub fn set_boot_kind(&self, boot_kind: BootKind) {
+        self.state.borrow_mut().boot_kind = boot_kind;
+    }
+
+    /// Renders the current state with the reference renderer (see [`ves_core_render::render()`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `tiles`: The tiles (from VROM).
+    ///
+    /// returns: The screen buffer with RGBA pixels. Pixels that are not covered by any object are transparent black.
+    pub fn render(&self, tiles: &[Tile]) -> Result<Vec<u8>, String> {
+        let state = self.state.borrow();
+        let scene = Scene {
+            tiles,
+            oam: &state.oam,
+            palettes: &state.palettes,
+            screen_control: state.screen_control,
+        };
+        let mut screen_buffer = vec![0; SCREEN_BUFFER_LEN];
+        ves_core_render::render(&scene, &mut screen_buffer, None)?;
+        Ok(screen_buffer)
+    }
+
+    fn record(&self, call: CoreCall) {
+        self.state.borrow_mut().calls.push(call);
+    }
+
+    /// Copies colors into the palette table.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset`: The index of the first color (see [`DmaTable::Palettes`]).
+    /// * `colors`: The colors.
+    fn copy_colors(&self, offset: usize, colors: &[PaletteColor]) -> ProtoResult<()> {
+        if offset + colors.len() > PALETTE_TABLE_SIZE * PALETTE_SIZE {
+            return Err(ProtoError::OutOfRange);
+        }
+        let palettes = &mut self.state.borrow_mut().palettes;
+        for (index, color) in (offset..).zip(colors) {
+            palettes[index / PALETTE_SIZE].colors[index % PALETTE_SIZE] = *color;
+        }
+        Ok(())
+    }
+}
+
+impl Core for TestCore {
+    fn oam_set(&self, index: &OamTableIndex, entry: &OamTableEntry) -> ProtoResult<()> {
+        self.record(CoreCall::OamSet(*index, *entry));
+        let mut state = self.state.borrow_mut();
+        let target = state
+            .oam
+            .get_mut(usize::from(*index))
+            .ok_or(ProtoError::OutOfRange)?;
+        *target = *entry;
+        Ok(())
+    }
+
+    fn oam_copy(&self, index: &OamTableIndex, entries: &[OamTableEntry]) -> ProtoResult<()> {
+        self.record(CoreCall::OamCopy(*index, entries.to_vec()));
+        let mut state = self.state.borrow_mut();
+        let offset = usize::from(*index);
+        let target = state
+            .oam
+            .get_mut(offset..offset + entries.len())
+            .ok_or(ProtoError::OutOfRange)?;
+        target.copy_from_slice(entries);
+        Ok(())
+    }
+
+    fn oam_hide(&self, index: &OamTableIndex) -> ProtoResult<()> {
+        self.record(CoreCall::OamHide(*index));
+        let mut state = self.state.borrow_mut();
+        let target = state
+            .oam
+            .get_mut(usize::from(*index))
+            .ok_or(ProtoError::OutOfRange)?;
+        target.set_hidden(true);
+        Ok(())
+    }
+
+    fn oam_clear(&self) -> ProtoResult<()> {
+        self.record(CoreCall::OamClear);
+        self.state.borrow_mut().oam = [OamTableEntry::new_hidden(); OAM_TABLE_SIZE];
+        Ok(())
+    }
+
+    fn oam_attributes_copy(
+        &self,
+        index: &OamTableIndex,
+        attributes: &[OamAttributes],
+    ) -> ProtoResult<()> {
+        self.record(CoreCall::OamAttributesCopy(*index, attributes.to_vec()));
+        let mut state = self.state.borrow_mut();
+        let offset = usize::from(*index);
+        let target = state
+            .oam_attributes
+            .get_mut(offset..offset + attributes.len())
+            .ok_or(ProtoError::OutOfRange)?;
+        target.copy_from_slice(attributes);
+        Ok(())
+    }
+
+    fn palette_set(
+        &self,
+        palette: &PaletteTableIndex,
+        index: &PaletteIndex,
+        color: &PaletteColor,
+    ) -> ProtoResult<()> {
+        self.record(CoreCall::PaletteSet(*palette, *index, *color));
+        let index = usize::from(*index);
+        if index >= PALETTE_SIZE {
+            return Err(ProtoError::OutOfRange);
+        }
+        self.copy_colors(usize::from(*palette) * PALETTE_SIZE + index, &[*color])
+    }
+
+    fn palette_copy(
+        &self,
+        palette: &PaletteTableIndex,
+        colors: &[PaletteColor],
+    ) -> ProtoResult<()> {
+        self.record(CoreCall::PaletteCopy(*palette, colors.to_vec()));
+        self.copy_colors(usize::from(*palette) * PALETTE_SIZE, colors)
+    }
+
+    fn screen_control_set(&self, control: &ScreenControl) -> ProtoResult<()> {
+        self.record(CoreCall::ScreenControlSet(*control));
+        self.state.borrow_mut().screen_control = *control;
+        Ok(())
+    }
+
+    fn input_buttons(&self, player: &PlayerIndex) -> ProtoResult<Buttons> {
+        let input = self.state.borrow().input;
+        let buttons = input
+            .get(usize::from(*player))
+            .ok_or(ProtoError::InvalidArgument)?;
+        // The buttons of a disconnected controller are never pressed
+        Ok(buttons.unwrap_or_default())
+    }
+
+    fn input_connected(&self, player: &PlayerIndex) -> ProtoResult<bool> {
+        let input = self.state.borrow().input;
+        let buttons = input
+            .get(usize::from(*player))
+            .ok_or(ProtoError::InvalidArgument)?;
+        Ok(buttons.is_some())
+    }
+
+    fn boot_kind(&self) -> ProtoResult<BootKind> {
+        Ok(self.state.borrow().boot_kind)
+    }
+}
+
+/// Runs a host call on the core of the current thread (see [`TestCore::bootstrap()`]).
+fn host_call(call: impl FnOnce(&TestCore) -> ProtoResult<u32>) -> i32 {
+    let core = CURRENT
+        .with(|current| current.borrow().clone())
+        .expect("No TestCore has been bootstrapped on this thread.");
+    to_code(call(&core))
+}
+
+extern "C" fn log_log(_: u32, _: *const u8, _: usize) -> i32 {
+    0
+}
+
+extern "C" fn gpu_oam_set(index: u8, entry: u64) -> i32 {
+    host_call(|core| core.oam_set(&index.into(), &entry.into()).map(|_| 0))
+}
+
+extern "C" fn gpu_oam_hide(index: u8) -> i32 {
+    host_call(|core| core.oam_hide(&index.into()).map(|_| 0))
+}
+
+extern "C" fn gpu_oam_clear() -> i32 {
+    host_call(|core| core.oam_clear().map(|_| 0))
+}
+
+extern "C" fn gpu_palette_set(palette: u8, index: u8, color: u16) -> i32 {
+    host_call(|core| {
+        core.palette_set(&palette.into(), &index.into(), &color.into())
+            .map(|_| 0)
+    })
+}
+
+extern "C" fn gpu_screen_control_set(control: u8) -> i32 {
+    host_call(|core| core.screen_control_set(&control.into()).map(|_| 0))
+}
+
+extern "C" fn gpu_dma_copy(src_ptr: *const u8, table: u8, offset: u32, len: u32) -> i32 {
+    host_call(|core| {
+        let table = DmaTable::try_from(table).map_err(|_| ProtoError::InvalidArgument)?;
+        let offset = usize::try_from(offset).map_err(|_| ProtoError::OutOfRange)?;
+        let len = usize::try_from(len).map_err(|_| ProtoError::OutOfRange)?;
+        // The game runs natively, so the pointer refers to a slice of entries that has been passed to the CoreBootstrap
+        match table {
+            DmaTable::Oam => {
+                let index = u8::try_from(offset).map_err(|_| ProtoError::OutOfRange)?;
+                let entries =
+                    unsafe { std::slice::from_raw_parts(src_ptr as *const OamTableEntry, len) };
+                core.oam_copy(&index.into(), entries)
+            }
+            DmaTable::Palettes => {
+                let palette = offset / PALETTE_SIZE;
+                if palette * PALETTE_SIZE != offset {
+                    return Err(ProtoError::InvalidArgument);
+                }
+                let palette = u8::try_from(palette).map_err(|_| ProtoError::OutOfRange)?;
+                let colors =
+                    unsafe { std::slice::from_raw_parts(src_ptr as *const PaletteColor, len) };
+                core.palette_copy(&palette.into(), colors)
+            }
+            DmaTable::OamAttributes => {
+                let index = u8::try_from(offset).map_err(|_| ProtoError::OutOfRange)?;
+                let attributes =
+                    unsafe { std::slice::from_raw_parts(src_ptr as *const OamAttributes, len) };
+                core.oam_attributes_copy(&index.into(), attributes)
+            }
+        }
+        .map(|_| 0)
+    })
+}
+
+extern "C" fn input_buttons_get(player: u8) -> i32 {
+    host_call(|core| {
+        core.input_buttons(&player.into())
+            .map(|buttons| u32::from(u16::from(buttons)))
+    })
+}
+
+extern "C" fn input_connected_get(player: u8) -> i32 {
+    host_call(|core| core.input_connected(&player.into()).map(u32::from))
+}
+
+extern "C" fn system_boot_kind_get() -> i32 {
+    host_call(|core| core.boot_kind().map(u32::from))
+}
+
+#[cfg(test)]
+mod test_test_core {
+    use super::*;
+    use ves_art_core::geom_art::Size;
+    use ves_art_core::sprite::TileSurface;
+    use ves_art_core::surface::Surface as _;
+    use ves_proto_common::gpu::ExtendedOamEntry;
+
+    #[test]
+    fn test_core() {
+        let core = TestCore::new();
+        let entry = OamTableEntry::new(10, 20, 1, 0, 0, 3);
+        core.oam_set(&OamTableIndex::new(5), &entry).unwrap();
+        core.palette_copy(
+            &PaletteTableIndex::new(1),
+            &[PaletteColor::new(1, 2, 3); 17],
+        )
+        .unwrap();
+
+        assert_eq!(entry, core.oam()[5]);
+        assert_eq!(
+            PaletteColor::new(1, 2, 3),
+            core.palette(PaletteTableIndex::new(2)).colors[0]
+        );
+        assert_eq!(
+            PaletteColor::default(),
+            core.palette(PaletteTableIndex::new(2)).colors[1]
+        );
+        assert_eq!(2, core.take_calls().len());
+        assert!(core.calls().is_empty());
+
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.oam_copy(&OamTableIndex::new(127), &[entry, entry])
+        );
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            core.palette_copy(&PaletteTableIndex::new(255), &[PaletteColor::default(); 17])
+        );
+    }
+
+    #[test]
+    fn test_bootstrap() {
+        let core = TestCore::new();
+        let bootstrap = core.bootstrap();
+
+        let entries = [
+            OamTableEntry::new(1, 2, 0, 1, 0, 7),
+            OamTableEntry::new(3, 4, 0, 0, 1, 8),
+        ];
+        bootstrap
+            .oam_copy(&OamTableIndex::new(10), &entries)
+            .unwrap();
+        bootstrap
+            .palette_set(
+                &PaletteTableIndex::new(3),
+                &PaletteIndex::new(4),
+                &PaletteColor::new(5, 6, 7),
+            )
+            .unwrap();
+        bootstrap
+            .screen_control_set(&ScreenControl::new(3, 0, 0, 0))
+            .unwrap();
+
+        assert_eq!(entries, core.oam()[10..12]);
+        assert_eq!(
+            PaletteColor::new(5, 6, 7),
+            core.palette(PaletteTableIndex::new(3)).colors[4]
+        );
+        assert_eq!(3, core.screen_control().brightness());
+        assert_eq!(
+            vec![
+                CoreCall::OamCopy(OamTableIndex::new(10), entries.to_vec()),
+                CoreCall::PaletteSet(
+                    PaletteTableIndex::new(3),
+                    PaletteIndex::new(4),
+                    PaletteColor::new(5, 6, 7)
+                ),
+                CoreCall::ScreenControlSet(ScreenControl::new(3, 0, 0, 0)),
+            ],
+            core.calls()
+        );
+
+        let player = PlayerIndex::new(1);
+        assert_eq!(Ok(false), bootstrap.input_connected(&player));
+        let buttons = Buttons::from(0b101);
+        core.set_buttons(player, buttons);
+        assert_eq!(Ok(true), bootstrap.input_connected(&player));
+        assert_eq!(Ok(buttons), bootstrap.input_buttons(&player));
+        core.disconnect(player);
+        assert_eq!(Ok(Buttons::default()), bootstrap.input_buttons(&player));
+
+        core.set_boot_kind(BootKind::Reset);
+        assert_eq!(Ok(BootKind::Reset), bootstrap.boot_kind());
+    }
+
+    #[test]
+    fn test_oam_copy_extended() {
+        let core = TestCore::new();
+        let bootstrap = core.bootstrap();
+
+        let entry = OamTableEntry::new(1, 2, 0, 1, 0, 7);
+        let attributes = OamAttributes::new(1, 2, 0, 0);
+        let entries = [
+            ExtendedOamEntry::new(entry, attributes),
+            ExtendedOamEntry::from(entry),
+        ];
+        bootstrap
+            .oam_copy_extended(&OamTableIndex::new(126), &entries)
+            .unwrap();
+
+        assert_eq!([entry, entry], core.oam()[126..]);
+        assert_eq!(
+            [attributes, OamAttributes::default()],
+            core.oam_attributes()[126..]
+        );
+        // Clearing the OAM table hides the entries, but keeps their attributes
+        bootstrap.oam_clear().unwrap();
+        assert_eq!(attributes, core.oam_attributes()[126]);
+
+        assert_eq!(
+            Err(ProtoError::OutOfRange),
+            bootstrap.oam_attributes_copy(&OamTableIndex::new(127), &[attributes; 2])
+        );
+    }
+
+    #[test]
+    fn test_oam_hide_clear() {
+        let core = TestCore::new();
+        let bootstrap = core.bootstrap();
+
+        let entry = OamTableEntry::new(1, 2, 0, 1, 0, 7);
+        bootstrap
+            .oam_copy(&OamTableIndex::new(0), &[entry; 2])
+            .unwrap();
+        bootstrap.oam_hide(&OamTableIndex::new(1)).unwrap();
+
+        let mut hidden = entry;
+        hidden.set_hidden(true);
+        assert_eq!([entry, hidden], core.oam()[0..2]);
+
+        bootstrap.oam_clear().unwrap();
+        assert!(core.oam().iter().all(OamTableEntry::hidden));
+        assert_eq!(
+            vec![
+                CoreCall::OamCopy(OamTableIndex::new(0), vec![entry; 2]),
+                CoreCall::OamHide(OamTableIndex::new(1)),
+                CoreCall::OamClear,
+            ],
+            core.calls()
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        // The unused OAM entries refer to the first tile, so that one is left transparent
+        let mut surface = TileSurface::new(Size::new(8, 8));
+        surface
+            .data_mut()
+            .fill(ves_art_core::sprite::PaletteIndex::new(1));
+        let tiles = [
+            Tile::new(TileSurface::new(Size::new(8, 8))),
+            Tile::new(surface),
+        ];
+
+        let core = TestCore::new();
+        core.palette_set(
+            &PaletteTableIndex::new(0),
+            &PaletteIndex::new(1),
+            &PaletteColor::new(0, 31, 0),
+        )
+        .unwrap();
+        core.oam_set(
+            &OamTableIndex::new(0),
+            &OamTableEntry::new(4, 0, 0, 0, 0, 1),
+        )
+        .unwrap();
+
+        let buffer = core.render(&tiles).unwrap();
+        assert_eq!(&[0, 0, 0, 0], &buffer[0..4]);
+        assert_eq!(&[0, 255, 0, 255], &buffer[16..20]);
+    }
+}
@@ -0,0 +1,166 @@
+//! A golden test for the entire pipeline, from the capture of a game to the screen of the core.
+//!
+//! The test takes the Mesen-S capture that is bundled with `ves-art-snes` and runs it through the same steps as a real game:
+//!
+//! 1. Extract a movie from the dump (`ves-art-snes`).
+//! 2. Optimize the movie by cropping it to the visible part of the screen (`ves-art-core`).
+//! 3. Compile the VROM and a metasprite for every frame, like the `build.rs` of the prototype game does (`ves-proto-common`).
+//! 4. Upload the palettes and place the metasprites through the protocol and render every frame with the [`TestCore`].
+//!
+//! The hashes of the rendered frames are compared with the goldens in [`GOLDEN_FILE`], such that a change in the format or the
+//! protocol of any of the crates shows up here. After an intentional change to the output, set [`UPDATE_GOLDENS`] to regenerate
+//! the goldens.
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use ves_art_core::geom_art::{Rect, Size};
+use ves_art_core::movie::Movie;
+use ves_art_core::sprite::{Cel, Tile};
+use ves_proto_common::api::Core as _;
+use ves_proto_common::gpu::{OamTableIndex, PaletteColor, PaletteTableIndex};
+use ves_proto_common::metasprite::{compile_cel, Metasprite, MetaspriteObject};
+use ves_proto_testing::TestCore;
+
+/// The number of frames of the capture that are used.
+const NR_OF_FRAMES: usize = 10;
+/// The number of the first frame of the capture.
+const FIRST_FRAME: u64 = 199250;
+/// The size of the visible part of the screen.
+const VISIBLE_SIZE: (u32, u32) = (256, 224);
+/// The width of the screen buffer of the core.
+const SCREEN_BUFFER_WIDTH: usize = 512;
+/// The goldens, relative to the manifest directory. Every line contains a frame number and the hash of the rendered frame.
+const GOLDEN_FILE: &str = "tests/golden/pipeline_frames.txt";
+/// Writes the goldens instead of comparing them. FOR UPDATING
+const UPDATE_GOLDENS: bool = false;
+
+/// Extracts the movie from the Mesen-S capture.
+fn extract_movie() -> Movie {
+    let mut input_frames_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    input_frames_dir.push("../../art/snes/resources/test/mesen-s_frames");
+    let files = (FIRST_FRAME..)
+        .take(NR_OF_FRAMES)
+        .map(|frame| input_frames_dir.join(format!("frame_{}.json", frame)));
+    ves_art_snes::create_movie(files.collect::<Vec<_>>().iter()).unwrap()
+}
+
+/// Serializes the tiles into the VROM format and reads them back, like the core does when it loads a game.
+fn compile_vrom(movie: &Movie) -> Vec<Tile> {
+    let vrom = bincode::serialize(movie.tiles()).unwrap();
+    bincode::deserialize(&vrom).unwrap()
+}
+
+/// Compiles every frame into a metasprite.
+///
+/// returns: The objects of every metasprite, together with the position of its origin on the screen.
+fn compile_frames(movie: &Movie) -> Vec<(Vec<MetaspriteObject>, (i32, i32))> {
+    movie
+        .frames()
+        .iter()
+        .map(|frame| {
+            let objects =
+                compile_cel(&Cel::new(frame.sprites().to_vec()), movie.screen_size()).unwrap();
+            // The origin follows from the first sprite, since the offsets are relative to the origin
+            let origin = match (frame.sprites().first(), objects.first()) {
+                (Some(sprite), Some(object)) => {
+                    let position = sprite.position();
+                    // The casts are OK here, because the positions lie within the screen
+                    (
+                        position.x.raw() as i32 - i32::from(object.x),
+                        position.y.raw() as i32 - i32::from(object.y),
+                    )
+                }
+                _ => (0, 0),
+            };
+            (objects, origin)
+        })
+        .collect()
+}
+
+/// Hashes the visible part of a screen buffer with FNV-1a, which (unlike the hasher of the standard library) is stable across
+/// releases.
+fn hash_visible(screen_buffer: &[u8]) -> u64 {
+    let row_len = 4 * VISIBLE_SIZE.0 as usize;
+    screen_buffer
+        .chunks(4 * SCREEN_BUFFER_WIDTH)
+        .take(VISIBLE_SIZE.1 as usize)
+        .flat_map(|row| &row[..row_len])
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+/// Runs the pipeline and renders every frame.
+///
+/// returns: The frame number and the hash of every frame.
+fn render_frames() -> Vec<(u64, u64)> {
+    let mut movie = extract_movie();
+    movie
+        .crop(Rect::new_from_size(
+            (0, 0),
+            Size::new(VISIBLE_SIZE.0, VISIBLE_SIZE.1),
+        ))
+        .unwrap();
+    let tiles = compile_vrom(&movie);
+    let metasprites = compile_frames(&movie);
+
+    let core = TestCore::new();
+    let bootstrap = core.bootstrap();
+    movie
+        .frames()
+        .iter()
+        .zip(metasprites)
+        .enumerate()
+        .map(|(frame_idx, (frame, (objects, origin)))| {
+            for (palette_idx, palette) in movie.palettes_at(frame_idx).unwrap().iter().enumerate() {
+                let colors: Vec<PaletteColor> = palette
+                    .iter()
+                    .map(|(_, color)| PaletteColor::from(*color))
+                    .collect();
+                let palette_idx = u8::try_from(palette_idx).unwrap();
+                bootstrap
+                    .palette_copy(&PaletteTableIndex::new(palette_idx), &colors)
+                    .unwrap();
+            }
+
+            // Remove the objects of the previous frame
+            bootstrap.oam_clear().unwrap();
+            // Metasprites are normally compiled into constants, hence the static lifetime
+            let metasprite = Metasprite::new(Box::leak(objects.into_boxed_slice()));
+            bootstrap
+                .place_metasprite(&metasprite, origin, &OamTableIndex::new(0))
+                .unwrap();
+
+            let screen_buffer = core.render(&tiles).unwrap();
+            (frame.frame_number(), hash_visible(&screen_buffer))
+        })
+        .collect()
+}
+
+#[test]
+fn test_pipeline() {
+    let actual = render_frames();
+    assert_eq!(NR_OF_FRAMES, actual.len());
+
+    let golden_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(GOLDEN_FILE);
+    if UPDATE_GOLDENS {
+        let mut out = String::new();
+        for (frame_number, hash) in &actual {
+            writeln!(out, "{} {:016x}", frame_number, hash).unwrap();
+        }
+        std::fs::write(&golden_path, out).unwrap();
+    }
+
+    let expected: Vec<(u64, u64)> = std::fs::read_to_string(&golden_path)
+        .unwrap()
+        .lines()
+        .map(|line| {
+            let (frame_number, hash) = line.split_once(' ').unwrap();
+            (
+                frame_number.parse().unwrap(),
+                u64::from_str_radix(hash, 16).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(expected, actual);
+}
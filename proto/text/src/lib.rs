@@ -0,0 +1,181 @@
+//! Helpers for rendering text with a tile font.
+//!
+//! A tile font is a bank of consecutive tiles in VROM, where each tile represents a single ASCII character. The bank starts with
+//! the tile for a specific character (usually a space) and contains the tiles for all subsequent characters. The [`TileFont`] converts
+//! strings into OAM entries that can be sent to the core.
+
+use ves_proto_common::api::Core;
+use ves_proto_common::gpu::{OamTableEntry, OamTableIndex, PaletteTableIndex};
+use ves_proto_common::result::ProtoResult;
+
+/// The number of entries in the OAM table.
+const OAM_TABLE_SIZE: usize = 128;
+
+/// A font that consists of a bank of tiles in VROM.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct TileFont {
+    char_table_index: u32,
+    first_char: u8,
+    char_count: u8,
+    char_size: (u16, u16),
+}
+
+impl TileFont {
+    /// Creates a new [`TileFont`].
+    ///
+    /// # Arguments
+    ///
+    /// * `char_table_index`: The index of the tile for `first_char` in the character table.
+    /// * `first_char`: The first ASCII character in the font.
+    /// * `char_count`: The number of characters in the font.
+    /// * `char_size`: The width and height of a character in pixels. This is used for advancing the cursor.
+    pub fn new(
+        char_table_index: u32,
+        first_char: u8,
+        char_count: u8,
+        char_size: (u16, u16),
+    ) -> Self {
+        Self {
+            char_table_index,
+            first_char,
+            char_count,
+            char_size,
+        }
+    }
+
+    /// Retrieves the character table index for a character.
+    ///
+    /// # Arguments
+    ///
+    /// * `chr`: The character.
+    ///
+    /// # Returns
+    /// The character table index or `None` if the font does not contain the character.
+    pub fn char_table_index(&self, chr: char) -> Option<u32> {
+        let chr = u8::try_from(chr).ok()?;
+        let offset = chr.checked_sub(self.first_char)?;
+        (offset < self.char_count).then(|| self.char_table_index + u32::from(offset))
+    }
+
+    /// Lays out a string as OAM entries.
+    ///
+    /// Every line in the text starts at the horizontal position of `position`. Whitespace and characters that are not part of the
+    /// font do not produce an entry, but do advance the cursor.
+    ///
+    /// # Arguments
+    ///
+    /// * `text`: The text.
+    /// * `position`: The position of the top-left pixel of the first character.
+    /// * `palette`: The palette to use for all characters.
+    pub fn layout<'a>(
+        &'a self,
+        text: &'a str,
+        position: (u16, u16),
+        palette: PaletteTableIndex,
+    ) -> impl Iterator<Item = OamTableEntry> + 'a {
+        let (width, height) = self.char_size;
+        let mut cursor = position;
+
+        text.chars().filter_map(move |chr| {
+            if chr == '\n' {
+                cursor = (position.0, cursor.1.wrapping_add(height));
+                return None;
+            }
+
+            let (x, y) = cursor;
+            cursor.0 = cursor.0.wrapping_add(width);
+
+            if chr.is_whitespace() {
+                return None;
+            }
+
+            self.char_table_index(chr)
+                .map(|index| OamTableEntry::new(x, y, palette.into(), 0, 0, index))
+        })
+    }
+
+    /// Writes a string to the OAM table of the core.
+    ///
+    /// See [`TileFont::layout()`] for the layout rules. Characters that do not fit in the OAM table are dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `core`: The core.
+    /// * `first_index`: The first OAM index to use.
+    /// * `text`: The text.
+    /// * `position`: The position of the top-left pixel of the first character.
+    /// * `palette`: The palette to use for all characters.
+    ///
+    /// # Returns
+    /// The number of OAM entries that were written or the error that was reported by the core.
+    pub fn write(
+        &self,
+        core: &impl Core,
+        first_index: u8,
+        text: &str,
+        position: (u16, u16),
+        palette: PaletteTableIndex,
+    ) -> ProtoResult<usize> {
+        let available = OAM_TABLE_SIZE.saturating_sub(usize::from(first_index));
+        let entries: Vec<OamTableEntry> = self
+            .layout(text, position, palette)
+            .take(available)
+            .collect();
+        // Write all entries with a single call into the core
+        if !entries.is_empty() {
+            core.oam_copy(&OamTableIndex::new(first_index), &entries)?;
+        }
+        Ok(entries.len())
+    }
+}
+
+#[cfg(test)]
+mod test_tile_font {
+    use super::TileFont;
+    use ves_proto_common::gpu::{OamTableEntry, PaletteTableIndex};
+    use ves_proto_testing::{CoreCall, TestCore};
+
+    fn font() -> TileFont {
+        TileFont::new(100, b' ', 64, (8, 10))
+    }
+
+    #[test]
+    fn test_char_table_index() {
+        let font = font();
+        assert_eq!(Some(100), font.char_table_index(' '));
+        assert_eq!(Some(133), font.char_table_index('A'));
+        assert_eq!(Some(163), font.char_table_index('_'));
+        assert_eq!(None, font.char_table_index('a'));
+        assert_eq!(None, font.char_table_index('\t'));
+        assert_eq!(None, font.char_table_index('é'));
+    }
+
+    #[test]
+    fn test_layout() {
+        let font = font();
+        let palette = PaletteTableIndex::new(3);
+        let actual: Vec<OamTableEntry> = font.layout("AB C\nx1", (20, 30), palette).collect();
+        let expected = vec![
+            OamTableEntry::new(20, 30, 3, 0, 0, 133),
+            OamTableEntry::new(28, 30, 3, 0, 0, 134),
+            OamTableEntry::new(44, 30, 3, 0, 0, 135),
+            OamTableEntry::new(28, 40, 3, 0, 0, 117),
+        ];
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_write() {
+        let font = font();
+        let core = TestCore::new();
+        let written = font.write(&core, 126, "ABC", (0, 0), PaletteTableIndex::new(0));
+
+        assert_eq!(Ok(2), written);
+        let entries = vec![
+            OamTableEntry::new(0, 0, 0, 0, 0, 133),
+            OamTableEntry::new(8, 0, 0, 0, 0, 134),
+        ];
+        assert_eq!(entries, core.oam()[126..]);
+        assert_eq!(vec![CoreCall::OamCopy(126.into(), entries)], core.calls());
+    }
+}